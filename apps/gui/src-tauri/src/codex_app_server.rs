@@ -10,6 +10,7 @@ use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tauri::Emitter;
+use tauri::Manager;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
@@ -23,6 +24,8 @@ use tokio::sync::Mutex;
 use tokio::sync::OnceCell;
 use tokio::time::timeout;
 
+use crate::codex_checkpoint;
+use crate::codex_file_change;
 use crate::codex_patch_diff;
 
 const EVENT_NAME: &str = "codex_app_server";
@@ -153,6 +156,14 @@ impl CodexAppServer {
         Ok(())
     }
 
+    pub fn app_server_id(&self) -> &str {
+        &self.inner.app_server_id
+    }
+
+    pub fn cwd(&self) -> &Path {
+        &self.inner.cwd
+    }
+
     pub async fn request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
         let id = self.inner.next_request_id.fetch_sub(1, Ordering::SeqCst);
         let (tx, rx) = oneshot::channel();
@@ -471,6 +482,20 @@ fn is_executable_file(path: &Path) -> bool {
     }
 }
 
+/// Where file-change records go: the orchestrator's workspace root, which the commands that
+/// read them use too (it can move away from this app-server's `cwd` when switching workspaces).
+fn records_root(inner: &CodexAppServerInner) -> PathBuf {
+    match inner.app.try_state::<crate::AppState>() {
+        Some(state) => state
+            .orchestrator
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .workspace_root()
+            .to_path_buf(),
+        None => inner.cwd.clone(),
+    }
+}
+
 async fn run_stdout_loop(inner: Arc<CodexAppServerInner>, stdout: ChildStdout) {
     let mut reader = BufReader::new(stdout);
     let mut buf = Vec::new();
@@ -563,8 +588,13 @@ async fn run_stdout_loop(inner: Arc<CodexAppServerInner>, stdout: ChildStdout) {
         // Notification (method + params, no id).
         if parsed.get("method").is_some() {
             if let Some(method) = parsed.get("method").and_then(|v| v.as_str()) {
+                if method == "turn/started" {
+                    codex_checkpoint::on_turn_started(&parsed);
+                }
                 if method == "item/started" || method == "item/completed" {
                     let cwd = inner.cwd.clone();
+                    let workspace_root = records_root(&inner);
+                    codex_file_change::track_notification(&parsed, &workspace_root, &cwd).await;
                     enrich_file_change_notification(&mut parsed, &cwd).await;
                 }
             }
@@ -1,5 +1,6 @@
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::path::Path;
@@ -7,15 +8,19 @@ use std::path::PathBuf;
 
 use crate::codex_app_server::CodexAppServer;
 
+/// One app-server per `CODEX_HOME` and profile, so threads on different profiles run side by
+/// side instead of restarting a shared instance.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct CodexAppServerKey {
     codex_home: PathBuf,
+    profile: Option<String>,
 }
 
 #[derive(Clone)]
 struct CodexAppServerEntry {
     server: CodexAppServer,
-    profile: Option<String>,
+    /// Threads started, resumed or forked on this instance (app-server only serves those).
+    loaded_threads: HashSet<String>,
 }
 
 pub struct CodexAppServerPool {
@@ -49,8 +54,10 @@ impl CodexAppServerPool {
         let codex_home = canonicalize_or_abs(codex_home)?;
         std::fs::create_dir_all(&codex_home).map_err(|e| e.to_string())?;
 
+        let profile = normalize_profile(profile.as_deref());
         let key = CodexAppServerKey {
             codex_home: codex_home.clone(),
+            profile: profile.clone(),
         };
         let app_server_id = self
             .id_by_key
@@ -58,18 +65,8 @@ impl CodexAppServerPool {
             .cloned()
             .unwrap_or_else(|| app_server_id_for_key(&key));
 
-        if let Some(existing) = self.servers_by_id.get(&app_server_id) {
-            // If the caller requests a different profile than the one the process was spawned with,
-            // restart this CODEX_HOME-scoped app-server instance.
-            let profile_changed = normalize_profile(existing.profile.as_deref())
-                != normalize_profile(profile.as_deref());
-            if !profile_changed {
-                return Ok(app_server_id);
-            }
-
-            let server = existing.server.clone();
-            server.shutdown().await;
-            self.servers_by_id.remove(&app_server_id);
+        if self.servers_by_id.contains_key(&app_server_id) {
+            return Ok(app_server_id);
         }
 
         if self.servers_by_id.len() >= self.max_servers {
@@ -79,24 +76,34 @@ impl CodexAppServerPool {
             ));
         }
 
-        let server = CodexAppServer::spawn(
-            app,
-            cwd,
-            profile.clone(),
-            Some(&codex_home),
-            app_server_id.clone(),
-        )
-        .await?;
+        let server =
+            CodexAppServer::spawn(app, cwd, profile, Some(&codex_home), app_server_id.clone())
+                .await?;
 
         self.id_by_key.insert(key, app_server_id.clone());
         self.servers_by_id.insert(
             app_server_id.clone(),
-            CodexAppServerEntry { server, profile },
+            CodexAppServerEntry {
+                server,
+                loaded_threads: HashSet::new(),
+            },
         );
 
         Ok(app_server_id)
     }
 
+    pub fn is_thread_loaded(&self, app_server_id: &str, thread_id: &str) -> bool {
+        self.servers_by_id
+            .get(app_server_id)
+            .is_some_and(|entry| entry.loaded_threads.contains(thread_id))
+    }
+
+    pub fn mark_thread_loaded(&mut self, app_server_id: &str, thread_id: &str) {
+        if let Some(entry) = self.servers_by_id.get_mut(app_server_id) {
+            entry.loaded_threads.insert(thread_id.to_string());
+        }
+    }
+
     pub async fn shutdown(&mut self, app_server_id: &str) {
         let Some(entry) = self.servers_by_id.remove(app_server_id) else {
             return;
@@ -113,18 +120,6 @@ impl CodexAppServerPool {
             entry.server.shutdown().await;
         }
     }
-
-    pub async fn shutdown_by_codex_home(&mut self, codex_home: &Path) {
-        let codex_home = match canonicalize_or_abs(codex_home) {
-            Ok(p) => p,
-            Err(_) => return,
-        };
-        let key = CodexAppServerKey { codex_home };
-        let Some(id) = self.id_by_key.get(&key).cloned() else {
-            return;
-        };
-        self.shutdown(&id).await;
-    }
 }
 
 fn normalize_profile(profile: Option<&str>) -> Option<String> {
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the selection file.
+static SELECTION_LOCK: Mutex<()> = Mutex::new(());
+
+/// Profile, model and approval policy for Codex chats. Unset fields fall back to the
+/// next layer (thread -> workspace -> `config.toml`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSelection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_reasoning_effort: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<String>,
+}
+
+impl ChatSelection {
+    fn normalized(self) -> Result<Self, String> {
+        let clean = |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let selection = Self {
+            profile: clean(self.profile),
+            model: clean(self.model),
+            model_reasoning_effort: clean(self.model_reasoning_effort),
+            approval_policy: clean(self.approval_policy),
+        };
+        if let Some(policy) = selection.approval_policy.as_deref() {
+            if !matches!(policy, "untrusted" | "on-failure" | "on-request" | "never") {
+                return Err(format!("invalid approval_policy: {policy}"));
+            }
+        }
+        Ok(selection)
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// A thread override that names a profile stands alone: the workspace's model and policy
+    /// were picked for the workspace profile and would defeat the thread's. Without a profile,
+    /// the override only replaces the fields it sets.
+    fn layered_over(&self, base: &Self) -> Self {
+        if self.profile.is_some() {
+            return self.clone();
+        }
+        Self {
+            profile: base.profile.clone(),
+            model: self.model.clone().or_else(|| base.model.clone()),
+            model_reasoning_effort: self
+                .model_reasoning_effort
+                .clone()
+                .or_else(|| base.model_reasoning_effort.clone()),
+            approval_policy: self
+                .approval_policy
+                .clone()
+                .or_else(|| base.approval_policy.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SelectionFile {
+    #[serde(default)]
+    workspace: ChatSelection,
+    #[serde(default)]
+    threads: BTreeMap<String, ChatSelection>,
+}
+
+/// The selection in effect for a thread (or for new threads, without one).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedChatSelection {
+    #[serde(flatten)]
+    pub selection: ChatSelection,
+    /// Whether the thread has its own override (as opposed to following the workspace).
+    pub thread_override: bool,
+}
+
+/// `<workspace>/.coco/codex/chat_selection.json`.
+fn selection_path(workspace_root: &Path) -> PathBuf {
+    workspace_root
+        .join(".coco")
+        .join("codex")
+        .join("chat_selection.json")
+}
+
+fn read_file(workspace_root: &Path) -> SelectionFile {
+    let path = selection_path(workspace_root);
+    let Ok(raw) = std::fs::read_to_string(&path) else {
+        return SelectionFile::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|err| {
+        log::warn!("ignoring unreadable {}: {err}", path.display());
+        SelectionFile::default()
+    })
+}
+
+fn write_file(workspace_root: &Path, file: &SelectionFile) -> Result<(), String> {
+    let path = selection_path(workspace_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+pub fn resolve(workspace_root: &Path, thread_id: Option<&str>) -> ResolvedChatSelection {
+    let file = read_file(workspace_root);
+    let thread = thread_id.and_then(|id| file.threads.get(id));
+    ResolvedChatSelection {
+        selection: match thread {
+            Some(thread) => thread.layered_over(&file.workspace),
+            None => file.workspace.clone(),
+        },
+        thread_override: thread.is_some(),
+    }
+}
+
+/// Replaces the workspace defaults.
+pub fn set_workspace(
+    workspace_root: &Path,
+    selection: ChatSelection,
+) -> Result<ResolvedChatSelection, String> {
+    let selection = selection.normalized()?;
+    let _guard = SELECTION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = read_file(workspace_root);
+    file.workspace = selection;
+    write_file(workspace_root, &file)?;
+    Ok(ResolvedChatSelection {
+        selection: file.workspace,
+        thread_override: false,
+    })
+}
+
+/// Replaces a thread's override; `None` (or an all-empty selection) makes the thread follow the
+/// workspace again.
+pub fn set_thread(
+    workspace_root: &Path,
+    thread_id: &str,
+    selection: Option<ChatSelection>,
+) -> Result<ResolvedChatSelection, String> {
+    let selection = selection
+        .map(ChatSelection::normalized)
+        .transpose()?
+        .filter(|s| !s.is_empty());
+    {
+        let _guard = SELECTION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = read_file(workspace_root);
+        let changed = match selection {
+            Some(selection) => {
+                file.threads
+                    .insert(thread_id.to_string(), selection.clone())
+                    != Some(selection)
+            }
+            None => file.threads.remove(thread_id).is_some(),
+        };
+        if changed {
+            write_file(workspace_root, &file)?;
+        }
+    }
+    Ok(resolve(workspace_root, Some(thread_id)))
+}
+
+/// Gives a forked thread the same override as its source, if any.
+pub fn copy_thread(workspace_root: &Path, from: &str, to: &str) -> Result<(), String> {
+    let _guard = SELECTION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = read_file(workspace_root);
+    let Some(selection) = file.threads.get(from).cloned() else {
+        return Ok(());
+    };
+    file.threads.insert(to.to_string(), selection);
+    write_file(workspace_root, &file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(profile: Option<&str>, model: Option<&str>) -> ChatSelection {
+        ChatSelection {
+            profile: profile.map(str::to_string),
+            model: model.map(str::to_string),
+            ..ChatSelection::default()
+        }
+    }
+
+    #[test]
+    fn thread_overrides_layer_over_workspace() {
+        let dir = std::env::temp_dir().join(format!("coco-selection-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let workspace = ChatSelection {
+            approval_policy: Some("never".into()),
+            ..selection(Some("fast"), Some("gpt-5.2"))
+        };
+        set_workspace(&dir, workspace).unwrap();
+
+        // Model-only override keeps the workspace profile and policy.
+        let resolved = set_thread(&dir, "t1", Some(selection(None, Some("o3")))).unwrap();
+        assert!(resolved.thread_override);
+        assert_eq!(resolved.selection.profile.as_deref(), Some("fast"));
+        assert_eq!(resolved.selection.model.as_deref(), Some("o3"));
+        assert_eq!(resolved.selection.approval_policy.as_deref(), Some("never"));
+
+        // A profile override does not inherit the workspace model or policy.
+        let resolved = set_thread(&dir, "t2", Some(selection(Some("deep-review"), None))).unwrap();
+        assert_eq!(resolved.selection, selection(Some("deep-review"), None));
+
+        copy_thread(&dir, "t2", "t3").unwrap();
+        assert_eq!(
+            resolve(&dir, Some("t3")).selection.profile.as_deref(),
+            Some("deep-review")
+        );
+
+        let cleared = set_thread(&dir, "t2", Some(selection(Some("  "), None))).unwrap();
+        assert!(!cleared.thread_override);
+        assert_eq!(cleared.selection.profile.as_deref(), Some("fast"));
+
+        let bad = ChatSelection {
+            approval_policy: Some("sometimes".into()),
+            ..ChatSelection::default()
+        };
+        assert!(set_workspace(&dir, bad).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
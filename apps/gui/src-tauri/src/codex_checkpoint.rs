@@ -0,0 +1,596 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use crate::path_policy::PathPolicy;
+
+/// Serializes git runs: checkpoints are taken from the app-server loop while commands may be
+/// previewing or restoring, and each shadow repo has a single index.
+static GIT_LOCK: Mutex<()> = Mutex::new(());
+
+const CHECKPOINT_AUTHOR_NAME: &str = "Coco";
+const CHECKPOINT_AUTHOR_EMAIL: &str = "coco@localhost";
+
+/// `<workspace>/.coco/codex/checkpoints/`:
+/// - `threads/<thread_id>.json`: the thread's checkpoints, oldest first (one per turn);
+/// - `repos/<work tree>.git`: a shadow git repo per work tree, so checkpoints never touch the
+///   user's own repository (refs, index or objects).
+pub fn checkpoint_dir(workspace_root: &Path) -> PathBuf {
+    workspace_root
+        .join(".coco")
+        .join("codex")
+        .join("checkpoints")
+}
+
+fn thread_index_path(workspace_root: &Path, thread_id: &str) -> Result<PathBuf, String> {
+    let valid = !thread_id.is_empty()
+        && thread_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(format!("invalid thread_id: {thread_id}"));
+    }
+    Ok(checkpoint_dir(workspace_root)
+        .join("threads")
+        .join(format!("{thread_id}.json")))
+}
+
+/// The working tree as it was when a turn started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub turn_id: String,
+    /// Commit in the shadow repo of `cwd`.
+    pub commit: String,
+    pub cwd: String,
+    pub created_at_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointFileChange {
+    /// Relative to the checkpoint's `cwd`.
+    pub path: String,
+    /// What happened since the checkpoint (`added`, `modified` or `deleted`); restoring
+    /// undoes it.
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointPreview {
+    pub checkpoint: Checkpoint,
+    pub files: Vec<CheckpointFileChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointRestore {
+    pub checkpoint: Checkpoint,
+    pub files: Vec<CheckpointFileChange>,
+    /// Shadow-repo commit of the tree as it was just before restoring.
+    pub backup_commit: String,
+}
+
+// ============================================================================
+// Shadow repo
+// ============================================================================
+
+struct ShadowRepo {
+    git_dir: PathBuf,
+    work_tree: PathBuf,
+}
+
+impl ShadowRepo {
+    fn open(workspace_root: &Path, work_tree: &Path) -> Result<Self, String> {
+        let work_tree = std::fs::canonicalize(work_tree).map_err(|e| e.to_string())?;
+        let name = work_tree
+            .to_string_lossy()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let git_dir = checkpoint_dir(workspace_root)
+            .join("repos")
+            .join(format!("{}.git", name.trim_matches('_')));
+        let repo = Self { git_dir, work_tree };
+
+        if !repo.git_dir.join("HEAD").exists() {
+            std::fs::create_dir_all(&repo.git_dir).map_err(|e| e.to_string())?;
+            run_git(&repo.git_dir, &["init", "--quiet", "--bare", "."], None)?;
+            let exclude_dir = repo.git_dir.join("info");
+            std::fs::create_dir_all(&exclude_dir).map_err(|e| e.to_string())?;
+            // Never snapshot Coco's own state (including these shadow repos).
+            std::fs::write(exclude_dir.join("exclude"), ".coco/\n").map_err(|e| e.to_string())?;
+        }
+        Ok(repo)
+    }
+
+    fn git(&self, args: &[&str], stdin: Option<&[u8]>) -> Result<String, String> {
+        let git_dir = format!("--git-dir={}", self.git_dir.to_string_lossy());
+        let work_tree = format!("--work-tree={}", self.work_tree.to_string_lossy());
+        let mut full = vec![
+            git_dir.as_str(),
+            work_tree.as_str(),
+            "--literal-pathspecs",
+            "-c",
+            "core.autocrlf=false",
+        ];
+        full.extend_from_slice(args);
+        run_git(&self.work_tree, &full, stdin)
+    }
+
+    /// Stage the whole work tree (honoring `.gitignore`) into the shadow index; returns the tree.
+    fn snapshot_tree(&self) -> Result<String, String> {
+        self.git(&["add", "--all", "--", "."], None)?;
+        Ok(self.git(&["write-tree"], None)?.trim().to_string())
+    }
+
+    fn commit(&self, tree: &str, parent: Option<&str>, message: &str) -> Result<String, String> {
+        let mut args = vec!["commit-tree", tree, "-m", message];
+        if let Some(parent) = parent {
+            args.extend_from_slice(&["-p", parent]);
+        }
+        Ok(self.git(&args, None)?.trim().to_string())
+    }
+
+    /// Make `files` match `commit`: files with status `remove_status` do not exist there and are
+    /// deleted, the rest are checked out from it.
+    fn put_back(
+        &self,
+        commit: &str,
+        files: &[CheckpointFileChange],
+        remove_status: &str,
+    ) -> Result<(), String> {
+        let mut checkout = Vec::new();
+        for file in files {
+            if file.status == remove_status {
+                let path = self.work_tree.join(&file.path);
+                match std::fs::remove_file(&path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(format!("{}: {e}", file.path)),
+                }
+            } else {
+                checkout.extend_from_slice(file.path.as_bytes());
+                checkout.push(0);
+            }
+        }
+        if !checkout.is_empty() {
+            self.git(
+                &[
+                    "checkout",
+                    commit,
+                    "--pathspec-from-file=-",
+                    "--pathspec-file-nul",
+                ],
+                Some(&checkout),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn changes_since(&self, commit: &str, tree: &str) -> Result<Vec<CheckpointFileChange>, String> {
+        let raw = self.git(
+            &[
+                "diff-tree",
+                "-r",
+                "-z",
+                "--no-renames",
+                "--name-status",
+                commit,
+                tree,
+            ],
+            None,
+        )?;
+        let mut parts = raw.split('\0').filter(|s| !s.is_empty());
+        let mut changes = Vec::new();
+        while let (Some(status), Some(path)) = (parts.next(), parts.next()) {
+            let status = match status.chars().next() {
+                Some('A') => "added",
+                Some('D') => "deleted",
+                _ => "modified",
+            };
+            changes.push(CheckpointFileChange {
+                path: path.to_string(),
+                status: status.to_string(),
+            });
+        }
+        Ok(changes)
+    }
+}
+
+fn run_git(cwd: &Path, args: &[&str], stdin: Option<&[u8]>) -> Result<String, String> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .env("GIT_AUTHOR_NAME", CHECKPOINT_AUTHOR_NAME)
+        .env("GIT_AUTHOR_EMAIL", CHECKPOINT_AUTHOR_EMAIL)
+        .env("GIT_COMMITTER_NAME", CHECKPOINT_AUTHOR_NAME)
+        .env("GIT_COMMITTER_EMAIL", CHECKPOINT_AUTHOR_EMAIL)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            "git command failed".to_string()
+        } else {
+            stderr
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// ============================================================================
+// Checkpoints
+// ============================================================================
+
+fn read_checkpoints(workspace_root: &Path, thread_id: &str) -> Result<Vec<Checkpoint>, String> {
+    let path = thread_index_path(workspace_root, thread_id)?;
+    match std::fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| e.to_string()),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn write_checkpoints(
+    workspace_root: &Path,
+    thread_id: &str,
+    checkpoints: &[Checkpoint],
+) -> Result<(), String> {
+    let path = thread_index_path(workspace_root, thread_id)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(checkpoints).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+pub fn list(workspace_root: &Path, thread_id: &str) -> Result<Vec<Checkpoint>, String> {
+    read_checkpoints(workspace_root, thread_id)
+}
+
+/// A snapshot taken just before `turn/start` was sent, waiting for the turn id.
+struct PendingSnapshot {
+    workspace_root: PathBuf,
+    commit: String,
+    cwd: String,
+    created_at_ms: u64,
+}
+
+/// Per thread: the snapshot of the turn being started. Only one turn runs per thread at a time.
+static PENDING: Mutex<Option<HashMap<String, PendingSnapshot>>> = Mutex::new(None);
+
+/// Snapshot `cwd` before a turn is sent to the app-server, so the snapshot cannot contain any of
+/// the turn's own edits. The commit is chained onto the thread's previous checkpoint in the same
+/// repo; it becomes a checkpoint once `attach` learns the turn id. `cwd` must be inside `policy`,
+/// since a restore later rewrites files there.
+pub fn snapshot(
+    workspace_root: &Path,
+    thread_id: &str,
+    cwd: &Path,
+    policy: &PathPolicy,
+) -> Result<(), String> {
+    let cwd = policy.resolve_dir("codex_checkpoint_snapshot", &cwd.to_string_lossy())?;
+    let _guard = GIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let checkpoints = read_checkpoints(workspace_root, thread_id)?;
+    let repo = ShadowRepo::open(workspace_root, &cwd)?;
+    let cwd = repo.work_tree.to_string_lossy().to_string();
+    let parent = checkpoints
+        .iter()
+        .rev()
+        .find(|c| c.cwd == cwd)
+        .map(|c| c.commit.clone());
+    let tree = repo.snapshot_tree()?;
+    let commit = repo.commit(&tree, parent.as_deref(), &format!("checkpoint {thread_id}"))?;
+    let pending = PendingSnapshot {
+        workspace_root: workspace_root.to_path_buf(),
+        commit,
+        cwd,
+        created_at_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    };
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(thread_id.to_string(), pending);
+    Ok(())
+}
+
+/// Drop the pending snapshot of a turn that never started (`turn/start` failed).
+pub fn discard(thread_id: &str) {
+    if let Some(pending) = PENDING.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        pending.remove(thread_id);
+    }
+}
+
+/// Record the thread's pending snapshot as the checkpoint of `turn_id`. Called with the
+/// `turn/start` response and again on `turn/started`, whichever comes first wins; later calls
+/// are no-ops.
+pub fn attach(thread_id: &str, turn_id: &str) -> Result<Option<Checkpoint>, String> {
+    let Some(pending) = PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+        .and_then(|pending| pending.remove(thread_id))
+    else {
+        return Ok(None);
+    };
+    let _guard = GIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let workspace_root = pending.workspace_root.as_path();
+    let mut checkpoints = read_checkpoints(workspace_root, thread_id)?;
+    if let Some(existing) = checkpoints.iter().find(|c| c.turn_id == turn_id) {
+        return Ok(Some(existing.clone()));
+    }
+    let repo = ShadowRepo::open(workspace_root, Path::new(&pending.cwd))?;
+    repo.git(
+        &[
+            "update-ref",
+            &format!("refs/coco/threads/{thread_id}"),
+            &pending.commit,
+        ],
+        None,
+    )?;
+
+    let checkpoint = Checkpoint {
+        turn_id: turn_id.to_string(),
+        commit: pending.commit,
+        cwd: pending.cwd,
+        created_at_ms: pending.created_at_ms,
+    };
+    checkpoints.push(checkpoint.clone());
+    write_checkpoints(workspace_root, thread_id, &checkpoints)?;
+    Ok(Some(checkpoint))
+}
+
+/// Attach the pending snapshot when the app-server reports `turn/started` (on a blocking thread,
+/// off the notification loop), in case the `turn/start` response has not done it yet.
+pub fn on_turn_started(message: &Value) {
+    let Some(params) = message.get("params") else {
+        return;
+    };
+    let thread_id = params.get("threadId").and_then(|v| v.as_str());
+    let turn_id = params
+        .get("turn")
+        .and_then(|t| t.get("id"))
+        .and_then(|v| v.as_str());
+    let (Some(thread_id), Some(turn_id)) = (thread_id, turn_id) else {
+        return;
+    };
+    let (thread_id, turn_id) = (thread_id.to_string(), turn_id.to_string());
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = attach(&thread_id, &turn_id) {
+            log::info!("[CodexCheckpoint] checkpoint for {thread_id}/{turn_id} failed: {err}");
+        }
+    });
+}
+
+/// The checkpoint taken before `turn_id`; an error when that turn has none, rather than falling
+/// back to a neighbouring turn's.
+fn checkpoint_for(
+    checkpoints: &[Checkpoint],
+    thread_id: &str,
+    turn_id: &str,
+) -> Result<Checkpoint, String> {
+    checkpoints
+        .iter()
+        .find(|c| c.turn_id == turn_id)
+        .cloned()
+        .ok_or_else(|| format!("no checkpoint for turn {turn_id} of thread {thread_id}"))
+}
+
+/// Which files rolling back to before `turn_id` would restore.
+pub fn preview(
+    workspace_root: &Path,
+    thread_id: &str,
+    turn_id: &str,
+) -> Result<CheckpointPreview, String> {
+    let _guard = GIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let checkpoint = checkpoint_for(
+        &read_checkpoints(workspace_root, thread_id)?,
+        thread_id,
+        turn_id,
+    )?;
+    let repo = ShadowRepo::open(workspace_root, Path::new(&checkpoint.cwd))?;
+    let tree = repo.snapshot_tree()?;
+    let files = repo.changes_since(&checkpoint.commit, &tree)?;
+    Ok(CheckpointPreview { checkpoint, files })
+}
+
+/// Put the files back as they were before `turn_id`. The tree as it was just before is kept as
+/// `backup_commit` so `undo_restore` can put it back. Files that are ignored (`.gitignore`) were
+/// never captured and stay as they are. Checkpoints are kept until `forget`. The checkpoint's
+/// directory is checked against `policy` again, as the policy may have changed since.
+pub fn restore(
+    workspace_root: &Path,
+    thread_id: &str,
+    turn_id: &str,
+    policy: &PathPolicy,
+) -> Result<CheckpointRestore, String> {
+    let _guard = GIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let checkpoints = read_checkpoints(workspace_root, thread_id)?;
+    let checkpoint = checkpoint_for(&checkpoints, thread_id, turn_id)?;
+    let cwd = policy.resolve_dir("codex_checkpoint_restore", &checkpoint.cwd)?;
+    let repo = ShadowRepo::open(workspace_root, &cwd)?;
+
+    let tree = repo.snapshot_tree()?;
+    let backup_commit = repo.commit(
+        &tree,
+        Some(&checkpoint.commit),
+        &format!("before restore {thread_id}"),
+    )?;
+    repo.git(
+        &[
+            "update-ref",
+            &format!("refs/coco/restores/{thread_id}"),
+            &backup_commit,
+        ],
+        None,
+    )?;
+
+    let files = repo.changes_since(&checkpoint.commit, &tree)?;
+    repo.put_back(&checkpoint.commit, &files, "added")?;
+    Ok(CheckpointRestore {
+        checkpoint,
+        files,
+        backup_commit,
+    })
+}
+
+/// Undo a `restore` whose thread rollback then failed: the files go back to `backup_commit`.
+pub fn undo_restore(workspace_root: &Path, restored: &CheckpointRestore) -> Result<(), String> {
+    let _guard = GIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let repo = ShadowRepo::open(workspace_root, Path::new(&restored.checkpoint.cwd))?;
+    repo.put_back(&restored.backup_commit, &restored.files, "deleted")
+}
+
+/// Drop the checkpoints of `turn_id` and every later turn, once a rollback removed them.
+pub fn forget(workspace_root: &Path, thread_id: &str, turn_id: &str) -> Result<(), String> {
+    let _guard = GIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut checkpoints = read_checkpoints(workspace_root, thread_id)?;
+    if let Some(idx) = checkpoints.iter().position(|c| c.turn_id == turn_id) {
+        checkpoints.truncate(idx);
+        write_checkpoints(workspace_root, thread_id, &checkpoints)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start_turn(base: &Path, thread_id: &str, turn_id: &str, work: &Path) {
+        snapshot(base, thread_id, work, &allow(work)).unwrap();
+        attach(thread_id, turn_id).unwrap().unwrap();
+        // `turn/started` after the response: already attached.
+        assert!(attach(thread_id, turn_id).unwrap().is_none());
+    }
+
+    fn allow(work: &Path) -> PathPolicy {
+        PathPolicy::with_roots([work.to_path_buf()])
+    }
+
+    fn scratch(name: &str) -> (PathBuf, PathBuf) {
+        let base =
+            std::env::temp_dir().join(format!("coco-checkpoint-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let work = base.join("work");
+        std::fs::create_dir_all(work.join("src")).unwrap();
+        (base, work)
+    }
+
+    #[test]
+    fn restores_files_to_the_checkpoint_of_a_rolled_back_turn() {
+        let (base, work) = scratch("restore");
+        std::fs::write(work.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(work.join("src/lib.rs"), "v1\n").unwrap();
+        std::fs::write(work.join("old.txt"), "keep me\n").unwrap();
+
+        start_turn(&base, "thread-1", "turn-1", &work);
+        std::fs::write(work.join("src/lib.rs"), "v2\n").unwrap();
+        start_turn(&base, "thread-1", "turn-2", &work);
+        std::fs::write(work.join("src/lib.rs"), "v3\n").unwrap();
+        std::fs::write(work.join("src/new.rs"), "new\n").unwrap();
+        std::fs::remove_file(work.join("old.txt")).unwrap();
+        std::fs::create_dir_all(work.join("target")).unwrap();
+        std::fs::write(work.join("target/out"), "build\n").unwrap();
+        assert_eq!(list(&base, "thread-1").unwrap().len(), 2);
+
+        let pending = preview(&base, "thread-1", "turn-1").unwrap();
+        assert_eq!(pending.checkpoint.turn_id, "turn-1");
+        let mut files = pending
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.status.as_str()))
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                ("old.txt", "deleted"),
+                ("src/lib.rs", "modified"),
+                ("src/new.rs", "added"),
+            ]
+        );
+
+        restore(&base, "thread-1", "turn-1", &allow(&work)).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(work.join("src/lib.rs")).unwrap(),
+            "v1\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(work.join("old.txt")).unwrap(),
+            "keep me\n"
+        );
+        assert!(!work.join("src/new.rs").exists());
+        assert!(work.join("target/out").exists());
+
+        forget(&base, "thread-1", "turn-1").unwrap();
+        assert!(list(&base, "thread-1").unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn refuses_turns_without_a_checkpoint_or_outside_the_policy_and_undoes_a_restore() {
+        let (base, work) = scratch("undo");
+        std::fs::write(work.join("src/lib.rs"), "v1\n").unwrap();
+        std::fs::write(work.join("old.txt"), "old\n").unwrap();
+
+        // A turn whose start failed leaves no checkpoint behind.
+        snapshot(&base, "thread-2", &work, &allow(&work)).unwrap();
+        discard("thread-2");
+        assert!(attach("thread-2", "turn-0").unwrap().is_none());
+
+        start_turn(&base, "thread-2", "turn-2", &work);
+        std::fs::write(work.join("src/lib.rs"), "v2\n").unwrap();
+        std::fs::write(work.join("src/new.rs"), "new\n").unwrap();
+        std::fs::remove_file(work.join("old.txt")).unwrap();
+
+        // Never fall back to another turn's checkpoint.
+        assert!(preview(&base, "thread-2", "turn-1").is_err());
+        assert!(restore(&base, "thread-2", "turn-1", &allow(&work)).is_err());
+        // Nor restore into a directory the path policy no longer allows.
+        let elsewhere = base.join("elsewhere");
+        std::fs::create_dir_all(&elsewhere).unwrap();
+        assert!(restore(&base, "thread-2", "turn-2", &allow(&elsewhere)).is_err());
+        assert!(snapshot(&base, "thread-3", &work, &allow(&elsewhere)).is_err());
+        assert_eq!(
+            std::fs::read_to_string(work.join("src/lib.rs")).unwrap(),
+            "v2\n"
+        );
+
+        let restored = restore(&base, "thread-2", "turn-2", &allow(&work)).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(work.join("src/lib.rs")).unwrap(),
+            "v1\n"
+        );
+        undo_restore(&base, &restored).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(work.join("src/lib.rs")).unwrap(),
+            "v2\n"
+        );
+        assert!(work.join("src/new.rs").exists());
+        assert!(!work.join("old.txt").exists());
+        assert_eq!(list(&base, "thread-2").unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+}
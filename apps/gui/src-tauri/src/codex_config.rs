@@ -0,0 +1,1043 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use toml_edit::{DocumentMut, InlineTable, Item, Table, TableLike};
+
+/// Serializes read-check-write cycles on `config.toml` and its backups.
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Backups kept per config file; older ones are pruned after each write.
+const MAX_BACKUPS: usize = 20;
+
+const BUILTIN_MODEL_PROVIDERS: &[&str] = &["openai", "oss", "ollama", "lmstudio"];
+const REASONING_EFFORTS: &[&str] = &["none", "minimal", "low", "medium", "high", "xhigh"];
+const REASONING_SUMMARIES: &[&str] = &["auto", "concise", "detailed", "none"];
+const VERBOSITIES: &[&str] = &["low", "medium", "high"];
+const APPROVAL_POLICIES: &[&str] = &["untrusted", "on-failure", "on-request", "never"];
+const SANDBOX_MODES: &[&str] = &["read-only", "workspace-write", "danger-full-access"];
+const WIRE_APIS: &[&str] = &["chat", "responses"];
+const ENV_INHERIT: &[&str] = &["all", "core", "none"];
+
+// ============================================================================
+// Schema
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Str,
+    Bool,
+    /// Non-negative integer.
+    Count,
+    /// Non-negative integer or float.
+    Seconds,
+    StrList,
+    StrMap,
+    OneOf(&'static [&'static str]),
+    HttpUrl,
+}
+
+/// Keys shared by the top level and `[profiles.<name>]`.
+const CHAT_FIELDS: &[(&str, Kind)] = &[
+    ("model", Kind::Str),
+    ("model_provider", Kind::Str),
+    ("model_reasoning_effort", Kind::OneOf(REASONING_EFFORTS)),
+    ("model_reasoning_summary", Kind::OneOf(REASONING_SUMMARIES)),
+    ("model_verbosity", Kind::OneOf(VERBOSITIES)),
+    ("approval_policy", Kind::OneOf(APPROVAL_POLICIES)),
+    ("sandbox_mode", Kind::OneOf(SANDBOX_MODES)),
+];
+
+const SANDBOX_WORKSPACE_WRITE_FIELDS: &[(&str, Kind)] = &[
+    ("writable_roots", Kind::StrList),
+    ("network_access", Kind::Bool),
+    ("exclude_tmpdir_env_var", Kind::Bool),
+    ("exclude_slash_tmp", Kind::Bool),
+];
+
+const SHELL_ENVIRONMENT_POLICY_FIELDS: &[(&str, Kind)] = &[
+    ("inherit", Kind::OneOf(ENV_INHERIT)),
+    ("ignore_default_excludes", Kind::Bool),
+    ("exclude", Kind::StrList),
+    ("include_only", Kind::StrList),
+    ("set", Kind::StrMap),
+    ("experimental_use_profile", Kind::Bool),
+];
+
+const MODEL_PROVIDER_FIELDS: &[(&str, Kind)] = &[
+    ("name", Kind::Str),
+    ("base_url", Kind::HttpUrl),
+    ("env_key", Kind::Str),
+    ("env_key_instructions", Kind::Str),
+    ("wire_api", Kind::OneOf(WIRE_APIS)),
+    ("query_params", Kind::StrMap),
+    ("http_headers", Kind::StrMap),
+    ("env_http_headers", Kind::StrMap),
+    ("request_max_retries", Kind::Count),
+    ("stream_max_retries", Kind::Count),
+    ("stream_idle_timeout_ms", Kind::Count),
+    ("requires_openai_auth", Kind::Bool),
+];
+
+const MCP_SERVER_FIELDS: &[(&str, Kind)] = &[
+    ("command", Kind::Str),
+    ("args", Kind::StrList),
+    ("env", Kind::StrMap),
+    ("env_vars", Kind::StrList),
+    ("cwd", Kind::Str),
+    ("url", Kind::HttpUrl),
+    ("bearer_token_env_var", Kind::Str),
+    ("http_headers", Kind::StrMap),
+    ("env_http_headers", Kind::StrMap),
+    ("enabled", Kind::Bool),
+    ("startup_timeout_sec", Kind::Seconds),
+    ("startup_timeout_ms", Kind::Count),
+    ("tool_timeout_sec", Kind::Seconds),
+    ("enabled_tools", Kind::StrList),
+    ("disabled_tools", Kind::StrList),
+];
+
+/// A value that would make Codex reject (or misread) the config. Any issue blocks a write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    /// Dotted key path (`profiles.fast.model`); empty for syntax errors.
+    pub path: String,
+    pub message: String,
+}
+
+fn key_path(segments: &[&str]) -> String {
+    segments
+        .iter()
+        .map(|s| {
+            let bare = !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if bare {
+                s.to_string()
+            } else {
+                format!("{s:?}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn check_kind(item: &Item, kind: Kind) -> Result<(), String> {
+    let is_str_list = |item: &Item| {
+        item.as_array()
+            .is_some_and(|arr| arr.iter().all(|v| v.is_str()))
+    };
+    match kind {
+        Kind::Str => item.as_str().map(|_| ()).ok_or("expected a string".into()),
+        Kind::Bool => item
+            .as_bool()
+            .map(|_| ())
+            .ok_or("expected true or false".into()),
+        Kind::Count => match item.as_integer() {
+            Some(n) if n >= 0 => Ok(()),
+            _ => Err("expected a non-negative integer".into()),
+        },
+        Kind::Seconds => match item.as_integer().map(|n| n as f64).or(item.as_float()) {
+            Some(n) if n >= 0.0 && n.is_finite() => Ok(()),
+            _ => Err("expected a non-negative number".into()),
+        },
+        Kind::StrList if is_str_list(item) => Ok(()),
+        Kind::StrList => Err("expected an array of strings".into()),
+        Kind::StrMap => match item.as_table_like() {
+            Some(table) if table.iter().all(|(_, v)| v.is_str()) => Ok(()),
+            _ => Err("expected a table of string values".into()),
+        },
+        Kind::OneOf(allowed) => match item.as_str() {
+            Some(v) if allowed.contains(&v) => Ok(()),
+            _ => Err(format!("expected one of: {}", allowed.join(", "))),
+        },
+        Kind::HttpUrl => match item.as_str() {
+            Some(v) if v.starts_with("http://") || v.starts_with("https://") => Ok(()),
+            _ => Err("expected an http(s) URL".into()),
+        },
+    }
+}
+
+fn check_fields(
+    table: &dyn TableLike,
+    fields: &[(&str, Kind)],
+    prefix: &[&str],
+    issues: &mut Vec<ConfigIssue>,
+) {
+    for (key, kind) in fields {
+        let Some(item) = table.get(key) else {
+            continue;
+        };
+        if let Err(message) = check_kind(item, *kind) {
+            let mut segments = prefix.to_vec();
+            segments.push(key);
+            issues.push(ConfigIssue {
+                path: key_path(&segments),
+                message,
+            });
+        }
+    }
+}
+
+/// A named section (`profiles`, `model_providers`, `mcp_servers`), as `(name, entry)` pairs in
+/// file order. Non-table entries are reported and skipped.
+fn named_tables<'a>(
+    root: &'a Table,
+    section: &'a str,
+    issues: &mut Vec<ConfigIssue>,
+) -> Vec<(&'a str, &'a dyn TableLike)> {
+    let Some(item) = root.get(section) else {
+        return Vec::new();
+    };
+    let Some(table) = item.as_table_like() else {
+        issues.push(ConfigIssue {
+            path: key_path(&[section]),
+            message: "expected a table".into(),
+        });
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for (name, entry) in table.iter() {
+        match entry.as_table_like() {
+            Some(entry) => out.push((name, entry)),
+            None => issues.push(ConfigIssue {
+                path: key_path(&[section, name]),
+                message: "expected a table".into(),
+            }),
+        }
+    }
+    out
+}
+
+/// Schema checks plus the cross-references Codex resolves at startup (`profile`,
+/// `model_provider`). Keys this editor does not know about are left alone.
+pub fn validate(doc: &DocumentMut) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let root = doc.as_table();
+
+    check_fields(root, CHAT_FIELDS, &[], &mut issues);
+    check_fields(root, &[("profile", Kind::Str)], &[], &mut issues);
+    for (section, fields) in [
+        ("sandbox_workspace_write", SANDBOX_WORKSPACE_WRITE_FIELDS),
+        ("shell_environment_policy", SHELL_ENVIRONMENT_POLICY_FIELDS),
+    ] {
+        match root.get(section).map(|item| item.as_table_like()) {
+            None => {}
+            Some(Some(table)) => check_fields(table, fields, &[section], &mut issues),
+            Some(None) => issues.push(ConfigIssue {
+                path: key_path(&[section]),
+                message: "expected a table".into(),
+            }),
+        }
+    }
+
+    let profiles = named_tables(root, "profiles", &mut issues);
+    let providers = named_tables(root, "model_providers", &mut issues);
+    let servers = named_tables(root, "mcp_servers", &mut issues);
+
+    for (name, profile) in &profiles {
+        check_fields(*profile, CHAT_FIELDS, &["profiles", name], &mut issues);
+    }
+    for (id, provider) in &providers {
+        check_fields(
+            *provider,
+            MODEL_PROVIDER_FIELDS,
+            &["model_providers", id],
+            &mut issues,
+        );
+        if provider.get("name").is_none() {
+            issues.push(ConfigIssue {
+                path: key_path(&["model_providers", id, "name"]),
+                message: "missing required key".into(),
+            });
+        }
+    }
+    for (name, server) in &servers {
+        check_fields(
+            *server,
+            MCP_SERVER_FIELDS,
+            &["mcp_servers", name],
+            &mut issues,
+        );
+        let transports = ["command", "url"]
+            .iter()
+            .filter(|key| server.get(key).is_some())
+            .count();
+        if transports != 1 {
+            issues.push(ConfigIssue {
+                path: key_path(&["mcp_servers", name]),
+                message: "set exactly one of `command` (stdio) or `url` (streamable HTTP)".into(),
+            });
+        }
+    }
+
+    if let Some(active) = root.get("profile").and_then(Item::as_str) {
+        if !profiles.iter().any(|(name, _)| *name == active) {
+            issues.push(ConfigIssue {
+                path: "profile".into(),
+                message: format!("no [profiles.{active}] table"),
+            });
+        }
+    }
+    let known_provider =
+        |id: &str| BUILTIN_MODEL_PROVIDERS.contains(&id) || providers.iter().any(|(p, _)| *p == id);
+    let mut provider_refs = vec![(Vec::new(), root as &dyn TableLike)];
+    provider_refs.extend(
+        profiles
+            .iter()
+            .map(|(name, t)| (vec!["profiles", *name], *t)),
+    );
+    for (mut prefix, table) in provider_refs {
+        if let Some(id) = table.get("model_provider").and_then(Item::as_str) {
+            if !known_provider(id) {
+                prefix.push("model_provider");
+                issues.push(ConfigIssue {
+                    path: key_path(&prefix),
+                    message: format!("unknown model provider `{id}` (not built in and no [model_providers.{id}])"),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+fn parse(content: &str) -> Result<DocumentMut, ConfigIssue> {
+    content.parse::<DocumentMut>().map_err(|e| {
+        let line = e.span().map(|span| {
+            content[..span.start.min(content.len())]
+                .matches('\n')
+                .count()
+                + 1
+        });
+        ConfigIssue {
+            path: String::new(),
+            message: match line {
+                Some(line) => format!("TOML syntax error at line {line}: {}", e.message()),
+                None => format!("TOML syntax error: {}", e.message()),
+            },
+        }
+    })
+}
+
+// ============================================================================
+// Typed model
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettings {
+    pub model: Option<String>,
+    pub model_provider: Option<String>,
+    pub model_reasoning_effort: Option<String>,
+    pub model_reasoning_summary: Option<String>,
+    pub model_verbosity: Option<String>,
+    pub approval_policy: Option<String>,
+    pub sandbox_mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: ChatSettings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelProviderConfig {
+    /// Key under `[model_providers]`, referenced by `model_provider`.
+    pub id: String,
+    pub name: Option<String>,
+    pub base_url: Option<String>,
+    pub env_key: Option<String>,
+    pub wire_api: Option<String>,
+    pub query_params: BTreeMap<String, String>,
+    pub http_headers: BTreeMap<String, String>,
+    pub request_max_retries: Option<u64>,
+    pub stream_max_retries: Option<u64>,
+    pub stream_idle_timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerConfig {
+    pub name: String,
+    pub command: Option<String>,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub cwd: Option<String>,
+    pub url: Option<String>,
+    pub bearer_token_env_var: Option<String>,
+    pub enabled: Option<bool>,
+    pub startup_timeout_sec: Option<f64>,
+    pub tool_timeout_sec: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxWorkspaceWrite {
+    pub writable_roots: Vec<String>,
+    pub network_access: Option<bool>,
+    pub exclude_tmpdir_env_var: Option<bool>,
+    pub exclude_slash_tmp: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellEnvironmentPolicy {
+    pub inherit: Option<String>,
+    pub ignore_default_excludes: Option<bool>,
+    pub exclude: Vec<String>,
+    pub include_only: Vec<String>,
+    pub set: BTreeMap<String, String>,
+}
+
+/// The parts of `config.toml` the GUI edits. Values of the wrong type read as unset; `validate`
+/// reports them.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexConfigModel {
+    pub profile: Option<String>,
+    #[serde(flatten)]
+    pub defaults: ChatSettings,
+    pub profiles: Vec<ProfileConfig>,
+    pub model_providers: Vec<ModelProviderConfig>,
+    pub mcp_servers: Vec<McpServerConfig>,
+    pub sandbox_workspace_write: SandboxWorkspaceWrite,
+    pub shell_environment_policy: ShellEnvironmentPolicy,
+}
+
+fn str_at(table: &dyn TableLike, key: &str) -> Option<String> {
+    table.get(key).and_then(Item::as_str).map(str::to_string)
+}
+
+fn bool_at(table: &dyn TableLike, key: &str) -> Option<bool> {
+    table.get(key).and_then(Item::as_bool)
+}
+
+fn count_at(table: &dyn TableLike, key: &str) -> Option<u64> {
+    table
+        .get(key)
+        .and_then(Item::as_integer)
+        .and_then(|n| u64::try_from(n).ok())
+}
+
+fn seconds_at(table: &dyn TableLike, key: &str) -> Option<f64> {
+    let item = table.get(key)?;
+    item.as_float().or(item.as_integer().map(|n| n as f64))
+}
+
+fn list_at(table: &dyn TableLike, key: &str) -> Vec<String> {
+    table
+        .get(key)
+        .and_then(Item::as_array)
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn map_at(table: &dyn TableLike, key: &str) -> BTreeMap<String, String> {
+    table
+        .get(key)
+        .and_then(Item::as_table_like)
+        .map(|t| {
+            t.iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn chat_settings(table: &dyn TableLike) -> ChatSettings {
+    ChatSettings {
+        model: str_at(table, "model"),
+        model_provider: str_at(table, "model_provider"),
+        model_reasoning_effort: str_at(table, "model_reasoning_effort"),
+        model_reasoning_summary: str_at(table, "model_reasoning_summary"),
+        model_verbosity: str_at(table, "model_verbosity"),
+        approval_policy: str_at(table, "approval_policy"),
+        sandbox_mode: str_at(table, "sandbox_mode"),
+    }
+}
+
+pub fn model(doc: &DocumentMut) -> CodexConfigModel {
+    let root = doc.as_table();
+    let mut ignored = Vec::new();
+    let section = |key: &str| root.get(key).and_then(Item::as_table_like);
+
+    CodexConfigModel {
+        profile: str_at(root, "profile"),
+        defaults: chat_settings(root),
+        profiles: named_tables(root, "profiles", &mut ignored)
+            .into_iter()
+            .map(|(name, t)| ProfileConfig {
+                name: name.to_string(),
+                settings: chat_settings(t),
+            })
+            .collect(),
+        model_providers: named_tables(root, "model_providers", &mut ignored)
+            .into_iter()
+            .map(|(id, t)| ModelProviderConfig {
+                id: id.to_string(),
+                name: str_at(t, "name"),
+                base_url: str_at(t, "base_url"),
+                env_key: str_at(t, "env_key"),
+                wire_api: str_at(t, "wire_api"),
+                query_params: map_at(t, "query_params"),
+                http_headers: map_at(t, "http_headers"),
+                request_max_retries: count_at(t, "request_max_retries"),
+                stream_max_retries: count_at(t, "stream_max_retries"),
+                stream_idle_timeout_ms: count_at(t, "stream_idle_timeout_ms"),
+            })
+            .collect(),
+        mcp_servers: named_tables(root, "mcp_servers", &mut ignored)
+            .into_iter()
+            .map(|(name, t)| McpServerConfig {
+                name: name.to_string(),
+                command: str_at(t, "command"),
+                args: list_at(t, "args"),
+                env: map_at(t, "env"),
+                cwd: str_at(t, "cwd"),
+                url: str_at(t, "url"),
+                bearer_token_env_var: str_at(t, "bearer_token_env_var"),
+                enabled: bool_at(t, "enabled"),
+                startup_timeout_sec: seconds_at(t, "startup_timeout_sec"),
+                tool_timeout_sec: seconds_at(t, "tool_timeout_sec"),
+            })
+            .collect(),
+        sandbox_workspace_write: section("sandbox_workspace_write")
+            .map(|t| SandboxWorkspaceWrite {
+                writable_roots: list_at(t, "writable_roots"),
+                network_access: bool_at(t, "network_access"),
+                exclude_tmpdir_env_var: bool_at(t, "exclude_tmpdir_env_var"),
+                exclude_slash_tmp: bool_at(t, "exclude_slash_tmp"),
+            })
+            .unwrap_or_default(),
+        shell_environment_policy: section("shell_environment_policy")
+            .map(|t| ShellEnvironmentPolicy {
+                inherit: str_at(t, "inherit"),
+                ignore_default_excludes: bool_at(t, "ignore_default_excludes"),
+                exclude: list_at(t, "exclude"),
+                include_only: list_at(t, "include_only"),
+                set: map_at(t, "set"),
+            })
+            .unwrap_or_default(),
+    }
+}
+
+// ============================================================================
+// Edits
+// ============================================================================
+
+/// Sets (or, with `null`, removes) one key. Objects merge key by key into an existing table, so
+/// untouched keys keep their comments and layout.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigEdit {
+    /// Unescaped segments, e.g. `["profiles", "gpt-5.2", "model"]`.
+    pub key_path: Vec<String>,
+    pub value: serde_json::Value,
+}
+
+/// What to write: edits against the current file, or a whole new file.
+pub enum ConfigChange {
+    Edits(Vec<ConfigEdit>),
+    Content(String),
+}
+
+impl ConfigChange {
+    pub fn from_parts(
+        edits: Option<Vec<ConfigEdit>>,
+        content: Option<String>,
+    ) -> Result<Self, String> {
+        match (edits, content) {
+            (Some(edits), None) => Ok(Self::Edits(edits)),
+            (None, Some(content)) => Ok(Self::Content(content)),
+            _ => Err("pass exactly one of edits or content".to_string()),
+        }
+    }
+}
+
+fn json_to_value(value: &serde_json::Value) -> Result<toml_edit::Value, String> {
+    use serde_json::Value as Json;
+    Ok(match value {
+        Json::Null => return Err("null is only allowed as a whole value (to remove a key)".into()),
+        Json::Bool(b) => (*b).into(),
+        Json::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => i.into(),
+            (None, Some(f)) if n.is_f64() => f.into(),
+            _ => return Err(format!("number out of range: {n}")),
+        },
+        Json::String(s) => s.as_str().into(),
+        Json::Array(items) => {
+            let mut arr = toml_edit::Array::new();
+            for item in items {
+                arr.push(json_to_value(item)?);
+            }
+            arr.into()
+        }
+        Json::Object(map) => {
+            let mut table = InlineTable::new();
+            for (k, v) in map {
+                if !v.is_null() {
+                    table.insert(k, json_to_value(v)?);
+                }
+            }
+            table.into()
+        }
+    })
+}
+
+fn set_key(
+    table: &mut dyn TableLike,
+    inline: bool,
+    key: &str,
+    value: &serde_json::Value,
+    nested: bool,
+) -> Result<(), String> {
+    if value.is_null() {
+        table.remove(key);
+        return Ok(());
+    }
+    if let serde_json::Value::Object(map) = value {
+        if table
+            .get(key)
+            .map_or(true, |item| item.as_table_like().is_none())
+        {
+            let container = if inline || nested {
+                Item::Value(InlineTable::new().into())
+            } else {
+                Item::Table(Table::new())
+            };
+            table.insert(key, container);
+        }
+        let item = table
+            .get_mut(key)
+            .ok_or_else(|| format!("failed to create table `{key}`"))?;
+        let child_inline = !item.is_table();
+        let child = item
+            .as_table_like_mut()
+            .ok_or_else(|| format!("`{key}` is not a table"))?;
+        for (k, v) in map {
+            set_key(child, child_inline, k, v, true)?;
+        }
+        return Ok(());
+    }
+
+    let mut next = json_to_value(value)?;
+    match table.get_mut(key) {
+        // Assign in place: `insert` would also replace the key, dropping the comments above it.
+        Some(slot) => {
+            if let Item::Value(old) = slot {
+                *next.decor_mut() = old.decor().clone();
+            }
+            *slot = Item::Value(next);
+        }
+        None => {
+            table.insert(key, Item::Value(next));
+        }
+    }
+    Ok(())
+}
+
+fn apply_edit(doc: &mut DocumentMut, edit: &ConfigEdit) -> Result<(), String> {
+    let Some((last, parents)) = edit.key_path.split_last() else {
+        return Err("empty keyPath".to_string());
+    };
+    if edit.key_path.iter().any(|s| s.is_empty()) {
+        return Err(format!("empty segment in keyPath {:?}", edit.key_path));
+    }
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    let mut inline = false;
+    for (depth, segment) in parents.iter().enumerate() {
+        if table.get(segment).is_none() {
+            if edit.value.is_null() {
+                return Ok(());
+            }
+            let container = if inline {
+                Item::Value(InlineTable::new().into())
+            } else {
+                let mut t = Table::new();
+                t.set_implicit(true);
+                Item::Table(t)
+            };
+            table.insert(segment, container);
+        }
+        let item = table.get_mut(segment).expect("inserted above");
+        inline = !item.is_table();
+        table = item.as_table_like_mut().ok_or_else(|| {
+            let at: Vec<&str> = parents[..=depth].iter().map(String::as_str).collect();
+            format!("`{}` is not a table", key_path(&at))
+        })?;
+    }
+    set_key(table, inline, last, &edit.value, false)
+}
+
+// ============================================================================
+// Store: read / plan / write with backups
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDocument {
+    pub path: String,
+    pub exists: bool,
+    pub content: String,
+    /// Pass back to `apply` to detect edits made on disk in between.
+    pub revision: String,
+    /// `None` when the file does not parse.
+    pub config: Option<CodexConfigModel>,
+    pub issues: Vec<ConfigIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPlan {
+    /// Revision of the file the plan was made against.
+    pub revision: String,
+    pub content: String,
+    pub changed: bool,
+    /// Unified diff from the current file to `content`.
+    pub diff: String,
+    pub config: Option<CodexConfigModel>,
+    pub issues: Vec<ConfigIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackup {
+    /// File name inside the backup directory.
+    pub id: String,
+    pub created_at_ms: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWriteResult {
+    pub revision: String,
+    pub changed: bool,
+    pub diff: String,
+    /// Copy of the file as it was before this write.
+    pub backup: Option<ConfigBackup>,
+    /// Issues left in the written file (restores skip validation).
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// FNV-1a; only compared for equality, never persisted.
+fn revision_of(content: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in content.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn is_backup_id(id: &str) -> bool {
+    id.strip_prefix("config-")
+        .and_then(|rest| rest.strip_suffix(".toml"))
+        .is_some_and(|stem| {
+            !stem.is_empty() && stem.chars().all(|c| c.is_ascii_digit() || c == '-')
+        })
+}
+
+fn backup_created_at_ms(id: &str) -> u64 {
+    id.trim_start_matches("config-")
+        .split(['-', '.'])
+        .next()
+        .and_then(|ms| ms.parse().ok())
+        .unwrap_or(0)
+}
+
+pub struct CodexConfigStore {
+    path: PathBuf,
+    backup_dir: PathBuf,
+}
+
+impl CodexConfigStore {
+    pub fn new(path: PathBuf, backup_dir: PathBuf) -> Self {
+        Self { path, backup_dir }
+    }
+
+    fn read(&self) -> Result<Option<String>, String> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => Ok(Some(content)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("failed to read {}: {err}", self.path.display())),
+        }
+    }
+
+    pub fn document(&self) -> Result<ConfigDocument, String> {
+        let current = self.read()?;
+        let exists = current.is_some();
+        let content = current.unwrap_or_default();
+        let (config, issues) = match parse(&content) {
+            Ok(doc) => (Some(model(&doc)), validate(&doc)),
+            Err(issue) => (None, vec![issue]),
+        };
+        Ok(ConfigDocument {
+            path: self.path.to_string_lossy().to_string(),
+            exists,
+            revision: revision_of(&content),
+            content,
+            config,
+            issues,
+        })
+    }
+
+    fn plan_against(&self, current: &str, change: &ConfigChange) -> Result<ConfigPlan, String> {
+        let (content, doc) = match change {
+            ConfigChange::Content(content) => (content.clone(), parse(content)),
+            ConfigChange::Edits(edits) => {
+                let mut doc = parse(current)
+                    .map_err(|issue| format!("{} (fix the file as text first)", issue.message))?;
+                for edit in edits {
+                    apply_edit(&mut doc, edit)?;
+                }
+                (doc.to_string(), Ok(doc))
+            }
+        };
+        let (config, issues) = match doc {
+            Ok(doc) => (Some(model(&doc)), validate(&doc)),
+            Err(issue) => (None, vec![issue]),
+        };
+        let changed = content != current;
+        let diff = if changed {
+            diffy::create_patch(current, &content).to_string()
+        } else {
+            String::new()
+        };
+        Ok(ConfigPlan {
+            revision: revision_of(current),
+            content,
+            changed,
+            diff,
+            config,
+            issues,
+        })
+    }
+
+    pub fn plan(&self, change: &ConfigChange) -> Result<ConfigPlan, String> {
+        let current = self.read()?.unwrap_or_default();
+        self.plan_against(&current, change)
+    }
+
+    /// Validates and writes `change`, backing up the previous file first. Fails without writing
+    /// on any issue, or when `expected_revision` no longer matches the file.
+    pub fn apply(
+        &self,
+        change: &ConfigChange,
+        expected_revision: Option<&str>,
+    ) -> Result<ConfigWriteResult, String> {
+        self.write(change, expected_revision, true)
+    }
+
+    fn write(
+        &self,
+        change: &ConfigChange,
+        expected_revision: Option<&str>,
+        validated: bool,
+    ) -> Result<ConfigWriteResult, String> {
+        let _guard = CONFIG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let current = self.read()?;
+        let current_text = current.as_deref().unwrap_or("");
+        if let Some(expected) = expected_revision {
+            if expected != revision_of(current_text) {
+                return Err(
+                    "config.toml changed on disk since it was read; reload and try again"
+                        .to_string(),
+                );
+            }
+        }
+        let plan = self.plan_against(current_text, change)?;
+        if validated && !plan.issues.is_empty() {
+            let listed: Vec<String> = plan
+                .issues
+                .iter()
+                .map(|i| match i.path.as_str() {
+                    "" => i.message.clone(),
+                    path => format!("{path}: {}", i.message),
+                })
+                .collect();
+            return Err(format!("invalid config.toml: {}", listed.join("; ")));
+        }
+        if !plan.changed {
+            return Ok(ConfigWriteResult {
+                revision: plan.revision,
+                changed: false,
+                diff: String::new(),
+                backup: None,
+                issues: plan.issues,
+            });
+        }
+
+        let backup = match &current {
+            Some(text) => Some(self.backup(text)?),
+            None => None,
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let tmp = self.path.with_extension("toml.coco-tmp");
+        std::fs::write(&tmp, &plan.content).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| e.to_string())?;
+        log::info!(
+            "codex config: wrote {} (backup: {:?})",
+            self.path.display(),
+            backup.as_ref().map(|b| &b.id)
+        );
+
+        Ok(ConfigWriteResult {
+            revision: revision_of(&plan.content),
+            changed: true,
+            diff: plan.diff,
+            backup,
+            issues: plan.issues,
+        })
+    }
+
+    fn backup(&self, content: &str) -> Result<ConfigBackup, String> {
+        std::fs::create_dir_all(&self.backup_dir).map_err(|e| e.to_string())?;
+        let created_at_ms = now_ms();
+        let mut id = format!("config-{created_at_ms}.toml");
+        let mut n = 1;
+        while self.backup_dir.join(&id).exists() {
+            id = format!("config-{created_at_ms}-{n}.toml");
+            n += 1;
+        }
+        std::fs::write(self.backup_dir.join(&id), content).map_err(|e| e.to_string())?;
+
+        for stale in self.backups()?.into_iter().skip(MAX_BACKUPS) {
+            let _ = std::fs::remove_file(self.backup_dir.join(&stale.id));
+        }
+        Ok(ConfigBackup {
+            id,
+            created_at_ms,
+            size: content.len() as u64,
+        })
+    }
+
+    /// Newest first.
+    pub fn backups(&self) -> Result<Vec<ConfigBackup>, String> {
+        let entries = match std::fs::read_dir(&self.backup_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.to_string()),
+        };
+        let mut out: Vec<ConfigBackup> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let id = entry.file_name().to_string_lossy().to_string();
+                if !is_backup_id(&id) {
+                    return None;
+                }
+                Some(ConfigBackup {
+                    created_at_ms: backup_created_at_ms(&id),
+                    size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    id,
+                })
+            })
+            .collect();
+        out.sort_by(|a, b| {
+            (b.created_at_ms, b.id.len(), &b.id).cmp(&(a.created_at_ms, a.id.len(), &a.id))
+        });
+        Ok(out)
+    }
+
+    /// Puts a backup back verbatim. The file being replaced is backed up in turn, so a restore
+    /// can itself be undone.
+    pub fn restore(
+        &self,
+        id: &str,
+        expected_revision: Option<&str>,
+    ) -> Result<ConfigWriteResult, String> {
+        if !is_backup_id(id) {
+            return Err(format!("invalid backup id: {id}"));
+        }
+        let content = std::fs::read_to_string(self.backup_dir.join(id))
+            .map_err(|e| format!("failed to read backup {id}: {e}"))?;
+        self.write(&ConfigChange::Content(content), expected_revision, false)
+    }
+}
+
+pub fn backup_dir_for(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("codex_config_backups")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn edit(path: &[&str], value: serde_json::Value) -> ConfigEdit {
+        ConfigEdit {
+            key_path: path.iter().map(|s| s.to_string()).collect(),
+            value,
+        }
+    }
+
+    #[test]
+    fn edits_preserve_comments_and_validation_blocks_bad_values() {
+        let dir = std::env::temp_dir().join(format!("coco-config-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let original = "# my settings\nmodel = \"gpt-5.2\" # pinned\n\n[profiles.fast]\nmodel_reasoning_effort = \"low\"\n";
+        std::fs::write(&path, original).unwrap();
+        let store = CodexConfigStore::new(path.clone(), dir.join("backups"));
+
+        let doc = store.document().unwrap();
+        assert!(doc.issues.is_empty());
+        assert_eq!(doc.config.as_ref().unwrap().profiles[0].name, "fast");
+
+        let change = ConfigChange::Edits(vec![
+            edit(&["model"], json!("gpt-5.2-codex")),
+            edit(
+                &["mcp_servers", "docs"],
+                json!({"command": "npx", "args": ["-y", "docs-mcp"], "env": {"TOKEN": "x"}}),
+            ),
+            edit(&["profiles", "fast", "approval_policy"], json!("never")),
+        ]);
+        let result = store.apply(&change, Some(&doc.revision)).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# my settings\nmodel = \"gpt-5.2-codex\" # pinned\n"));
+        assert!(written.contains("[mcp_servers.docs]\n"));
+        assert!(written.contains("command = \"npx\""));
+        assert!(written.contains("env = { TOKEN = \"x\" }"));
+        assert!(result.diff.contains("+model = \"gpt-5.2-codex\" # pinned"));
+        let backup = result.backup.unwrap();
+
+        // Stale revision, then a value Codex would reject: both refused, file untouched.
+        assert!(store.apply(&change, Some(&doc.revision)).is_err());
+        let bad = ConfigChange::Edits(vec![edit(&["profile"], json!("missing"))]);
+        let err = store.apply(&bad, None).unwrap_err();
+        assert!(
+            err.contains("profile: no [profiles.missing] table"),
+            "{err}"
+        );
+        let bad = ConfigChange::Content("sandbox_mode = \"yolo\"\n".into());
+        assert_eq!(store.plan(&bad).unwrap().issues[0].path, "sandbox_mode");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+
+        store.restore(&backup.id, None).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+        assert_eq!(store.backups().unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
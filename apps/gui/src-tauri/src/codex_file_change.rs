@@ -0,0 +1,706 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::codex_patch_diff;
+
+const MAX_SNAPSHOT_BYTES: u64 = 1_000_000;
+
+const STATUS_IN_PROGRESS: &str = "inProgress";
+const STATUS_COMPLETED: &str = "completed";
+
+/// `<workspace>/.coco/codex/file_changes/<item_id>.json`: pre/post-images of each `fileChange`
+/// item, so the GUI can revert (or re-apply) it later, as a whole or per hunk.
+pub fn file_change_dir(workspace_root: &Path) -> PathBuf {
+    workspace_root
+        .join(".coco")
+        .join("codex")
+        .join("file_changes")
+}
+
+fn record_path(workspace_root: &Path, item_id: &str) -> Option<PathBuf> {
+    let valid = !item_id.is_empty()
+        && item_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    valid.then(|| file_change_dir(workspace_root).join(format!("{item_id}.json")))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeRecord {
+    pub item_id: String,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    #[serde(default)]
+    pub turn_id: Option<String>,
+    /// `inProgress` between `item/started` and `item/completed`; only completed records can be
+    /// reverted.
+    pub status: String,
+    pub files: Vec<FileSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSnapshot {
+    /// Absolute path before the change.
+    pub path: String,
+    /// Absolute path after the change, when the change moved the file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_path: Option<String>,
+    pub kind: String,
+    /// The change's own diff; recovers the pre-image when the snapshot raced the apply.
+    #[serde(default)]
+    pub diff: String,
+    /// None: the file did not exist.
+    #[serde(default)]
+    pub before: Option<String>,
+    #[serde(default)]
+    pub after: Option<String>,
+    /// False when an image could not be captured (binary, too large); never rewritten.
+    pub tracked: bool,
+}
+
+impl FileSnapshot {
+    fn after_path(&self) -> &str {
+        self.move_path.as_deref().unwrap_or(&self.path)
+    }
+}
+
+// ============================================================================
+// Tracking (item/started → pre-image, item/completed → post-image)
+// ============================================================================
+
+/// Record pre-images on `item/started` and post-images on `item/completed` for `fileChange`
+/// items. Records go under `workspace_root` (the orchestrator's, where `read_record` looks);
+/// relative paths in the item are resolved against `cwd`, the app-server's working directory.
+/// Must run before the diff is enriched for display (it needs the raw diff).
+pub async fn track_notification(message: &Value, workspace_root: &Path, cwd: &Path) {
+    let method = message.get("method").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(params) = message.get("params") else {
+        return;
+    };
+    let Some(item) = params.get("item") else {
+        return;
+    };
+    if item.get("type").and_then(|v| v.as_str()) != Some("fileChange") {
+        return;
+    }
+    let Some(item_id) = item.get("id").and_then(|v| v.as_str()) else {
+        return;
+    };
+    let Some(path) = record_path(workspace_root, item_id) else {
+        return;
+    };
+
+    let result = match method {
+        "item/started" => on_started(&path, params, item, cwd).await,
+        "item/completed" => on_completed(&path, params, item, cwd).await,
+        _ => Ok(()),
+    };
+    if let Err(err) = result {
+        log::info!("[CodexFileChange] tracking {item_id} failed: {err}");
+    }
+}
+
+/// Best-effort pre-image: the patch may already be applied by the time `item/started` arrives,
+/// so `on_completed` only keeps it when the diff turns it into the post-image.
+async fn on_started(
+    record_path: &Path,
+    params: &Value,
+    item: &Value,
+    cwd: &Path,
+) -> Result<(), String> {
+    if tokio::fs::try_exists(record_path).await.unwrap_or(false) {
+        return Ok(());
+    }
+    let mut record = record_from_item(params, item, cwd);
+    for file in &mut record.files {
+        if file.kind != "add" {
+            match read_snapshot(Path::new(&file.path)).await {
+                Snapshot::Text(text) => file.before = Some(text),
+                Snapshot::Missing => {}
+                Snapshot::Unreadable => file.tracked = false,
+            }
+        }
+    }
+    write_record(record_path, &record).await
+}
+
+async fn on_completed(
+    record_path: &Path,
+    params: &Value,
+    item: &Value,
+    cwd: &Path,
+) -> Result<(), String> {
+    if item.get("status").and_then(|v| v.as_str()) != Some("completed") {
+        // Declined or failed: nothing was written.
+        let _ = tokio::fs::remove_file(record_path).await;
+        return Ok(());
+    }
+    let mut record = match tokio::fs::read_to_string(record_path).await {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| e.to_string())?,
+        Err(_) => record_from_item(params, item, cwd),
+    };
+
+    for file in &mut record.files {
+        if !file.tracked {
+            continue;
+        }
+        file.after = match read_snapshot(Path::new(file.after_path())).await {
+            Snapshot::Text(text) => Some(text),
+            Snapshot::Missing => None,
+            Snapshot::Unreadable => {
+                file.tracked = false;
+                continue;
+            }
+        };
+        if file.kind == "add" {
+            file.before = None;
+        }
+        if file.kind == "update" && !is_pre_image(file) {
+            file.before = file
+                .after
+                .as_deref()
+                .and_then(|after| codex_patch_diff::reverse_apply(&file.diff, after));
+            file.tracked = file.before.is_some();
+        }
+        if file.kind == "delete" && file.before.is_none() {
+            file.before = codex_patch_diff::deleted_content(&file.diff);
+            file.tracked = file.before.is_some();
+        }
+    }
+    record.status = STATUS_COMPLETED.to_string();
+    write_record(record_path, &record).await
+}
+
+/// Whether the snapshot taken on `item/started` is the file before the change: applying the
+/// change's diff to it gives the post-image. A snapshot that raced the apply fails this.
+fn is_pre_image(file: &FileSnapshot) -> bool {
+    let (Some(before), Some(after)) = (file.before.as_deref(), file.after.as_deref()) else {
+        return false;
+    };
+    before != after && codex_patch_diff::apply(&file.diff, before).as_deref() == Some(after)
+}
+
+fn record_from_item(params: &Value, item: &Value, cwd: &Path) -> FileChangeRecord {
+    let changes = item
+        .get("changes")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let files = changes
+        .iter()
+        .filter_map(|change| {
+            let path = change.get("path").and_then(|v| v.as_str())?;
+            let kind = change.get("kind").cloned().unwrap_or(Value::Null);
+            let move_path = codex_patch_diff::move_path(&kind)
+                .map(|p| absolute(&p, cwd).to_string_lossy().to_string());
+            Some(FileSnapshot {
+                path: absolute(path, cwd).to_string_lossy().to_string(),
+                move_path,
+                kind: codex_patch_diff::patch_kind_name(&kind).to_string(),
+                diff: change
+                    .get("diff")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                before: None,
+                after: None,
+                tracked: true,
+            })
+        })
+        .collect();
+    FileChangeRecord {
+        item_id: item
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        thread_id: params
+            .get("threadId")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        turn_id: params
+            .get("turnId")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        status: STATUS_IN_PROGRESS.to_string(),
+        files,
+    }
+}
+
+fn absolute(path: &str, base: &Path) -> PathBuf {
+    let raw = Path::new(path);
+    if raw.is_absolute() {
+        raw.to_path_buf()
+    } else {
+        base.join(raw)
+    }
+}
+
+enum Snapshot {
+    Text(String),
+    Missing,
+    Unreadable,
+}
+
+async fn read_snapshot(path: &Path) -> Snapshot {
+    match tokio::fs::metadata(path).await {
+        Err(_) => return Snapshot::Missing,
+        Ok(meta) if !meta.is_file() || meta.len() > MAX_SNAPSHOT_BYTES => {
+            return Snapshot::Unreadable
+        }
+        Ok(_) => {}
+    }
+    match tokio::fs::read(path).await {
+        Ok(bytes) => String::from_utf8(bytes)
+            .map(Snapshot::Text)
+            .unwrap_or(Snapshot::Unreadable),
+        Err(_) => Snapshot::Unreadable,
+    }
+}
+
+async fn write_record(path: &Path, record: &FileChangeRecord) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    tokio::fs::write(path, json)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub fn read_record(workspace_root: &Path, item_id: &str) -> Result<FileChangeRecord, String> {
+    let path = record_path(workspace_root, item_id)
+        .ok_or_else(|| format!("invalid item_id: {item_id}"))?;
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|_| format!("no recorded file change for item {item_id}"))?;
+    let record: FileChangeRecord = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    if record.status != STATUS_COMPLETED {
+        return Err(format!("file change {item_id} has not completed"));
+    }
+    Ok(record)
+}
+
+// ============================================================================
+// View
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeHunk {
+    pub index: usize,
+    /// `@@ -a,b +c,d @@` followed by the hunk lines.
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeFileView {
+    pub path: String,
+    pub move_path: Option<String>,
+    pub kind: String,
+    /// `applied` (file matches the post-image), `reverted` (matches the pre-image), `modified`
+    /// (edited since) or `untracked`.
+    pub state: String,
+    pub hunks: Vec<FileChangeHunk>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeView {
+    pub item_id: String,
+    pub thread_id: Option<String>,
+    pub turn_id: Option<String>,
+    pub files: Vec<FileChangeFileView>,
+}
+
+pub fn view(record: &FileChangeRecord) -> FileChangeView {
+    let files = record
+        .files
+        .iter()
+        .map(|file| {
+            let (state, hunks) = if file.tracked {
+                let before = file.before.as_deref().unwrap_or("");
+                let after = file.after.as_deref().unwrap_or("");
+                let patch = diffy::create_patch(before, after);
+                let hunks = patch
+                    .hunks()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, hunk)| FileChangeHunk {
+                        index,
+                        diff: format_hunk(hunk),
+                    })
+                    .collect();
+                (file_state(file), hunks)
+            } else {
+                ("untracked".to_string(), Vec::new())
+            };
+            FileChangeFileView {
+                path: file.path.clone(),
+                move_path: file.move_path.clone(),
+                kind: file.kind.clone(),
+                state,
+                hunks,
+            }
+        })
+        .collect();
+    FileChangeView {
+        item_id: record.item_id.clone(),
+        thread_id: record.thread_id.clone(),
+        turn_id: record.turn_id.clone(),
+        files,
+    }
+}
+
+fn file_state(file: &FileSnapshot) -> String {
+    if read_current(Path::new(file.after_path())) == file.after {
+        "applied".to_string()
+    } else if read_current(Path::new(&file.path)) == file.before {
+        "reverted".to_string()
+    } else {
+        "modified".to_string()
+    }
+}
+
+fn format_hunk(hunk: &diffy::Hunk<'_, str>) -> String {
+    let mut out = format!("@@ -{} +{} @@\n", hunk.old_range(), hunk.new_range());
+    for line in hunk.lines() {
+        let (prefix, text) = match line {
+            diffy::Line::Context(text) => (' ', *text),
+            diffy::Line::Delete(text) => ('-', *text),
+            diffy::Line::Insert(text) => ('+', *text),
+        };
+        out.push(prefix);
+        out.push_str(text);
+        if !text.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn read_current(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+// ============================================================================
+// Revert / re-apply
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Revert,
+    Reapply,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeOutcome {
+    pub path: String,
+    /// `written`, `unchanged` or `conflict`.
+    pub result: String,
+    /// Merged text with conflict markers (or why the file could not be merged).
+    pub conflict: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeApplyResult {
+    /// False when any file conflicted; then nothing was written.
+    pub applied: bool,
+    pub files: Vec<FileChangeOutcome>,
+}
+
+struct PlannedWrite {
+    from: PathBuf,
+    to: PathBuf,
+    /// None: delete.
+    content: Option<String>,
+}
+
+/// Revert or re-apply a recorded change: every file, one file (`path`, its pre- or
+/// post-change path) or one hunk of it. Edits made since are kept through a three-way merge;
+/// if any file conflicts nothing is written. `check` resolves each path that will be touched
+/// (the GUI's path policy).
+pub fn apply(
+    record: &FileChangeRecord,
+    direction: Direction,
+    path: Option<&str>,
+    hunk: Option<usize>,
+    check: impl Fn(&str) -> Result<PathBuf, String>,
+) -> Result<FileChangeApplyResult, String> {
+    let files = record
+        .files
+        .iter()
+        .filter(|f| path.map_or(true, |p| f.path == p || f.move_path.as_deref() == Some(p)))
+        .collect::<Vec<_>>();
+    if hunk.is_some() && path.is_none() {
+        return Err("a hunk index needs a file path".to_string());
+    }
+    if files.is_empty() {
+        return Err("no such file in this change".to_string());
+    }
+
+    let mut outcomes = Vec::new();
+    let mut writes = Vec::new();
+    for file in files {
+        if !file.tracked {
+            outcomes.push(FileChangeOutcome {
+                path: file.path.clone(),
+                result: "conflict".to_string(),
+                conflict: Some("file was not tracked (binary or too large)".to_string()),
+            });
+            continue;
+        }
+        let (ancestor, from, whole_target, whole_to) = match direction {
+            Direction::Revert => (&file.after, file.after_path(), &file.before, &file.path[..]),
+            Direction::Reapply => (&file.before, &file.path[..], &file.after, file.after_path()),
+        };
+        let (target, to) = match hunk {
+            None => (whole_target.clone(), whole_to),
+            Some(index) => (Some(splice_hunk(file, direction, index)?), from),
+        };
+        let from = check(from)?;
+        let to = check(to)?;
+        let current = if from.exists() {
+            Some(std::fs::read_to_string(&from).map_err(|e| e.to_string())?)
+        } else {
+            None
+        };
+
+        let outcome_path = to.to_string_lossy().to_string();
+        if current == target && from == to {
+            outcomes.push(FileChangeOutcome {
+                path: outcome_path,
+                result: "unchanged".to_string(),
+                conflict: None,
+            });
+            continue;
+        }
+        match merge(ancestor.as_deref(), current.as_deref(), target.as_deref()) {
+            Ok(content) => {
+                outcomes.push(FileChangeOutcome {
+                    path: outcome_path,
+                    result: "written".to_string(),
+                    conflict: None,
+                });
+                writes.push(PlannedWrite { from, to, content });
+            }
+            Err(conflict) => outcomes.push(FileChangeOutcome {
+                path: outcome_path,
+                result: "conflict".to_string(),
+                conflict: Some(conflict),
+            }),
+        }
+    }
+
+    let applied = outcomes.iter().all(|o| o.result != "conflict");
+    if applied {
+        for write in writes {
+            match write.content {
+                Some(content) => {
+                    if let Some(parent) = write.to.parent() {
+                        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    }
+                    std::fs::write(&write.to, content).map_err(|e| e.to_string())?;
+                    if write.from != write.to && write.from.exists() {
+                        std::fs::remove_file(&write.from).map_err(|e| e.to_string())?;
+                    }
+                }
+                None => {
+                    if write.from.exists() {
+                        std::fs::remove_file(&write.from).map_err(|e| e.to_string())?;
+                    }
+                }
+            }
+        }
+    } else {
+        for outcome in &mut outcomes {
+            if outcome.result == "written" {
+                outcome.result = "unchanged".to_string();
+            }
+        }
+    }
+    Ok(FileChangeApplyResult {
+        applied,
+        files: outcomes,
+    })
+}
+
+/// The file with only hunk `index` (of pre → post) undone (revert) or applied (re-apply),
+/// starting from the post- or pre-image respectively.
+fn splice_hunk(file: &FileSnapshot, direction: Direction, index: usize) -> Result<String, String> {
+    let before = file.before.as_deref().unwrap_or("");
+    let after = file.after.as_deref().unwrap_or("");
+    let patch = diffy::create_patch(before, after);
+    let hunk = patch
+        .hunks()
+        .get(index)
+        .ok_or_else(|| format!("hunk {index} out of range"))?;
+
+    let (base, range, replacement) = match direction {
+        Direction::Revert => (after, hunk.new_range(), side(hunk, false)),
+        Direction::Reapply => (before, hunk.old_range(), side(hunk, true)),
+    };
+    let lines = base.split_inclusive('\n').collect::<Vec<_>>();
+    let start = if range.is_empty() {
+        range.start()
+    } else {
+        range.start() - 1
+    };
+    let end = (start + range.len()).min(lines.len());
+    let mut out = lines[..start.min(lines.len())].concat();
+    out.push_str(&replacement);
+    out.push_str(&lines[end..].concat());
+    Ok(out)
+}
+
+/// Hunk text on the new side (`new == true`) or the old side.
+fn side(hunk: &diffy::Hunk<'_, str>, new: bool) -> String {
+    hunk.lines()
+        .iter()
+        .filter_map(|line| match line {
+            diffy::Line::Context(text) => Some(*text),
+            diffy::Line::Insert(text) if new => Some(*text),
+            diffy::Line::Delete(text) if !new => Some(*text),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Three-way merge of `ours` (the file now) and `theirs` (the wanted result) over `ancestor`.
+/// None means the file does not exist (on that side).
+fn merge(
+    ancestor: Option<&str>,
+    ours: Option<&str>,
+    theirs: Option<&str>,
+) -> Result<Option<String>, String> {
+    if ours == ancestor {
+        return Ok(theirs.map(|s| s.to_string()));
+    }
+    if ours == theirs {
+        return Ok(theirs.map(|s| s.to_string()));
+    }
+    let Some(ours) = ours else {
+        return Err("file was deleted since the change".to_string());
+    };
+    let merged = diffy::merge(ancestor.unwrap_or(""), ours, theirs.unwrap_or(""))?;
+    if theirs.is_none() && merged.is_empty() {
+        return Ok(None);
+    }
+    if theirs.is_none() {
+        return Err(format!(
+            "file was edited since the change; keeping it would leave:\n{merged}"
+        ));
+    }
+    Ok(Some(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(dir: &Path, before: &str, after: &str) -> FileChangeRecord {
+        let path = dir.join("a.txt");
+        std::fs::write(&path, after).unwrap();
+        FileChangeRecord {
+            item_id: "item-1".to_string(),
+            thread_id: None,
+            turn_id: None,
+            status: STATUS_COMPLETED.to_string(),
+            files: vec![FileSnapshot {
+                path: path.to_string_lossy().to_string(),
+                move_path: None,
+                kind: "update".to_string(),
+                diff: String::new(),
+                before: Some(before.to_string()),
+                after: Some(after.to_string()),
+                tracked: true,
+            }],
+        }
+    }
+
+    #[test]
+    fn reverts_and_reapplies_hunks_through_later_edits() {
+        let dir = std::env::temp_dir().join(format!("coco-file-change-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let before = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+        let after = "A\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nL\n";
+        let record = snapshot(&dir, before, after);
+        let file = dir.join("a.txt");
+        let check = |p: &str| Ok(PathBuf::from(p));
+
+        assert_eq!(view(&record).files[0].hunks.len(), 2);
+        assert_eq!(view(&record).files[0].state, "applied");
+
+        // The user edits an unrelated line after Codex's change; reverting hunk 1 keeps it.
+        std::fs::write(&file, "A\nb\nc\nd\ne\nF\ng\nh\ni\nj\nk\nL\n").unwrap();
+        let result = apply(&record, Direction::Revert, None, Some(1), check);
+        assert!(result.is_err(), "a hunk index needs a file path");
+        let path = record.files[0].path.clone();
+        let result = apply(&record, Direction::Revert, Some(&path), Some(1), check).unwrap();
+        assert!(result.applied);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "A\nb\nc\nd\ne\nF\ng\nh\ni\nj\nk\nl\n"
+        );
+
+        // Re-applying restores it; reverting the whole change keeps the edit too.
+        apply(&record, Direction::Reapply, Some(&path), Some(1), check).unwrap();
+        let result = apply(&record, Direction::Revert, None, None, check).unwrap();
+        assert!(result.applied);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "a\nb\nc\nd\ne\nF\ng\nh\ni\nj\nk\nl\n"
+        );
+
+        // An edit on the changed line itself conflicts and leaves the file alone.
+        std::fs::write(&file, "X\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nL\n").unwrap();
+        let result = apply(&record, Direction::Revert, None, None, check).unwrap();
+        assert!(!result.applied);
+        assert_eq!(result.files[0].result, "conflict");
+        assert!(result.files[0]
+            .conflict
+            .as_deref()
+            .unwrap()
+            .contains("<<<<<<<"));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "X\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nL\n"
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn keeps_the_started_snapshot_only_when_it_is_the_pre_image() {
+        let diff = "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n";
+        let mut file = FileSnapshot {
+            path: "/tmp/a.txt".to_string(),
+            move_path: None,
+            kind: "update".to_string(),
+            diff: diff.to_string(),
+            before: Some("a\nb\nc\n".to_string()),
+            after: Some("a\nB\nc\n".to_string()),
+            tracked: true,
+        };
+        assert!(is_pre_image(&file));
+
+        // Snapshots that raced the apply: already patched, or taken mid-way through an
+        // unrelated rewrite.
+        file.before = file.after.clone();
+        assert!(!is_pre_image(&file));
+        file.before = Some("x\nB\nc\n".to_string());
+        assert!(!is_pre_image(&file));
+        assert_eq!(
+            codex_patch_diff::reverse_apply(diff, file.after.as_deref().unwrap()).as_deref(),
+            Some("a\nb\nc\n")
+        );
+    }
+}
@@ -49,6 +49,19 @@ fn normalize_patch_kind(kind: &Value) -> PatchKind {
     PatchKind::Update
 }
 
+/// `add`, `delete` or `update`.
+pub fn patch_kind_name(kind: &Value) -> &'static str {
+    match normalize_patch_kind(kind) {
+        PatchKind::Add => "add",
+        PatchKind::Delete => "delete",
+        PatchKind::Update => "update",
+    }
+}
+
+pub fn move_path(kind: &Value) -> Option<String> {
+    extract_move_path(kind)
+}
+
 fn extract_move_path(kind: &Value) -> Option<String> {
     let obj = kind.as_object()?;
     obj.get("move_path")
@@ -125,11 +138,18 @@ fn collect_lines_by_kind(hunks: &[PatchHunk], kind: PatchLineKind) -> Vec<String
     out
 }
 
-fn match_hunk_start(file_lines: &[String], hunk: &PatchHunk, search_start: usize) -> Option<usize> {
+/// Where `hunk` starts in `file_lines`, matching every line except those of kind `absent` (the
+/// side of the patch the file does not have).
+fn match_hunk_start(
+    file_lines: &[String],
+    hunk: &PatchHunk,
+    search_start: usize,
+    absent: PatchLineKind,
+) -> Option<usize> {
     let pattern: Vec<&str> = hunk
         .lines
         .iter()
-        .filter(|line| line.kind != PatchLineKind::Delete)
+        .filter(|line| line.kind != absent)
         .map(|line| line.text.as_str())
         .collect();
     if pattern.is_empty() {
@@ -154,6 +174,60 @@ fn match_hunk_start(file_lines: &[String], hunk: &PatchHunk, search_start: usize
     None
 }
 
+/// Undo `diff` on the patched `content`, recovering the file as it was before the patch.
+/// None when a hunk cannot be located.
+pub fn reverse_apply(diff: &str, content: &str) -> Option<String> {
+    apply_hunks(diff, content, PatchLineKind::Delete)
+}
+
+/// Apply `diff` to the unpatched `content`. None when a hunk cannot be located.
+pub fn apply(diff: &str, content: &str) -> Option<String> {
+    apply_hunks(diff, content, PatchLineKind::Add)
+}
+
+/// Replace each hunk's side present in `content` with the other one; `absent` is the kind of
+/// line `content` does not have (`Add` before the patch, `Delete` after it).
+fn apply_hunks(diff: &str, content: &str, absent: PatchLineKind) -> Option<String> {
+    let hunks = parse_apply_patch_hunks(diff);
+    if hunks.is_empty() {
+        return None;
+    }
+    let present = match absent {
+        PatchLineKind::Add => PatchLineKind::Delete,
+        _ => PatchLineKind::Add,
+    };
+    let lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut cursor = 0usize;
+    for hunk in &hunks {
+        let start = match_hunk_start(&lines, hunk, cursor, absent)?;
+        out.extend_from_slice(&lines[cursor..start]);
+        out.extend(
+            hunk.lines
+                .iter()
+                .filter(|line| line.kind != present)
+                .map(|line| line.text.clone()),
+        );
+        cursor = start + hunk.lines.iter().filter(|line| line.kind != absent).count();
+    }
+    out.extend_from_slice(&lines[cursor..]);
+
+    let mut text = out.join("\n");
+    if content.ends_with('\n') || (content.is_empty() && !text.is_empty()) {
+        text.push('\n');
+    }
+    Some(text)
+}
+
+/// The content a delete patch removed (its `-` lines).
+pub fn deleted_content(diff: &str) -> Option<String> {
+    let lines = collect_lines_by_kind(&parse_apply_patch_hunks(diff), PatchLineKind::Delete);
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!("{}\n", lines.join("\n")))
+}
+
 fn build_unified_diff_from_hunks(hunks: &[PatchHunk], file_lines: &[String]) -> Option<String> {
     if hunks.is_empty() {
         return None;
@@ -163,7 +237,7 @@ fn build_unified_diff_from_hunks(hunks: &[PatchHunk], file_lines: &[String]) ->
     let mut search_start = 0usize;
 
     for hunk in hunks {
-        let start_idx = match_hunk_start(file_lines, hunk, search_start)?;
+        let start_idx = match_hunk_start(file_lines, hunk, search_start, PatchLineKind::Delete)?;
         let new_start = start_idx as isize + 1;
         let old_start = new_start + offset;
         if old_start < 1 {
@@ -0,0 +1,465 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the sidecars. All windows opened via `window_new`
+/// share this process, so one lock covers them; files are replaced by rename so a reader (or
+/// another app instance) never sees a partial write.
+static META_LOCK: Mutex<()> = Mutex::new(());
+
+const MAX_TAGS: usize = 20;
+const MAX_TAG_CHARS: usize = 32;
+const MAX_FOLDER_CHARS: usize = 64;
+const MAX_NOTE_CHARS: usize = 4_000;
+
+/// Per-thread organization metadata, stored at `<workspace>/.coco/codex/threads/<threadId>.json`.
+/// The file predates everything but `title`/`source`, so older sidecars still parse.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `"manual"` for user-set titles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Coco task the thread belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at_ms: Option<u64>,
+}
+
+impl ThreadMeta {
+    pub fn manual_title(&self) -> Option<&str> {
+        if self.source.as_deref() != Some("manual") {
+            return None;
+        }
+        self.title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+
+    fn is_empty(&self) -> bool {
+        Self {
+            updated_at_ms: None,
+            ..self.clone()
+        } == Self::default()
+    }
+}
+
+/// Changes applied to every selected thread; unset fields are left alone. For `folder`, `note`
+/// and `taskId` an empty string clears the field.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadMetaPatch {
+    #[serde(default)]
+    pub pinned: Option<bool>,
+    /// Replaces the tag set (applied before `addTags`/`removeTags`).
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub task_id: Option<String>,
+}
+
+/// `codex_thread_list` filter; all set conditions must hold.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadMetaFilter {
+    /// Threads carrying every one of these tags (case-insensitive).
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Exact folder; an empty string selects threads without one.
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub pinned: Option<bool>,
+    #[serde(default)]
+    pub task_id: Option<String>,
+}
+
+impl ThreadMetaFilter {
+    pub fn matches(&self, meta: &ThreadMeta) -> bool {
+        let has_tag = |tag: &String| meta.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()));
+        self.tags.iter().all(has_tag)
+            && self.folder.as_deref().map_or(true, |folder| {
+                meta.folder.as_deref().unwrap_or("") == folder.trim()
+            })
+            && self.pinned.map_or(true, |pinned| meta.pinned == pinned)
+            && self
+                .task_id
+                .as_deref()
+                .map_or(true, |task_id| meta.task_id.as_deref() == Some(task_id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadMetaEntry {
+    pub thread_id: String,
+    pub meta: ThreadMeta,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Tags and folders in use in the workspace, for filter pickers.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadMetaCatalog {
+    pub tags: Vec<NameCount>,
+    pub folders: Vec<NameCount>,
+    pub pinned: usize,
+}
+
+// ============================================================================
+// Normalization
+// ============================================================================
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn normalize_tag(tag: &str) -> Result<Option<String>, String> {
+    let tag = collapse_whitespace(tag.trim().trim_start_matches('#'));
+    if tag.is_empty() {
+        return Ok(None);
+    }
+    if tag.contains(',') {
+        return Err(format!("tag must not contain ',': {tag}"));
+    }
+    if tag.chars().count() > MAX_TAG_CHARS {
+        return Err(format!("tag longer than {MAX_TAG_CHARS} chars: {tag}"));
+    }
+    Ok(Some(tag))
+}
+
+fn push_tag(tags: &mut Vec<String>, tag: String) {
+    if !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+        tags.push(tag);
+    }
+}
+
+/// `Some(None)` clears the field.
+fn normalize_text(
+    value: Option<&String>,
+    label: &str,
+    max_chars: usize,
+    single_line: bool,
+) -> Result<Option<Option<String>>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let value = if single_line {
+        collapse_whitespace(value)
+    } else {
+        value.trim().to_string()
+    };
+    if value.chars().count() > max_chars {
+        return Err(format!("{label} longer than {max_chars} chars"));
+    }
+    Ok(Some(Some(value).filter(|v| !v.is_empty())))
+}
+
+impl ThreadMetaPatch {
+    fn apply(&self, meta: &mut ThreadMeta) -> Result<(), String> {
+        if let Some(pinned) = self.pinned {
+            meta.pinned = pinned;
+        }
+        if let Some(tags) = self.tags.as_ref() {
+            meta.tags.clear();
+            for tag in tags {
+                if let Some(tag) = normalize_tag(tag)? {
+                    push_tag(&mut meta.tags, tag);
+                }
+            }
+        }
+        for tag in &self.add_tags {
+            if let Some(tag) = normalize_tag(tag)? {
+                push_tag(&mut meta.tags, tag);
+            }
+        }
+        for tag in &self.remove_tags {
+            if let Some(tag) = normalize_tag(tag)? {
+                meta.tags.retain(|t| !t.eq_ignore_ascii_case(&tag));
+            }
+        }
+        if meta.tags.len() > MAX_TAGS {
+            return Err(format!("at most {MAX_TAGS} tags per thread"));
+        }
+        if let Some(folder) =
+            normalize_text(self.folder.as_ref(), "folder", MAX_FOLDER_CHARS, true)?
+        {
+            meta.folder = folder;
+        }
+        if let Some(note) = normalize_text(self.note.as_ref(), "note", MAX_NOTE_CHARS, false)? {
+            meta.note = note;
+        }
+        if let Some(task_id) = normalize_text(self.task_id.as_ref(), "taskId", 128, true)? {
+            meta.task_id = task_id;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+fn meta_dir(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".coco").join("codex").join("threads")
+}
+
+fn meta_path(workspace_root: &Path, thread_id: &str) -> PathBuf {
+    meta_dir(workspace_root).join(format!("{thread_id}.json"))
+}
+
+/// A missing sidecar is empty metadata; an unreadable or corrupt one is an error, so updates
+/// never replace it with defaults.
+pub fn read(workspace_root: &Path, thread_id: &str) -> Result<ThreadMeta, String> {
+    let path = meta_path(workspace_root, thread_id);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(ThreadMeta::default()),
+        Err(err) => return Err(format!("{}: {err}", path.display())),
+    };
+    serde_json::from_str(&content)
+        .map_err(|err| format!("corrupt thread metadata {}: {err}", path.display()))
+}
+
+fn write(workspace_root: &Path, thread_id: &str, meta: &ThreadMeta) -> Result<(), String> {
+    let path = meta_path(workspace_root, thread_id);
+    if meta.is_empty() {
+        return match std::fs::remove_file(&path) {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.to_string()),
+        };
+    }
+    std::fs::create_dir_all(meta_dir(workspace_root)).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(meta).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+fn now_ms() -> Option<u64> {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .and_then(|d| u64::try_from(d.as_millis()).ok())
+}
+
+/// Applies `patch` to each thread against its latest on-disk state, so concurrent edits of
+/// different fields from other windows are kept. Every thread is read and validated (including
+/// the per-thread tag limit) before anything is written.
+pub fn update(
+    workspace_root: &Path,
+    thread_ids: &[String],
+    patch: &ThreadMetaPatch,
+) -> Result<Vec<ThreadMetaEntry>, String> {
+    let _guard = META_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut changes = Vec::with_capacity(thread_ids.len());
+    for thread_id in thread_ids {
+        let mut meta = read(workspace_root, thread_id)?;
+        let before = meta.clone();
+        patch
+            .apply(&mut meta)
+            .map_err(|err| format!("thread {thread_id}: {err}"))?;
+        let changed = meta != before;
+        if changed {
+            meta.updated_at_ms = now_ms();
+        }
+        changes.push((thread_id, meta, changed));
+    }
+
+    let mut entries = Vec::with_capacity(changes.len());
+    for (thread_id, meta, changed) in changes {
+        if changed {
+            write(workspace_root, thread_id, &meta)?;
+        }
+        entries.push(ThreadMetaEntry {
+            thread_id: thread_id.clone(),
+            meta,
+        });
+    }
+    Ok(entries)
+}
+
+pub fn set_manual_title(
+    workspace_root: &Path,
+    thread_id: &str,
+    title: String,
+) -> Result<(), String> {
+    let _guard = META_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut meta = read(workspace_root, thread_id)?;
+    meta.title = Some(title);
+    meta.source = Some("manual".to_string());
+    meta.updated_at_ms = now_ms();
+    write(workspace_root, thread_id, &meta)
+}
+
+pub fn remove(workspace_root: &Path, thread_id: &str) -> Result<(), String> {
+    let _guard = META_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    write(workspace_root, thread_id, &ThreadMeta::default())
+}
+
+pub fn catalog(workspace_root: &Path) -> ThreadMetaCatalog {
+    let Ok(entries) = std::fs::read_dir(meta_dir(workspace_root)) else {
+        return ThreadMetaCatalog::default();
+    };
+    let mut tags: BTreeMap<String, NameCount> = BTreeMap::new();
+    let mut folders: BTreeMap<String, usize> = BTreeMap::new();
+    let mut pinned = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(thread_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let meta = match read(workspace_root, thread_id) {
+            Ok(meta) => meta,
+            Err(err) => {
+                log::warn!("skipping thread metadata in catalog: {err}");
+                continue;
+            }
+        };
+        for tag in meta.tags {
+            tags.entry(tag.to_lowercase())
+                .or_insert_with(|| NameCount {
+                    name: tag,
+                    count: 0,
+                })
+                .count += 1;
+        }
+        if let Some(folder) = meta.folder {
+            *folders.entry(folder).or_default() += 1;
+        }
+        pinned += usize::from(meta.pinned);
+    }
+    ThreadMetaCatalog {
+        tags: tags.into_values().collect(),
+        folders: folders
+            .into_iter()
+            .map(|(name, count)| NameCount { name, count })
+            .collect(),
+        pinned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patches_merge_with_existing_sidecars() {
+        let dir = std::env::temp_dir().join(format!("coco-thread-meta-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(meta_dir(&dir)).unwrap();
+        // Title-only sidecar written by older versions.
+        std::fs::write(
+            meta_path(&dir, "t1"),
+            r#"{"title":"Fix migrations","source":"manual","updatedAtMs":1}"#,
+        )
+        .unwrap();
+
+        let ids = vec!["t1".to_string(), "t2".to_string()];
+        let patch = ThreadMetaPatch {
+            pinned: Some(true),
+            add_tags: vec!["#db".into(), " Bug  fix ".into(), "DB".into()],
+            folder: Some(" backend ".into()),
+            ..ThreadMetaPatch::default()
+        };
+        update(&dir, &ids, &patch).unwrap();
+
+        let t1 = read(&dir, "t1").unwrap();
+        assert_eq!(t1.manual_title(), Some("Fix migrations"));
+        assert_eq!(t1.tags, ["db", "Bug fix"]);
+        assert_eq!(t1.folder.as_deref(), Some("backend"));
+        assert!(read(&dir, "t2").unwrap().pinned);
+
+        let filter = ThreadMetaFilter {
+            tags: vec!["BUG FIX".into()],
+            folder: Some("backend".into()),
+            ..ThreadMetaFilter::default()
+        };
+        assert!(filter.matches(&t1));
+        assert!(!filter.matches(&ThreadMeta::default()));
+
+        let clear = ThreadMetaPatch {
+            pinned: Some(false),
+            tags: Some(Vec::new()),
+            folder: Some(String::new()),
+            ..ThreadMetaPatch::default()
+        };
+        update(&dir, &ids[1..], &clear).unwrap();
+        assert!(!meta_path(&dir, "t2").exists());
+
+        let catalog = catalog(&dir);
+        assert_eq!(catalog.tags.len(), 2);
+        assert_eq!(
+            catalog.folders,
+            [NameCount {
+                name: "backend".into(),
+                count: 1
+            }]
+        );
+        assert_eq!(catalog.pinned, 1);
+
+        let bad = ThreadMetaPatch {
+            add_tags: vec!["a,b".into()],
+            ..ThreadMetaPatch::default()
+        };
+        assert!(update(&dir, &ids, &bad).is_err());
+
+        // One thread over the tag limit: nothing is written, not even the threads before it.
+        let full = ThreadMetaPatch {
+            tags: Some((0..MAX_TAGS).map(|i| format!("t{i}")).collect()),
+            ..ThreadMetaPatch::default()
+        };
+        update(&dir, &ids[1..], &full).unwrap();
+        let one_more = ThreadMetaPatch {
+            add_tags: vec!["extra".into()],
+            ..ThreadMetaPatch::default()
+        };
+        assert!(update(&dir, &ids, &one_more).is_err());
+        assert!(!read(&dir, "t1").unwrap().tags.iter().any(|t| t == "extra"));
+
+        // A corrupt sidecar is reported, and left as it was.
+        std::fs::write(meta_path(&dir, "t3"), "{not json").unwrap();
+        assert!(read(&dir, "t3").is_err());
+        let ids = vec!["t1".to_string(), "t3".to_string()];
+        assert!(update(&dir, &ids, &clear).is_err());
+        assert_eq!(
+            std::fs::read_to_string(meta_path(&dir, "t3")).unwrap(),
+            "{not json"
+        );
+        assert!(read(&dir, "t1").unwrap().pinned);
+        assert_eq!(super::catalog(&dir).pinned, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,814 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const INDEX_VERSION: u32 = 1;
+/// Only the head of long agent replies, commands and messages is indexed.
+const MAX_DOC_CHARS: usize = 4_000;
+const SNIPPET_CHARS: usize = 160;
+const MAX_MATCHES_PER_THREAD: usize = 5;
+const DEFAULT_LIMIT: usize = 20;
+/// Bytes hashed at the start of a rollout to notice files rewritten in place.
+const HEAD_FINGERPRINT_BYTES: u64 = 1024;
+/// Searches within this long of the last walk over the Codex home reuse it; the thread watcher
+/// folds appends in meanwhile.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+/// Minimum gap between rewrites of the persisted index; `flush` writes what is still pending.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchDocKind {
+    UserMessage,
+    AgentMessage,
+    Command,
+    File,
+}
+
+impl SearchDocKind {
+    fn weight(self) -> i64 {
+        match self {
+            Self::UserMessage => 3,
+            Self::AgentMessage | Self::Command | Self::File => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadSearchFilters {
+    /// Threads started in this directory or below it.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Restricts which parts of a thread are searched (all of them when unset).
+    #[serde(default)]
+    pub kinds: Option<Vec<SearchDocKind>>,
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub updated_after_ms: Option<u64>,
+    #[serde(default)]
+    pub updated_before_ms: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadSearchMatch {
+    pub kind: SearchDocKind,
+    /// 0-based index into the thread's turns, as returned by `thread/resume`.
+    pub turn_index: u32,
+    /// The matching text, cut to a window around the first match when long.
+    pub snippet: String,
+    /// Char range of the match inside `snippet`.
+    pub match_start: u32,
+    pub match_end: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadSearchHit {
+    pub thread_id: String,
+    pub path: String,
+    pub cwd: Option<String>,
+    pub archived: bool,
+    pub updated_at_ms: u64,
+    pub turn_count: u32,
+    pub score: i64,
+    /// Best matches, in turn order.
+    pub matches: Vec<ThreadSearchMatch>,
+}
+
+// ============================================================================
+// Rollout parsing
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchDoc {
+    kind: SearchDocKind,
+    turn: u32,
+    text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexedRollout {
+    thread_id: String,
+    cwd: Option<String>,
+    archived: bool,
+    /// Bytes of complete lines consumed so far; appends are parsed from here.
+    offset: u64,
+    head_fingerprint: u64,
+    modified_ms: u64,
+    /// Turns seen so far, net of rollbacks; the next user message opens turn `turns`.
+    turns: u32,
+    docs: Vec<SearchDoc>,
+}
+
+impl IndexedRollout {
+    fn new(path: &Path) -> Self {
+        let thread_id = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("rollout-")?.strip_suffix(".jsonl"))
+            .filter(|core| core.len() >= 36)
+            .map(|core| core[core.len() - 36..].to_string())
+            .unwrap_or_default();
+        Self {
+            thread_id,
+            archived: is_archived_path(path),
+            ..Self::default()
+        }
+    }
+
+    fn current_turn(&self) -> u32 {
+        self.turns.saturating_sub(1)
+    }
+
+    fn push(&mut self, kind: SearchDocKind, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let text = match text.char_indices().nth(MAX_DOC_CHARS) {
+            Some((cut, _)) => &text[..cut],
+            None => text,
+        };
+        let turn = self.current_turn();
+        if kind == SearchDocKind::File
+            && self
+                .docs
+                .iter()
+                .rev()
+                .take_while(|d| d.turn == turn)
+                .any(|d| d.kind == kind && d.text == text)
+        {
+            return;
+        }
+        self.docs.push(SearchDoc {
+            kind,
+            turn,
+            text: text.to_string(),
+        });
+    }
+
+    fn ingest_line(&mut self, line: &str) {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+        let Some(payload) = value.get("payload") else {
+            return;
+        };
+        let str_field =
+            |v: &Value, key: &str| v.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let payload_type = payload.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        match value.get("type").and_then(|v| v.as_str()).unwrap_or("") {
+            "session_meta" => {
+                if let Some(id) = str_field(payload, "id") {
+                    self.thread_id = id;
+                }
+                if let Some(cwd) = str_field(payload, "cwd") {
+                    self.cwd = Some(cwd);
+                }
+            }
+            "event_msg" => match payload_type {
+                "user_message" => {
+                    self.turns += 1;
+                    if let Some(message) = str_field(payload, "message") {
+                        self.push(SearchDocKind::UserMessage, &message);
+                    }
+                }
+                "agent_message" => {
+                    if let Some(message) = str_field(payload, "message") {
+                        self.push(SearchDocKind::AgentMessage, &message);
+                    }
+                }
+                "thread_rolled_back" => {
+                    let dropped = payload
+                        .get("num_turns")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    let dropped = u32::try_from(dropped).unwrap_or(u32::MAX);
+                    self.turns = self.turns.saturating_sub(dropped);
+                    let turns = self.turns;
+                    self.docs.retain(|d| d.turn < turns);
+                }
+                _ => {}
+            },
+            "response_item" => {
+                let name = payload.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                match (payload_type, name) {
+                    ("function_call", "apply_patch") => {
+                        let input = str_field(payload, "arguments")
+                            .and_then(|args| serde_json::from_str::<Value>(&args).ok())
+                            .and_then(|args| str_field(&args, "input"));
+                        for path in patch_paths(input.as_deref().unwrap_or("")) {
+                            self.push(SearchDocKind::File, &path);
+                        }
+                    }
+                    ("function_call", _) => {
+                        if let Some(command) = str_field(payload, "arguments")
+                            .and_then(|args| serde_json::from_str::<Value>(&args).ok())
+                            .and_then(|args| command_text(&args))
+                        {
+                            self.push(SearchDocKind::Command, &command);
+                        }
+                    }
+                    ("custom_tool_call", "apply_patch") => {
+                        let input = str_field(payload, "input").unwrap_or_default();
+                        for path in patch_paths(&input) {
+                            self.push(SearchDocKind::File, &path);
+                        }
+                    }
+                    ("local_shell_call", _) => {
+                        if let Some(command) = payload.get("action").and_then(command_text) {
+                            self.push(SearchDocKind::Command, &command);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Parses whatever was appended since the last update, starting over when the file shrank
+    /// or its head changed.
+    fn update(&mut self, path: &Path, len: u64, modified_ms: u64) -> std::io::Result<()> {
+        let mut file = std::fs::File::open(path)?;
+        if self.offset > 0
+            && (len < self.offset
+                || head_fingerprint(&mut file, self.offset)? != self.head_fingerprint)
+        {
+            *self = Self::new(path);
+        }
+        let mut buf = Vec::new();
+        file.seek(SeekFrom::Start(self.offset))?;
+        file.by_ref()
+            .take(len.saturating_sub(self.offset))
+            .read_to_end(&mut buf)?;
+        // A trailing partial line is left for the next update.
+        if let Some(end) = buf.iter().rposition(|b| *b == b'\n') {
+            for line in buf[..end].split(|b| *b == b'\n') {
+                if let Ok(line) = std::str::from_utf8(line) {
+                    self.ingest_line(line);
+                }
+            }
+            let previous = self.offset;
+            self.offset += end as u64 + 1;
+            if previous < HEAD_FINGERPRINT_BYTES {
+                self.head_fingerprint = head_fingerprint(&mut file, self.offset)?;
+            }
+        }
+        self.modified_ms = modified_ms;
+        Ok(())
+    }
+}
+
+/// `cmd`/`command` of a shell tool call; `bash -lc <script>` is reduced to the script.
+fn command_text(args: &Value) -> Option<String> {
+    match args.get("cmd").or_else(|| args.get("command"))? {
+        Value::String(command) => Some(command.to_string()),
+        Value::Array(parts) => {
+            let parts = parts.iter().filter_map(|p| p.as_str()).collect::<Vec<_>>();
+            match parts.as_slice() {
+                [_, "-lc" | "-c", script] => Some(script.to_string()),
+                _ => Some(parts.join(" ")),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn patch_paths(patch: &str) -> Vec<String> {
+    const HEADERS: [&str; 4] = [
+        "*** Update File: ",
+        "*** Add File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    patch
+        .lines()
+        .filter_map(|line| HEADERS.iter().find_map(|h| line.strip_prefix(h)))
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+fn head_fingerprint(file: &mut std::fs::File, offset: u64) -> std::io::Result<u64> {
+    let mut head = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.by_ref()
+        .take(offset.min(HEAD_FINGERPRINT_BYTES))
+        .read_to_end(&mut head)?;
+    // FNV-1a
+    Ok(head.iter().fold(0xcbf2_9ce4_8422_2325, |hash: u64, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    }))
+}
+
+fn is_archived_path(path: &Path) -> bool {
+    path.components()
+        .any(|c| c.as_os_str() == "archived_sessions")
+}
+
+fn modified_ms(meta: &std::fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .and_then(|d| u64::try_from(d.as_millis()).ok())
+        .unwrap_or(0)
+}
+
+fn collect_rollouts(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_rollouts(&path, out);
+        } else if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("rollout-") && n.ends_with(".jsonl"))
+        {
+            out.push(path);
+        }
+    }
+}
+
+// ============================================================================
+// Matching
+// ============================================================================
+
+/// Lowercases char by char so char offsets in the folded text line up with the original.
+fn fold(text: &str) -> String {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+/// Whitespace-separated terms; `"quoted phrases"` stay together.
+fn parse_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            terms.push(part.trim().to_string());
+        } else {
+            terms.extend(part.split_whitespace().map(str::to_string));
+        }
+    }
+    let mut terms = terms
+        .into_iter()
+        .filter(|t| !t.is_empty())
+        .map(|t| fold(&t))
+        .collect::<Vec<_>>();
+    terms.dedup();
+    terms
+}
+
+/// At most `SNIPPET_CHARS` chars of `text` around the match at char `start`, with the match's
+/// char range inside it.
+fn snippet(text: &str, start: usize, len: usize) -> (String, u32, u32) {
+    let chars = text.chars().collect::<Vec<_>>();
+    let context = SNIPPET_CHARS.saturating_sub(len) / 2;
+    let from = if chars.len() <= SNIPPET_CHARS {
+        0
+    } else {
+        start
+            .saturating_sub(context)
+            .min(chars.len() - SNIPPET_CHARS)
+    };
+    let to = (from + SNIPPET_CHARS).min(chars.len());
+    let snippet = chars[from..to]
+        .iter()
+        .map(|c| if c.is_whitespace() { ' ' } else { *c })
+        .collect();
+    let match_start = start - from;
+    let match_end = (match_start + len).min(to - from);
+    (snippet, match_start as u32, match_end as u32)
+}
+
+fn cwd_matches(thread_cwd: Option<&str>, filter: &str) -> bool {
+    thread_cwd.is_some_and(|cwd| Path::new(cwd).starts_with(Path::new(filter)))
+}
+
+// ============================================================================
+// Index
+// ============================================================================
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredIndex {
+    version: u32,
+    codex_home: PathBuf,
+    rollouts: BTreeMap<String, IndexedRollout>,
+}
+
+/// Search index over the rollout files of one Codex home, persisted between runs so only new
+/// or appended rollouts are parsed.
+pub struct ThreadSearchIndex {
+    store_path: Option<PathBuf>,
+    codex_home: Option<PathBuf>,
+    loaded: bool,
+    dirty: bool,
+    last_scan: Option<Instant>,
+    last_save: Option<Instant>,
+    rollouts: BTreeMap<String, IndexedRollout>,
+}
+
+impl ThreadSearchIndex {
+    pub fn new(store_path: Option<PathBuf>) -> Self {
+        Self {
+            store_path,
+            codex_home: None,
+            loaded: false,
+            dirty: false,
+            last_scan: None,
+            last_save: None,
+            rollouts: BTreeMap::new(),
+        }
+    }
+
+    fn load(&mut self) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+        let Some(store_path) = self.store_path.as_ref() else {
+            return;
+        };
+        let Ok(raw) = std::fs::read_to_string(store_path) else {
+            return;
+        };
+        match serde_json::from_str::<StoredIndex>(&raw) {
+            Ok(stored) if stored.version == INDEX_VERSION => {
+                self.codex_home = Some(stored.codex_home);
+                self.rollouts = stored.rollouts;
+            }
+            Ok(_) => log::info!("[ThreadSearch] discarding index from an older version"),
+            Err(err) => log::warn!("ignoring unreadable {}: {err}", store_path.display()),
+        }
+    }
+
+    fn save(&mut self) -> Result<(), String> {
+        let (Some(store_path), Some(codex_home)) =
+            (self.store_path.as_ref(), self.codex_home.as_ref())
+        else {
+            return Ok(());
+        };
+        if let Some(parent) = store_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let stored = StoredIndex {
+            version: INDEX_VERSION,
+            codex_home: codex_home.clone(),
+            rollouts: std::mem::take(&mut self.rollouts),
+        };
+        let json = serde_json::to_string(&stored);
+        self.rollouts = stored.rollouts;
+        let tmp = store_path.with_extension("json.tmp");
+        std::fs::write(&tmp, json.map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, store_path).map_err(|e| e.to_string())?;
+        self.dirty = false;
+        self.last_save = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Persists pending changes now (e.g. when a window closes) instead of at the next due save.
+    pub fn flush(&mut self) -> Result<(), String> {
+        if self.dirty {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn update_rollout(&mut self, path: &Path, meta: &std::fs::Metadata) {
+        let key = path.to_string_lossy().to_string();
+        let modified = modified_ms(meta);
+        let entry = self
+            .rollouts
+            .entry(key.clone())
+            .or_insert_with(|| IndexedRollout::new(path));
+        if entry.offset == meta.len() && entry.modified_ms == modified {
+            return;
+        }
+        if let Err(err) = entry.update(path, meta.len(), modified) {
+            log::warn!("[ThreadSearch] failed to index {}: {err}", path.display());
+            self.rollouts.remove(&key);
+        }
+        self.dirty = true;
+    }
+
+    /// Brings the index up to date with `<codex_home>/sessions` and `archived_sessions`:
+    /// unchanged rollouts cost a `stat`, grown ones are parsed from where indexing stopped.
+    /// The walk is skipped within `RESCAN_INTERVAL` of the previous one, and the index is
+    /// rewritten at most every `SAVE_INTERVAL`.
+    pub fn refresh(&mut self, codex_home: &Path) -> Result<(), String> {
+        self.load();
+        if self.codex_home.as_deref() != Some(codex_home) {
+            self.codex_home = Some(codex_home.to_path_buf());
+            self.rollouts.clear();
+            self.dirty = true;
+            self.last_scan = None;
+        }
+        if self
+            .last_scan
+            .is_some_and(|scan| scan.elapsed() < RESCAN_INTERVAL)
+        {
+            return Ok(());
+        }
+        let mut paths = Vec::new();
+        collect_rollouts(&codex_home.join("sessions"), &mut paths);
+        collect_rollouts(&codex_home.join("archived_sessions"), &mut paths);
+
+        let before = self.rollouts.len();
+        let live = paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect::<std::collections::HashSet<_>>();
+        self.rollouts.retain(|key, _| live.contains(key));
+        self.dirty |= self.rollouts.len() != before;
+
+        for path in &paths {
+            if let Ok(meta) = std::fs::metadata(path) {
+                self.update_rollout(path, &meta);
+            }
+        }
+        self.last_scan = Some(Instant::now());
+        if self.dirty
+            && self
+                .last_save
+                .map_or(true, |save| save.elapsed() >= SAVE_INTERVAL)
+        {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Folds newly appended lines of one rollout into the index. Rollouts outside the indexed
+    /// Codex home are ignored.
+    pub fn update_file(&mut self, path: &Path) {
+        self.load();
+        let Some(codex_home) = self.codex_home.as_ref() else {
+            return;
+        };
+        if !path.starts_with(codex_home) {
+            return;
+        }
+        match std::fs::metadata(path) {
+            Ok(meta) => self.update_rollout(path, &meta),
+            Err(_) => {
+                self.dirty |= self
+                    .rollouts
+                    .remove(path.to_string_lossy().as_ref())
+                    .is_some();
+            }
+        }
+    }
+
+    /// Threads containing every term of `query` (across any of their messages, commands and
+    /// touched files), best first.
+    pub fn search(&self, query: &str, filters: &ThreadSearchFilters) -> Vec<ThreadSearchHit> {
+        let terms = parse_terms(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let cwd_filter = filters
+            .cwd
+            .as_deref()
+            .map(str::trim)
+            .filter(|cwd| !cwd.is_empty());
+
+        let mut hits = Vec::new();
+        for (path, rollout) in &self.rollouts {
+            if (rollout.archived && !filters.include_archived)
+                || cwd_filter.is_some_and(|cwd| !cwd_matches(rollout.cwd.as_deref(), cwd))
+                || filters
+                    .updated_after_ms
+                    .is_some_and(|after| rollout.modified_ms < after)
+                || filters
+                    .updated_before_ms
+                    .is_some_and(|before| rollout.modified_ms > before)
+            {
+                continue;
+            }
+
+            let mut covered = vec![false; terms.len()];
+            // (terms matched, turn, match)
+            let mut matches: Vec<(usize, u32, ThreadSearchMatch)> = Vec::new();
+            let mut score = 0;
+            for doc in &rollout.docs {
+                if filters
+                    .kinds
+                    .as_ref()
+                    .is_some_and(|kinds| !kinds.contains(&doc.kind))
+                {
+                    continue;
+                }
+                let folded = fold(&doc.text);
+                let mut first: Option<(usize, usize)> = None;
+                let mut matched = 0;
+                for (i, term) in terms.iter().enumerate() {
+                    let Some(byte_idx) = folded.find(term.as_str()) else {
+                        continue;
+                    };
+                    covered[i] = true;
+                    matched += 1;
+                    let start = folded[..byte_idx].chars().count();
+                    match first {
+                        Some((s, _)) if s <= start => {}
+                        _ => first = Some((start, term.chars().count())),
+                    }
+                }
+                let Some((start, len)) = first else {
+                    continue;
+                };
+                let all = if matched == terms.len() { 2 } else { 1 };
+                score += doc.kind.weight() * matched as i64 * all;
+                let (snippet, match_start, match_end) = snippet(&doc.text, start, len);
+                let m = ThreadSearchMatch {
+                    kind: doc.kind,
+                    turn_index: doc.turn,
+                    snippet,
+                    match_start,
+                    match_end,
+                };
+                matches.push((matched, doc.turn, m));
+            }
+            if !covered.iter().all(|c| *c) {
+                continue;
+            }
+            matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            matches.truncate(MAX_MATCHES_PER_THREAD);
+            matches.sort_by_key(|(_, turn, _)| *turn);
+            hits.push(ThreadSearchHit {
+                thread_id: rollout.thread_id.clone(),
+                path: path.clone(),
+                cwd: rollout.cwd.clone(),
+                archived: rollout.archived,
+                updated_at_ms: rollout.modified_ms,
+                turn_count: rollout.turns,
+                score,
+                matches: matches.into_iter().map(|(_, _, m)| m).collect(),
+            });
+        }
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.updated_at_ms.cmp(&a.updated_at_ms))
+        });
+        hits.truncate(filters.limit.unwrap_or(DEFAULT_LIMIT).max(1));
+        hits
+    }
+}
+
+/// Thread-watcher hook. Skipped while a search holds the index; the next rescan picks the change
+/// up.
+pub fn note_rollout_changed(index: &Mutex<ThreadSearchIndex>, path: &Path) {
+    if let Ok(mut index) = index.try_lock() {
+        index.update_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const THREAD_ID: &str = "0199a0b1-2c3d-7e4f-8a9b-0c1d2e3f4a5b";
+
+    fn line(kind: &str, payload: Value) -> String {
+        format!(
+            "{}\n",
+            serde_json::json!({ "type": kind, "payload": payload })
+        )
+    }
+
+    #[test]
+    fn indexes_appends_and_rollbacks() {
+        let home = std::env::temp_dir().join(format!("coco-thread-search-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        let day = home.join("sessions").join("2026").join("01").join("02");
+        std::fs::create_dir_all(&day).unwrap();
+        let rollout = day.join(format!("rollout-2026-01-02T10-00-00-{THREAD_ID}.jsonl"));
+
+        let mut file = std::fs::File::create(&rollout).unwrap();
+        let head = [
+            line(
+                "session_meta",
+                serde_json::json!({ "id": THREAD_ID, "cwd": "/work/app" }),
+            ),
+            line(
+                "event_msg",
+                serde_json::json!({ "type": "user_message", "message": "Fix the migration bug" }),
+            ),
+            line(
+                "response_item",
+                serde_json::json!({
+                    "type": "function_call",
+                    "name": "exec_command",
+                    "arguments": "{\"cmd\":\"cargo test -p migrations\"}",
+                }),
+            ),
+            line(
+                "response_item",
+                serde_json::json!({
+                    "type": "custom_tool_call",
+                    "name": "apply_patch",
+                    "input": "*** Begin Patch\n*** Update File: src/migrate.rs\n@@\n-a\n+b\n*** End Patch",
+                }),
+            ),
+        ];
+        file.write_all(head.concat().as_bytes()).unwrap();
+        // A partial line is not indexed until it is complete.
+        file.write_all(b"{\"type\":\"event_msg\",\"payload\":{\"type\":\"agent_mess")
+            .unwrap();
+        file.flush().unwrap();
+
+        let mut index = ThreadSearchIndex::new(Some(home.join("index.json")));
+        index.refresh(&home).unwrap();
+        let filters = ThreadSearchFilters::default();
+        let hits = index.search("MIGRATION migrate.rs", &filters);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].thread_id, THREAD_ID);
+        assert_eq!(hits[0].turn_count, 1);
+        let kinds = hits[0].matches.iter().map(|m| m.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SearchDocKind::UserMessage,
+                SearchDocKind::Command,
+                SearchDocKind::File
+            ]
+        );
+        assert_eq!(hits[0].matches[0].snippet, "Fix the migration bug");
+        assert_eq!(
+            (hits[0].matches[0].match_start, hits[0].matches[0].match_end),
+            (8, 17)
+        );
+
+        file.write_all(b"age\",\"message\":\"Renamed the column\"}}\n")
+            .unwrap();
+        file.write_all(
+            line(
+                "event_msg",
+                serde_json::json!({ "type": "user_message", "message": "Now add a rollback test" }),
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        file.flush().unwrap();
+        index.update_file(&rollout);
+        let hits = index.search("\"renamed the column\"", &filters);
+        assert_eq!(hits[0].matches[0].kind, SearchDocKind::AgentMessage);
+        assert_eq!(
+            index.search("rollback", &filters)[0].matches[0].turn_index,
+            1
+        );
+        // Searching right away neither rewalks nor rewrites the index; `flush` persists it.
+        index.refresh(&home).unwrap();
+        let stored = || std::fs::read_to_string(home.join("index.json")).unwrap();
+        assert!(!stored().contains("Renamed the column"));
+        index.flush().unwrap();
+        assert!(stored().contains("Renamed the column"));
+
+        // Reloaded from disk, then a rollback of the last turn drops its documents.
+        let mut index = ThreadSearchIndex::new(Some(home.join("index.json")));
+        file.write_all(
+            line(
+                "event_msg",
+                serde_json::json!({ "type": "thread_rolled_back", "num_turns": 1 }),
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        file.flush().unwrap();
+        index.refresh(&home).unwrap();
+        assert!(index.search("rollback", &filters).is_empty());
+        assert_eq!(index.search("column", &filters).len(), 1);
+
+        let elsewhere = ThreadSearchFilters {
+            cwd: Some("/work/other".into()),
+            ..ThreadSearchFilters::default()
+        };
+        assert!(index.search("migration", &elsewhere).is_empty());
+        let commands_only = ThreadSearchFilters {
+            kinds: Some(vec![SearchDocKind::Command]),
+            ..ThreadSearchFilters::default()
+        };
+        assert!(index.search("bug", &commands_only).is_empty());
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+}
@@ -2,16 +2,23 @@ use tauri::Manager;
 
 mod codex_app_server;
 mod codex_app_server_pool;
+mod codex_chat_selection;
+mod codex_checkpoint;
+mod codex_config;
+mod codex_file_change;
 mod codex_patch_diff;
 mod codex_rollout_restore;
-mod thread_watch;
+mod codex_thread_meta;
+mod codex_thread_search;
+mod path_policy;
+mod watch_service;
+mod workspace_search;
 
 use codex_app_server::CodexAppServer;
 use codex_app_server::CodexDiagnostics;
 use codex_app_server_pool::CodexAppServerPool;
 use std::sync::atomic::AtomicU32;
 use std::sync::atomic::Ordering;
-use thread_watch::ThreadWatchState;
 use tokio::sync::Mutex as TokioMutex;
 
 const CODEX_APP_SERVER_POOL_MAX: usize = 8;
@@ -86,6 +93,16 @@ struct CodexThreadSummary {
     created_at: i64,
     updated_at_ms: Option<u64>,
     interaction_count: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    folder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_id: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -102,15 +119,6 @@ struct CodexThreadLoadedListResponse {
     next_cursor: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct CodexThreadTitleSidecar {
-    title: String,
-    source: String, // "manual"
-    #[serde(default)]
-    updated_at_ms: Option<u64>,
-}
-
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CodexThreadArchiveGuard {
@@ -138,44 +146,78 @@ pub fn run() {
             workspace_rename_file,
             list_shared_artifacts,
             read_shared_artifact,
+            task_evidence_check,
+            task_evidence_resolve,
             workspace_root_get,
             workspace_root_set,
             workspace_recent_list,
+            workspace_allowed_roots_list,
+            workspace_allowed_root_pick,
+            workspace_allowed_root_remove,
             window_new,
             codex_app_server_ensure,
             codex_app_server_shutdown,
             codex_thread_list,
             codex_thread_loaded_list,
             codex_thread_title_set,
+            codex_thread_meta_update,
+            codex_thread_meta_catalog,
             codex_thread_archive,
             codex_thread_start,
             codex_thread_resume,
             codex_thread_fork,
             codex_thread_rollback,
-            codex_thread_watch_start,
-            codex_thread_watch_stop,
+            codex_checkpoint_list,
+            codex_checkpoint_preview,
+            codex_thread_search,
             codex_turn_start,
             codex_turn_interrupt,
             codex_respond_approval,
             codex_model_list,
             codex_config_read_effective,
             codex_config_write_chat_defaults,
-            codex_set_profile,
+            codex_chat_selection_get,
+            codex_chat_selection_set,
             codex_read_config,
             codex_write_config,
+            codex_config_document,
+            codex_config_preview,
+            codex_config_apply,
+            codex_config_backups_list,
+            codex_config_backup_restore,
             codex_diagnostics,
             codex_skill_list,
             codex_prompt_list,
+            prompt_render,
+            codex_file_change_get,
+            codex_file_change_revert,
+            codex_file_change_reapply,
             // Context management commands
             search_workspace_files,
+            grep_workspace_files,
             read_file_content,
             get_auto_context,
             git_worktree_list,
             git_branch_list,
             git_worktree_create,
+            // File watching
+            watch_subscribe,
+            watch_unsubscribe,
         ])
         .on_window_event(|window, event| {
+            if matches!(event, tauri::WindowEvent::Destroyed) {
+                if let Some(state) = window.try_state::<AppState>() {
+                    state.watch.unsubscribe_window(window.label());
+                }
+            }
             if matches!(event, tauri::WindowEvent::CloseRequested { .. }) {
+                if let Some(state) = window.try_state::<AppState>() {
+                    if let Ok(mut index) = state.thread_search.try_lock() {
+                        if let Err(err) = index.flush() {
+                            log::warn!("[ThreadSearch] failed to save the index: {err}");
+                        }
+                    }
+                }
                 let app_handle = window.app_handle().clone();
                 tauri::async_runtime::spawn(async move {
                     let state = app_handle.state::<AppState>();
@@ -187,13 +229,32 @@ pub fn run() {
         .setup(|app| {
             let workspace_root = resolve_workspace_root(app.handle())?;
             let _ = update_recent_workspaces(app.handle(), &workspace_root);
+            let thread_search = std::sync::Arc::new(std::sync::Mutex::new(
+                codex_thread_search::ThreadSearchIndex::new(
+                    app.path()
+                        .app_data_dir()
+                        .ok()
+                        .map(|dir| dir.join("codex_thread_search.json")),
+                ),
+            ));
+            let search_index = std::sync::Arc::clone(&thread_search);
+            let app_handle = app.handle().clone();
+            let watch = watch_service::WatchService::new(
+                std::sync::Arc::new(move |path: &std::path::Path| {
+                    codex_thread_search::note_rollout_changed(&search_index, path)
+                }),
+                Box::new(move |emission| emit_watch_event(&app_handle, emission)),
+            );
             app.manage(AppState {
                 orchestrator: std::sync::Mutex::new(coco_orchestrator::Orchestrator::new(
                     workspace_root,
                 )),
                 codex_pool: TokioMutex::new(CodexAppServerPool::new(CODEX_APP_SERVER_POOL_MAX)),
-                codex_profile: TokioMutex::new(None),
-                thread_watch: TokioMutex::new(ThreadWatchState::default()),
+                watch,
+                thread_search,
+                workspace_search: std::sync::Mutex::new(
+                    workspace_search::WorkspaceSearchState::default(),
+                ),
             });
 
             if cfg!(debug_assertions) {
@@ -212,8 +273,9 @@ pub fn run() {
 struct AppState {
     orchestrator: std::sync::Mutex<coco_orchestrator::Orchestrator>,
     codex_pool: TokioMutex<CodexAppServerPool>,
-    codex_profile: TokioMutex<Option<String>>,
-    thread_watch: TokioMutex<ThreadWatchState>,
+    watch: watch_service::WatchService,
+    thread_search: std::sync::Arc<std::sync::Mutex<codex_thread_search::ThreadSearchIndex>>,
+    workspace_search: std::sync::Mutex<workspace_search::WorkspaceSearchState>,
 }
 
 static NEXT_WINDOW_ID: AtomicU32 = AtomicU32::new(2);
@@ -329,6 +391,80 @@ fn update_recent_workspaces<R: tauri::Runtime>(
     persist_recent_workspaces(app, &recent)
 }
 
+fn allowed_roots_path<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> tauri::Result<std::path::PathBuf> {
+    Ok(app
+        .path()
+        .app_data_dir()?
+        .join(path_policy::ALLOWED_ROOTS_FILE_NAME))
+}
+
+fn read_allowed_roots<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Vec<String> {
+    let Ok(path) = allowed_roots_path(app) else {
+        return Vec::new();
+    };
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Vec<String>>(&content).unwrap_or_default()
+}
+
+fn persist_allowed_roots<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    roots: &[String],
+) -> Result<(), String> {
+    let path = allowed_roots_path(app).map_err(|e| e.to_string())?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(roots).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// The policy every file command goes through: the current workspace root (and its linked
+/// worktrees) plus the persisted allowlist; denials are appended to the audit log.
+fn path_policy_for<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &AppState,
+) -> path_policy::PathPolicy {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    // Entries persisted before roots were vetted are re-checked, so a stale `/` opens nothing.
+    let home = home_dir();
+    let extra_roots = read_allowed_roots(app)
+        .into_iter()
+        .filter_map(|root| {
+            path_policy::validate_extra_root(std::path::Path::new(&root), home.as_deref()).ok()
+        })
+        .collect::<Vec<_>>();
+    let policy = path_policy::PathPolicy::new(&workspace_root, &extra_roots);
+    match app.path().app_data_dir() {
+        Ok(dir) => policy.with_audit_log(dir.join(path_policy::AUDIT_LOG_FILE_NAME)),
+        Err(_) => policy,
+    }
+}
+
+/// Rollout paths come from the webview, so watches are confined to Codex's session directories.
+fn rollout_policy_for<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Result<path_policy::PathPolicy, String> {
+    let codex_home = default_codex_home_dir()?;
+    let policy = path_policy::PathPolicy::with_roots([
+        codex_home.join("sessions"),
+        codex_home.join("archived_sessions"),
+    ]);
+    Ok(match app.path().app_data_dir() {
+        Ok(dir) => policy.with_audit_log(dir.join(path_policy::AUDIT_LOG_FILE_NAME)),
+        Err(_) => policy,
+    })
+}
+
 fn validate_id(value: &str, label: &str) -> Result<(), String> {
     let is_ok = !value.is_empty()
         && value
@@ -341,17 +477,6 @@ fn validate_id(value: &str, label: &str) -> Result<(), String> {
     }
 }
 
-fn codex_thread_sidecar_dir(workspace_root: &std::path::Path) -> std::path::PathBuf {
-    workspace_root.join(".coco").join("codex").join("threads")
-}
-
-fn codex_thread_sidecar_path(
-    workspace_root: &std::path::Path,
-    thread_id: &str,
-) -> std::path::PathBuf {
-    codex_thread_sidecar_dir(workspace_root).join(format!("{thread_id}.json"))
-}
-
 fn codex_thread_archive_guard_dir(workspace_root: &std::path::Path) -> std::path::PathBuf {
     workspace_root
         .join(".coco")
@@ -480,40 +605,6 @@ fn derive_auto_thread_title_from_preview(preview: &str) -> Option<String> {
     }
 }
 
-fn read_codex_thread_title_sidecar(
-    workspace_root: &std::path::Path,
-    thread_id: &str,
-) -> Option<CodexThreadTitleSidecar> {
-    let path = codex_thread_sidecar_path(workspace_root, thread_id);
-    let content = std::fs::read_to_string(path).ok()?;
-    serde_json::from_str::<CodexThreadTitleSidecar>(&content).ok()
-}
-
-fn write_codex_thread_title_sidecar(
-    workspace_root: &std::path::Path,
-    thread_id: &str,
-    sidecar: &CodexThreadTitleSidecar,
-) -> Result<(), String> {
-    let dir = codex_thread_sidecar_dir(workspace_root);
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-    let path = codex_thread_sidecar_path(workspace_root, thread_id);
-    let json = serde_json::to_string_pretty(sidecar).map_err(|e| e.to_string())?;
-    std::fs::write(path, json).map_err(|e| e.to_string())?;
-    Ok(())
-}
-
-fn remove_codex_thread_title_sidecar(
-    workspace_root: &std::path::Path,
-    thread_id: &str,
-) -> Result<(), String> {
-    let path = codex_thread_sidecar_path(workspace_root, thread_id);
-    match std::fs::remove_file(path) {
-        Ok(_) => Ok(()),
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
-        Err(err) => Err(err.to_string()),
-    }
-}
-
 fn read_codex_thread_archive_guard(
     workspace_root: &std::path::Path,
     thread_id: &str,
@@ -670,48 +761,6 @@ fn shared_category_dir(
     Ok(task_shared_dir(workspace_root, task_id).join(subdir))
 }
 
-fn validate_artifact_rel_path(value: &str) -> Result<std::path::PathBuf, String> {
-    if value.trim().is_empty() {
-        return Err("artifact path cannot be empty".to_string());
-    }
-    let path = std::path::PathBuf::from(value);
-    if path.is_absolute() {
-        return Err("absolute paths are not allowed".to_string());
-    }
-    for component in path.components() {
-        match component {
-            std::path::Component::ParentDir
-            | std::path::Component::RootDir
-            | std::path::Component::Prefix(_) => {
-                return Err("path traversal is not allowed".to_string())
-            }
-            _ => {}
-        }
-    }
-    Ok(path)
-}
-
-fn validate_task_rel_path(value: &str) -> Result<std::path::PathBuf, String> {
-    if value.trim().is_empty() {
-        return Err("path cannot be empty".to_string());
-    }
-    let path = std::path::PathBuf::from(value);
-    if path.is_absolute() {
-        return Err("absolute paths are not allowed".to_string());
-    }
-    for component in path.components() {
-        match component {
-            std::path::Component::ParentDir
-            | std::path::Component::RootDir
-            | std::path::Component::Prefix(_) => {
-                return Err("path traversal is not allowed".to_string())
-            }
-            _ => {}
-        }
-    }
-    Ok(path)
-}
-
 fn collect_artifact_summaries(
     base_dir: &std::path::Path,
     current_dir: &std::path::Path,
@@ -1025,13 +1074,13 @@ fn tail_subagent_stderr(
 
 #[tauri::command]
 fn task_read_text_file(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     task_id: String,
     path: String,
     max_bytes: Option<usize>,
 ) -> Result<TaskTextFileContent, String> {
     validate_id(&task_id, "task_id")?;
-    let rel_path = validate_task_rel_path(&path)?;
 
     let workspace_root = state
         .orchestrator
@@ -1040,9 +1089,7 @@ fn task_read_text_file(
         .workspace_root()
         .to_path_buf();
     let base_dir = task_root_dir(&workspace_root, &task_id);
-    let full_path = base_dir.join(&rel_path);
-
-    if !full_path.exists() {
+    if !base_dir.exists() {
         return Ok(TaskTextFileContent {
             path,
             exists: false,
@@ -1052,11 +1099,18 @@ fn task_read_text_file(
             truncated: false,
         });
     }
+    let canonical_file =
+        path_policy_for(&app, &state).resolve_in("task_read_text_file", &base_dir, &path)?;
 
-    let canonical_base = std::fs::canonicalize(&base_dir).map_err(|e| e.to_string())?;
-    let canonical_file = std::fs::canonicalize(&full_path).map_err(|e| e.to_string())?;
-    if !canonical_file.starts_with(&canonical_base) {
-        return Err("path escapes task directory".to_string());
+    if !canonical_file.exists() {
+        return Ok(TaskTextFileContent {
+            path,
+            exists: false,
+            content: None,
+            updated_at_ms: None,
+            size_bytes: None,
+            truncated: false,
+        });
     }
 
     let meta = std::fs::metadata(&canonical_file).ok();
@@ -1084,16 +1138,12 @@ fn task_read_text_file(
 
 #[tauri::command]
 fn task_list_directory(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     task_id: String,
     relative_path: String,
 ) -> Result<Vec<TaskDirectoryEntry>, String> {
     validate_id(&task_id, "task_id")?;
-    let rel_path = if relative_path.trim().is_empty() {
-        std::path::PathBuf::new()
-    } else {
-        validate_task_rel_path(&relative_path)?
-    };
 
     let workspace_root = state
         .orchestrator
@@ -1102,22 +1152,25 @@ fn task_list_directory(
         .workspace_root()
         .to_path_buf();
     let base_dir = task_root_dir(&workspace_root, &task_id);
-    let target_dir = if rel_path.as_os_str().is_empty() {
-        base_dir.clone()
+    if !base_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let canonical_base = std::fs::canonicalize(&base_dir).map_err(|e| e.to_string())?;
+    let is_root = relative_path.trim().is_empty();
+    let canonical_target = if is_root {
+        canonical_base.clone()
     } else {
-        base_dir.join(&rel_path)
+        path_policy_for(&app, &state).resolve_in(
+            "task_list_directory",
+            &base_dir,
+            &relative_path,
+        )?
     };
 
-    if !target_dir.exists() || !target_dir.is_dir() {
+    if !canonical_target.is_dir() {
         return Ok(Vec::new());
     }
 
-    let canonical_base = std::fs::canonicalize(&base_dir).map_err(|e| e.to_string())?;
-    let canonical_target = std::fs::canonicalize(&target_dir).map_err(|e| e.to_string())?;
-    if !canonical_target.starts_with(&canonical_base) {
-        return Err("path escapes task directory".to_string());
-    }
-
     let mut entries = Vec::new();
     for entry in std::fs::read_dir(&canonical_target).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
@@ -1140,7 +1193,7 @@ fn task_list_directory(
             .as_ref()
             .and_then(|m| m.modified().ok())
             .and_then(to_epoch_ms);
-        let entry_rel_path = if rel_path.as_os_str().is_empty() {
+        let entry_rel_path = if is_root {
             entry.file_name().to_string_lossy().to_string()
         } else {
             format!("{}/{}", relative_path, entry.file_name().to_string_lossy())
@@ -1167,39 +1220,24 @@ fn task_list_directory(
 
 #[tauri::command]
 fn workspace_list_directory(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
     cwd: String,
     relative_path: String,
 ) -> Result<Vec<TaskDirectoryEntry>, String> {
-    if cwd.trim().is_empty() {
-        return Err("cwd cannot be empty".to_string());
-    }
-    let base_dir = std::path::PathBuf::from(&cwd);
-    if !base_dir.exists() || !base_dir.is_dir() {
-        return Err("cwd is not a directory".to_string());
-    }
-
-    let rel_path = if relative_path.trim().is_empty() {
-        std::path::PathBuf::new()
-    } else {
-        validate_task_rel_path(&relative_path)?
-    };
-
-    let target_dir = if rel_path.as_os_str().is_empty() {
-        base_dir.clone()
+    let policy = path_policy_for(&app, &state);
+    let canonical_base = policy.resolve_dir("workspace_list_directory", &cwd)?;
+    let is_root = relative_path.trim().is_empty();
+    let canonical_target = if is_root {
+        canonical_base.clone()
     } else {
-        base_dir.join(&rel_path)
+        policy.resolve_in("workspace_list_directory", &canonical_base, &relative_path)?
     };
 
-    if !target_dir.exists() || !target_dir.is_dir() {
+    if !canonical_target.is_dir() {
         return Ok(Vec::new());
     }
 
-    let canonical_base = std::fs::canonicalize(&base_dir).map_err(|e| e.to_string())?;
-    let canonical_target = std::fs::canonicalize(&target_dir).map_err(|e| e.to_string())?;
-    if !canonical_target.starts_with(&canonical_base) {
-        return Err("path escapes workspace root".to_string());
-    }
-
     let mut entries = Vec::new();
     for entry in std::fs::read_dir(&canonical_target).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
@@ -1222,7 +1260,7 @@ fn workspace_list_directory(
             .as_ref()
             .and_then(|m| m.modified().ok())
             .and_then(to_epoch_ms);
-        let entry_rel_path = if rel_path.as_os_str().is_empty() {
+        let entry_rel_path = if is_root {
             entry.file_name().to_string_lossy().to_string()
         } else {
             format!("{}/{}", relative_path, entry.file_name().to_string_lossy())
@@ -1248,17 +1286,16 @@ fn workspace_list_directory(
 }
 
 #[tauri::command]
-fn workspace_write_file(cwd: String, relative_path: String, content: String) -> Result<(), String> {
-    if cwd.trim().is_empty() {
-        return Err("cwd cannot be empty".to_string());
-    }
-    let base_dir = std::path::PathBuf::from(cwd.trim());
-    if !base_dir.exists() || !base_dir.is_dir() {
-        return Err("cwd is not a directory".to_string());
-    }
-
-    let rel_path = validate_task_rel_path(&relative_path)?;
-    let target_file = base_dir.join(&rel_path);
+fn workspace_write_file(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    cwd: String,
+    relative_path: String,
+    content: String,
+) -> Result<(), String> {
+    let policy = path_policy_for(&app, &state);
+    let base_dir = policy.resolve_dir("workspace_write_file", &cwd)?;
+    let target_file = policy.resolve_in("workspace_write_file", &base_dir, &relative_path)?;
     if !target_file.exists() {
         return Err("file does not exist".to_string());
     }
@@ -1271,69 +1308,46 @@ fn workspace_write_file(cwd: String, relative_path: String, content: String) ->
         return Err("content too large (max 1MB)".to_string());
     }
 
-    let canonical_base = std::fs::canonicalize(&base_dir).map_err(|e| e.to_string())?;
-    let canonical_target = std::fs::canonicalize(&target_file).map_err(|e| e.to_string())?;
-    if !canonical_target.starts_with(&canonical_base) {
-        return Err("path escapes workspace root".to_string());
-    }
-
-    std::fs::write(&canonical_target, content.as_bytes()).map_err(|e| e.to_string())?;
+    std::fs::write(&target_file, content.as_bytes()).map_err(|e| e.to_string())?;
     Ok(())
 }
 
 #[tauri::command]
 fn workspace_rename_file(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
     cwd: String,
     from_relative_path: String,
     to_relative_path: String,
 ) -> Result<(), String> {
     use std::path::Path;
 
-    if cwd.trim().is_empty() {
-        return Err("cwd cannot be empty".to_string());
-    }
-    let base_dir = std::path::PathBuf::from(cwd.trim());
-    if !base_dir.exists() || !base_dir.is_dir() {
-        return Err("cwd is not a directory".to_string());
-    }
-
-    let from_rel = validate_task_rel_path(&from_relative_path)?;
-    let to_rel = validate_task_rel_path(&to_relative_path)?;
+    let policy = path_policy_for(&app, &state);
+    let base_dir = policy.resolve_dir("workspace_rename_file", &cwd)?;
+    let canonical_from =
+        policy.resolve_in("workspace_rename_file", &base_dir, &from_relative_path)?;
+    let canonical_to = policy.resolve_in("workspace_rename_file", &base_dir, &to_relative_path)?;
 
     // Only allow renaming within the same directory (no moves).
-    let from_parent = from_rel.parent().unwrap_or(Path::new(""));
-    let to_parent = to_rel.parent().unwrap_or(Path::new(""));
+    let from_parent = Path::new(&from_relative_path)
+        .parent()
+        .unwrap_or(Path::new(""));
+    let to_parent = Path::new(&to_relative_path)
+        .parent()
+        .unwrap_or(Path::new(""));
     if from_parent != to_parent {
         return Err("renaming across directories is not allowed".to_string());
     }
 
-    let from_path = base_dir.join(&from_rel);
-    if !from_path.exists() {
+    if !canonical_from.exists() {
         return Err("source file does not exist".to_string());
     }
-    if !from_path.is_file() {
+    if !canonical_from.is_file() {
         return Err("source path is not a file".to_string());
     }
-
-    let canonical_base = std::fs::canonicalize(&base_dir).map_err(|e| e.to_string())?;
-    let canonical_from = std::fs::canonicalize(&from_path).map_err(|e| e.to_string())?;
-    if !canonical_from.starts_with(&canonical_base) {
-        return Err("path escapes workspace root".to_string());
-    }
-
-    let to_file_name = to_rel
-        .file_name()
-        .ok_or_else(|| "destination path must be a file".to_string())?;
-    let to_parent_path = base_dir.join(to_parent);
-    if !to_parent_path.exists() || !to_parent_path.is_dir() {
+    if !canonical_to.parent().is_some_and(|p| p.is_dir()) {
         return Err("destination directory does not exist".to_string());
     }
-    let canonical_to_parent = std::fs::canonicalize(&to_parent_path).map_err(|e| e.to_string())?;
-    if !canonical_to_parent.starts_with(&canonical_base) {
-        return Err("path escapes workspace root".to_string());
-    }
-
-    let canonical_to = canonical_to_parent.join(to_file_name);
     if canonical_to.exists() {
         return Err("destination already exists".to_string());
     }
@@ -1373,6 +1387,7 @@ fn list_shared_artifacts(
 
 #[tauri::command]
 fn read_shared_artifact(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     task_id: String,
     category: String,
@@ -1385,18 +1400,12 @@ fn read_shared_artifact(
         .workspace_root()
         .to_path_buf();
     let base_dir = shared_category_dir(&workspace_root, &task_id, &category)?;
-    let rel_path = validate_artifact_rel_path(&path)?;
-    let full_path = base_dir.join(&rel_path);
-    if !full_path.exists() {
+    let canonical_file =
+        path_policy_for(&app, &state).resolve_in("read_shared_artifact", &base_dir, &path)?;
+    if !canonical_file.is_file() {
         return Err("artifact not found".to_string());
     }
 
-    let canonical_base = std::fs::canonicalize(&base_dir).map_err(|e| e.to_string())?;
-    let canonical_file = std::fs::canonicalize(&full_path).map_err(|e| e.to_string())?;
-    if !canonical_file.starts_with(&canonical_base) {
-        return Err("artifact path escapes category directory".to_string());
-    }
-
     let content = std::fs::read_to_string(&canonical_file).map_err(|e| e.to_string())?;
     let updated_at_ms = modified_ms(&canonical_file);
 
@@ -1407,6 +1416,35 @@ fn read_shared_artifact(
     })
 }
 
+#[tauri::command]
+fn task_evidence_check(
+    state: tauri::State<'_, AppState>,
+    task_id: String,
+) -> Result<coco_core::evidence::EvidenceCheckReport, String> {
+    validate_id(&task_id, "task_id")?;
+    state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .evidence_check(&task_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn task_evidence_resolve(
+    state: tauri::State<'_, AppState>,
+    task_id: String,
+    evidence_id: String,
+) -> Result<coco_core::evidence::ResolvedEvidence, String> {
+    validate_id(&task_id, "task_id")?;
+    state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .evidence_resolve(&task_id, &evidence_id)
+        .map_err(|e| e.to_string())
+}
+
 fn codex_config_path() -> Result<std::path::PathBuf, String> {
     let home = std::env::var_os("HOME")
         .or_else(|| std::env::var_os("USERPROFILE"))
@@ -1417,11 +1455,15 @@ fn codex_config_path() -> Result<std::path::PathBuf, String> {
         .join("config.toml"))
 }
 
-fn default_codex_home_dir() -> Result<std::path::PathBuf, String> {
-    let home = std::env::var_os("HOME")
+fn home_dir() -> Option<std::path::PathBuf> {
+    std::env::var_os("HOME")
         .or_else(|| std::env::var_os("USERPROFILE"))
-        .ok_or_else(|| "HOME directory not found".to_string())?;
-    Ok(std::path::PathBuf::from(home).join(".codex"))
+        .map(std::path::PathBuf::from)
+}
+
+fn default_codex_home_dir() -> Result<std::path::PathBuf, String> {
+    let home = home_dir().ok_or_else(|| "HOME directory not found".to_string())?;
+    Ok(home.join(".codex"))
 }
 
 fn rollout_date_parts_from_filename(name: &str) -> Option<(String, String, String)> {
@@ -1542,12 +1584,145 @@ async fn get_or_start_codex(
     }
 
     let codex_home = default_codex_home_dir()?;
-    let profile = { state.codex_profile.lock().await.clone() };
+    let profile = codex_chat_selection::resolve(&cwd, None).selection.profile;
     let id = pool.ensure(app, &cwd, &codex_home, profile).await?;
     pool.get(&id)
         .ok_or_else(|| "codex app-server pool internal error: missing server".to_string())
 }
 
+/// The app-server for a thread's resolved profile (see `codex_chat_selection`). With `load`, the
+/// thread is resumed there first unless that instance already has it, e.g. right after the
+/// thread was switched to another profile.
+async fn get_or_start_codex_for_thread(
+    state: &tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    app_server_id: Option<String>,
+    thread_id: &str,
+    load: bool,
+) -> Result<CodexAppServer, String> {
+    let codex = if app_server_id.is_some() {
+        get_or_start_codex(state, app, app_server_id).await?
+    } else {
+        let cwd = state
+            .orchestrator
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .workspace_root()
+            .to_path_buf();
+        let codex_home = default_codex_home_dir()?;
+        let profile = codex_chat_selection::resolve(&cwd, Some(thread_id))
+            .selection
+            .profile;
+        let mut pool = state.codex_pool.lock().await;
+        let id = pool.ensure(app, &cwd, &codex_home, profile).await?;
+        pool.get(&id)
+            .ok_or_else(|| "codex app-server pool internal error: missing server".to_string())?
+    };
+
+    if load {
+        let loaded = state
+            .codex_pool
+            .lock()
+            .await
+            .is_thread_loaded(codex.app_server_id(), thread_id);
+        if !loaded {
+            let params = serde_json::json!({ "threadId": thread_id });
+            codex.request("thread/resume", Some(params)).await?;
+            mark_codex_thread_loaded(state, &codex, thread_id).await;
+        }
+    }
+    Ok(codex)
+}
+
+async fn mark_codex_thread_loaded(
+    state: &tauri::State<'_, AppState>,
+    codex: &CodexAppServer,
+    thread_id: &str,
+) {
+    state
+        .codex_pool
+        .lock()
+        .await
+        .mark_thread_loaded(codex.app_server_id(), thread_id);
+}
+
+#[tauri::command]
+fn codex_file_change_get(
+    state: tauri::State<'_, AppState>,
+    item_id: String,
+) -> Result<codex_file_change::FileChangeView, String> {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    let record = codex_file_change::read_record(&workspace_root, &item_id)?;
+    Ok(codex_file_change::view(&record))
+}
+
+fn codex_file_change_apply(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    op: &str,
+    item_id: &str,
+    direction: codex_file_change::Direction,
+    path: Option<String>,
+    hunk: Option<usize>,
+) -> Result<codex_file_change::FileChangeApplyResult, String> {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    let record = codex_file_change::read_record(&workspace_root, item_id)?;
+    let policy = path_policy_for(app, state);
+    codex_file_change::apply(&record, direction, path.as_deref(), hunk, |p| {
+        policy.resolve(op, p).map_err(String::from)
+    })
+}
+
+/// Undo a completed `fileChange` item (all files, one `path`, or one `hunk` of it), keeping
+/// later edits via a three-way merge; nothing is written if any file conflicts.
+#[tauri::command]
+fn codex_file_change_revert(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    item_id: String,
+    path: Option<String>,
+    hunk: Option<usize>,
+) -> Result<codex_file_change::FileChangeApplyResult, String> {
+    codex_file_change_apply(
+        &app,
+        &state,
+        "codex_file_change_revert",
+        &item_id,
+        codex_file_change::Direction::Revert,
+        path,
+        hunk,
+    )
+}
+
+#[tauri::command]
+fn codex_file_change_reapply(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    item_id: String,
+    path: Option<String>,
+    hunk: Option<usize>,
+) -> Result<codex_file_change::FileChangeApplyResult, String> {
+    codex_file_change_apply(
+        &app,
+        &state,
+        "codex_file_change_reapply",
+        &item_id,
+        codex_file_change::Direction::Reapply,
+        path,
+        hunk,
+    )
+}
+
 #[tauri::command]
 fn workspace_root_get(state: tauri::State<'_, AppState>) -> Result<String, String> {
     Ok(state
@@ -1600,14 +1775,64 @@ fn workspace_recent_list(app: tauri::AppHandle) -> Vec<String> {
 }
 
 #[tauri::command]
-fn window_new(app: tauri::AppHandle) -> Result<String, String> {
-    let id = NEXT_WINDOW_ID.fetch_add(1, Ordering::SeqCst);
-    let label = format!("main-{id}");
+fn workspace_allowed_roots_list(app: tauri::AppHandle) -> Vec<String> {
+    read_allowed_roots(&app)
+}
 
-    tauri::WebviewWindowBuilder::new(&app, label.clone(), tauri::WebviewUrl::default())
-        .title("Coco")
-        .inner_size(900.0, 650.0)
-        .min_inner_size(900.0, 650.0)
+/// Ask the user for a directory in a native folder picker and allow file commands under it in
+/// addition to the workspace root (e.g. a related repo). The webview never supplies the path, so
+/// it cannot widen the policy on its own. Returns the added root, or `None` when cancelled.
+#[tauri::command]
+async fn workspace_allowed_root_pick(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+    let dialog_app = app.clone();
+    let picked = tauri::async_runtime::spawn_blocking(move || {
+        dialog_app
+            .dialog()
+            .file()
+            .set_title("Allow access to folder")
+            .blocking_pick_folder()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let picked = picked.into_path().map_err(|e| e.to_string())?;
+    let root = path_policy::validate_extra_root(&picked, home_dir().as_deref())?;
+    let root = root.to_string_lossy().to_string();
+
+    let mut roots = read_allowed_roots(&app);
+    if !roots.contains(&root) {
+        roots.push(root.clone());
+        persist_allowed_roots(&app, &roots)?;
+    }
+    Ok(Some(root))
+}
+
+#[tauri::command]
+fn workspace_allowed_root_remove(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let canonical = std::fs::canonicalize(path.trim())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.trim().to_string());
+    let mut roots = read_allowed_roots(&app);
+    roots.retain(|r| r != &canonical && r != path.trim());
+    persist_allowed_roots(&app, &roots)?;
+    Ok(roots)
+}
+
+#[tauri::command]
+fn window_new(app: tauri::AppHandle) -> Result<String, String> {
+    let id = NEXT_WINDOW_ID.fetch_add(1, Ordering::SeqCst);
+    let label = format!("main-{id}");
+
+    tauri::WebviewWindowBuilder::new(&app, label.clone(), tauri::WebviewUrl::default())
+        .title("Coco")
+        .inner_size(900.0, 650.0)
+        .min_inner_size(900.0, 650.0)
         .build()
         .map_err(|e| e.to_string())?;
 
@@ -1645,7 +1870,7 @@ async fn codex_app_server_ensure(
         .filter(|v| !v.is_empty());
     let profile = match profile {
         Some(p) => Some(p),
-        None => state.codex_profile.lock().await.clone(),
+        None => codex_chat_selection::resolve(&cwd, None).selection.profile,
     };
 
     let mut pool = state.codex_pool.lock().await;
@@ -1664,6 +1889,7 @@ async fn codex_app_server_shutdown(
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn codex_thread_list(
     state: tauri::State<'_, AppState>,
     app: tauri::AppHandle,
@@ -1672,6 +1898,7 @@ async fn codex_thread_list(
     cwd_filter: Option<String>,
     pinned_thread_id: Option<String>,
     app_server_id: Option<String>,
+    meta_filter: Option<codex_thread_meta::ThreadMetaFilter>,
 ) -> Result<CodexThreadListResponse, String> {
     // Get current workspace for filtering
     let workspace_root = state
@@ -1736,6 +1963,14 @@ async fn codex_thread_list(
             }
         }
 
+        let meta = codex_thread_meta::read(&workspace_root, id).unwrap_or_else(|err| {
+            log::warn!("ignoring thread metadata: {err}");
+            codex_thread_meta::ThreadMeta::default()
+        });
+        if !is_pinned && !meta_filter.as_ref().map_or(true, |f| f.matches(&meta)) {
+            continue;
+        }
+
         let preview = entry
             .get("preview")
             .and_then(|v| v.as_str())
@@ -1743,15 +1978,10 @@ async fn codex_thread_list(
             .to_string();
 
         let mut title: Option<String> = None;
-        if let Some(sidecar) = read_codex_thread_title_sidecar(&workspace_root, id) {
-            if sidecar.source == "manual" {
-                let raw = sidecar.title.trim();
-                if !raw.is_empty() {
-                    let trimmed = truncate_unicode_chars(raw, 50).trim().to_string();
-                    if !trimmed.is_empty() {
-                        title = Some(trimmed);
-                    }
-                }
+        if let Some(raw) = meta.manual_title() {
+            let trimmed = truncate_unicode_chars(raw, 50).trim().to_string();
+            if !trimmed.is_empty() {
+                title = Some(trimmed);
             }
         }
 
@@ -1785,14 +2015,24 @@ async fn codex_thread_list(
             created_at,
             updated_at_ms,
             interaction_count,
+            tags: meta.tags,
+            pinned: meta.pinned,
+            folder: meta.folder,
+            note: meta.note,
+            task_id: meta.task_id,
         });
     }
 
-    threads.sort_by(|a, b| match (a.updated_at_ms, b.updated_at_ms) {
-        (Some(a_ts), Some(b_ts)) => b_ts.cmp(&a_ts),
-        (Some(_), None) => std::cmp::Ordering::Less,
-        (None, Some(_)) => std::cmp::Ordering::Greater,
-        (None, None) => b.created_at.cmp(&a.created_at),
+    // Pinned threads first, then most recently updated.
+    threads.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| match (a.updated_at_ms, b.updated_at_ms) {
+                (Some(a_ts), Some(b_ts)) => b_ts.cmp(&a_ts),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => b.created_at.cmp(&a.created_at),
+            })
     });
 
     if let Some(pinned_id) = pinned_thread_id.as_deref() {
@@ -1833,13 +2073,43 @@ fn codex_thread_title_set(
         return Err("title must not be empty".to_string());
     }
 
-    let sidecar = CodexThreadTitleSidecar {
-        title: truncated,
-        source: "manual".to_string(),
-        updated_at_ms: to_epoch_ms(std::time::SystemTime::now()),
-    };
-    write_codex_thread_title_sidecar(&workspace_root, &thread_id, &sidecar)?;
-    Ok(())
+    codex_thread_meta::set_manual_title(&workspace_root, &thread_id, truncated)
+}
+
+/// Applies one metadata patch (pin, tags, folder, note, task link) to several threads at once.
+#[tauri::command]
+fn codex_thread_meta_update(
+    state: tauri::State<'_, AppState>,
+    thread_ids: Vec<String>,
+    patch: codex_thread_meta::ThreadMetaPatch,
+) -> Result<Vec<codex_thread_meta::ThreadMetaEntry>, String> {
+    for thread_id in &thread_ids {
+        validate_id(thread_id, "thread_id")?;
+    }
+    if let Some(task_id) = patch.task_id.as_deref().filter(|v| !v.trim().is_empty()) {
+        validate_id(task_id.trim(), "task_id")?;
+    }
+
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    codex_thread_meta::update(&workspace_root, &thread_ids, &patch)
+}
+
+#[tauri::command]
+fn codex_thread_meta_catalog(
+    state: tauri::State<'_, AppState>,
+) -> Result<codex_thread_meta::ThreadMetaCatalog, String> {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    Ok(codex_thread_meta::catalog(&workspace_root))
 }
 
 #[tauri::command]
@@ -1858,7 +2128,8 @@ async fn codex_thread_archive(
         .workspace_root()
         .to_path_buf();
 
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
+    let codex =
+        get_or_start_codex_for_thread(&state, app, app_server_id, &thread_id, false).await?;
     let params = serde_json::json!({ "threadId": thread_id.clone() });
     let _ = codex.request("thread/archive", Some(params)).await?;
 
@@ -1868,7 +2139,7 @@ async fn codex_thread_archive(
     }
 
     // Best-effort: ignore sidecar cleanup failures.
-    let _ = remove_codex_thread_title_sidecar(&workspace_root, &thread_id);
+    let _ = codex_thread_meta::remove(&workspace_root, &thread_id);
     Ok(())
 }
 
@@ -1936,7 +2207,15 @@ async fn codex_thread_start(
         "model": model,
     });
 
-    codex.request("thread/start", Some(params)).await
+    let res = codex.request("thread/start", Some(params)).await?;
+    if let Some(thread_id) = res
+        .get("thread")
+        .and_then(|t| t.get("id"))
+        .and_then(|v| v.as_str())
+    {
+        mark_codex_thread_loaded(&state, &codex, thread_id).await;
+    }
+    Ok(res)
 }
 
 #[tauri::command]
@@ -1946,9 +2225,11 @@ async fn codex_thread_resume(
     thread_id: String,
     app_server_id: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
+    let codex =
+        get_or_start_codex_for_thread(&state, app, app_server_id, &thread_id, false).await?;
     let params = serde_json::json!({ "threadId": thread_id });
     let res = codex.request("thread/resume", Some(params)).await?;
+    mark_codex_thread_loaded(&state, &codex, &thread_id).await;
     codex_rollout_restore::augment_thread_resume_response(res, &thread_id).await
 }
 
@@ -1960,7 +2241,8 @@ async fn codex_thread_fork(
     path: Option<String>,
     app_server_id: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
+    let codex =
+        get_or_start_codex_for_thread(&state, app, app_server_id, &thread_id, false).await?;
 
     let cwd = state
         .orchestrator
@@ -1985,66 +2267,255 @@ async fn codex_thread_fork(
     if new_thread_id.is_empty() {
         return Err("invalid thread/fork response: thread.id".to_string());
     }
+    mark_codex_thread_loaded(&state, &codex, &new_thread_id).await;
+    // The fork stays on its source's profile.
+    if let Err(err) =
+        codex_chat_selection::copy_thread(std::path::Path::new(&cwd), &thread_id, &new_thread_id)
+    {
+        log::warn!("failed to copy chat selection to forked thread {new_thread_id}: {err}");
+    }
 
     codex_rollout_restore::augment_thread_resume_response(res, &new_thread_id).await
 }
 
+/// Roll back the last `num_turns` turns. `from_turn_id` is the earliest of them: its checkpoint
+/// and every later one are dropped, and with `restore_files` the files first go back to that
+/// checkpoint (see `codex_checkpoint_preview`). The files are restored before the thread is
+/// touched, and put back if the rollback then fails.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn codex_thread_rollback(
     state: tauri::State<'_, AppState>,
     app: tauri::AppHandle,
     thread_id: String,
     num_turns: Option<u32>,
+    from_turn_id: Option<String>,
+    restore_files: Option<bool>,
     app_server_id: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
-
-    let num_turns = num_turns.unwrap_or(1);
+    let policy = path_policy_for(&app, &state);
+    let codex = get_or_start_codex_for_thread(&state, app, app_server_id, &thread_id, true).await?;
+
+    let from_turn_id = from_turn_id.filter(|v| !v.trim().is_empty());
+    // With `fromTurnId`, that turn and everything after it is dropped; the count follows from
+    // the thread's turns so files and history cannot end up at different turns.
+    let num_turns = match &from_turn_id {
+        Some(turn_id) => {
+            let params = serde_json::json!({ "threadId": thread_id });
+            let resumed = codex.request("thread/resume", Some(params)).await?;
+            let turns = resumed
+                .get("thread")
+                .and_then(|t| t.get("turns"))
+                .and_then(|v| v.as_array())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let idx = turns
+                .iter()
+                .position(|t| t.get("id").and_then(|v| v.as_str()) == Some(turn_id.as_str()))
+                .ok_or_else(|| format!("turn {turn_id} is not part of thread {thread_id}"))?;
+            let derived = (turns.len() - idx) as u32;
+            if num_turns.is_some_and(|n| n != derived) {
+                return Err(format!(
+                    "numTurns {} does not match fromTurnId {turn_id} ({derived} turns)",
+                    num_turns.unwrap_or_default()
+                ));
+            }
+            derived
+        }
+        None => num_turns.unwrap_or(1),
+    };
     if num_turns < 1 {
         return Err("numTurns must be >= 1".to_string());
     }
+    let restore_files = restore_files.unwrap_or(false);
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+
+    let restored = match (&from_turn_id, restore_files) {
+        (None, true) => return Err("restoreFiles requires fromTurnId".to_string()),
+        (Some(turn_id), true) => {
+            let (root, thread_id, turn_id) =
+                (workspace_root.clone(), thread_id.clone(), turn_id.clone());
+            let restored = tauri::async_runtime::spawn_blocking(move || {
+                codex_checkpoint::restore(&root, &thread_id, &turn_id, &policy)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+            Some(restored)
+        }
+        _ => None,
+    };
 
     let params = serde_json::json!({ "threadId": thread_id, "numTurns": num_turns });
-    let res = codex.request("thread/rollback", Some(params)).await?;
-    let resolved_thread_id = res
-        .get("thread")
-        .and_then(|t| t.get("id"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
+    let rolled_back = codex
+        .request("thread/rollback", Some(params))
+        .await
+        .and_then(|res| {
+            let resolved_thread_id = res
+                .get("thread")
+                .and_then(|t| t.get("id"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            if resolved_thread_id.is_empty() {
+                return Err("invalid thread/rollback response: thread.id".to_string());
+            }
+            Ok((res, resolved_thread_id))
+        });
+    let (res, resolved_thread_id) = match rolled_back {
+        Ok(ok) => ok,
+        Err(err) => {
+            let Some(restored) = restored else {
+                return Err(err);
+            };
+            let root = workspace_root.clone();
+            let undone = tauri::async_runtime::spawn_blocking(move || {
+                codex_checkpoint::undo_restore(&root, &restored)
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+            return Err(match undone {
+                Ok(()) => err,
+                Err(undo_err) => {
+                    format!("{err} (restoring the files back also failed: {undo_err})")
+                }
+            });
+        }
+    };
 
-    if resolved_thread_id.is_empty() {
-        return Err("invalid thread/rollback response: thread.id".to_string());
+    if let Some(turn_id) = from_turn_id {
+        let checkpoint_thread_id = thread_id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            codex_checkpoint::forget(&workspace_root, &checkpoint_thread_id, &turn_id)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
     }
 
-    codex_rollout_restore::augment_thread_resume_response(res, &resolved_thread_id).await
+    let mut res =
+        codex_rollout_restore::augment_thread_resume_response(res, &resolved_thread_id).await?;
+    if let Some(restored) = restored {
+        res["checkpointRestore"] = serde_json::to_value(restored).map_err(|e| e.to_string())?;
+    }
+    Ok(res)
 }
 
 #[tauri::command]
-async fn codex_thread_watch_start(
+fn codex_checkpoint_list(
     state: tauri::State<'_, AppState>,
-    app: tauri::AppHandle,
     thread_id: String,
-    path: String,
-) -> Result<(), String> {
-    let thread_id = thread_id.trim().to_string();
-    if thread_id.is_empty() {
-        return Err("threadId must not be empty".to_string());
+) -> Result<Vec<codex_checkpoint::Checkpoint>, String> {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    codex_checkpoint::list(&workspace_root, &thread_id)
+}
+
+/// Files that `codex_thread_rollback(from_turn_id, restore_files)` would restore.
+#[tauri::command]
+async fn codex_checkpoint_preview(
+    state: tauri::State<'_, AppState>,
+    thread_id: String,
+    turn_id: String,
+) -> Result<codex_checkpoint::CheckpointPreview, String> {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        codex_checkpoint::preview(&workspace_root, &thread_id, &turn_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Full-text search over Codex rollouts (messages, commands and touched files).
+#[tauri::command]
+async fn codex_thread_search(
+    state: tauri::State<'_, AppState>,
+    query: String,
+    filters: Option<codex_thread_search::ThreadSearchFilters>,
+) -> Result<Vec<codex_thread_search::ThreadSearchHit>, String> {
+    let codex_home = default_codex_home_dir()?;
+    let index = std::sync::Arc::clone(&state.thread_search);
+    let filters = filters.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut index = index
+            .lock()
+            .map_err(|_| "thread search index poisoned".to_string())?;
+        index.refresh(&codex_home)?;
+        Ok(index.search(&query, &filters))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn emit_watch_event(app: &tauri::AppHandle, emission: watch_service::WatchEmit) {
+    use tauri::Emitter;
+    match emission {
+        watch_service::WatchEmit::Thread { windows, event } => {
+            for window in windows {
+                let _ = app.emit_to(window.as_str(), watch_service::THREAD_FS_EVENT, &event);
+            }
+        }
+        watch_service::WatchEmit::Task { windows, event } => {
+            for window in windows {
+                let _ = app.emit_to(window.as_str(), watch_service::TASK_FS_EVENT, &event);
+            }
+        }
     }
-    let path = path.trim();
-    if path.is_empty() {
-        return Err("path must not be empty".to_string());
+}
+
+/// Subscribes the calling window to a rollout file or a task directory. Events are delivered only
+/// to subscribed windows; the returned id is passed to `watch_unsubscribe`.
+#[tauri::command]
+fn watch_subscribe(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    window: tauri::Window,
+    target: watch_service::WatchRequest,
+) -> Result<u64, String> {
+    match target {
+        watch_service::WatchRequest::Rollout { thread_id, path } => {
+            let thread_id = thread_id.trim().to_string();
+            if thread_id.is_empty() {
+                return Err("threadId must not be empty".to_string());
+            }
+            let path = rollout_policy_for(&app)?.resolve("watch_subscribe", path.trim())?;
+            if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+                return Err("path is not a rollout file".to_string());
+            }
+            state
+                .watch
+                .subscribe_rollout(window.label(), thread_id, path)
+        }
+        watch_service::WatchRequest::Task { task_id } => {
+            validate_id(&task_id, "taskId")?;
+            let workspace_root = state
+                .orchestrator
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .workspace_root()
+                .to_path_buf();
+            let dir = task_root_dir(&workspace_root, &task_id);
+            state.watch.subscribe_task(window.label(), task_id, dir)
+        }
     }
-    let path = std::path::PathBuf::from(path);
-    let mut watcher = state.thread_watch.lock().await;
-    watcher.start(app, thread_id, path)
 }
 
 #[tauri::command]
-async fn codex_thread_watch_stop(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let mut watcher = state.thread_watch.lock().await;
-    watcher.stop();
-    Ok(())
+fn watch_unsubscribe(state: tauri::State<'_, AppState>, subscription_id: u64) {
+    state.watch.unsubscribe(subscription_id);
 }
 
 #[tauri::command]
@@ -2060,13 +2531,36 @@ async fn codex_turn_start(
     cwd: Option<String>,
     app_server_id: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
+    // The turn runs (and may write) in `cwd`; it has to be inside the path policy like any
+    // other file command.
+    let policy = path_policy_for(&app, &state);
+    let cwd = cwd
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            policy
+                .resolve_dir("codex_turn_start", v)
+                .map(|dir| dir.to_string_lossy().into_owned())
+        })
+        .transpose()?;
+    let codex = get_or_start_codex_for_thread(&state, app, app_server_id, &thread_id, true).await?;
 
-    let effort = match effort.as_deref() {
-        None => None,
-        Some("") => None,
-        Some(v) => Some(v.to_string()),
-    };
+    // Anything the caller leaves unset comes from the thread's stored selection.
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    let selection = codex_chat_selection::resolve(&workspace_root, Some(&thread_id)).selection;
+    let model = model.filter(|v| !v.is_empty()).or(selection.model);
+    let effort = effort
+        .filter(|v| !v.is_empty())
+        .or(selection.model_reasoning_effort);
+    let approval_policy = approval_policy
+        .filter(|v| !v.is_empty())
+        .or(selection.approval_policy);
 
     let approval_policy = match approval_policy.as_deref() {
         None => None,
@@ -2080,12 +2574,6 @@ async fn codex_turn_start(
         }
     };
 
-    let cwd = cwd
-        .as_deref()
-        .map(str::trim)
-        .filter(|v| !v.is_empty())
-        .map(|v| v.to_string());
-
     let params = serde_json::json!({
         "threadId": thread_id,
         "input": input,
@@ -2095,7 +2583,43 @@ async fn codex_turn_start(
         "cwd": cwd,
     });
 
-    codex.request("turn/start", Some(params)).await
+    // Snapshot the tree before the app-server can touch it; the snapshot becomes the turn's
+    // checkpoint once its id is known (here or on `turn/started`).
+    let snapshot_cwd = cwd
+        .as_deref()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| codex.cwd().to_path_buf());
+    let snapshot_thread_id = thread_id.clone();
+    let snapshot = tauri::async_runtime::spawn_blocking(move || {
+        codex_checkpoint::snapshot(&workspace_root, &snapshot_thread_id, &snapshot_cwd, &policy)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    if let Err(err) = snapshot {
+        log::info!("[CodexCheckpoint] snapshot before turn on {thread_id} failed: {err}");
+    }
+
+    let res = match codex.request("turn/start", Some(params)).await {
+        Ok(res) => res,
+        Err(err) => {
+            codex_checkpoint::discard(&thread_id);
+            return Err(err);
+        }
+    };
+    if let Some(turn_id) = res
+        .get("turn")
+        .and_then(|t| t.get("id"))
+        .and_then(|v| v.as_str())
+    {
+        let (thread_id, turn_id) = (thread_id.clone(), turn_id.to_string());
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(err) = codex_checkpoint::attach(&thread_id, &turn_id) {
+                log::info!("[CodexCheckpoint] checkpoint for {thread_id}/{turn_id} failed: {err}");
+            }
+        });
+    }
+    Ok(res)
 }
 
 #[tauri::command]
@@ -2106,7 +2630,8 @@ async fn codex_turn_interrupt(
     turn_id: String,
     app_server_id: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
+    let codex =
+        get_or_start_codex_for_thread(&state, app, app_server_id, &thread_id, false).await?;
     let params = serde_json::json!({ "threadId": thread_id, "turnId": turn_id });
     codex.request("turn/interrupt", Some(params)).await
 }
@@ -2117,6 +2642,7 @@ async fn codex_respond_approval(
     app: tauri::AppHandle,
     request_id: i64,
     decision: String,
+    thread_id: Option<String>,
     app_server_id: Option<String>,
 ) -> Result<(), String> {
     let decision = decision.to_lowercase();
@@ -2124,7 +2650,13 @@ async fn codex_respond_approval(
         return Err("decision must be accept or decline".to_string());
     }
 
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
+    // Request ids are per app-server; the thread tells which instance asked.
+    let codex = match thread_id.as_deref() {
+        Some(thread_id) => {
+            get_or_start_codex_for_thread(&state, app, app_server_id, thread_id, false).await?
+        }
+        None => get_or_start_codex(&state, app, app_server_id).await?,
+    };
     codex
         .respond(request_id, serde_json::json!({ "decision": decision }))
         .await
@@ -2148,9 +2680,16 @@ async fn codex_config_read_effective(
     state: tauri::State<'_, AppState>,
     app: tauri::AppHandle,
     include_layers: Option<bool>,
+    thread_id: Option<String>,
     app_server_id: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    let codex = get_or_start_codex(&state, app, app_server_id).await?;
+    // Effective config depends on the profile the thread's app-server runs with.
+    let codex = match thread_id.as_deref() {
+        Some(thread_id) => {
+            get_or_start_codex_for_thread(&state, app, app_server_id, thread_id, false).await?
+        }
+        None => get_or_start_codex(&state, app, app_server_id).await?,
+    };
     let params = serde_json::json!({
         "includeLayers": include_layers.unwrap_or(false),
     });
@@ -2226,29 +2765,41 @@ async fn codex_config_write_chat_defaults(
 }
 
 #[tauri::command]
-async fn codex_set_profile(
+fn codex_chat_selection_get(
     state: tauri::State<'_, AppState>,
-    profile: Option<String>,
-) -> Result<(), String> {
-    let normalized = profile
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty());
+    thread_id: Option<String>,
+) -> codex_chat_selection::ResolvedChatSelection {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    codex_chat_selection::resolve(&workspace_root, thread_id.as_deref())
+}
 
-    {
-        let mut active_profile = state.codex_profile.lock().await;
-        if *active_profile == normalized {
-            return Ok(());
+/// Stores the workspace defaults, or with `thread_id` that thread's override (`selection: null`
+/// clears it). Profile changes take effect on the next request: each profile has its own
+/// app-server, and a thread is resumed on its new instance before its next turn.
+#[tauri::command]
+fn codex_chat_selection_set(
+    state: tauri::State<'_, AppState>,
+    thread_id: Option<String>,
+    selection: Option<codex_chat_selection::ChatSelection>,
+) -> Result<codex_chat_selection::ResolvedChatSelection, String> {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    match thread_id {
+        Some(thread_id) => {
+            validate_id(&thread_id, "thread_id")?;
+            codex_chat_selection::set_thread(&workspace_root, &thread_id, selection)
         }
-        *active_profile = normalized;
+        None => codex_chat_selection::set_workspace(&workspace_root, selection.unwrap_or_default()),
     }
-
-    // Profile is currently treated as a GUI-global setting. For safety, only restart the
-    // default CODEX_HOME-scoped app-server instance.
-    let codex_home = default_codex_home_dir()?;
-    let mut pool = state.codex_pool.lock().await;
-    pool.shutdown_by_codex_home(&codex_home).await;
-
-    Ok(())
 }
 
 #[tauri::command]
@@ -2261,13 +2812,66 @@ fn codex_read_config() -> Result<String, String> {
     }
 }
 
+/// Raw-text save from the config drawer; validated and backed up like structured edits.
 #[tauri::command]
-fn codex_write_config(content: String) -> Result<(), String> {
-    let path = codex_config_path()?;
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    std::fs::write(path, content).map_err(|e| e.to_string())
+fn codex_write_config(app: tauri::AppHandle, content: String) -> Result<(), String> {
+    codex_config_store(&app)?
+        .apply(&codex_config::ConfigChange::Content(content), None)
+        .map(|_| ())
+}
+
+fn codex_config_store<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Result<codex_config::CodexConfigStore, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(codex_config::CodexConfigStore::new(
+        codex_config_path()?,
+        codex_config::backup_dir_for(&app_data_dir),
+    ))
+}
+
+#[tauri::command]
+fn codex_config_document(app: tauri::AppHandle) -> Result<codex_config::ConfigDocument, String> {
+    codex_config_store(&app)?.document()
+}
+
+/// Resulting file, diff and validation issues for `edits` (or replacement `content`), without
+/// writing anything.
+#[tauri::command]
+fn codex_config_preview(
+    app: tauri::AppHandle,
+    edits: Option<Vec<codex_config::ConfigEdit>>,
+    content: Option<String>,
+) -> Result<codex_config::ConfigPlan, String> {
+    let change = codex_config::ConfigChange::from_parts(edits, content)?;
+    codex_config_store(&app)?.plan(&change)
+}
+
+#[tauri::command]
+fn codex_config_apply(
+    app: tauri::AppHandle,
+    edits: Option<Vec<codex_config::ConfigEdit>>,
+    content: Option<String>,
+    revision: Option<String>,
+) -> Result<codex_config::ConfigWriteResult, String> {
+    let change = codex_config::ConfigChange::from_parts(edits, content)?;
+    codex_config_store(&app)?.apply(&change, revision.as_deref())
+}
+
+#[tauri::command]
+fn codex_config_backups_list(
+    app: tauri::AppHandle,
+) -> Result<Vec<codex_config::ConfigBackup>, String> {
+    codex_config_store(&app)?.backups()
+}
+
+#[tauri::command]
+fn codex_config_backup_restore(
+    app: tauri::AppHandle,
+    id: String,
+    revision: Option<String>,
+) -> Result<codex_config::ConfigWriteResult, String> {
+    codex_config_store(&app)?.restore(&id, revision.as_deref())
 }
 
 #[tauri::command]
@@ -2292,21 +2896,10 @@ struct SkillsListResponse {
     skills: Vec<SkillMetadata>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct PromptMetadata {
-    name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    argument_hint: Option<String>,
-    path: String,
-}
-
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 struct PromptsListResponse {
-    prompts: Vec<PromptMetadata>,
+    prompts: Vec<coco_core::prompt_library::PromptEntry>,
 }
 
 #[tauri::command]
@@ -2366,96 +2959,34 @@ async fn codex_skill_list(
     Ok(SkillsListResponse { skills })
 }
 
-#[tauri::command]
-fn codex_prompt_list() -> Result<PromptsListResponse, String> {
-    let home = std::env::var_os("HOME")
-        .or_else(|| std::env::var_os("USERPROFILE"))
-        .ok_or_else(|| "HOME directory not found".to_string())?;
-
-    let prompts_dir = std::path::PathBuf::from(home)
-        .join(".codex")
-        .join("prompts");
-
-    if !prompts_dir.exists() {
-        return Ok(PromptsListResponse { prompts: vec![] });
-    }
-
-    let mut prompts = Vec::new();
-
-    let read_dir = match std::fs::read_dir(&prompts_dir) {
-        Ok(entries) => entries,
-        Err(_) => return Ok(PromptsListResponse { prompts: vec![] }),
-    };
-
-    for entry in read_dir {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
-
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-
-        let extension = path.extension().and_then(|e| e.to_str());
-        if extension != Some("md") {
-            continue;
-        }
-
-        let name = match path.file_stem().and_then(|s| s.to_str()) {
-            Some(n) => n.to_string(),
-            None => continue,
-        };
-
-        let content = match std::fs::read_to_string(&path) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-
-        // Parse YAML frontmatter
-        let (description, argument_hint) = parse_prompt_frontmatter(&content);
-
-        prompts.push(PromptMetadata {
-            name,
-            description,
-            argument_hint,
-            path: path.to_string_lossy().to_string(),
-        });
-    }
-
-    // Sort by name
-    prompts.sort_by(|a, b| a.name.cmp(&b.name));
-
-    Ok(PromptsListResponse { prompts })
+fn prompt_library(state: &AppState) -> coco_core::prompt_library::PromptLibrary {
+    let workspace_root = state
+        .orchestrator
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .workspace_root()
+        .to_path_buf();
+    coco_core::prompt_library::PromptLibrary::discover(Some(&workspace_root))
 }
 
-fn parse_prompt_frontmatter(content: &str) -> (Option<String>, Option<String>) {
-    let trimmed = content.trim_start();
-    if !trimmed.starts_with("---") {
-        return (None, None);
-    }
-
-    let after_start = &trimmed[3..];
-    let end_pos = after_start.find("\n---");
-    let frontmatter = match end_pos {
-        Some(pos) => &after_start[..pos],
-        None => return (None, None),
-    };
-
-    let mut description = None;
-    let mut argument_hint = None;
-
-    for line in frontmatter.lines() {
-        let line = line.trim();
-        if let Some(rest) = line.strip_prefix("description:") {
-            description = Some(rest.trim().trim_matches('"').trim_matches('\'').to_string());
-        } else if let Some(rest) = line.strip_prefix("argument-hint:") {
-            argument_hint = Some(rest.trim().trim_matches('"').trim_matches('\'').to_string());
-        }
+/// Custom prompts from `.coco/prompts`, `.codex/prompts` and `$CODEX_HOME/prompts`, in that
+/// order of precedence.
+#[tauri::command]
+fn codex_prompt_list(state: tauri::State<'_, AppState>) -> PromptsListResponse {
+    PromptsListResponse {
+        prompts: prompt_library(&state).list(),
     }
+}
 
-    (description, argument_hint)
+#[tauri::command]
+fn prompt_render(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    args: Option<String>,
+) -> Result<coco_core::prompt_library::RenderedPrompt, String> {
+    prompt_library(&state)
+        .render(name.trim(), args.as_deref().unwrap_or(""))
+        .map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -2493,99 +3024,89 @@ struct WorktreeInfo {
     branch: Option<String>,
 }
 
+fn workspace_index(
+    app: &tauri::AppHandle,
+    state: &tauri::State<'_, AppState>,
+    op: &str,
+    cwd: &str,
+) -> Result<std::sync::Arc<std::sync::Mutex<workspace_search::WorkspaceIndex>>, String> {
+    let root = path_policy_for(app, state).resolve_dir(op, cwd)?;
+    let mut search = state
+        .workspace_search
+        .lock()
+        .map_err(|_| "workspace search state poisoned".to_string())?;
+    Ok(search.index_for(&root))
+}
+
+/// Fuzzy path search over the workspace, honoring `.gitignore`/`.ignore`.
 #[tauri::command]
 async fn search_workspace_files(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
     cwd: String,
     query: String,
     limit: Option<u32>,
 ) -> Result<Vec<FileInfo>, String> {
-    use std::path::Path;
-
-    let cwd_path = Path::new(&cwd);
-    if !cwd_path.exists() {
-        return Err("cwd does not exist".to_string());
-    }
-
+    let index = workspace_index(&app, &state, "search_workspace_files", &cwd)?;
     let limit = limit.unwrap_or(10) as usize;
-    let query_lower = query.to_lowercase();
-
-    let mut results = Vec::new();
-    search_files_recursive(cwd_path, cwd_path, &query_lower, limit, &mut results)?;
+    let matches = tauri::async_runtime::spawn_blocking(move || {
+        let mut index = index
+            .lock()
+            .map_err(|_| "workspace index poisoned".to_string())?;
+        Ok::<_, String>(index.search(&query, limit))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
 
-    Ok(results)
+    Ok(matches
+        .into_iter()
+        .map(|m| FileInfo {
+            name: m.path.rsplit('/').next().unwrap_or(&m.path).to_string(),
+            path: m.path,
+            is_directory: m.is_directory,
+        })
+        .collect())
 }
 
-fn search_files_recursive(
-    base: &std::path::Path,
-    current: &std::path::Path,
-    query: &str,
-    limit: usize,
-    results: &mut Vec<FileInfo>,
-) -> Result<(), String> {
-    if results.len() >= limit {
-        return Ok(());
-    }
-
-    let entries = match std::fs::read_dir(current) {
-        Ok(entries) => entries,
-        Err(_) => return Ok(()),
+/// Content search over the same (non-ignored) files as `search_workspace_files`.
+#[tauri::command]
+async fn grep_workspace_files(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    cwd: String,
+    query: String,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    limit: Option<u32>,
+) -> Result<Vec<workspace_search::GrepMatch>, String> {
+    let index = workspace_index(&app, &state, "grep_workspace_files", &cwd)?;
+    let opts = workspace_search::GrepOptions {
+        regex: regex.unwrap_or(false),
+        case_sensitive: case_sensitive.unwrap_or(false),
+        limit: limit.unwrap_or(50) as usize,
     };
-
-    for entry in entries {
-        if results.len() >= limit {
-            break;
-        }
-
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
+    tauri::async_runtime::spawn_blocking(move || {
+        // Snapshot the file list so the index is not held while reading files.
+        let (root, files) = {
+            let mut index = index
+                .lock()
+                .map_err(|_| "workspace index poisoned".to_string())?;
+            (index.root().to_path_buf(), index.files())
         };
-
-        let path = entry.path();
-        let file_name = match path.file_name().and_then(|n| n.to_str()) {
-            Some(name) => name.to_string(),
-            None => continue,
-        };
-
-        // Skip hidden files and common ignore patterns
-        if file_name.starts_with('.')
-            || file_name == "node_modules"
-            || file_name == "target"
-            || file_name == "dist"
-            || file_name == "build"
-        {
-            continue;
-        }
-
-        let is_dir = path.is_dir();
-        let rel_path = path
-            .strip_prefix(base)
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| file_name.clone());
-
-        // Match if query is empty or file name contains query
-        if query.is_empty() || file_name.to_lowercase().contains(query) {
-            results.push(FileInfo {
-                path: rel_path.clone(),
-                name: file_name.clone(),
-                is_directory: is_dir,
-            });
-        }
-
-        // Recurse into directories
-        if is_dir && results.len() < limit {
-            search_files_recursive(base, &path, query, limit, results)?;
-        }
-    }
-
-    Ok(())
+        workspace_search::grep_files(&root, &files, &query, &opts)
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
-async fn read_file_content(path: String) -> Result<String, String> {
-    use std::path::Path;
-
-    let file_path = Path::new(&path);
+async fn read_file_content(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<String, String> {
+    let file_path = path_policy_for(&app, &state).resolve("read_file_content", &path)?;
+    let file_path = file_path.as_path();
     if !file_path.exists() {
         return Err("file does not exist".to_string());
     }
@@ -2606,13 +3127,13 @@ async fn read_file_content(path: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn get_auto_context(cwd: String) -> Result<AutoContextInfo, String> {
-    use std::path::Path;
-
-    let cwd_path = Path::new(&cwd);
-    if !cwd_path.exists() {
-        return Err("cwd does not exist".to_string());
-    }
+async fn get_auto_context(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    cwd: String,
+) -> Result<AutoContextInfo, String> {
+    let cwd_path = path_policy_for(&app, &state).resolve_dir("get_auto_context", &cwd)?;
+    let cwd_path = cwd_path.as_path();
 
     // Get recent files (modified in last 24 hours)
     let recent_files = get_recent_files(cwd_path, 10);
@@ -2658,14 +3179,12 @@ fn run_git_command(cwd: &std::path::Path, args: &[&str]) -> Result<String, Strin
 }
 
 #[tauri::command]
-async fn git_worktree_list(cwd: String) -> Result<Vec<WorktreeInfo>, String> {
-    let cwd_path = std::path::PathBuf::from(cwd.trim());
-    if cwd_path.as_os_str().is_empty() {
-        return Err("cwd cannot be empty".to_string());
-    }
-    if !cwd_path.exists() || !cwd_path.is_dir() {
-        return Err("cwd is not a directory".to_string());
-    }
+async fn git_worktree_list(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    cwd: String,
+) -> Result<Vec<WorktreeInfo>, String> {
+    let cwd_path = path_policy_for(&app, &state).resolve_dir("git_worktree_list", &cwd)?;
     ensure_git_repo(&cwd_path)?;
 
     let output = run_git_command(&cwd_path, &["worktree", "list", "--porcelain"])?;
@@ -2704,14 +3223,12 @@ async fn git_worktree_list(cwd: String) -> Result<Vec<WorktreeInfo>, String> {
 }
 
 #[tauri::command]
-async fn git_branch_list(cwd: String) -> Result<Vec<String>, String> {
-    let cwd_path = std::path::PathBuf::from(cwd.trim());
-    if cwd_path.as_os_str().is_empty() {
-        return Err("cwd cannot be empty".to_string());
-    }
-    if !cwd_path.exists() || !cwd_path.is_dir() {
-        return Err("cwd is not a directory".to_string());
-    }
+async fn git_branch_list(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    cwd: String,
+) -> Result<Vec<String>, String> {
+    let cwd_path = path_policy_for(&app, &state).resolve_dir("git_branch_list", &cwd)?;
     ensure_git_repo(&cwd_path)?;
 
     let output = run_git_command(
@@ -2729,17 +3246,13 @@ async fn git_branch_list(cwd: String) -> Result<Vec<String>, String> {
 
 #[tauri::command]
 async fn git_worktree_create(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
     cwd: String,
     worktree_name: String,
     branch: String,
 ) -> Result<String, String> {
-    let cwd_path = std::path::PathBuf::from(cwd.trim());
-    if cwd_path.as_os_str().is_empty() {
-        return Err("cwd cannot be empty".to_string());
-    }
-    if !cwd_path.exists() || !cwd_path.is_dir() {
-        return Err("cwd is not a directory".to_string());
-    }
+    let cwd_path = path_policy_for(&app, &state).resolve_dir("git_worktree_create", &cwd)?;
     ensure_git_repo(&cwd_path)?;
 
     let trimmed_name = worktree_name.trim();
@@ -0,0 +1,434 @@
+use serde::Serialize;
+use std::fmt;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+/// `<app_data_dir>/allowed_roots.json`: extra roots (besides the workspace) that file commands
+/// may touch, e.g. related repos picked in Codex Chat.
+pub const ALLOWED_ROOTS_FILE_NAME: &str = "allowed_roots.json";
+/// `<app_data_dir>/path_audit.jsonl`: one line per denied access.
+pub const AUDIT_LOG_FILE_NAME: &str = "path_audit.jsonl";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathPolicyError {
+    EmptyPath,
+    /// An absolute path was expected (or a relative one, for `resolve_in`).
+    InvalidForm {
+        path: String,
+    },
+    /// `..` (or a root/prefix inside a relative path).
+    Traversal {
+        path: String,
+    },
+    OutsideRoots {
+        path: String,
+    },
+    /// Inside an allowed root by name, but a symlink points out of it.
+    SymlinkEscape {
+        path: String,
+    },
+    Io {
+        path: String,
+        message: String,
+    },
+}
+
+impl PathPolicyError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::EmptyPath => "empty-path",
+            Self::InvalidForm { .. } => "invalid-form",
+            Self::Traversal { .. } => "traversal",
+            Self::OutsideRoots { .. } => "outside-roots",
+            Self::SymlinkEscape { .. } => "symlink-escape",
+            Self::Io { .. } => "io",
+        }
+    }
+
+    /// Denials are audited; malformed input and IO failures are not.
+    fn is_denial(&self) -> bool {
+        matches!(
+            self,
+            Self::Traversal { .. } | Self::OutsideRoots { .. } | Self::SymlinkEscape { .. }
+        )
+    }
+}
+
+impl fmt::Display for PathPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyPath => write!(f, "path cannot be empty"),
+            Self::InvalidForm { path } => write!(f, "invalid path form: {path}"),
+            Self::Traversal { path } => write!(f, "path traversal is not allowed: {path}"),
+            Self::OutsideRoots { path } => {
+                write!(f, "path is outside the workspace and allowed roots: {path}")
+            }
+            Self::SymlinkEscape { path } => {
+                write!(f, "path escapes its root through a symlink: {path}")
+            }
+            Self::Io { path, message } => write!(f, "{path}: {message}"),
+        }
+    }
+}
+
+impl From<PathPolicyError> for String {
+    fn from(err: PathPolicyError) -> Self {
+        err.to_string()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditRecord<'a> {
+    ts_ms: u128,
+    op: &'a str,
+    path: &'a str,
+    reason: &'static str,
+    message: String,
+}
+
+#[derive(Debug)]
+struct Root {
+    /// As configured, for telling "named inside but resolves outside" (symlink escape) apart.
+    given: PathBuf,
+    canonical: PathBuf,
+}
+
+/// Where the GUI's file commands may read and write: the workspace root, its linked git
+/// worktrees and an explicit allowlist. Paths are resolved through symlinks before the check.
+#[derive(Debug)]
+pub struct PathPolicy {
+    roots: Vec<Root>,
+    audit_log: Option<PathBuf>,
+}
+
+impl PathPolicy {
+    pub fn new(workspace_root: &Path, extra_roots: &[PathBuf]) -> Self {
+        let mut given = vec![workspace_root.to_path_buf()];
+        given.extend(linked_worktrees(workspace_root));
+        given.extend(extra_roots.iter().cloned());
+        Self::with_roots(given)
+    }
+
+    /// Exactly `roots`, e.g. Codex's session directories for rollout paths.
+    pub fn with_roots(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        let roots = roots
+            .into_iter()
+            .filter_map(|given| {
+                let canonical = std::fs::canonicalize(&given).ok()?;
+                canonical.is_dir().then_some(Root { given, canonical })
+            })
+            .collect();
+        Self {
+            roots,
+            audit_log: None,
+        }
+    }
+
+    pub fn with_audit_log(mut self, path: PathBuf) -> Self {
+        self.audit_log = Some(path);
+        self
+    }
+
+    /// Resolve an absolute path (which need not exist yet) inside one of the roots.
+    pub fn resolve(&self, op: &str, path: &str) -> Result<PathBuf, PathPolicyError> {
+        self.audited(op, path, || {
+            let path = path.trim();
+            if path.is_empty() {
+                return Err(PathPolicyError::EmptyPath);
+            }
+            let raw = Path::new(path);
+            if !raw.is_absolute() {
+                return Err(PathPolicyError::InvalidForm {
+                    path: path.to_string(),
+                });
+            }
+            if raw.components().any(|c| c == Component::ParentDir) {
+                return Err(PathPolicyError::Traversal {
+                    path: path.to_string(),
+                });
+            }
+
+            let resolved = resolve_lenient(raw)?;
+            if self
+                .roots
+                .iter()
+                .any(|root| resolved.starts_with(&root.canonical))
+            {
+                return Ok(resolved);
+            }
+            let named_inside = self
+                .roots
+                .iter()
+                .any(|root| raw.starts_with(&root.given) || raw.starts_with(&root.canonical));
+            if named_inside {
+                Err(PathPolicyError::SymlinkEscape {
+                    path: path.to_string(),
+                })
+            } else {
+                Err(PathPolicyError::OutsideRoots {
+                    path: path.to_string(),
+                })
+            }
+        })
+    }
+
+    /// Like `resolve`, but the path must be an existing directory (a command's `cwd`).
+    pub fn resolve_dir(&self, op: &str, path: &str) -> Result<PathBuf, PathPolicyError> {
+        let resolved = self.resolve(op, path)?;
+        if !resolved.is_dir() {
+            return Err(PathPolicyError::Io {
+                path: path.to_string(),
+                message: "not a directory".to_string(),
+            });
+        }
+        Ok(resolved)
+    }
+
+    /// Resolve `rel` (relative, no `..`) under `base`, which must already have been resolved.
+    /// The result stays inside `base` even through symlinks.
+    pub fn resolve_in(&self, op: &str, base: &Path, rel: &str) -> Result<PathBuf, PathPolicyError> {
+        self.audited(op, rel, || {
+            if rel.trim().is_empty() {
+                return Err(PathPolicyError::EmptyPath);
+            }
+            let rel_path = Path::new(rel);
+            if rel_path.is_absolute() {
+                return Err(PathPolicyError::InvalidForm {
+                    path: rel.to_string(),
+                });
+            }
+            if rel_path
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+            {
+                return Err(PathPolicyError::Traversal {
+                    path: rel.to_string(),
+                });
+            }
+
+            let base = std::fs::canonicalize(base).map_err(|e| PathPolicyError::Io {
+                path: base.to_string_lossy().to_string(),
+                message: e.to_string(),
+            })?;
+            let resolved = resolve_lenient(&base.join(rel_path))?;
+            if resolved.starts_with(&base) {
+                Ok(resolved)
+            } else {
+                Err(PathPolicyError::SymlinkEscape {
+                    path: rel.to_string(),
+                })
+            }
+        })
+    }
+
+    fn audited(
+        &self,
+        op: &str,
+        path: &str,
+        check: impl FnOnce() -> Result<PathBuf, PathPolicyError>,
+    ) -> Result<PathBuf, PathPolicyError> {
+        let result = check();
+        if let Err(err) = &result {
+            if err.is_denial() {
+                self.audit(op, path, err);
+            }
+        }
+        result
+    }
+
+    fn audit(&self, op: &str, path: &str, err: &PathPolicyError) {
+        log::warn!("[PathPolicy] denied {op}: {err}");
+        let Some(audit_log) = &self.audit_log else {
+            return;
+        };
+        let record = AuditRecord {
+            ts_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0),
+            op,
+            path,
+            reason: err.kind(),
+            message: err.to_string(),
+        };
+        let Ok(line) = serde_json::to_string(&record) else {
+            return;
+        };
+        if let Some(parent) = audit_log.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(audit_log);
+        if let Ok(mut file) = file {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+}
+
+/// Checks a directory the user asked to allowlist: it must exist and must not be the filesystem
+/// root, the home directory or one of its ancestors, which would open up everything below.
+pub fn validate_extra_root(path: &Path, home: Option<&Path>) -> Result<PathBuf, String> {
+    let root = std::fs::canonicalize(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    if !root.is_dir() {
+        return Err("allowed root must be a directory".to_string());
+    }
+    if root.parent().is_none() {
+        return Err("the filesystem root cannot be an allowed root".to_string());
+    }
+    let home = home.and_then(|home| std::fs::canonicalize(home).ok());
+    if home.is_some_and(|home| home.starts_with(&root)) {
+        return Err("the home directory and its ancestors cannot be allowed roots".to_string());
+    }
+    Ok(root)
+}
+
+/// Canonicalize the longest existing prefix of `path` and append the rest, so targets that do
+/// not exist yet (rename destinations) are still checked. A dangling symlink in the path is
+/// treated as an escape: it cannot be resolved, and writing through it would follow it.
+fn resolve_lenient(path: &Path) -> Result<PathBuf, PathPolicyError> {
+    let mut existing = path;
+    let mut tail = Vec::new();
+    loop {
+        match std::fs::canonicalize(existing) {
+            Ok(canonical) => {
+                let mut resolved = canonical;
+                for name in tail.iter().rev() {
+                    resolved.push(name);
+                }
+                return Ok(resolved);
+            }
+            Err(_) if std::fs::symlink_metadata(existing).is_ok() => {
+                return Err(PathPolicyError::SymlinkEscape {
+                    path: path.to_string_lossy().to_string(),
+                });
+            }
+            Err(e) => {
+                let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                    return Err(PathPolicyError::Io {
+                        path: path.to_string_lossy().to_string(),
+                        message: e.to_string(),
+                    });
+                };
+                tail.push(name);
+                existing = parent;
+            }
+        }
+    }
+}
+
+/// Worktrees created from `repo` (`git worktree add`), read from `.git/worktrees/*/gitdir`
+/// instead of running git on every file command.
+fn linked_worktrees(repo: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(repo.join(".git").join("worktrees")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let gitdir = std::fs::read_to_string(entry.path().join("gitdir")).ok()?;
+            // Relative with `worktree.useRelativePaths`.
+            let gitdir = entry.path().join(gitdir.trim());
+            Some(gitdir.parent()?.to_path_buf())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn denies_traversal_outside_roots_and_symlink_escapes() {
+        let base = std::env::temp_dir().join(format!("coco-path-policy-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let workspace = base.join("workspace");
+        let extra = base.join("extra");
+        let outside = base.join("outside");
+        for dir in [&workspace, &extra, &outside] {
+            std::fs::create_dir_all(dir.join("src")).unwrap();
+        }
+        std::fs::write(outside.join("secret.txt"), "x").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&outside, workspace.join("link")).unwrap();
+
+        let audit = base.join("audit.jsonl");
+        let policy =
+            PathPolicy::new(&workspace, std::slice::from_ref(&extra)).with_audit_log(audit.clone());
+        let p = |path: &Path| path.to_string_lossy().to_string();
+
+        let new_file = policy
+            .resolve("write", &p(&workspace.join("src/new.rs")))
+            .unwrap();
+        assert!(new_file.ends_with("workspace/src/new.rs"));
+        assert!(policy.resolve("read", &p(&extra.join("src"))).is_ok());
+        assert_eq!(
+            policy.resolve("read", "src/main.rs"),
+            Err(PathPolicyError::InvalidForm {
+                path: "src/main.rs".to_string()
+            })
+        );
+        assert_eq!(
+            policy
+                .resolve("read", &p(&workspace.join("../outside/secret.txt")))
+                .map_err(|e| e.kind()),
+            Err("traversal")
+        );
+        assert_eq!(
+            policy
+                .resolve("read", &p(&outside.join("secret.txt")))
+                .map_err(|e| e.kind()),
+            Err("outside-roots")
+        );
+        assert_eq!(
+            policy
+                .resolve_in("rename", &workspace, "src/../../x")
+                .map_err(|e| e.kind()),
+            Err("traversal")
+        );
+        #[cfg(unix)]
+        {
+            assert_eq!(
+                policy
+                    .resolve("read", &p(&workspace.join("link/secret.txt")))
+                    .map_err(|e| e.kind()),
+                Err("symlink-escape")
+            );
+            assert_eq!(
+                policy
+                    .resolve_in("write", &workspace, "link/secret.txt")
+                    .map_err(|e| e.kind()),
+                Err("symlink-escape")
+            );
+        }
+
+        let audit = std::fs::read_to_string(audit).unwrap();
+        let reasons = audit
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["reason"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(reasons[..3], ["traversal", "outside-roots", "traversal"]);
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn rejects_root_home_and_its_ancestors_as_extra_roots() {
+        let base = std::env::temp_dir().join(format!("coco-extra-root-{}", std::process::id()));
+        let home = base.join("home").join("me");
+        let repo = home.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+
+        assert!(validate_extra_root(Path::new("/"), Some(&home)).is_err());
+        assert!(validate_extra_root(&home, Some(&home)).is_err());
+        assert!(validate_extra_root(&base.join("home"), Some(&home)).is_err());
+        assert_eq!(
+            validate_extra_root(&repo, Some(&home)).unwrap(),
+            std::fs::canonicalize(&repo).unwrap()
+        );
+
+        let _ = std::fs::remove_dir_all(base);
+    }
+}
@@ -0,0 +1,533 @@
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const THREAD_FS_EVENT: &str = "codex_thread_fs_update";
+pub const TASK_FS_EVENT: &str = "coco_task_fs_update";
+
+/// Changes are held until their target has been quiet this long...
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// ...but no longer than this, so a rollout that is appended to continuously still updates.
+const MAX_DELAY: Duration = Duration::from_millis(1_000);
+
+/// Called for every change to a watched rollout, ahead of the debounced event.
+pub type RolloutChangeHook = Arc<dyn Fn(&Path) + Send + Sync>;
+/// Delivers a debounced event to the windows subscribed to its target.
+pub type EmitHook = Box<dyn Fn(WatchEmit) + Send>;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum WatchRequest {
+    /// A Codex rollout file.
+    Rollout { thread_id: String, path: String },
+    /// `<workspace>/.coco/tasks/<taskId>`, recursively.
+    Task { task_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexThreadWatchEvent {
+    pub thread_id: String,
+    pub path: String,
+    pub updated_at_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskChangeKind {
+    /// `agents/<instance>/runtime/events.jsonl`
+    AgentEvents,
+    /// `agents/<instance>/runtime/stderr.log`
+    AgentStderr,
+    /// `agents/<instance>/artifacts/final.json`
+    AgentFinal,
+    /// Anything else under `agents/<instance>/` (session, pid, deadline, other artifacts).
+    AgentStatus,
+    /// `shared/...`
+    SharedArtifact,
+    /// `task.yaml`, the task event log and other top-level files.
+    Task,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskChange {
+    pub kind: TaskChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_instance: Option<String>,
+    /// First directory under `shared/` (`reports`, `contracts`, `decisions`, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Task-relative, `/`-separated.
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskWatchEvent {
+    pub task_id: String,
+    pub changes: Vec<TaskChange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchEmit {
+    Thread {
+        windows: Vec<String>,
+        event: CodexThreadWatchEvent,
+    },
+    Task {
+        windows: Vec<String>,
+        event: TaskWatchEvent,
+    },
+}
+
+/// Maps a task-relative path to the change it represents; `None` for noise such as the
+/// agents' private `codex_home`.
+fn classify_task_path(rel: &Path) -> Option<TaskChange> {
+    let parts = rel
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect::<Vec<_>>();
+    let path = parts.join("/");
+    let change = |kind, agent_instance: Option<&str>, category: Option<&str>| TaskChange {
+        kind,
+        agent_instance: agent_instance.map(str::to_string),
+        category: category.map(str::to_string),
+        path: path.clone(),
+    };
+    match parts.as_slice() {
+        [] => None,
+        ["agents", instance, rest @ ..] => match rest {
+            ["codex_home", ..] => None,
+            ["runtime", "events.jsonl"] => {
+                Some(change(TaskChangeKind::AgentEvents, Some(instance), None))
+            }
+            ["runtime", "stderr.log"] => {
+                Some(change(TaskChangeKind::AgentStderr, Some(instance), None))
+            }
+            ["artifacts", "final.json"] => {
+                Some(change(TaskChangeKind::AgentFinal, Some(instance), None))
+            }
+            _ => Some(change(TaskChangeKind::AgentStatus, Some(instance), None)),
+        },
+        ["agents"] => None,
+        ["shared", category, _, ..] => {
+            Some(change(TaskChangeKind::SharedArtifact, None, Some(category)))
+        }
+        ["shared", ..] => Some(change(TaskChangeKind::SharedArtifact, None, None)),
+        _ => Some(change(TaskChangeKind::Task, None, None)),
+    }
+}
+
+fn is_relevant_event(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(modify) => matches!(
+            modify,
+            ModifyKind::Data(_) | ModifyKind::Name(_) | ModifyKind::Metadata(_) | ModifyKind::Any
+        ),
+        _ => false,
+    }
+}
+
+fn modified_ms(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .and_then(|d| u64::try_from(d.as_millis()).ok())
+}
+
+// ============================================================================
+// Subscriptions
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum WatchKey {
+    Rollout(PathBuf),
+    TaskDir(PathBuf),
+}
+
+impl WatchKey {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Rollout(path) | Self::TaskDir(path) => path,
+        }
+    }
+}
+
+struct WatchedTarget {
+    /// Thread or task id.
+    id: String,
+    /// `canonicalize`d form of the key path; backends may report either.
+    canonical: Option<PathBuf>,
+    /// Subscription count per window label.
+    windows: HashMap<String, usize>,
+}
+
+#[derive(Default)]
+struct Subscriptions {
+    next_id: u64,
+    by_id: HashMap<u64, (WatchKey, String)>,
+    targets: HashMap<WatchKey, WatchedTarget>,
+}
+
+impl Subscriptions {
+    /// The watched target `path` belongs to, with the task change it represents. Rollouts are
+    /// matched first: a sub-agent's rollout lives inside its task directory.
+    fn lookup(&self, path: &Path) -> Option<(WatchKey, Option<TaskChange>)> {
+        let roots = |key: &WatchKey, target: &WatchedTarget| {
+            let canonical = target.canonical.clone();
+            std::iter::once(key.path().to_path_buf()).chain(canonical)
+        };
+        for (key, target) in &self.targets {
+            let WatchKey::Rollout(rollout) = key else {
+                continue;
+            };
+            // Some backends report the file through another path form; fall back to the file
+            // name, which embeds the thread id.
+            if roots(key, target).any(|root| root == path)
+                || (path.file_name().is_some() && path.file_name() == rollout.file_name())
+            {
+                return Some((key.clone(), None));
+            }
+        }
+        for (key, target) in &self.targets {
+            if !matches!(key, WatchKey::TaskDir(_)) {
+                continue;
+            }
+            for root in roots(key, target) {
+                if let Ok(rel) = path.strip_prefix(&root) {
+                    return classify_task_path(rel).map(|change| (key.clone(), Some(change)));
+                }
+            }
+        }
+        None
+    }
+
+    fn windows(&self, key: &WatchKey) -> Option<(String, Vec<String>)> {
+        let target = self.targets.get(key)?;
+        let mut windows = target.windows.keys().cloned().collect::<Vec<_>>();
+        windows.sort();
+        Some((target.id.clone(), windows))
+    }
+}
+
+#[derive(Default)]
+struct Pending {
+    first: Option<Instant>,
+    last: Option<Instant>,
+    changes: BTreeSet<TaskChange>,
+}
+
+impl Pending {
+    fn due(&self, now: Instant) -> Option<Instant> {
+        let (first, last) = (self.first?, self.last?);
+        let due = (last + DEBOUNCE).min(first + MAX_DELAY);
+        Some(due.max(now))
+    }
+}
+
+fn run_dispatcher(
+    rx: Receiver<notify::Result<notify::Event>>,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+    on_rollout_change: RolloutChangeHook,
+    emit: EmitHook,
+) {
+    let mut pending: HashMap<WatchKey, Pending> = HashMap::new();
+    loop {
+        let now = Instant::now();
+        let next_due = pending.values().filter_map(|p| p.due(now)).min();
+        let received = match next_due {
+            Some(due) => rx.recv_timeout(due.saturating_duration_since(now)),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(Ok(event)) if is_relevant_event(&event.kind) => {
+                let now = Instant::now();
+                for path in &event.paths {
+                    let found = subscriptions
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .lookup(path);
+                    let Some((key, change)) = found else {
+                        continue;
+                    };
+                    if let WatchKey::Rollout(rollout) = &key {
+                        on_rollout_change(rollout);
+                    }
+                    let entry = pending.entry(key).or_default();
+                    entry.first.get_or_insert(now);
+                    entry.last = Some(now);
+                    entry.changes.extend(change);
+                }
+            }
+            Ok(Ok(_)) | Err(RecvTimeoutError::Timeout) => {}
+            Ok(Err(err)) => log::warn!("[Watch] watcher error: {err}"),
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let now = Instant::now();
+        let due = pending
+            .iter()
+            .filter(|(_, p)| p.due(now).is_some_and(|due| due <= now))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in due {
+            let Some(batch) = pending.remove(&key) else {
+                continue;
+            };
+            let target = subscriptions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .windows(&key);
+            let Some((id, windows)) = target else {
+                continue;
+            };
+            let emission = match &key {
+                WatchKey::Rollout(path) => WatchEmit::Thread {
+                    windows,
+                    event: CodexThreadWatchEvent {
+                        thread_id: id,
+                        path: path.to_string_lossy().to_string(),
+                        updated_at_ms: modified_ms(path),
+                    },
+                },
+                WatchKey::TaskDir(_) => WatchEmit::Task {
+                    windows,
+                    event: TaskWatchEvent {
+                        task_id: id,
+                        changes: batch.changes.into_iter().collect(),
+                    },
+                },
+            };
+            emit(emission);
+        }
+    }
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+/// One `notify` watcher shared by every window: rollout files and task directories are watched
+/// once however many windows subscribe, and unwatched when the last subscription goes away.
+pub struct WatchService {
+    // Not reachable from the dispatcher thread, so dropping the service disconnects it.
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+}
+
+impl WatchService {
+    pub fn new(on_rollout_change: RolloutChangeHook, emit: EmitHook) -> Self {
+        let (tx, rx) = channel();
+        let subscriptions = Arc::new(Mutex::new(Subscriptions::default()));
+        let watcher = match notify::recommended_watcher(move |res| {
+            let _ = tx.send(res);
+        }) {
+            Ok(watcher) => {
+                let subscriptions = Arc::clone(&subscriptions);
+                std::thread::spawn(move || {
+                    run_dispatcher(rx, subscriptions, on_rollout_change, emit)
+                });
+                Some(watcher)
+            }
+            Err(err) => {
+                log::warn!("[Watch] file watching unavailable: {err}");
+                None
+            }
+        };
+        Self {
+            watcher: Mutex::new(watcher),
+            subscriptions,
+        }
+    }
+
+    fn subscribe(&self, window: &str, key: WatchKey, id: String) -> Result<u64, String> {
+        let mut subs = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        if !subs.targets.contains_key(&key) {
+            let mut watcher = self.watcher.lock().unwrap_or_else(|e| e.into_inner());
+            let watcher = watcher
+                .as_mut()
+                .ok_or_else(|| "file watching is unavailable".to_string())?;
+            // On macOS, `notify`'s kqueue backend does not reliably report writes to a file when
+            // only its parent directory is watched, so rollouts are watched directly.
+            let mode = match key {
+                WatchKey::Rollout(_) => RecursiveMode::NonRecursive,
+                WatchKey::TaskDir(_) => RecursiveMode::Recursive,
+            };
+            watcher
+                .watch(key.path(), mode)
+                .map_err(|e| format!("failed to watch {}: {e}", key.path().display()))?;
+            log::info!("[Watch] watching {:?}", key.path());
+            let canonical = std::fs::canonicalize(key.path())
+                .ok()
+                .filter(|p| p != key.path());
+            subs.targets.insert(
+                key.clone(),
+                WatchedTarget {
+                    id,
+                    canonical,
+                    windows: HashMap::new(),
+                },
+            );
+        }
+        if let Some(target) = subs.targets.get_mut(&key) {
+            *target.windows.entry(window.to_string()).or_default() += 1;
+        }
+        subs.next_id += 1;
+        let subscription_id = subs.next_id;
+        subs.by_id
+            .insert(subscription_id, (key, window.to_string()));
+        Ok(subscription_id)
+    }
+
+    pub fn subscribe_rollout(
+        &self,
+        window: &str,
+        thread_id: String,
+        path: PathBuf,
+    ) -> Result<u64, String> {
+        self.subscribe(window, WatchKey::Rollout(path), thread_id)
+    }
+
+    pub fn subscribe_task(
+        &self,
+        window: &str,
+        task_id: String,
+        dir: PathBuf,
+    ) -> Result<u64, String> {
+        if !dir.is_dir() {
+            return Err(format!("task directory not found: {}", dir.display()));
+        }
+        self.subscribe(window, WatchKey::TaskDir(dir), task_id)
+    }
+
+    /// Unknown ids are ignored, so a window may unsubscribe twice.
+    pub fn unsubscribe(&self, subscription_id: u64) {
+        let mut subs = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        let Some((key, window)) = subs.by_id.remove(&subscription_id) else {
+            return;
+        };
+        let Some(target) = subs.targets.get_mut(&key) else {
+            return;
+        };
+        if let Some(count) = target.windows.get_mut(&window) {
+            *count -= 1;
+            if *count == 0 {
+                target.windows.remove(&window);
+            }
+        }
+        if target.windows.is_empty() {
+            subs.targets.remove(&key);
+            if let Some(watcher) = self
+                .watcher
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_mut()
+            {
+                let _ = watcher.unwatch(key.path());
+            }
+            log::info!("[Watch] stopped watching {:?}", key.path());
+        }
+    }
+
+    /// Drops every subscription held by a closed window.
+    pub fn unsubscribe_window(&self, window: &str) {
+        let ids = self
+            .subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .by_id
+            .iter()
+            .filter(|(_, (_, w))| w == window)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in ids {
+            self.unsubscribe(id);
+        }
+    }
+
+    #[cfg(test)]
+    fn watched_count(&self) -> usize {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .targets
+            .len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_task_paths() {
+        let kind = |p: &str| {
+            classify_task_path(Path::new(p)).map(|c| (c.kind, c.agent_instance, c.category))
+        };
+        assert_eq!(
+            kind("agents/w1/runtime/events.jsonl"),
+            Some((TaskChangeKind::AgentEvents, Some("w1".into()), None))
+        );
+        assert_eq!(
+            kind("shared/reports/w1.md"),
+            Some((TaskChangeKind::SharedArtifact, None, Some("reports".into())))
+        );
+        assert_eq!(kind("agents/w1/codex_home/sessions/x.jsonl"), None);
+        assert_eq!(kind("task.yaml").map(|k| k.0), Some(TaskChangeKind::Task));
+    }
+
+    #[test]
+    fn shares_targets_and_debounces_task_changes() {
+        let dir = std::env::temp_dir().join(format!("coco-watch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let runtime = dir.join("agents").join("w1").join("runtime");
+        std::fs::create_dir_all(&runtime).unwrap();
+
+        let (tx, rx) = channel();
+        let tx = Mutex::new(tx);
+        let service = WatchService::new(
+            Arc::new(|_: &Path| {}),
+            Box::new(move |emit| {
+                let _ = tx.lock().unwrap().send(emit);
+            }),
+        );
+        let a = service
+            .subscribe_task("main", "t1".into(), dir.clone())
+            .unwrap();
+        let b = service
+            .subscribe_task("main-2", "t1".into(), dir.clone())
+            .unwrap();
+        assert_eq!(service.watched_count(), 1);
+
+        std::fs::write(runtime.join("events.jsonl"), "{}\n").unwrap();
+        std::fs::write(runtime.join("events.jsonl"), "{}\n{}\n").unwrap();
+        let WatchEmit::Task { windows, event } = rx.recv_timeout(Duration::from_secs(5)).unwrap()
+        else {
+            panic!("expected a task event");
+        };
+        assert_eq!(windows, ["main", "main-2"]);
+        assert_eq!(event.task_id, "t1");
+        assert_eq!(event.changes.len(), 1);
+        assert_eq!(event.changes[0].agent_instance.as_deref(), Some("w1"));
+
+        service.unsubscribe(a);
+        assert_eq!(service.watched_count(), 1);
+        service.unsubscribe_window("main-2");
+        assert_eq!(service.watched_count(), 0);
+        service.unsubscribe(b);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
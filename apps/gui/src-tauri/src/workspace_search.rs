@@ -0,0 +1,739 @@
+use glob::{MatchOptions, Pattern};
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Per-directory ignore files, in precedence order (`.ignore` overrides `.gitignore`).
+const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".ignore"];
+const MAX_INDEXED_ENTRIES: usize = 200_000;
+/// Directories are watched one by one (non-recursively) so ignored trees such as
+/// `node_modules` cost nothing; past this many the index falls back to periodic rebuilds.
+const MAX_WATCHED_DIRS: usize = 8_192;
+const UNWATCHED_REBUILD_AFTER: Duration = Duration::from_secs(30);
+const MAX_OPEN_INDEXES: usize = 4;
+const GREP_MAX_FILE_BYTES: u64 = 1_000_000;
+const GREP_BINARY_SNIFF_BYTES: usize = 8 * 1024;
+const GREP_PREVIEW_CHARS: usize = 160;
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileMatch {
+    /// Workspace-relative, `/`-separated.
+    pub path: String,
+    pub is_directory: bool,
+    pub score: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    pub path: String,
+    /// 1-based.
+    pub line: u32,
+    /// 1-based, in chars.
+    pub column: u32,
+    /// The matching line, cut to a window around the match when long.
+    pub preview: String,
+    /// Char range of the match inside `preview`.
+    pub match_start: u32,
+    pub match_end: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GrepOptions {
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub limit: usize,
+}
+
+// ============================================================================
+// Ignore rules (.gitignore / .ignore / .git/info/exclude)
+// ============================================================================
+
+#[derive(Debug)]
+struct IgnoreRule {
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Patterns with a `/` match the path relative to the ignore file's directory; the others
+    /// match the file name at any depth.
+    anchored: bool,
+}
+
+fn parse_ignore_rules(content: &str) -> Vec<IgnoreRule> {
+    let mut rules = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        if line.is_empty() {
+            continue;
+        }
+        let Ok(pattern) = Pattern::new(line) else {
+            continue;
+        };
+        rules.push(IgnoreRule {
+            pattern,
+            negated,
+            dir_only,
+            anchored,
+        });
+    }
+    rules
+}
+
+#[derive(Debug)]
+struct IgnoreMatcher {
+    root: PathBuf,
+    /// Rules per workspace-relative directory (`""` is the root), loaded on first use.
+    by_dir: HashMap<String, Arc<Vec<IgnoreRule>>>,
+}
+
+impl IgnoreMatcher {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            by_dir: HashMap::new(),
+        }
+    }
+
+    fn rules_for(&mut self, rel_dir: &str) -> Arc<Vec<IgnoreRule>> {
+        if let Some(rules) = self.by_dir.get(rel_dir) {
+            return Arc::clone(rules);
+        }
+        let dir = self.root.join(rel_dir);
+        let mut content = String::new();
+        if rel_dir.is_empty() {
+            let exclude = dir.join(".git").join("info").join("exclude");
+            content.push_str(&std::fs::read_to_string(exclude).unwrap_or_default());
+            content.push('\n');
+        }
+        for name in IGNORE_FILE_NAMES {
+            content.push_str(&std::fs::read_to_string(dir.join(name)).unwrap_or_default());
+            content.push('\n');
+        }
+        let rules = Arc::new(parse_ignore_rules(&content));
+        self.by_dir.insert(rel_dir.to_string(), Arc::clone(&rules));
+        rules
+    }
+
+    /// Whether `rel` itself is ignored, assuming its parent directories are not (the walk
+    /// never descends into ignored directories). Deeper files and later lines win.
+    fn matches(&mut self, rel: &str, is_dir: bool) -> bool {
+        if rel == ".git" || rel.ends_with("/.git") {
+            return true;
+        }
+        let name = rel.rsplit('/').next().unwrap_or(rel);
+        let mut bases = vec![""];
+        bases.extend(rel.match_indices('/').map(|(i, _)| &rel[..i]));
+
+        let mut ignored = false;
+        for base in bases {
+            let rules = self.rules_for(base);
+            let sub = if base.is_empty() {
+                rel
+            } else {
+                &rel[base.len() + 1..]
+            };
+            for rule in rules.iter() {
+                if rule.dir_only && !is_dir {
+                    continue;
+                }
+                let target = if rule.anchored { sub } else { name };
+                if rule.pattern.matches_with(target, GLOB_OPTIONS) {
+                    ignored = !rule.negated;
+                }
+            }
+        }
+        ignored
+    }
+
+    /// Like `matches`, but also ignored when any parent directory is.
+    fn is_ignored(&mut self, rel: &str, is_dir: bool) -> bool {
+        let parents = rel
+            .match_indices('/')
+            .map(|(i, _)| rel[..i].to_string())
+            .collect::<Vec<_>>();
+        parents.iter().any(|dir| self.matches(dir, true)) || self.matches(rel, is_dir)
+    }
+}
+
+// ============================================================================
+// Index
+// ============================================================================
+
+/// In-memory list of the workspace's non-ignored paths, kept current from `notify` events.
+/// Events are queued on a channel and applied on the next query.
+pub struct WorkspaceIndex {
+    root: PathBuf,
+    /// Relative path → is directory.
+    entries: BTreeMap<String, bool>,
+    ignore: IgnoreMatcher,
+    watcher: Option<RecommendedWatcher>,
+    events: Option<Receiver<notify::Result<notify::Event>>>,
+    /// Watches added since the last rebuild (deleted directories are not subtracted).
+    watched_dirs: usize,
+    /// None until the first query, and after an ignore file changed.
+    built_at: Option<Instant>,
+    truncated: bool,
+}
+
+impl WorkspaceIndex {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            ignore: IgnoreMatcher::new(root.clone()),
+            root,
+            entries: BTreeMap::new(),
+            watcher: None,
+            events: None,
+            watched_dirs: 0,
+            built_at: None,
+            truncated: false,
+        }
+    }
+
+    fn fully_watched(&self) -> bool {
+        self.watcher.is_some() && self.watched_dirs <= MAX_WATCHED_DIRS && !self.truncated
+    }
+
+    fn rebuild(&mut self) {
+        self.entries.clear();
+        self.ignore = IgnoreMatcher::new(self.root.clone());
+        self.watched_dirs = 0;
+        self.truncated = false;
+        self.watcher = None;
+        let (tx, rx) = channel();
+        match notify::recommended_watcher(tx) {
+            Ok(watcher) => {
+                self.watcher = Some(watcher);
+                self.events = Some(rx);
+            }
+            Err(e) => {
+                log::info!("[WorkspaceSearch] watcher unavailable: {:?}", e);
+                self.events = None;
+            }
+        }
+        self.walk("");
+        self.built_at = Some(Instant::now());
+        log::info!(
+            "[WorkspaceSearch] indexed {:?}: entries={}, watchedDirs={}, truncated={}",
+            self.root,
+            self.entries.len(),
+            self.watched_dirs,
+            self.truncated
+        );
+    }
+
+    /// Add every non-ignored path under `rel_dir` (itself already indexed, or the root).
+    fn walk(&mut self, rel_dir: &str) {
+        let mut stack = vec![rel_dir.to_string()];
+        while let Some(dir) = stack.pop() {
+            let abs = self.root.join(&dir);
+            self.watch_dir(&abs);
+            let Ok(read_dir) = std::fs::read_dir(&abs) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                if self.entries.len() >= MAX_INDEXED_ENTRIES {
+                    self.truncated = true;
+                    return;
+                }
+                let Some(name) = entry.file_name().to_str().map(|s| s.to_string()) else {
+                    continue;
+                };
+                // Symlinks are listed but never followed.
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                let rel = join_rel(&dir, &name);
+                if self.ignore.matches(&rel, is_dir) {
+                    continue;
+                }
+                self.entries.insert(rel.clone(), is_dir);
+                if is_dir {
+                    stack.push(rel);
+                }
+            }
+        }
+    }
+
+    fn watch_dir(&mut self, abs: &Path) {
+        let Some(watcher) = self.watcher.as_mut() else {
+            return;
+        };
+        if self.watched_dirs >= MAX_WATCHED_DIRS {
+            self.watched_dirs = MAX_WATCHED_DIRS + 1;
+            return;
+        }
+        if watcher.watch(abs, RecursiveMode::NonRecursive).is_ok() {
+            self.watched_dirs += 1;
+        }
+    }
+
+    /// Build on first use, then apply queued file-system events (or rebuild when they cannot
+    /// be applied incrementally).
+    pub fn refresh(&mut self) {
+        let stale = match self.built_at {
+            None => true,
+            Some(built_at) => {
+                !self.fully_watched() && built_at.elapsed() >= UNWATCHED_REBUILD_AFTER
+            }
+        };
+        if stale {
+            self.rebuild();
+            return;
+        }
+
+        let mut changed = Vec::new();
+        if let Some(events) = &self.events {
+            for event in events.try_iter() {
+                let Ok(event) = event else {
+                    self.built_at = None;
+                    continue;
+                };
+                if event.need_rescan() || event.paths.iter().any(|p| is_ignore_file(p)) {
+                    self.built_at = None;
+                    continue;
+                }
+                if is_structural_event(&event.kind) {
+                    changed.extend(event.paths);
+                }
+            }
+        }
+        if self.built_at.is_none() {
+            self.rebuild();
+            return;
+        }
+        for path in changed {
+            self.reconcile(&path);
+        }
+    }
+
+    fn reconcile(&mut self, abs: &Path) {
+        let Some(rel) = self.relative(abs) else {
+            return;
+        };
+        match std::fs::symlink_metadata(abs) {
+            Ok(meta) => {
+                let is_dir = meta.is_dir();
+                if self.ignore.is_ignored(&rel, is_dir) {
+                    return;
+                }
+                let existed = self.entries.insert(rel.clone(), is_dir) == Some(is_dir);
+                if is_dir && !existed {
+                    self.walk(&rel);
+                }
+            }
+            Err(_) => self.remove_subtree(&rel),
+        }
+    }
+
+    fn remove_subtree(&mut self, rel: &str) {
+        self.entries.remove(rel);
+        let prefix = format!("{rel}/");
+        let children = self
+            .entries
+            .range(prefix.clone()..)
+            .take_while(|(k, _)| k.starts_with(&prefix))
+            .map(|(k, _)| k.clone())
+            .collect::<Vec<_>>();
+        for child in children {
+            self.entries.remove(&child);
+        }
+    }
+
+    fn relative(&self, abs: &Path) -> Option<String> {
+        let rel = abs.strip_prefix(&self.root).ok()?;
+        let parts = rel
+            .components()
+            .map(|c| c.as_os_str().to_str().map(|s| s.to_string()))
+            .collect::<Option<Vec<_>>>()?;
+        if parts.is_empty() || parts.iter().any(|p| p == ".git") {
+            return None;
+        }
+        Some(parts.join("/"))
+    }
+
+    /// Fuzzy-ranked matches on the full relative path. Whitespace separates terms that must all
+    /// match; an empty query lists the shallowest paths.
+    pub fn search(&mut self, query: &str, limit: usize) -> Vec<FileMatch> {
+        self.refresh();
+        let terms = query
+            .split_whitespace()
+            .map(|t| t.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut matches = Vec::new();
+        for (path, is_dir) in &self.entries {
+            let score = if terms.is_empty() {
+                Some(-(path.matches('/').count() as i64))
+            } else {
+                terms
+                    .iter()
+                    .map(|term| score_path(path, term))
+                    .sum::<Option<i64>>()
+            };
+            if let Some(score) = score {
+                matches.push(FileMatch {
+                    path: path.clone(),
+                    is_directory: *is_dir,
+                    score,
+                });
+            }
+        }
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.path.len().cmp(&b.path.len()))
+                .then(a.path.cmp(&b.path))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Indexed files (not directories), for grep.
+    pub fn files(&mut self) -> Vec<String> {
+        self.refresh();
+        self.entries
+            .iter()
+            .filter(|(_, is_dir)| !**is_dir)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+fn join_rel(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+fn is_ignore_file(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    IGNORE_FILE_NAMES.contains(&name) || path.ends_with(".git/info/exclude")
+}
+
+fn is_structural_event(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Any => true,
+        EventKind::Modify(modify) => matches!(modify, ModifyKind::Name(_) | ModifyKind::Any),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Fuzzy scoring
+// ============================================================================
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_SEGMENT_START: i64 = 10;
+const BONUS_WORD_START: i64 = 8;
+const BONUS_CAMEL: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = 6;
+const PENALTY_GAP_START: i64 = 3;
+const PENALTY_GAP_EXTENSION: i64 = 1;
+const BONUS_IN_FILE_NAME: i64 = 24;
+const BONUS_EXACT_FILE_NAME: i64 = 48;
+
+/// Score of `term` as a case-insensitive subsequence of `path`, preferring matches inside the
+/// file name, at word boundaries and in runs. None when it does not match.
+fn score_path(path: &str, term: &[char]) -> Option<i64> {
+    let chars = path.chars().collect::<Vec<_>>();
+    let name_start = chars
+        .iter()
+        .rposition(|c| *c == '/')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    if let Some(score) = score_range(&chars, name_start, term) {
+        let name = chars[name_start..]
+            .iter()
+            .collect::<String>()
+            .to_lowercase();
+        let term = term.iter().collect::<String>().to_lowercase();
+        let stem = name.split('.').next().unwrap_or(&name);
+        let exact = if name == term || stem == term {
+            BONUS_EXACT_FILE_NAME
+        } else {
+            0
+        };
+        return Some(score + BONUS_IN_FILE_NAME + exact);
+    }
+    score_range(&chars, 0, term)
+}
+
+/// fzf v1: find the first complete match from `from`, shrink it backwards to the shortest
+/// window ending there, then score that window greedily.
+fn score_range(chars: &[char], from: usize, term: &[char]) -> Option<i64> {
+    if term.is_empty() {
+        return Some(0);
+    }
+    let eq = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+
+    let mut ti = 0;
+    let mut end = None;
+    for (i, c) in chars.iter().enumerate().skip(from) {
+        if eq(*c, term[ti]) {
+            ti += 1;
+            if ti == term.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut ti = term.len();
+    let mut start = end;
+    for i in (from..=end).rev() {
+        if eq(chars[i], term[ti - 1]) {
+            ti -= 1;
+            if ti == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    let mut score = 0;
+    let mut ti = 0;
+    let mut last_match: Option<usize> = None;
+    for (i, c) in chars.iter().enumerate().take(end + 1).skip(start) {
+        if ti < term.len() && eq(*c, term[ti]) {
+            score += SCORE_MATCH + boundary_bonus(chars, i);
+            if *c == term[ti] {
+                score += 1;
+            }
+            match last_match {
+                Some(last) if last + 1 == i => score += BONUS_CONSECUTIVE,
+                Some(last) => {
+                    let gap = (i - last - 1) as i64;
+                    score -= PENALTY_GAP_START + (gap - 1) * PENALTY_GAP_EXTENSION;
+                }
+                None => {}
+            }
+            last_match = Some(i);
+            ti += 1;
+        }
+    }
+    Some(score)
+}
+
+fn boundary_bonus(chars: &[char], i: usize) -> i64 {
+    let Some(prev) = i.checked_sub(1).map(|p| chars[p]) else {
+        return BONUS_SEGMENT_START;
+    };
+    match prev {
+        '/' => BONUS_SEGMENT_START,
+        '-' | '_' | '.' | ' ' => BONUS_WORD_START,
+        _ if prev.is_lowercase() && chars[i].is_uppercase() => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+// ============================================================================
+// Grep
+// ============================================================================
+
+/// Search file contents line by line. Binary files and files over 1MB are skipped; at most one
+/// match is reported per line.
+pub fn grep_files(
+    root: &Path,
+    files: &[String],
+    query: &str,
+    opts: &GrepOptions,
+) -> Result<Vec<GrepMatch>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = if opts.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let re = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(!opts.case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    for rel in files {
+        let abs = root.join(rel);
+        let Ok(meta) = std::fs::metadata(&abs) else {
+            continue;
+        };
+        if meta.len() > GREP_MAX_FILE_BYTES {
+            continue;
+        }
+        let Ok(bytes) = std::fs::read(&abs) else {
+            continue;
+        };
+        if bytes[..bytes.len().min(GREP_BINARY_SNIFF_BYTES)].contains(&0) {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        for (line_idx, line) in text.lines().enumerate() {
+            let Some(found) = re.find(line) else {
+                continue;
+            };
+            let start = line[..found.start()].chars().count();
+            let len = found.as_str().chars().count();
+            let (preview, offset) = preview_window(line, start, len);
+            out.push(GrepMatch {
+                path: rel.clone(),
+                line: (line_idx + 1) as u32,
+                column: (start + 1) as u32,
+                preview,
+                match_start: (start - offset) as u32,
+                match_end: (start - offset + len) as u32,
+            });
+            if out.len() >= opts.limit {
+                return Ok(out);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// At most `GREP_PREVIEW_CHARS` chars of `line` around the match; returns the char offset the
+/// window starts at.
+fn preview_window(line: &str, start: usize, len: usize) -> (String, usize) {
+    let chars = line.chars().collect::<Vec<_>>();
+    if chars.len() <= GREP_PREVIEW_CHARS {
+        return (line.to_string(), 0);
+    }
+    let context = GREP_PREVIEW_CHARS.saturating_sub(len) / 2;
+    let from = start
+        .saturating_sub(context)
+        .min(chars.len() - GREP_PREVIEW_CHARS);
+    let to = (from + GREP_PREVIEW_CHARS).min(chars.len());
+    (chars[from..to].iter().collect(), from)
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+/// One index per searched root (most recently used last).
+#[derive(Default)]
+pub struct WorkspaceSearchState {
+    indexes: Vec<(PathBuf, Arc<Mutex<WorkspaceIndex>>)>,
+}
+
+impl WorkspaceSearchState {
+    pub fn index_for(&mut self, root: &Path) -> Arc<Mutex<WorkspaceIndex>> {
+        let index = match self.indexes.iter().position(|(r, _)| r == root) {
+            Some(pos) => self.indexes.remove(pos).1,
+            None => Arc::new(Mutex::new(WorkspaceIndex::new(root.to_path_buf()))),
+        };
+        self.indexes.push((root.to_path_buf(), Arc::clone(&index)));
+        if self.indexes.len() > MAX_OPEN_INDEXES {
+            self.indexes.remove(0);
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignore_rules_follow_gitignore_semantics() {
+        let root = std::env::temp_dir().join(format!("coco-search-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["src/gen", "docs", "node_modules/x", "web/dist"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(
+            root.join(".gitignore"),
+            "node_modules/\n*.log\n!keep.log\n/docs/*.tmp\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("web/.gitignore"), "dist\n").unwrap();
+        std::fs::write(root.join("src/.ignore"), "gen/\n").unwrap();
+        for file in [
+            "src/main.rs",
+            "src/gen/out.rs",
+            "a.log",
+            "keep.log",
+            "docs/a.tmp",
+            "docs/readme.md",
+            "web/dist/app.js",
+            "web/index.ts",
+        ] {
+            std::fs::write(root.join(file), "fn main() {}\n").unwrap();
+        }
+
+        let mut index = WorkspaceIndex::new(root.clone());
+        let files = index.files();
+        assert_eq!(
+            files,
+            vec![
+                ".gitignore",
+                "docs/readme.md",
+                "keep.log",
+                "src/.ignore",
+                "src/main.rs",
+                "web/.gitignore",
+                "web/index.ts",
+            ]
+        );
+
+        let hits = grep_files(
+            &root,
+            &files,
+            "MAIN",
+            &GrepOptions {
+                limit: 10,
+                ..GrepOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 4);
+        assert_eq!(
+            (hits[0].column, hits[0].match_start, hits[0].match_end),
+            (4, 3, 7)
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn fuzzy_ranking_prefers_file_name_and_boundaries() {
+        let term = |s: &str| s.chars().collect::<Vec<_>>();
+        let name = score_path("src/components/ChatPanel.tsx", &term("chp")).unwrap();
+        let spread = score_path("src/chat/helpers/pool.ts", &term("chp")).unwrap();
+        assert!(name > spread);
+        let exact = score_path("crates/coco-core/src/lib.rs", &term("lib")).unwrap();
+        let partial = score_path("crates/coco-core/src/library.rs", &term("lib")).unwrap();
+        assert!(exact > partial);
+        assert_eq!(score_path("src/main.rs", &term("xyz")), None);
+    }
+}
@@ -3,6 +3,8 @@ import type {
 	ClusterStatus,
 	CreateTaskRequest,
 	CreateTaskResponse,
+	EvidenceCheckReport,
+	ResolvedEvidence,
 	SharedArtifactCategory,
 	SharedArtifactContent,
 	SharedArtifactSummary,
@@ -15,13 +17,33 @@ import type {
 import type { TaskDirectoryEntry } from '../types/sidebar';
 import type {
 	AutoContextInfo,
+	CodexChatSelection,
+	CodexCheckpoint,
+	CodexCheckpointPreview,
+	CodexConfigBackup,
+	CodexConfigDocument,
+	CodexConfigEdit,
+	CodexConfigPlan,
+	CodexConfigWriteResult,
+	CodexFileChangeApplyResult,
+	CodexFileChangeView,
 	CodexModelListResponse,
+	CodexResolvedChatSelection,
 	CodexThreadLoadedListResponse,
 	CodexThreadListResponse,
+	CodexThreadMetaCatalog,
+	CodexThreadMetaEntry,
+	CodexThreadMetaFilter,
+	CodexThreadMetaPatch,
+	CodexThreadSearchFilters,
+	CodexThreadSearchHit,
 	CodexUserInput,
 	FileInfo,
 	PromptsListResponse,
+	RenderedPrompt,
 	SkillsListResponse,
+	WatchTarget,
+	WorkspaceGrepMatch,
 	WorktreeInfo,
 } from '../types/codex';
 
@@ -151,6 +173,17 @@ export async function readSharedArtifact(taskId: string, category: SharedArtifac
 	});
 }
 
+export async function taskEvidenceCheck(taskId: string): Promise<EvidenceCheckReport> {
+	return invoke<EvidenceCheckReport>('task_evidence_check', { task_id: taskId });
+}
+
+export async function taskEvidenceResolve(taskId: string, evidenceId: string): Promise<ResolvedEvidence> {
+	return invoke<ResolvedEvidence>('task_evidence_resolve', {
+		task_id: taskId,
+		evidence_id: evidenceId,
+	});
+}
+
 export async function codexAppServerEnsure(options?: { codexHome?: string | null; profile?: string | null }): Promise<{ appServerId: string }> {
 	return invoke<{ appServerId: string }>('codex_app_server_ensure', {
 		codexHome: options?.codexHome ?? null,
@@ -169,6 +202,7 @@ export async function codexThreadList(
 		cwdFilter?: string | null;
 		pinnedThreadId?: string | null;
 		appServerId?: string | null;
+		metaFilter?: CodexThreadMetaFilter | null;
 	}
 ): Promise<CodexThreadListResponse> {
 	return invoke<CodexThreadListResponse>('codex_thread_list', {
@@ -177,9 +211,25 @@ export async function codexThreadList(
 		cwdFilter: options?.cwdFilter ?? null,
 		pinnedThreadId: options?.pinnedThreadId ?? null,
 		appServerId: options?.appServerId ?? null,
+		metaFilter: options?.metaFilter ?? null,
 	});
 }
 
+export async function codexThreadMetaUpdate(threadIds: string[], patch: CodexThreadMetaPatch): Promise<CodexThreadMetaEntry[]> {
+	return invoke<CodexThreadMetaEntry[]>('codex_thread_meta_update', { threadIds, patch });
+}
+
+export async function codexThreadMetaCatalog(): Promise<CodexThreadMetaCatalog> {
+	return invoke<CodexThreadMetaCatalog>('codex_thread_meta_catalog');
+}
+
+export async function codexThreadSearch(
+	query: string,
+	filters?: CodexThreadSearchFilters | null
+): Promise<CodexThreadSearchHit[]> {
+	return invoke<CodexThreadSearchHit[]>('codex_thread_search', { query, filters: filters ?? null });
+}
+
 export async function codexThreadLoadedList(
 	cursor?: string | null,
 	limit?: number | null,
@@ -232,20 +282,36 @@ export async function codexThreadFork(
 	});
 }
 
-export async function codexThreadRollback(threadId: string, numTurns?: number | null, appServerId?: string | null): Promise<unknown> {
+export async function codexThreadRollback(
+	threadId: string,
+	numTurns?: number | null,
+	appServerId?: string | null,
+	restoreFiles?: boolean | null,
+	fromTurnId?: string | null
+): Promise<unknown> {
 	return invoke<unknown>('codex_thread_rollback', {
 		threadId,
 		numTurns: numTurns ?? null,
+		fromTurnId: fromTurnId ?? null,
+		restoreFiles: restoreFiles ?? null,
 		appServerId: appServerId ?? null,
 	});
 }
 
-export async function codexThreadWatchStart(threadId: string, path: string): Promise<void> {
-	await invoke<void>('codex_thread_watch_start', { threadId, path });
+export async function codexCheckpointList(threadId: string): Promise<CodexCheckpoint[]> {
+	return invoke<CodexCheckpoint[]>('codex_checkpoint_list', { threadId });
 }
 
-export async function codexThreadWatchStop(): Promise<void> {
-	await invoke<void>('codex_thread_watch_stop');
+export async function codexCheckpointPreview(threadId: string, turnId: string): Promise<CodexCheckpointPreview> {
+	return invoke<CodexCheckpointPreview>('codex_checkpoint_preview', { threadId, turnId });
+}
+
+export async function watchSubscribe(target: WatchTarget): Promise<number> {
+	return invoke<number>('watch_subscribe', { target });
+}
+
+export async function watchUnsubscribe(subscriptionId: number): Promise<void> {
+	await invoke<void>('watch_unsubscribe', { subscriptionId });
 }
 
 export async function codexTurnStart(
@@ -276,10 +342,16 @@ export async function codexTurnInterrupt(threadId: string, turnId: string, appSe
 	});
 }
 
-export async function codexRespondApproval(requestId: number, decision: 'accept' | 'decline', appServerId?: string | null): Promise<void> {
+export async function codexRespondApproval(
+	requestId: number,
+	decision: 'accept' | 'decline',
+	appServerId?: string | null,
+	threadId?: string | null
+): Promise<void> {
 	await invoke<void>('codex_respond_approval', {
 		requestId,
 		decision,
+		threadId: threadId ?? null,
 		appServerId: appServerId ?? null,
 	});
 }
@@ -292,9 +364,14 @@ export async function codexModelList(cursor?: string | null, limit?: number | nu
 	});
 }
 
-export async function codexConfigReadEffective(includeLayers?: boolean | null, appServerId?: string | null): Promise<unknown> {
+export async function codexConfigReadEffective(
+	includeLayers?: boolean | null,
+	appServerId?: string | null,
+	threadId?: string | null
+): Promise<unknown> {
 	return invoke<unknown>('codex_config_read_effective', {
 		includeLayers: includeLayers ?? null,
+		threadId: threadId ?? null,
 		appServerId: appServerId ?? null,
 	});
 }
@@ -315,9 +392,15 @@ export async function codexConfigWriteChatDefaults(options: {
 	});
 }
 
-export async function codexSetProfile(profile?: string | null): Promise<void> {
-	await invoke<void>('codex_set_profile', {
-		profile: profile ?? null,
+export async function codexChatSelectionGet(threadId?: string | null): Promise<CodexResolvedChatSelection> {
+	return invoke<CodexResolvedChatSelection>('codex_chat_selection_get', { threadId: threadId ?? null });
+}
+
+/** Without `threadId`, sets the workspace defaults; with it, the thread's override (`null` clears it). */
+export async function codexChatSelectionSet(selection: CodexChatSelection | null, threadId?: string | null): Promise<CodexResolvedChatSelection> {
+	return invoke<CodexResolvedChatSelection>('codex_chat_selection_set', {
+		selection,
+		threadId: threadId ?? null,
 	});
 }
 
@@ -329,6 +412,39 @@ export async function codexWriteConfig(content: string): Promise<void> {
 	await invoke<void>('codex_write_config', { content });
 }
 
+export async function codexConfigDocument(): Promise<CodexConfigDocument> {
+	return invoke<CodexConfigDocument>('codex_config_document');
+}
+
+/** Pass either `edits` or `content`. Nothing is written. */
+export async function codexConfigPreview(change: { edits?: CodexConfigEdit[]; content?: string }): Promise<CodexConfigPlan> {
+	return invoke<CodexConfigPlan>('codex_config_preview', {
+		edits: change.edits ?? null,
+		content: change.content ?? null,
+	});
+}
+
+/** Fails without writing on validation issues or when `revision` is stale. */
+export async function codexConfigApply(change: {
+	edits?: CodexConfigEdit[];
+	content?: string;
+	revision?: string | null;
+}): Promise<CodexConfigWriteResult> {
+	return invoke<CodexConfigWriteResult>('codex_config_apply', {
+		edits: change.edits ?? null,
+		content: change.content ?? null,
+		revision: change.revision ?? null,
+	});
+}
+
+export async function codexConfigBackupsList(): Promise<CodexConfigBackup[]> {
+	return invoke<CodexConfigBackup[]>('codex_config_backups_list');
+}
+
+export async function codexConfigBackupRestore(id: string, revision?: string | null): Promise<CodexConfigWriteResult> {
+	return invoke<CodexConfigWriteResult>('codex_config_backup_restore', { id, revision: revision ?? null });
+}
+
 export async function codexDiagnostics(): Promise<{
 	path: string;
 	resolvedCodexBin: string | null;
@@ -363,6 +479,19 @@ export async function workspaceRecentList(): Promise<string[]> {
 	return invoke<string[]>('workspace_recent_list');
 }
 
+export async function workspaceAllowedRootsList(): Promise<string[]> {
+	return invoke<string[]>('workspace_allowed_roots_list');
+}
+
+/** Opens a native folder picker; resolves to the allowed root, or null when cancelled. */
+export async function workspaceAllowedRootPick(): Promise<string | null> {
+	return invoke<string | null>('workspace_allowed_root_pick');
+}
+
+export async function workspaceAllowedRootRemove(path: string): Promise<string[]> {
+	return invoke<string[]>('workspace_allowed_root_remove', { path });
+}
+
 export async function windowNew(): Promise<string> {
 	return invoke<string>('window_new');
 }
@@ -379,6 +508,20 @@ export async function searchWorkspaceFiles(cwd: string, query: string, limit?: n
 	});
 }
 
+export async function grepWorkspaceFiles(
+	cwd: string,
+	query: string,
+	options?: { regex?: boolean; caseSensitive?: boolean; limit?: number }
+): Promise<WorkspaceGrepMatch[]> {
+	return invoke<WorkspaceGrepMatch[]>('grep_workspace_files', {
+		cwd,
+		query,
+		regex: options?.regex ?? null,
+		caseSensitive: options?.caseSensitive ?? null,
+		limit: options?.limit ?? null,
+	});
+}
+
 export async function readFileContent(path: string): Promise<string> {
 	return invoke<string>('read_file_content', { path });
 }
@@ -407,6 +550,39 @@ export async function codexPromptList(): Promise<PromptsListResponse> {
 	return invoke<PromptsListResponse>('codex_prompt_list');
 }
 
+/** Expands a custom prompt: `args` fills `$1`..`$9` / `$ARGUMENTS`, and `NAME=value` words fill `$NAME`. */
+export async function promptRender(name: string, args: string): Promise<RenderedPrompt> {
+	return invoke<RenderedPrompt>('prompt_render', { name, args });
+}
+
+export async function codexFileChangeGet(itemId: string): Promise<CodexFileChangeView> {
+	return invoke<CodexFileChangeView>('codex_file_change_get', { itemId });
+}
+
+export async function codexFileChangeRevert(
+	itemId: string,
+	path?: string | null,
+	hunk?: number | null
+): Promise<CodexFileChangeApplyResult> {
+	return invoke<CodexFileChangeApplyResult>('codex_file_change_revert', {
+		itemId,
+		path: path ?? null,
+		hunk: hunk ?? null,
+	});
+}
+
+export async function codexFileChangeReapply(
+	itemId: string,
+	path?: string | null,
+	hunk?: number | null
+): Promise<CodexFileChangeApplyResult> {
+	return invoke<CodexFileChangeApplyResult>('codex_file_change_reapply', {
+		itemId,
+		path: path ?? null,
+		hunk: hunk ?? null,
+	});
+}
+
 export const apiClient = {
 	listTasks,
 	getTask,
@@ -423,38 +599,59 @@ export const apiClient = {
 	workspaceListDirectory,
 	listSharedArtifacts,
 	readSharedArtifact,
+	taskEvidenceCheck,
+	taskEvidenceResolve,
 	codexAppServerEnsure,
 	codexAppServerShutdown,
 	codexThreadList,
+	codexThreadSearch,
 	codexThreadTitleSet,
+	codexThreadMetaUpdate,
+	codexThreadMetaCatalog,
 	codexThreadArchive,
 	codexThreadStart,
 	codexThreadResume,
 	codexThreadFork,
 	codexThreadRollback,
-	codexThreadWatchStart,
-	codexThreadWatchStop,
+	codexCheckpointList,
+	codexCheckpointPreview,
+	watchSubscribe,
+	watchUnsubscribe,
 	codexTurnStart,
 	codexTurnInterrupt,
 	codexRespondApproval,
 	codexModelList,
 	codexConfigReadEffective,
 	codexConfigWriteChatDefaults,
-	codexSetProfile,
+	codexChatSelectionGet,
+	codexChatSelectionSet,
 	codexReadConfig,
 	codexWriteConfig,
+	codexConfigDocument,
+	codexConfigPreview,
+	codexConfigApply,
+	codexConfigBackupsList,
+	codexConfigBackupRestore,
 	codexDiagnostics,
 	codexSkillList,
 	codexPromptList,
+	promptRender,
+	codexFileChangeGet,
+	codexFileChangeRevert,
+	codexFileChangeReapply,
 	codexThreadLoadedList,
 	workspaceRootGet,
 	workspaceRootSet,
 	workspaceRecentList,
+	workspaceAllowedRootsList,
+	workspaceAllowedRootPick,
+	workspaceAllowedRootRemove,
 	workspaceWriteFile,
 	workspaceRenameFile,
 	windowNew,
 	// Context management APIs
 	searchWorkspaceFiles,
+	grepWorkspaceFiles,
 	readFileContent,
 	getAutoContext,
 	gitWorktreeList,
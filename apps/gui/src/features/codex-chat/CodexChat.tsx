@@ -46,9 +46,16 @@ import {
 } from './lib/parsing';
 import type {
 	AutoContextInfo,
+	CodexChatSelection,
+	CodexConfigBackup,
+	CodexConfigIssue,
+	CodexConfigPlan,
 	CodexModelInfo,
 	CodexThread,
 	CodexThreadItem,
+	CodexThreadMetaCatalog,
+	CodexThreadMetaFilter,
+	CodexThreadSearchHit,
 	CodexThreadSummary,
 	CodexThreadWatchEvent,
 	CodexUserInput,
@@ -270,6 +277,26 @@ type RenameTaskState = {
 	error: string | null;
 };
 
+type ThreadMetaDialogState = {
+	threadId: string;
+	/** Threads spawned under this one (orchestrator/workers), offered for bulk apply. */
+	linkedThreadIds: string[];
+	applyToLinked: boolean;
+	tags: string;
+	folder: string;
+	taskId: string;
+	note: string;
+	error: string | null;
+};
+
+/** Session list filter encoded for the sidebar picker: `all`, `pinned`, `tag:<name>` or `folder:<name>`. */
+function threadMetaFilterFromKey(key: string): CodexThreadMetaFilter | null {
+	if (key === 'pinned') return { pinned: true };
+	if (key.startsWith('tag:')) return { tags: [key.slice(4)] };
+	if (key.startsWith('folder:')) return { folder: key.slice(7) };
+	return null;
+}
+
 type RenameFileState = {
 	tabId: string;
 	fromPath: string;
@@ -282,6 +309,12 @@ export function CodexChat() {
 	const [sessions, setSessions] = useState<CodexThreadSummary[]>([]);
 	const [sessionsLoading, setSessionsLoading] = useState(true);
 	const [sessionsError, setSessionsError] = useState<string | null>(null);
+	const [threadSearchQuery, setThreadSearchQuery] = useState('');
+	const [threadSearchResults, setThreadSearchResults] = useState<CodexThreadSearchHit[] | null>(null);
+	const [threadSearchLoading, setThreadSearchLoading] = useState(false);
+	const [threadSearchError, setThreadSearchError] = useState<string | null>(null);
+	// Turn to scroll to once the thread opened from a search result has rendered.
+	const pendingTurnAnchorRef = useRef<{ threadId: string; turnIndex: number } | null>(null);
 	const [sessionsLoadedOnce, setSessionsLoadedOnce] = useState(false);
 	// "updatedAtMs" can change for non-history events (e.g. resume/view). We keep a stable
 	// timestamp that only moves forward when the session history meaningfully changes.
@@ -294,6 +327,10 @@ export function CodexChat() {
 	const [taskContextMenu, setTaskContextMenu] = useState<TaskContextMenuState | null>(null);
 	const [panelTabContextMenu, setPanelTabContextMenu] = useState<PanelTabContextMenuState | null>(null);
 	const [renameTaskDialog, setRenameTaskDialog] = useState<RenameTaskState | null>(null);
+	const [threadMetaDialog, setThreadMetaDialog] = useState<ThreadMetaDialogState | null>(null);
+	const [threadMetaCatalog, setThreadMetaCatalog] = useState<CodexThreadMetaCatalog | null>(null);
+	const [threadMetaFilterKey, setThreadMetaFilterKey] = useState('all');
+	const threadMetaFilterKeyRef = useRef('all');
 	const [renameFileDialog, setRenameFileDialog] = useState<RenameFileState | null>(null);
 	type RerunDialogState = {
 		entry: Extract<ChatEntry, { kind: 'user' }>;
@@ -306,7 +343,6 @@ export function CodexChat() {
 	const externalRefreshInFlightRef = useRef(false);
 	const externalRefreshPendingRef = useRef<{ threadId: string; updatedAtMs: number | null } | null>(null);
 	const lastExternalRefreshAtRef = useRef(0);
-	const threadWatchSeqRef = useRef(0);
 	const archiveTaskInFlightRef = useRef<Set<string>>(new Set());
 	const renameTaskInputRef = useRef<HTMLInputElement>(null);
 	const renameFileInputRef = useRef<HTMLInputElement>(null);
@@ -339,6 +375,7 @@ export function CodexChat() {
 	const [modelsError, setModelsError] = useState<string | null>(null);
 	const [profiles, setProfiles] = useState<string[]>([]);
 	const [selectedProfile, setSelectedProfile] = useState<string | null>(null);
+	const [threadSelectionOverride, setThreadSelectionOverride] = useState(false);
 
 	const [selectedThreadId, setSelectedThreadId] = useState<string | null>(null);
 	const [activeThread, setActiveThread] = useState<CodexThread | null>(null);
@@ -379,6 +416,10 @@ export function CodexChat() {
 	const [configText, setConfigText] = useState('');
 	const [configSaving, setConfigSaving] = useState(false);
 	const [configError, setConfigError] = useState<string | null>(null);
+	const [configRevision, setConfigRevision] = useState<string | null>(null);
+	const [configIssues, setConfigIssues] = useState<CodexConfigIssue[]>([]);
+	const [configPlan, setConfigPlan] = useState<CodexConfigPlan | null>(null);
+	const [configBackups, setConfigBackups] = useState<CodexConfigBackup[]>([]);
 	const [autoContextEnabled, setAutoContextEnabled] = useState(true);
 	const [diagnostics, setDiagnostics] = useState<{
 		path: string;
@@ -845,7 +886,11 @@ export function CodexChat() {
 		}
 		setSessionsError(null);
 		try {
-			const res = await apiClient.codexThreadList(null, 200);
+			void apiClient
+				.codexThreadMetaCatalog()
+				.then(setThreadMetaCatalog)
+				.catch(() => {});
+			const res = await apiClient.codexThreadList(null, 200, { metaFilter: threadMetaFilterFromKey(threadMetaFilterKeyRef.current) });
 			const data = res.data ?? [];
 			setMeaningfulUpdatedAtMsByThreadId((prev) => {
 				const prevSummaryById = prevSessionSummaryByIdRef.current;
@@ -901,12 +946,18 @@ export function CodexChat() {
 		};
 	}, []);
 
-	const loadModelsAndChatDefaults = useCallback(async () => {
+	// Stored selection (thread override, else workspace) wins over config.toml defaults.
+	const loadModelsAndChatDefaults = useCallback(async (threadId: string | null = null) => {
 		setModelsError(null);
 		setStatusPopoverError(null);
 
 		try {
-			const [modelsRes, configRes] = await Promise.all([apiClient.codexModelList(null, 200), apiClient.codexConfigReadEffective(false)]);
+			const [modelsRes, configRes, chatSelection] = await Promise.all([
+				apiClient.codexModelList(null, 200),
+				apiClient.codexConfigReadEffective(false, null, threadId),
+				apiClient.codexChatSelectionGet(threadId),
+			]);
+			setThreadSelectionOverride(chatSelection.threadOverride);
 
 			const config = (configRes as any)?.config ?? {};
 			const { profiles: configProfiles, models: configProfileModels, selectedProfile: configSelectedProfile } = collectProfilesFromConfig(config);
@@ -925,7 +976,8 @@ export function CodexChat() {
 			const uniqueProfiles = uniqueStrings(rawProfiles);
 			const profileModels = uniqueStrings(rawProfileModels);
 			setProfiles(uniqueProfiles);
-			const normalizedSelectedProfile = configSelectedProfile && uniqueProfiles.includes(configSelectedProfile) ? configSelectedProfile : null;
+			const activeProfile = chatSelection.profile ?? configSelectedProfile;
+			const normalizedSelectedProfile = activeProfile && uniqueProfiles.includes(activeProfile) ? activeProfile : null;
 			setSelectedProfile(normalizedSelectedProfile);
 
 			let nextModels = (modelsRes as { data: CodexModelInfo[] }).data ?? [];
@@ -937,9 +989,12 @@ export function CodexChat() {
 
 			// Read from the selected profile if one is active, otherwise use top-level config
 			const profileConfig = config.profile && config.profiles?.[config.profile] ? config.profiles[config.profile] : {};
-			const configuredModel = typeof profileConfig.model === 'string' ? profileConfig.model : typeof config.model === 'string' ? config.model : null;
-			const configuredEffort = parseReasoningEffortValue(profileConfig.model_reasoning_effort ?? config.model_reasoning_effort);
-			const configuredApproval = parseApprovalPolicyValue(profileConfig.approval_policy ?? config.approval_policy);
+			const configuredModel =
+				chatSelection.model ?? (typeof profileConfig.model === 'string' ? profileConfig.model : typeof config.model === 'string' ? config.model : null);
+			const configuredEffort = parseReasoningEffortValue(
+				chatSelection.modelReasoningEffort ?? profileConfig.model_reasoning_effort ?? config.model_reasoning_effort
+			);
+			const configuredApproval = parseApprovalPolicyValue(chatSelection.approvalPolicy ?? profileConfig.approval_policy ?? config.approval_policy);
 
 			if (configuredApproval) setApprovalPolicy(configuredApproval);
 
@@ -956,28 +1011,81 @@ export function CodexChat() {
 		}
 	}, []);
 
+	const loadConfigDocument = useCallback(async () => {
+		const [doc, backups] = await Promise.all([apiClient.codexConfigDocument(), apiClient.codexConfigBackupsList()]);
+		setConfigText(doc.content);
+		setConfigRevision(doc.revision);
+		setConfigIssues(doc.issues);
+		setConfigPlan(null);
+		setConfigBackups(backups);
+	}, []);
+
 	const openConfig = useCallback(async () => {
 		setIsConfigOpen(true);
 		setConfigError(null);
 		try {
-			const content = await apiClient.codexReadConfig();
-			setConfigText(content);
+			await loadConfigDocument();
 		} catch (err) {
 			setConfigError(errorMessage(err, 'Failed to read config'));
 		}
-	}, []);
+	}, [loadConfigDocument]);
 
+	// Two steps: review the validated diff, then write it (the previous file is backed up).
 	const saveConfig = useCallback(async () => {
 		setConfigSaving(true);
 		setConfigError(null);
 		try {
-			await apiClient.codexWriteConfig(configText);
+			if (!configPlan) {
+				const plan = await apiClient.codexConfigPreview({ content: configText });
+				setConfigIssues(plan.issues);
+				if (plan.issues.length === 0 && plan.changed) setConfigPlan(plan);
+				else if (plan.issues.length === 0) setConfigError('No changes to save.');
+				return;
+			}
+			await apiClient.codexConfigApply({ content: configPlan.content, revision: configRevision });
+			await loadConfigDocument();
 		} catch (err) {
 			setConfigError(errorMessage(err, 'Failed to write config'));
 		} finally {
 			setConfigSaving(false);
 		}
-	}, [configText]);
+	}, [configPlan, configRevision, configText, loadConfigDocument]);
+
+	const restoreConfigBackup = useCallback(
+		async (backup: CodexConfigBackup) => {
+			const confirmed = await dialogConfirm(
+				`Restore config.toml from ${new Date(backup.createdAtMs).toLocaleString()}? The current file is backed up first.`,
+				{ title: 'Restore config', kind: 'warning' }
+			);
+			if (!confirmed) return;
+			setConfigSaving(true);
+			setConfigError(null);
+			try {
+				await apiClient.codexConfigBackupRestore(backup.id, configRevision);
+				await loadConfigDocument();
+			} catch (err) {
+				setConfigError(errorMessage(err, 'Failed to restore config'));
+			} finally {
+				setConfigSaving(false);
+			}
+		},
+		[configRevision, loadConfigDocument]
+	);
+
+	// Writes to the selected thread's override when it has one, otherwise to the workspace defaults.
+	const persistChatSelection = useCallback(
+		async (patch: CodexChatSelection) => {
+			const next: CodexChatSelection = {
+				profile: selectedProfile,
+				model: selectedModel,
+				modelReasoningEffort: selectedEffort,
+				approvalPolicy,
+				...patch,
+			};
+			await apiClient.codexChatSelectionSet(next, threadSelectionOverride ? selectedThreadId : null);
+		},
+		[approvalPolicy, selectedEffort, selectedModel, selectedProfile, selectedThreadId, threadSelectionOverride]
+	);
 
 	const applyApprovalPolicy = useCallback(
 		async (next: ApprovalPolicy) => {
@@ -987,13 +1095,13 @@ export function CodexChat() {
 			setApprovalPolicy(next);
 			setOpenStatusPopover(null);
 			try {
-				await apiClient.codexConfigWriteChatDefaults({ approvalPolicy: next, profile: selectedProfile });
+				await persistChatSelection({ approvalPolicy: next });
 			} catch (err) {
 				setApprovalPolicy(prev);
 				setStatusPopoverError(errorMessage(err, 'Failed to update approval_policy'));
 			}
 		},
-		[approvalPolicy, selectedProfile]
+		[approvalPolicy, persistChatSelection]
 	);
 
 	const applyModel = useCallback(
@@ -1013,18 +1121,14 @@ export function CodexChat() {
 			setOpenStatusPopover(null);
 
 			try {
-				await apiClient.codexConfigWriteChatDefaults({
-					model: nextModel,
-					modelReasoningEffort: nextEffort,
-					profile: selectedProfile,
-				});
+				await persistChatSelection({ model: nextModel, modelReasoningEffort: nextEffort });
 			} catch (err) {
 				setSelectedModel(prevModel);
 				setSelectedEffort(prevEffort);
 				setStatusPopoverError(errorMessage(err, 'Failed to update model'));
 			}
 		},
-		[models, selectedEffort, selectedModel, selectedProfile]
+		[models, persistChatSelection, selectedEffort, selectedModel]
 	);
 
 	const applyReasoningEffort = useCallback(
@@ -1035,16 +1139,13 @@ export function CodexChat() {
 			setSelectedEffort(nextEffort);
 			setOpenStatusPopover(null);
 			try {
-				await apiClient.codexConfigWriteChatDefaults({
-					modelReasoningEffort: nextEffort,
-					profile: selectedProfile,
-				});
+				await persistChatSelection({ modelReasoningEffort: nextEffort });
 			} catch (err) {
 				setSelectedEffort(prev);
 				setStatusPopoverError(errorMessage(err, 'Failed to update model_reasoning_effort'));
 			}
 		},
-		[selectedEffort, selectedProfile]
+		[persistChatSelection, selectedEffort]
 	);
 
 	const ingestCollabItems = useCallback((threadId: string, items: Array<Extract<CodexThreadItem, { type: 'collabAgentToolCall' }>>) => {
@@ -1621,7 +1722,13 @@ export function CodexChat() {
 					interactionCount: session.interactionCount ?? null,
 					isActive: taskIsActive,
 					status: taskIsActive ? 'running' : undefined,
-					metadata: { threadId: session.id, wtLabel: worktreeLabelForThreadId(session.id) },
+					metadata: {
+						threadId: session.id,
+						wtLabel: worktreeLabelForThreadId(session.id),
+						pinned: session.pinned,
+						tags: session.tags,
+						note: session.note ?? undefined,
+					},
 					children: children.length > 0 ? children : undefined,
 				};
 				registerNode(taskNode);
@@ -1629,11 +1736,26 @@ export function CodexChat() {
 			});
 
 		const nowMs = Date.now();
+		const pinnedNodes: TreeNodeData[] = [];
+		const folderNodesByName: Record<string, TreeNodeData[]> = {};
 		const activeNodes: TreeNodeData[] = [];
 		const archivedNodesByDate: Record<string, Record<string, TreeNodeData[]>> = {};
 		for (const node of taskNodes) {
 			const threadId = node.metadata?.threadId ?? '';
 			const summary = threadSummaryById.get(threadId);
+			// Pinned and foldered sessions stay put instead of aging into "Archived".
+			if (summary?.pinned) {
+				pinnedNodes.push(node);
+				continue;
+			}
+			const folder = summary?.folder?.trim();
+			if (folder) {
+				if (!folderNodesByName[folder]) {
+					folderNodesByName[folder] = [];
+				}
+				folderNodesByName[folder].push(node);
+				continue;
+			}
 			const updatedAtMs = taskLatestUpdateMsByThreadId[threadId] ?? meaningfulUpdatedAtMsByThreadId[threadId] ?? summary?.updatedAtMs ?? null;
 			const isArchived = updatedAtMs != null && nowMs - updatedAtMs > 60 * 60 * 1000;
 			if (!isArchived) {
@@ -1697,11 +1819,24 @@ export function CodexChat() {
 		};
 		registerNode(archivedGroupRootNode);
 
+		const folderNodes: TreeNodeData[] = Object.keys(folderNodesByName)
+			.sort((a, b) => a.localeCompare(b))
+			.map((folder) => {
+				const folderNode: TreeNodeData = {
+					id: `thread-folder-${folder}`,
+					type: 'folder',
+					label: folder,
+					children: folderNodesByName[folder],
+				};
+				registerNode(folderNode);
+				return folderNode;
+			});
+
 		const rootNode: TreeNodeData = {
 			id: rootId,
 			type: 'repo',
 			label: rootLabel,
-			children: [...activeNodes, archivedGroupRootNode],
+			children: [...pinnedNodes, ...folderNodes, ...activeNodes, archivedGroupRootNode],
 		};
 		registerNode(rootNode);
 		const treeData: TreeNodeData[] = [rootNode];
@@ -1807,6 +1942,57 @@ export function CodexChat() {
 		[selectedThreadId, selectSession, sessions]
 	);
 
+	useEffect(() => {
+		const query = threadSearchQuery.trim();
+		if (!query) {
+			setThreadSearchResults(null);
+			setThreadSearchError(null);
+			setThreadSearchLoading(false);
+			return;
+		}
+		let cancelled = false;
+		setThreadSearchLoading(true);
+		const timer = window.setTimeout(() => {
+			apiClient
+				.codexThreadSearch(query, { cwd: workspaceRoot, limit: 30 })
+				.then((hits) => {
+					if (cancelled) return;
+					setThreadSearchResults(hits);
+					setThreadSearchError(null);
+				})
+				.catch((err) => {
+					if (cancelled) return;
+					setThreadSearchError(errorMessage(err, 'Failed to search sessions'));
+				})
+				.finally(() => {
+					if (!cancelled) setThreadSearchLoading(false);
+				});
+		}, 250);
+		return () => {
+			cancelled = true;
+			window.clearTimeout(timer);
+		};
+	}, [threadSearchQuery, workspaceRoot]);
+
+	const scrollToPendingTurnAnchor = useCallback(() => {
+		const anchor = pendingTurnAnchorRef.current;
+		if (!anchor || anchor.threadId !== selectedThreadIdRef.current) return;
+		const turnId = turnOrderRef.current.filter((id) => id && id !== PENDING_TURN_ID)[anchor.turnIndex];
+		if (!turnId) return;
+		const el = scrollRef.current?.querySelector<HTMLElement>(`[data-turn-id="${CSS.escape(turnId)}"]`);
+		if (!el) return;
+		pendingTurnAnchorRef.current = null;
+		el.scrollIntoView({ block: 'start' });
+	}, []);
+
+	const openThreadSearchResult = useCallback(
+		(threadId: string, turnIndex: number | null) => {
+			pendingTurnAnchorRef.current = turnIndex == null ? null : { threadId, turnIndex };
+			void openAgentPanel(threadId).then(scrollToPendingTurnAnchor);
+		},
+		[openAgentPanel, scrollToPendingTurnAnchor]
+	);
+
 	const openFilePreview = useCallback(
 		(path: string) => {
 			const normalizedPath = path.replace(/\\/g, '/');
@@ -2137,6 +2323,64 @@ export function CodexChat() {
 		}
 	}, [listSessions, renameTaskDialog]);
 
+	const changeThreadMetaFilter = useCallback(
+		(key: string) => {
+			threadMetaFilterKeyRef.current = key;
+			setThreadMetaFilterKey(key);
+			void listSessions();
+		},
+		[listSessions]
+	);
+
+	const toggleThreadPinned = useCallback(
+		async (threadId: string) => {
+			const pinned = Boolean(sessions.find((s) => s.id === threadId)?.pinned);
+			try {
+				await apiClient.codexThreadMetaUpdate([threadId], { pinned: !pinned });
+				await listSessions();
+			} catch (err) {
+				setSessionsError(errorMessage(err, 'Failed to update session'));
+			}
+		},
+		[listSessions, sessions]
+	);
+
+	const openThreadMetaDialog = useCallback(
+		(nodeId: string, threadId: string) => {
+			const summary = sessions.find((s) => s.id === threadId) ?? null;
+			const root = sessionTree.nodeById[nodeId];
+			const linkedThreadIds = root ? collectThreadIdsInNode(root).filter((id) => id !== threadId) : [];
+			setThreadMetaDialog({
+				threadId,
+				linkedThreadIds,
+				applyToLinked: false,
+				tags: (summary?.tags ?? []).join(', '),
+				folder: summary?.folder ?? '',
+				taskId: summary?.taskId ?? '',
+				note: summary?.note ?? '',
+				error: null,
+			});
+		},
+		[collectThreadIdsInNode, sessionTree.nodeById, sessions]
+	);
+
+	const submitThreadMetaDialog = useCallback(async () => {
+		if (!threadMetaDialog) return;
+		const threadIds = threadMetaDialog.applyToLinked ? [threadMetaDialog.threadId, ...threadMetaDialog.linkedThreadIds] : [threadMetaDialog.threadId];
+		try {
+			await apiClient.codexThreadMetaUpdate(threadIds, {
+				tags: threadMetaDialog.tags.split(','),
+				folder: threadMetaDialog.folder,
+				taskId: threadMetaDialog.taskId,
+				note: threadMetaDialog.note,
+			});
+			setThreadMetaDialog(null);
+			await listSessions();
+		} catch (err) {
+			setThreadMetaDialog((prev) => (prev ? { ...prev, error: errorMessage(err, 'Failed to update session') } : prev));
+		}
+	}, [listSessions, threadMetaDialog]);
+
 	const renameFileTab = useCallback((tabId: string) => {
 		const tab = panelTabsRef.current.find((t): t is FilePanelTab => t.id === tabId && t.kind === 'file');
 		if (!tab) return;
@@ -2402,6 +2646,7 @@ export function CodexChat() {
 		workbenchGraph.workerThreadIds,
 	]);
 
+	// Each profile runs on its own app-server; the session is resumed on the new one.
 	const applyProfile = useCallback(
 		async (nextProfile: string) => {
 			if (nextProfile === selectedProfile) return;
@@ -2420,19 +2665,54 @@ export function CodexChat() {
 			setOpenStatusPopover(null);
 
 			try {
-				await apiClient.codexSetProfile(nextProfile);
+				if (runningFocusedTurn && selectedThreadId && activeTurnId) {
+					await apiClient.codexTurnInterrupt(selectedThreadId, activeTurnId);
+				}
+				// The profile decides model and effort unless they are picked again afterwards.
+				await persistChatSelection({ profile: nextProfile, model: null, modelReasoningEffort: null });
 				if (selectedThreadId) {
 					await selectSession(selectedThreadId);
 				}
-				await loadModelsAndChatDefaults();
+				await loadModelsAndChatDefaults(selectedThreadId);
 			} catch (err) {
 				setSelectedProfile(prevProfile);
 				setStatusPopoverError(errorMessage(err, 'Failed to switch profile'));
 			}
 		},
-		[activeTurnId, loadModelsAndChatDefaults, selectedProfile, selectedThreadId, selectSession, turnsById]
+		[activeTurnId, loadModelsAndChatDefaults, persistChatSelection, selectedProfile, selectedThreadId, selectSession, turnsById]
 	);
 
+	// Pin the current selection to this thread, or let it follow the workspace again.
+	const toggleThreadSelectionOverride = useCallback(async () => {
+		if (!selectedThreadId) return;
+		setStatusPopoverError(null);
+		setOpenStatusPopover(null);
+		try {
+			const before = selectedProfile;
+			const resolved = threadSelectionOverride
+				? await apiClient.codexChatSelectionSet(null, selectedThreadId)
+				: await apiClient.codexChatSelectionSet(
+						{ profile: selectedProfile, model: selectedModel, modelReasoningEffort: selectedEffort, approvalPolicy },
+						selectedThreadId
+					);
+			if ((resolved.profile ?? null) !== before) {
+				await selectSession(selectedThreadId);
+			}
+			await loadModelsAndChatDefaults(selectedThreadId);
+		} catch (err) {
+			setStatusPopoverError(errorMessage(err, 'Failed to update thread profile'));
+		}
+	}, [
+		approvalPolicy,
+		loadModelsAndChatDefaults,
+		selectSession,
+		selectedEffort,
+		selectedModel,
+		selectedProfile,
+		selectedThreadId,
+		threadSelectionOverride,
+	]);
+
 	const createNewSession = useCallback(async () => {
 		setTurnOrder([]);
 		setTurnsById({});
@@ -2685,6 +2965,10 @@ export function CodexChat() {
 				attachments.push({ type: 'prompt', name: selectedPrompt.name });
 			}
 
+			// A selected custom prompt takes the typed text as its arguments; render it before starting a
+			// thread so a missing argument does not leave an empty session behind.
+			const promptText = selectedPrompt ? (await apiClient.promptRender(selectedPrompt.name, userInput)).text : userInput;
+
 			let threadId = selectedThreadId;
 			let currentRepoPath = effectiveCwd;
 			if (!threadId) {
@@ -2701,11 +2985,11 @@ export function CodexChat() {
 
 			const outgoingText = autoContextEnabled
 				? wrapUserInputWithRepoContext({
-						userInput,
+						userInput: promptText,
 						currentRepoPath,
 						relatedRepoPaths,
 					})
-				: userInput;
+				: promptText;
 
 			// Build CodexUserInput array for API
 			const codexInput: CodexUserInput[] = [];
@@ -2884,8 +3168,27 @@ export function CodexChat() {
 				}
 			}
 
+			// Offer to put the files back too when a checkpoint exists and the tree has moved since.
+			let restoreFiles = false;
+			try {
+				const preview = await apiClient.codexCheckpointPreview(selectedThreadId, targetTurnId);
+				if (preview.files.length > 0) {
+					const listed = preview.files
+						.slice(0, 10)
+						.map((file) => `${file.status === 'added' ? '删除' : '还原'} ${file.path}`)
+						.join('\n');
+					const more = preview.files.length > 10 ? `\n… 以及另外 ${preview.files.length - 10} 个文件` : '';
+					restoreFiles = await dialogConfirm(`是否同时将文件恢复到该回合开始前的状态？\n\n${listed}${more}`, {
+						title: '重新运行',
+						kind: 'warning',
+					});
+				}
+			} catch {
+				// No checkpoint for this turn (e.g. created before checkpoints existed); roll back history only.
+			}
+
 			try {
-				await apiClient.codexThreadRollback(selectedThreadId, rollbackTurns);
+				await apiClient.codexThreadRollback(selectedThreadId, rollbackTurns, null, restoreFiles, targetTurnId);
 				// Rollback is a meaningful history change; let it move the session out of "Archived" immediately.
 				bumpMeaningfulUpdatedAtMs(selectedThreadId, Date.now());
 				await selectSession(selectedThreadId);
@@ -2985,7 +3288,7 @@ export function CodexChat() {
 	}, [rerunDialog, requestUserEntryRerun]);
 
 	const approve = useCallback(async (requestId: number, decision: 'accept' | 'decline') => {
-		await apiClient.codexRespondApproval(requestId, decision);
+		await apiClient.codexRespondApproval(requestId, decision, null, selectedThreadIdRef.current);
 	}, []);
 
 	const toggleEntryCollapse = useCallback(
@@ -3111,9 +3414,16 @@ export function CodexChat() {
 		const currentRepoPath = effectiveCwd;
 		if (!currentRepoPath) return;
 
-		const selection = await openDialog({ directory: true, multiple: false });
-		const selectedPath = Array.isArray(selection) ? selection[0] : selection;
-		if (typeof selectedPath !== 'string' || selectedPath.length === 0) return;
+		// File commands only reach the workspace and explicitly allowed roots; the backend owns
+		// the folder picker so the allowlist only grows through a user choice.
+		let selectedPath: string | null;
+		try {
+			selectedPath = await apiClient.workspaceAllowedRootPick();
+		} catch (err) {
+			void dialogMessage(errorMessage(err, 'Cannot add folder'), { title: 'Related repo', kind: 'error' });
+			return;
+		}
+		if (!selectedPath) return;
 
 		setRelatedRepoPaths((prev) => {
 			if (prev.length >= 3) return prev;
@@ -3792,12 +4102,16 @@ export function CodexChat() {
 	useEffect(() => {
 		listSessions();
 		void seedRunningThreads();
-		loadModelsAndChatDefaults();
 		void loadWorkspaceRoot();
 		void loadRecentWorkspaces();
 		void loadSkills();
 		void loadPrompts();
-	}, [listSessions, seedRunningThreads, loadModelsAndChatDefaults, loadWorkspaceRoot, loadRecentWorkspaces, loadSkills, loadPrompts]);
+	}, [listSessions, seedRunningThreads, loadWorkspaceRoot, loadRecentWorkspaces, loadSkills, loadPrompts]);
+
+	// Profile, model and approval policy can differ per thread.
+	useEffect(() => {
+		void loadModelsAndChatDefaults(selectedThreadId);
+	}, [loadModelsAndChatDefaults, selectedThreadId]);
 
 	useEffect(() => {
 		externalRefreshPendingRef.current = null;
@@ -3813,38 +4127,34 @@ export function CodexChat() {
 	}, [selectedThreadId, settings.defaultCollapseDetails]);
 
 	useEffect(() => {
-		const seq = ++threadWatchSeqRef.current;
 		const threadId = selectedThreadId;
 		const path = activeThread?.path ?? null;
-		console.log('[ThreadWatch] setup effect:', { threadId, path, seq });
-		void (async () => {
-			try {
-				await apiClient.codexThreadWatchStop();
-			} catch {
-				// ignore
-			}
-			if (seq !== threadWatchSeqRef.current) return;
-			if (threadId && path) {
-				try {
-					await apiClient.codexThreadWatchStart(threadId, path);
-					console.log('[ThreadWatch] started watching:', { threadId, path });
-				} catch (err) {
-					console.error('[ThreadWatch] failed to start:', err);
+		if (!threadId || !path) return;
+		let cancelled = false;
+		let subscriptionId: number | null = null;
+		void apiClient
+			.watchSubscribe({ kind: 'rollout', threadId, path })
+			.then((id) => {
+				if (cancelled) {
+					void apiClient.watchUnsubscribe(id).catch(() => {
+						// ignore
+					});
+					return;
 				}
-			} else {
-				console.log('[ThreadWatch] skipped: no threadId or path', { threadId, path });
-			}
-		})();
-	}, [activeThread?.path, selectedThreadId]);
-
-	// Ensure we always stop the watcher on unmount.
-	useEffect(() => {
-		return () => {
-			void apiClient.codexThreadWatchStop().catch(() => {
-				// ignore
+				subscriptionId = id;
+			})
+			.catch((err) => {
+				console.error('[ThreadWatch] failed to subscribe:', err);
 			});
+		return () => {
+			cancelled = true;
+			if (subscriptionId != null) {
+				void apiClient.watchUnsubscribe(subscriptionId).catch(() => {
+					// ignore
+				});
+			}
 		};
-	}, []);
+	}, [activeThread?.path, selectedThreadId]);
 
 	useEffect(() => {
 		let mounted = true;
@@ -4116,6 +4426,10 @@ export function CodexChat() {
 		el.scrollTop = el.scrollHeight;
 	}, [renderCount]);
 
+	useEffect(() => {
+		scrollToPendingTurnAnchor();
+	}, [renderCount, selectedThreadId, turnOrder, scrollToPendingTurnAnchor]);
+
 	return (
 		<div className="flex h-full min-w-0 flex-col overflow-x-hidden">
 			{/* 自定义标题栏 */}
@@ -4167,12 +4481,27 @@ export function CodexChat() {
 					onRefresh={listSessions}
 					loading={sessionsLoading}
 					error={sessionsError}
+					filterKey={threadMetaFilterKey}
+					filterCatalog={threadMetaCatalog}
+					onFilterKeyChange={changeThreadMetaFilter}
+					searchQuery={threadSearchQuery}
+					onSearchQueryChange={setThreadSearchQuery}
+					searchResults={threadSearchResults}
+					searchLoading={threadSearchLoading}
+					searchError={threadSearchError}
+					searchResultTitle={(hit) =>
+						labelForThread(
+							sessions.find((s) => s.id === hit.threadId),
+							hit.matches.find((m) => m.kind === 'userMessage')?.snippet ?? hit.threadId
+						)
+					}
+					onSelectSearchResult={openThreadSearchResult}
 				/>
 
 				{taskContextMenu
 					? (() => {
 							const menuWidth = 188;
-							const menuHeight = 72;
+							const menuHeight = 128;
 							const x = typeof window !== 'undefined' ? Math.min(taskContextMenu.x, Math.max(8, window.innerWidth - menuWidth - 8)) : taskContextMenu.x;
 							const y = typeof window !== 'undefined' ? Math.min(taskContextMenu.y, Math.max(8, window.innerHeight - menuHeight - 8)) : taskContextMenu.y;
 
@@ -4197,6 +4526,28 @@ export function CodexChat() {
 									>
 										Rename…
 									</button>
+									<button
+										type="button"
+										className="flex w-full items-center rounded px-2 py-1.5 hover:bg-white/5"
+										onClick={() => {
+											const threadId = taskContextMenu.threadId;
+											closeTaskContextMenu();
+											void toggleThreadPinned(threadId);
+										}}
+									>
+										{sessions.find((s) => s.id === taskContextMenu.threadId)?.pinned ? 'Unpin' : 'Pin'}
+									</button>
+									<button
+										type="button"
+										className="flex w-full items-center rounded px-2 py-1.5 hover:bg-white/5"
+										onClick={() => {
+											const { nodeId, threadId } = taskContextMenu;
+											closeTaskContextMenu();
+											openThreadMetaDialog(nodeId, threadId);
+										}}
+									>
+										Organize…
+									</button>
 									<button
 										type="button"
 										className="flex w-full items-center rounded px-2 py-1.5 text-status-error hover:bg-status-error/10"
@@ -4322,6 +4673,109 @@ export function CodexChat() {
 					</div>
 				) : null}
 
+				{threadMetaDialog ? (
+					<div
+						className="fixed inset-0 z-50 flex items-center justify-center bg-black/70 p-6"
+						onMouseDown={(event) => {
+							if (event.target === event.currentTarget) setThreadMetaDialog(null);
+						}}
+						onKeyDown={(event) => {
+							if (event.key === 'Escape') {
+								event.preventDefault();
+								setThreadMetaDialog(null);
+							}
+						}}
+					>
+						<div className="w-full max-w-sm rounded-xl border border-white/10 bg-bg-popover p-4 text-sm text-text-main">
+							<div className="mb-3 flex items-center justify-between gap-2">
+								<div className="text-sm font-semibold">Organize session</div>
+								<button
+									type="button"
+									className="rounded-md border border-white/10 bg-bg-panelHover px-2 py-1 text-xs hover:border-white/20"
+									onClick={() => setThreadMetaDialog(null)}
+								>
+									✕
+								</button>
+							</div>
+
+							{threadMetaDialog.error ? (
+								<div className="mb-3 rounded-md border border-status-error/30 bg-status-error/10 px-2 py-1 text-xs text-status-error">
+									{threadMetaDialog.error}
+								</div>
+							) : null}
+
+							<div className="space-y-2 text-xs">
+								{(
+									[
+										['tags', 'Tags (comma separated)'],
+										['folder', 'Folder'],
+										['taskId', 'Coco task id'],
+									] as const
+								).map(([field, label]) => (
+									<label key={field} className="block">
+										<div className="mb-1 text-[11px] text-text-dim">{label}</div>
+										<input
+											className="w-full rounded-md border border-white/10 bg-bg-panelHover px-2 py-1.5 text-xs outline-none focus:border-border-active"
+											value={threadMetaDialog[field]}
+											list={field === 'folder' ? 'codex-thread-folders' : undefined}
+											onChange={(event) => {
+												const value = event.target.value;
+												setThreadMetaDialog((prev) => (prev ? { ...prev, [field]: value, error: null } : prev));
+											}}
+										/>
+									</label>
+								))}
+								<datalist id="codex-thread-folders">
+									{(threadMetaCatalog?.folders ?? []).map((folder) => (
+										<option key={folder.name} value={folder.name} />
+									))}
+								</datalist>
+								<label className="block">
+									<div className="mb-1 text-[11px] text-text-dim">Note</div>
+									<textarea
+										className="h-20 w-full resize-none rounded-md border border-white/10 bg-bg-panelHover px-2 py-1.5 text-xs outline-none focus:border-border-active"
+										value={threadMetaDialog.note}
+										onChange={(event) => {
+											const value = event.target.value;
+											setThreadMetaDialog((prev) => (prev ? { ...prev, note: value, error: null } : prev));
+										}}
+									/>
+								</label>
+								{threadMetaDialog.linkedThreadIds.length > 0 ? (
+									<label className="flex items-center gap-2 text-[11px] text-text-dim">
+										<input
+											type="checkbox"
+											checked={threadMetaDialog.applyToLinked}
+											onChange={(event) => {
+												const checked = event.target.checked;
+												setThreadMetaDialog((prev) => (prev ? { ...prev, applyToLinked: checked } : prev));
+											}}
+										/>
+										Also apply to {threadMetaDialog.linkedThreadIds.length} spawned thread(s)
+									</label>
+								) : null}
+							</div>
+
+							<div className="mt-3 flex justify-end gap-2">
+								<button
+									type="button"
+									className="rounded-md border border-white/10 bg-bg-panelHover px-3 py-1 text-xs hover:border-white/20"
+									onClick={() => setThreadMetaDialog(null)}
+								>
+									Cancel
+								</button>
+								<button
+									type="button"
+									className="rounded-md border border-primary/40 bg-primary/20 px-3 py-1 text-xs text-text-main hover:bg-primary/30"
+									onClick={() => void submitThreadMetaDialog()}
+								>
+									Save
+								</button>
+							</div>
+						</div>
+					</div>
+				) : null}
+
 				{renameFileDialog ? (
 					<div
 						className="fixed inset-0 z-50 flex items-center justify-center bg-black/70 p-6"
@@ -4662,6 +5116,8 @@ export function CodexChat() {
 											applyApprovalPolicy={applyApprovalPolicy}
 											applyModel={applyModel}
 											applyProfile={applyProfile}
+											threadSelectionOverride={selectedThreadId ? threadSelectionOverride : null}
+											toggleThreadSelectionOverride={() => void toggleThreadSelectionOverride()}
 											applyReasoningEffort={applyReasoningEffort}
 											isWorkbenchEnabled={isWorkbenchEnabled}
 										/>
@@ -4738,7 +5194,7 @@ export function CodexChat() {
 									<div className="mb-4 flex items-start justify-between gap-3">
 										<div>
 											<div className="text-sm font-semibold">~/.codex/config.toml</div>
-											<div className="mt-1 text-xs text-text-muted">Edit Codex configuration directly. Changes apply to future turns.</div>
+											<div className="mt-1 text-xs text-text-muted">Edit Codex configuration directly. Saves are validated, previewed as a diff and backed up; changes apply to future turns.</div>
 										</div>
 										<button
 											type="button"
@@ -4753,20 +5209,41 @@ export function CodexChat() {
 										<div className="mb-3 rounded-lg border border-status-error/30 bg-status-error/10 p-3 text-sm text-status-error">{configError}</div>
 									) : null}
 
-									<textarea
-										className="h-[60vh] w-full resize-none rounded-xl border border-white/10 bg-black/30 px-4 py-3 font-mono text-[12px] text-text-main outline-none focus:border-border-active"
-										value={configText}
-										onChange={(e) => setConfigText(e.target.value)}
-										spellCheck={false}
-									/>
+									{configIssues.length > 0 ? (
+										<div className="mb-3 space-y-1 rounded-lg border border-status-warning/30 bg-status-warning/10 p-3 text-xs text-status-warning">
+											{configIssues.map((issue, idx) => (
+												<div key={`${issue.path}-${idx}`}>
+													{issue.path ? <span className="font-mono">{issue.path}</span> : null}
+													{issue.path ? ': ' : null}
+													{issue.message}
+												</div>
+											))}
+										</div>
+									) : null}
+
+									{configPlan ? (
+										<pre className="h-[52vh] w-full overflow-auto rounded-xl border border-white/10 bg-black/30 px-4 py-3 font-mono text-[12px] text-text-main">
+											{configPlan.diff}
+										</pre>
+									) : (
+										<textarea
+											className="h-[52vh] w-full resize-none rounded-xl border border-white/10 bg-black/30 px-4 py-3 font-mono text-[12px] text-text-main outline-none focus:border-border-active"
+											value={configText}
+											onChange={(e) => {
+												setConfigText(e.target.value);
+												setConfigIssues([]);
+											}}
+											spellCheck={false}
+										/>
+									)}
 
 									<div className="mt-4 flex items-center justify-end gap-3">
 										<button
 											type="button"
 											className="rounded-md border border-white/10 bg-bg-panelHover px-4 py-2 text-sm hover:border-white/20"
-											onClick={() => setIsConfigOpen(false)}
+											onClick={() => (configPlan ? setConfigPlan(null) : setIsConfigOpen(false))}
 										>
-											Cancel
+											{configPlan ? 'Back' : 'Cancel'}
 										</button>
 										<button
 											type="button"
@@ -4774,9 +5251,30 @@ export function CodexChat() {
 											onClick={() => void saveConfig()}
 											disabled={configSaving}
 										>
-											{configSaving ? 'Saving…' : 'Save'}
+											{configSaving ? 'Saving…' : configPlan ? 'Apply' : 'Review changes'}
 										</button>
 									</div>
+
+									{configBackups.length > 0 ? (
+										<div className="mt-4">
+											<div className="mb-2 text-xs font-semibold text-text-muted">Backups</div>
+											<div className="max-h-40 space-y-1 overflow-auto">
+												{configBackups.map((backup) => (
+													<div key={backup.id} className="flex items-center justify-between gap-3 text-xs">
+														<span className="text-text-muted">{new Date(backup.createdAtMs).toLocaleString()}</span>
+														<button
+															type="button"
+															className="rounded-md border border-white/10 bg-bg-panelHover px-2 py-1 hover:border-white/20 disabled:opacity-50"
+															onClick={() => void restoreConfigBackup(backup)}
+															disabled={configSaving}
+														>
+															Restore
+														</button>
+													</div>
+												))}
+											</div>
+										</div>
+									) : null}
 								</div>
 							</div>
 						) : null}
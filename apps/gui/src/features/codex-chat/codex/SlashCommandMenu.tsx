@@ -142,6 +142,7 @@ export function SlashCommandMenu({
 					{filteredPrompts.map(({ prompt, indices }, idx) => {
 						const globalIdx = filteredCommands.length + idx;
 						const isPinned = pinnedPromptNames.has(prompt.name);
+						const promptDetail = [prompt.description || 'send saved prompt', prompt.argumentHint].filter(Boolean).join(' · ');
 						return (
 							<button
 								key={prompt.name}
@@ -157,8 +158,8 @@ export function SlashCommandMenu({
 										{indices && indices.length > 0 ? highlightMatches(`prompts:${prompt.name}`, indices) : `prompts:${prompt.name}`}
 									</span>
 									<span aria-hidden className="shrink-0 w-[3ch]" />
-									<span className={MENU_STYLES.popoverItemDescFill} title={prompt.description || 'send saved prompt'}>
-										{prompt.description || 'send saved prompt'}
+									<span className={MENU_STYLES.popoverItemDescFill} title={`${promptDetail}\n${prompt.path}`}>
+										{promptDetail}
 									</span>
 								</div>
 								<span
@@ -24,6 +24,9 @@ interface StatusBarProps {
 	applyApprovalPolicy: (policy: ApprovalPolicy) => void | Promise<void>;
 	applyModel: (model: string) => void | Promise<void>;
 	applyProfile: (profile: string) => void | Promise<void>;
+	/** Whether the selected thread pins its own profile/model/policy; null without a thread. */
+	threadSelectionOverride?: boolean | null;
+	toggleThreadSelectionOverride?: () => void;
 	applyReasoningEffort: (effort: ReasoningEffort) => void | Promise<void>;
 	isWorkbenchEnabled?: boolean;
 }
@@ -139,6 +142,8 @@ export function StatusBar({
 	applyModel,
 	applyProfile,
 	applyReasoningEffort,
+	threadSelectionOverride = null,
+	toggleThreadSelectionOverride,
 	isWorkbenchEnabled = false,
 }: StatusBarProps) {
 	return (
@@ -221,6 +226,7 @@ export function StatusBar({
 								title="profile"
 							>
 								<span className="truncate">{selectedProfile ?? 'profile'}</span>
+								{threadSelectionOverride ? <span className="text-[10px] text-text-menuLabel">(thread)</span> : null}
 								<ChevronDown className="h-3 w-3" />
 							</button>
 
@@ -239,6 +245,17 @@ export function StatusBar({
 										})}
 										{statusPopoverError ? <div className="px-3 py-1 text-[11px] text-status-warning">{statusPopoverError}</div> : null}
 									</div>
+									{threadSelectionOverride !== null && toggleThreadSelectionOverride ? (
+										<button
+											type="button"
+											className={`${MENU_STYLES.popoverItem} border-t border-white/10`}
+											onClick={toggleThreadSelectionOverride}
+											title="Keep this profile, model and approval policy for this thread only"
+										>
+											<span>This thread only</span>
+											<Check className={`ml-auto ${MENU_STYLES.iconSm} shrink-0 ${threadSelectionOverride ? '' : 'invisible'}`} />
+										</button>
+									) : null}
 								</div>
 							) : null}
 						</div>
@@ -1,5 +1,6 @@
-import { ChevronLeft, Plus, RefreshCw } from 'lucide-react';
+import { ChevronLeft, Plus, RefreshCw, Search, X } from 'lucide-react';
 import { useCallback, useEffect, useRef, useState } from 'react';
+import type { CodexThreadMetaCatalog, CodexThreadSearchHit, CodexThreadSearchKind } from '@/types/codex';
 import type { TreeNodeData } from '@/types/sidebar';
 import { TreeNode } from './TreeNode';
 import { SIDEBAR_WIDTH_PX, SIDEBAR_EXPANDED_WIDTH_PX } from '../styles/menu-styles';
@@ -23,6 +24,37 @@ interface SessionTreeSidebarProps {
 	onRefresh?: () => void;
 	loading?: boolean;
 	error?: string | null;
+	/** Session list filter: `all`, `pinned`, `tag:<name>` or `folder:<name>`. */
+	filterKey?: string;
+	filterCatalog?: CodexThreadMetaCatalog | null;
+	onFilterKeyChange?: (key: string) => void;
+	/** Full-text thread search; the tree is replaced by results while a query is set. */
+	searchQuery?: string;
+	onSearchQueryChange?: (query: string) => void;
+	searchResults?: CodexThreadSearchHit[] | null;
+	searchLoading?: boolean;
+	searchError?: string | null;
+	searchResultTitle?: (hit: CodexThreadSearchHit) => string;
+	onSelectSearchResult?: (threadId: string, turnIndex: number | null) => void;
+}
+
+const SEARCH_KIND_LABEL: Record<CodexThreadSearchKind, string> = {
+	userMessage: 'You',
+	agentMessage: 'Codex',
+	command: 'Command',
+	file: 'File',
+};
+
+function HighlightedSnippet({ snippet, start, end }: { snippet: string; start: number; end: number }) {
+	// Offsets are in chars (code points), not UTF-16 units.
+	const chars = Array.from(snippet);
+	return (
+		<>
+			{chars.slice(0, start).join('')}
+			<mark className="rounded-sm bg-primary/30 text-text-main">{chars.slice(start, end).join('')}</mark>
+			{chars.slice(end).join('')}
+		</>
+	);
 }
 
 export function SessionTreeSidebar({
@@ -44,6 +76,16 @@ export function SessionTreeSidebar({
 	onRefresh,
 	loading,
 	error,
+	filterKey,
+	filterCatalog,
+	onFilterKeyChange,
+	searchQuery,
+	onSearchQueryChange,
+	searchResults,
+	searchLoading,
+	searchError,
+	searchResultTitle,
+	onSelectSearchResult,
 }: SessionTreeSidebarProps) {
 	const handleNodeAction = useCallback(
 		(node: TreeNodeData, actionId: string) => {
@@ -170,12 +212,92 @@ export function SessionTreeSidebar({
 					</div>
 				</div>
 
-				{/* Tree content */}
-				<div className="flex-1 min-h-0 overflow-y-auto px-1 py-2">
-					{error ? <div className="px-2 py-1 text-[10px] text-status-error">{error}</div> : null}
-					{!error && loading ? <div className="px-2 py-1 text-[10px] text-text-dim">Loading…</div> : null}
-					{treeData.map((node) => renderNode(node, 0))}
-				</div>
+				{onSearchQueryChange ? (
+					<div className="border-b border-white/10 px-2 py-1.5">
+						<div className="flex items-center gap-1.5 rounded-md border border-white/10 bg-bg-panelHover px-2 py-1">
+							<Search size={12} className="shrink-0 text-text-dim" />
+							<input
+								className="min-w-0 flex-1 bg-transparent text-[11px] text-text-main outline-none placeholder:text-text-dim"
+								placeholder="Search sessions…"
+								value={searchQuery ?? ''}
+								onChange={(e) => onSearchQueryChange(e.target.value)}
+								onKeyDown={(e) => {
+									if (e.key === 'Escape') onSearchQueryChange('');
+								}}
+							/>
+							{searchQuery ? (
+								<button type="button" className="shrink-0 text-text-dim hover:text-text-main" onClick={() => onSearchQueryChange('')} title="Clear search">
+									<X size={12} />
+								</button>
+							) : null}
+						</div>
+					</div>
+				) : null}
+
+				{onFilterKeyChange && (filterKey !== 'all' || (filterCatalog && (filterCatalog.pinned > 0 || filterCatalog.tags.length > 0 || filterCatalog.folders.length > 0))) ? (
+					<div className="border-b border-white/10 px-2 py-1.5">
+						<select
+							className="w-full rounded-md border border-white/10 bg-bg-panelHover px-1.5 py-1 text-[11px] text-text-main outline-none"
+							value={filterKey ?? 'all'}
+							onChange={(e) => onFilterKeyChange(e.target.value)}
+							title="Filter sessions"
+						>
+							<option value="all">All sessions</option>
+							<option value="pinned">Pinned ({filterCatalog?.pinned ?? 0})</option>
+							{(filterCatalog?.folders ?? []).map((folder) => (
+								<option key={`folder:${folder.name}`} value={`folder:${folder.name}`}>
+									Folder: {folder.name} ({folder.count})
+								</option>
+							))}
+							{(filterCatalog?.tags ?? []).map((tag) => (
+								<option key={`tag:${tag.name}`} value={`tag:${tag.name}`}>
+									#{tag.name} ({tag.count})
+								</option>
+							))}
+						</select>
+					</div>
+				) : null}
+
+				{searchQuery?.trim() ? (
+					<div className="flex-1 min-h-0 overflow-y-auto px-1 py-2">
+						{searchError ? <div className="px-2 py-1 text-[10px] text-status-error">{searchError}</div> : null}
+						{!searchError && searchLoading && !searchResults ? <div className="px-2 py-1 text-[10px] text-text-dim">Searching…</div> : null}
+						{!searchError && searchResults && searchResults.length === 0 ? <div className="px-2 py-1 text-[10px] text-text-dim">No matching sessions</div> : null}
+						{(searchResults ?? []).map((hit) => (
+							<div key={hit.path} className="mb-1 rounded-md px-1 py-1 hover:bg-white/5">
+								<button
+									type="button"
+									className="block w-full truncate px-1 text-left text-[11px] font-medium text-text-main"
+									onClick={() => onSelectSearchResult?.(hit.threadId, null)}
+									title={hit.cwd ?? undefined}
+								>
+									{searchResultTitle ? searchResultTitle(hit) : hit.threadId}
+								</button>
+								{hit.matches.map((m, idx) => (
+									<button
+										key={`${m.turnIndex}-${idx}`}
+										type="button"
+										className="block w-full rounded px-1 py-0.5 text-left text-[10px] leading-snug text-text-dim hover:bg-white/10"
+										onClick={() => onSelectSearchResult?.(hit.threadId, m.turnIndex)}
+									>
+										<span className="mr-1 text-text-muted">
+											#{m.turnIndex + 1} {SEARCH_KIND_LABEL[m.kind]}
+										</span>
+										<span className={m.kind === 'command' || m.kind === 'file' ? 'font-mono' : undefined}>
+											<HighlightedSnippet snippet={m.snippet} start={m.matchStart} end={m.matchEnd} />
+										</span>
+									</button>
+								))}
+							</div>
+						))}
+					</div>
+				) : (
+					<div className="flex-1 min-h-0 overflow-y-auto px-1 py-2">
+						{error ? <div className="px-2 py-1 text-[10px] text-status-error">{error}</div> : null}
+						{!error && loading ? <div className="px-2 py-1 text-[10px] text-text-dim">Loading…</div> : null}
+						{treeData.map((node) => renderNode(node, 0))}
+					</div>
+				)}
 
 				{/* Footer with new session button */}
 				{onCreateNewSession ? (
@@ -1,4 +1,4 @@
-import { Archive, ChevronRight } from 'lucide-react';
+import { Archive, ChevronRight, Pin } from 'lucide-react';
 import type { TreeNodeData } from '@/types/sidebar';
 import { SessionTreeIcon, getStatusColor } from './SessionTreeIcons';
 import { SessionRunningIndicator } from '../SessionRunningIndicator';
@@ -30,7 +30,9 @@ export function TreeNode({ node, depth, isExpanded, isSelected, onSelect, onTogg
 
 	const showInteractionCount = node.interactionCount != null && (node.type === 'task' || node.type === 'orchestrator' || node.type === 'worker');
 
-	const labelTitle = node.label;
+	const note = node.metadata?.note?.trim();
+	const labelTitle = note ? `${node.label}\n\n${note}` : node.label;
+	const tags = node.metadata?.tags ?? [];
 	const wtLabel = node.metadata?.wtLabel ?? null;
 	const showWtLabel = Boolean(wtLabel && (node.type === 'task' || node.type === 'orchestrator' || node.type === 'worker'));
 	const action = node.actions?.[0] ?? null;
@@ -78,6 +80,15 @@ export function TreeNode({ node, depth, isExpanded, isSelected, onSelect, onTogg
 					{node.label}
 				</span>
 
+				{node.metadata?.pinned ? <Pin size={10} className="shrink-0 text-text-muted" aria-label="Pinned" /> : null}
+
+				{tags.slice(0, 2).map((tag) => (
+					<span key={tag} className="ml-1 max-w-[72px] shrink-0 truncate rounded bg-primary/10 px-1 py-0.5 text-[9px] text-text-muted" title={tags.join(', ')}>
+						#{tag}
+					</span>
+				))}
+				{tags.length > 2 ? <span className="shrink-0 text-[9px] text-text-muted" title={tags.join(', ')}>+{tags.length - 2}</span> : null}
+
 				{showWtLabel ? (
 					<span className="ml-1 min-w-0 max-w-[120px] truncate rounded bg-white/5 px-1 py-0.5 text-[9px] text-text-muted" title={wtLabel ?? undefined}>
 						{wtLabel}
@@ -1,4 +1,5 @@
 import { useCallback, useEffect, useRef, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { apiClient } from '@/api/client';
 import type {
 	ClusterStatus,
@@ -9,7 +10,9 @@ import type {
 	SubagentFinalOutput,
 	SubagentSessionSummary,
 	Task,
+	TaskChange,
 	TaskEvent,
+	TaskWatchEvent,
 } from '@/types/task';
 
 const getErrorMessage = (err: unknown, fallback: string) => (err instanceof Error ? err.message : fallback);
@@ -70,6 +73,59 @@ const sortRuntimeEvents = (lines: string[]): string[] => {
 		.map((item) => item.line);
 };
 
+// ============ useTaskWatch ============
+
+/**
+ * Subscribes to file changes under the task directory and calls `onChange` with each debounced batch.
+ * Returns whether the subscription is live, so callers can fall back to polling when it is not.
+ */
+export function useTaskWatch(taskId: string | null, enabled: boolean, onChange: (changes: TaskChange[]) => void): boolean {
+	const [watching, setWatching] = useState(false);
+	const onChangeRef = useRef(onChange);
+	onChangeRef.current = onChange;
+
+	useEffect(() => {
+		setWatching(false);
+		if (!enabled || !taskId) return;
+		let cancelled = false;
+		let subscriptionId: number | null = null;
+		const unlistenPromise = listen<TaskWatchEvent>('coco_task_fs_update', (event) => {
+			if (cancelled || event.payload?.taskId !== taskId) return;
+			onChangeRef.current(event.payload.changes ?? []);
+		});
+		void unlistenPromise
+			.then(() => apiClient.watchSubscribe({ kind: 'task', taskId }))
+			.then((id) => {
+				if (cancelled) {
+					void apiClient.watchUnsubscribe(id).catch(() => {
+						// ignore
+					});
+					return;
+				}
+				subscriptionId = id;
+				setWatching(true);
+			})
+			.catch((err) => {
+				console.warn('[TaskWatch] falling back to polling:', err);
+			});
+		return () => {
+			cancelled = true;
+			if (subscriptionId != null) {
+				void apiClient.watchUnsubscribe(subscriptionId).catch(() => {
+					// ignore
+				});
+			}
+			unlistenPromise
+				.then((unlisten) => unlisten())
+				.catch(() => {
+					// ignore
+				});
+		};
+	}, [enabled, taskId]);
+
+	return watching;
+}
+
 // ============ useTasks ============
 
 interface UseTasksState {
@@ -401,8 +457,17 @@ export function useSubagentSessions(
 		refresh();
 	}, [enabled, taskId, refresh]);
 
+	const refreshRef = useRef(refresh);
+	refreshRef.current = refresh;
+	const watching = useTaskWatch(taskId, enabled, (changes) => {
+		if (!changes.some((change) => change.kind !== 'sharedArtifact')) return;
+		refreshRef.current({ background: true }).catch(() => {
+			// ignore refresh errors; keep last successful state
+		});
+	});
+
 	useEffect(() => {
-		if (!enabled || !taskId) return;
+		if (!enabled || !taskId || watching) return;
 
 		const timer = setInterval(() => {
 			refresh({ background: true }).catch(() => {
@@ -411,7 +476,7 @@ export function useSubagentSessions(
 		}, pollIntervalMs);
 
 		return () => clearInterval(timer);
-	}, [enabled, taskId, pollIntervalMs, refresh]);
+	}, [enabled, taskId, watching, pollIntervalMs, refresh]);
 
 	return { ...state, refresh, selectAgentInstance };
 }
@@ -525,8 +590,18 @@ export function useSharedArtifacts(
 		refresh();
 	}, [enabled, taskId, category, refresh]);
 
+	const refreshRef = useRef(refresh);
+	refreshRef.current = refresh;
+	const watching = useTaskWatch(taskId, enabled, (changes) => {
+		const relevant = changes.some((change) => change.kind === 'sharedArtifact' && (!change.category || change.category === category));
+		if (!relevant) return;
+		refreshRef.current({ background: true }).catch(() => {
+			// ignore refresh errors; keep last successful state
+		});
+	});
+
 	useEffect(() => {
-		if (!enabled || !taskId) return;
+		if (!enabled || !taskId || watching) return;
 
 		const timer = setInterval(() => {
 			refresh({ background: true }).catch(() => {
@@ -535,7 +610,7 @@ export function useSharedArtifacts(
 		}, pollIntervalMs);
 
 		return () => clearInterval(timer);
-	}, [enabled, taskId, category, pollIntervalMs, refresh]);
+	}, [enabled, taskId, category, watching, pollIntervalMs, refresh]);
 
 	return { ...state, refresh, selectArtifact };
 }
@@ -0,0 +1,133 @@
+import { useState } from 'react';
+import type { ReactNode } from 'react';
+import ReactMarkdown from 'react-markdown';
+import { apiClient } from '@/api/client';
+import { TextPreview } from '@/shared/ui/TextPreview';
+import type { EvidenceLink, ResolvedEvidence } from '@/types/task';
+
+// Hash links survive react-markdown's URL sanitizing; a custom `evidence:` scheme would not.
+const EVIDENCE_HREF_PREFIX = '#evidence:';
+// Same rules as `scan_evidence_tokens` in coco-core: the prefix must not be glued to a preceding
+// id character, and trailing dots are sentence punctuation.
+const EVIDENCE_TOKEN = /(^|[^A-Za-z0-9._-])evidence:([A-Za-z0-9._-]+)/g;
+const FENCE = /^\s*(```|~~~)/;
+
+function linkifyText(text: string): string {
+	return text.replace(EVIDENCE_TOKEN, (match: string, lead: string, rawId: string) => {
+		const id = rawId.replace(/\.+$/, '');
+		if (!id) return match;
+		return `${lead}[evidence:${id}](${EVIDENCE_HREF_PREFIX}${id})${rawId.slice(id.length)}`;
+	});
+}
+
+/** Turn `evidence:<id>` tokens into links, leaving code spans and fenced blocks alone. */
+export function linkifyEvidence(markdown: string): string {
+	let inFence = false;
+	return markdown
+		.split('\n')
+		.map((line) => {
+			if (FENCE.test(line)) {
+				inFence = !inFence;
+				return line;
+			}
+			if (inFence) return line;
+			return line
+				.split(/(`[^`]*`)/)
+				.map((part, idx) => (idx % 2 === 1 ? part : linkifyText(part)))
+				.join('');
+		})
+		.join('\n');
+}
+
+function describeLink(link: EvidenceLink): string {
+	switch (link.type) {
+		case 'fileRange':
+			return `${link.path}:${link.startLine}-${link.endLine}`;
+		case 'commandOutput': {
+			const exit = link.exitCode == null ? '' : ` (exit ${link.exitCode})`;
+			const output = [link.stdoutPath, link.stderrPath].filter(Boolean).join(', ');
+			return `$ ${link.command}${exit} in ${link.cwd}${output ? ` → ${output}` : ''}`;
+		}
+		case 'eventRange':
+			return link.startLine == null ? link.path : `${link.path}:${link.startLine}-${link.endLine ?? link.startLine}`;
+	}
+}
+
+function EvidenceToken({ taskId, evidenceId }: { taskId: string; evidenceId: string }) {
+	const [open, setOpen] = useState(false);
+	const [resolved, setResolved] = useState<ResolvedEvidence | null>(null);
+	const [error, setError] = useState<string | null>(null);
+
+	const toggle = () => {
+		const next = !open;
+		setOpen(next);
+		if (next && !resolved) {
+			setError(null);
+			apiClient.taskEvidenceResolve(taskId, evidenceId).then(setResolved, (err: unknown) => setError(String(err)));
+		}
+	};
+
+	return (
+		<span className="relative inline-block">
+			<button type="button" className="font-mono text-primary underline decoration-dotted hover:text-primary-hover" onClick={toggle}>
+				evidence:{evidenceId}
+			</button>
+			{open ? (
+				<span className="absolute left-0 top-full z-10 mt-1 block w-96 max-w-[80vw] space-y-2 rounded-md border border-white/10 bg-bg-panel p-3 text-xs shadow-lg">
+					{error ? (
+						<span className="block text-status-error">{error}</span>
+					) : !resolved ? (
+						<span className="block text-text-muted">Resolving…</span>
+					) : (
+						<>
+							<span className="block font-semibold text-text-main">{resolved.entry.title || resolved.entry.id}</span>
+							{resolved.entry.summary ? <span className="block text-text-muted">{resolved.entry.summary}</span> : null}
+							{resolved.links.length === 0 ? (
+								<span className="block text-text-dim">No sources recorded.</span>
+							) : (
+								resolved.links.map((link, idx) => (
+									<span key={idx} className="block break-all font-mono text-text-main">
+										{describeLink(link)}
+									</span>
+								))
+							)}
+						</>
+					)}
+				</span>
+			) : null}
+		</span>
+	);
+}
+
+type TaskTextPreviewProps = {
+	taskId: string;
+	content: string;
+	path: string;
+	allowHtml?: boolean;
+	preClassName?: string;
+};
+
+/** `TextPreview` for task files: Markdown `evidence:<id>` tokens link to their sources. */
+export function TaskTextPreview({ taskId, content, path, allowHtml, preClassName }: TaskTextPreviewProps) {
+	if (!path.toLowerCase().endsWith('.md')) {
+		return <TextPreview content={content} path={path} allowHtml={allowHtml} preClassName={preClassName} />;
+	}
+	return (
+		<div className="space-y-3 text-sm text-text-main">
+			<ReactMarkdown
+				components={{
+					a: ({ href, children }): ReactNode =>
+						href?.startsWith(EVIDENCE_HREF_PREFIX) ? (
+							<EvidenceToken taskId={taskId} evidenceId={href.slice(EVIDENCE_HREF_PREFIX.length)} />
+						) : (
+							<a href={href}>{children}</a>
+						),
+				}}
+			>
+				{linkifyEvidence(content)}
+			</ReactMarkdown>
+		</div>
+	);
+}
+
+export default TaskTextPreview;
@@ -323,6 +323,7 @@ export function TaskDetail({ task, events, loading, error, hasMoreEvents, onLoad
 
 			{tab === 'workbench' && (
 				<WorkbenchTab
+					taskId={task.id}
 					sessionAutoFollow={sessionAutoFollow}
 					onToggleAutoFollow={setSessionAutoFollow}
 					onRefresh={() => {
@@ -362,6 +363,7 @@ export function TaskDetail({ task, events, loading, error, hasMoreEvents, onLoad
 
 			{tab === 'artifacts' && (
 				<ArtifactsTab
+					taskId={task.id}
 					artifactCategory={artifactCategory}
 					onSelectCategory={setArtifactCategory}
 					artifacts={artifacts}
@@ -1,9 +1,10 @@
 import type { SharedArtifactCategory, SharedArtifactContent, SharedArtifactSummary } from '@/types/task';
-import { TextPreview } from '@/shared/ui/TextPreview';
+import { TaskTextPreview } from '../EvidenceMarkdown';
 import { ARTIFACT_CATEGORIES } from '../../model/workbench';
 import { formatEpochMs } from '../../lib/format';
 
 interface ArtifactsTabProps {
+	taskId: string;
 	artifactCategory: SharedArtifactCategory;
 	onSelectCategory: (category: SharedArtifactCategory) => void;
 	artifacts: SharedArtifactSummary[];
@@ -17,6 +18,7 @@ interface ArtifactsTabProps {
 }
 
 export function ArtifactsTab({
+	taskId,
 	artifactCategory,
 	onSelectCategory,
 	artifacts,
@@ -98,7 +100,8 @@ export function ArtifactsTab({
 									{!artifactContent ? (
 										<div className="text-sm text-text-muted">Loading…</div>
 									) : (
-										<TextPreview
+										<TaskTextPreview
+											taskId={taskId}
 											content={artifactContent.content}
 											path={selectedArtifactPath}
 											allowHtml={false}
@@ -7,11 +7,12 @@ import type {
 	TaskDirEntry,
 	TaskTextFileContent,
 } from '@/types/task';
-import { TextPreview } from '@/shared/ui/TextPreview';
+import { TaskTextPreview } from '../EvidenceMarkdown';
 import { ARTIFACT_CATEGORIES, DEFAULT_SHARED_FILES, type WorkbenchNode, workbenchNodeKey } from '../../model/workbench';
 import { formatEpochMs } from '../../lib/format';
 
 interface WorkbenchTabProps {
+	taskId: string;
 	sessionAutoFollow: boolean;
 	onToggleAutoFollow: (value: boolean) => void;
 	onRefresh: () => void;
@@ -42,6 +43,7 @@ interface WorkbenchTabProps {
 }
 
 export function WorkbenchTab({
+	taskId,
 	sessionAutoFollow,
 	onToggleAutoFollow,
 	onRefresh,
@@ -378,7 +380,7 @@ export function WorkbenchTab({
 										) : !content ? (
 											<div className="text-sm text-text-muted">(empty)</div>
 										) : (
-											<TextPreview content={content} path={workbenchTextFile?.path ?? 'session.json'} />
+											<TaskTextPreview taskId={taskId} content={content} path={workbenchTextFile?.path ?? 'session.json'} />
 										)}
 									</div>
 									{workbenchTextFile?.updatedAtMs ? (
@@ -401,7 +403,7 @@ export function WorkbenchTab({
 										{!workbenchArtifactContent ? (
 											<div className="text-sm text-text-muted">Loading…</div>
 										) : (
-											<TextPreview content={workbenchArtifactContent.content} path={previewPath} />
+											<TaskTextPreview taskId={taskId} content={workbenchArtifactContent.content} path={previewPath} />
 										)}
 									</div>
 									{workbenchArtifactContent?.updatedAtMs ? (
@@ -426,7 +428,7 @@ export function WorkbenchTab({
 									) : !content ? (
 										<div className="text-sm text-text-muted">(empty)</div>
 									) : (
-										<TextPreview content={content} path={workbenchSelected.path} />
+										<TaskTextPreview taskId={taskId} content={content} path={workbenchSelected.path} />
 									)}
 								</div>
 								{workbenchTextFile?.updatedAtMs ? (
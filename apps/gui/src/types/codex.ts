@@ -12,6 +12,9 @@ export interface CodexThreadWatchEvent {
 	updatedAtMs: number | null;
 }
 
+/** A file-watch subscription: a rollout (`codex_thread_fs_update`) or a task directory (`coco_task_fs_update`). */
+export type WatchTarget = { kind: 'rollout'; threadId: string; path: string } | { kind: 'task'; taskId: string };
+
 export interface CodexThreadSummary {
 	id: string;
 	preview: string;
@@ -21,6 +24,53 @@ export interface CodexThreadSummary {
 	createdAt: number;
 	updatedAtMs: number | null;
 	interactionCount?: number | null;
+	tags?: string[];
+	pinned?: boolean;
+	folder?: string | null;
+	note?: string | null;
+	/** Coco task the thread is linked to. */
+	taskId?: string | null;
+}
+
+export interface CodexThreadMeta {
+	title?: string | null;
+	source?: string | null;
+	tags?: string[];
+	pinned?: boolean;
+	folder?: string | null;
+	note?: string | null;
+	taskId?: string | null;
+	updatedAtMs?: number | null;
+}
+
+/** Unset fields are left alone; for `folder`, `note` and `taskId` an empty string clears. */
+export interface CodexThreadMetaPatch {
+	pinned?: boolean;
+	tags?: string[];
+	addTags?: string[];
+	removeTags?: string[];
+	folder?: string;
+	note?: string;
+	taskId?: string;
+}
+
+export interface CodexThreadMetaFilter {
+	tags?: string[];
+	/** Exact folder; `''` selects threads without one. */
+	folder?: string | null;
+	pinned?: boolean | null;
+	taskId?: string | null;
+}
+
+export interface CodexThreadMetaEntry {
+	threadId: string;
+	meta: CodexThreadMeta;
+}
+
+export interface CodexThreadMetaCatalog {
+	tags: Array<{ name: string; count: number }>;
+	folders: Array<{ name: string; count: number }>;
+	pinned: number;
 }
 
 export interface CodexThreadListResponse {
@@ -28,6 +78,39 @@ export interface CodexThreadListResponse {
 	nextCursor: string | null;
 }
 
+export type CodexThreadSearchKind = 'userMessage' | 'agentMessage' | 'command' | 'file';
+
+export interface CodexThreadSearchFilters {
+	/** Threads started in this directory or below it. */
+	cwd?: string | null;
+	kinds?: CodexThreadSearchKind[] | null;
+	includeArchived?: boolean;
+	updatedAfterMs?: number | null;
+	updatedBeforeMs?: number | null;
+	limit?: number | null;
+}
+
+export interface CodexThreadSearchMatch {
+	kind: CodexThreadSearchKind;
+	/** 0-based index into `CodexThread.turns`. */
+	turnIndex: number;
+	snippet: string;
+	/** Char range of the match inside `snippet`. */
+	matchStart: number;
+	matchEnd: number;
+}
+
+export interface CodexThreadSearchHit {
+	threadId: string;
+	path: string;
+	cwd: string | null;
+	archived: boolean;
+	updatedAtMs: number;
+	turnCount: number;
+	score: number;
+	matches: CodexThreadSearchMatch[];
+}
+
 export interface CodexThreadLoadedListResponse {
 	data: string[];
 	nextCursor: string | null;
@@ -189,6 +272,16 @@ export interface FileInfo {
 	isDirectory: boolean;
 }
 
+export interface WorkspaceGrepMatch {
+	path: string;
+	line: number;
+	column: number;
+	preview: string;
+	/** Char range of the match inside `preview`. */
+	matchStart: number;
+	matchEnd: number;
+}
+
 export interface GitStatus {
 	branch: string;
 	modified: string[];
@@ -249,14 +342,215 @@ export interface SkillsListResponse {
 	skills: SkillMetadata[];
 }
 
-// Prompts types (custom prompts from ~/.codex/prompts/)
+// Custom prompts: `.coco/prompts` > `.codex/prompts` (workspace) > `$CODEX_HOME/prompts`
+export type CustomPromptScope = 'workspace' | 'workspaceCodex' | 'user';
+
 export interface CustomPrompt {
 	name: string;
 	description?: string;
 	argumentHint?: string;
 	path: string;
+	scope: CustomPromptScope;
+	/** Lower-precedence prompts with the same name. */
+	shadows?: string[];
+}
+
+export interface RenderedPrompt {
+	name: string;
+	path: string;
+	scope: CustomPromptScope;
+	text: string;
+	includes?: string[];
 }
 
 export interface PromptsListResponse {
 	prompts: CustomPrompt[];
 }
+
+// ============================================================================
+// Recorded fileChange items (revert / re-apply)
+// ============================================================================
+
+export interface CodexFileChangeHunk {
+	index: number;
+	diff: string;
+}
+
+export interface CodexFileChangeFile {
+	path: string;
+	movePath: string | null;
+	kind: 'add' | 'delete' | 'update';
+	state: 'applied' | 'reverted' | 'modified' | 'untracked';
+	hunks: CodexFileChangeHunk[];
+}
+
+export interface CodexFileChangeView {
+	itemId: string;
+	threadId: string | null;
+	turnId: string | null;
+	files: CodexFileChangeFile[];
+}
+
+export interface CodexFileChangeOutcome {
+	path: string;
+	result: 'written' | 'unchanged' | 'conflict';
+	conflict: string | null;
+}
+
+export interface CodexFileChangeApplyResult {
+	/** False when any file conflicted; nothing was written then. */
+	applied: boolean;
+	files: CodexFileChangeOutcome[];
+}
+
+// ============================================================================
+// Chat selection (profile / model / approval policy per workspace and thread)
+// ============================================================================
+
+/** Unset fields fall back to the next layer: thread -> workspace -> config.toml. */
+export interface CodexChatSelection {
+	profile?: string | null;
+	model?: string | null;
+	modelReasoningEffort?: string | null;
+	approvalPolicy?: string | null;
+}
+
+export interface CodexResolvedChatSelection extends CodexChatSelection {
+	/** True when the thread has its own override instead of following the workspace. */
+	threadOverride: boolean;
+}
+
+// ============================================================================
+// Structured config.toml editing
+// ============================================================================
+
+export interface CodexConfigIssue {
+	/** Dotted key path; empty for TOML syntax errors. */
+	path: string;
+	message: string;
+}
+
+export interface CodexConfigChatSettings {
+	model: string | null;
+	modelProvider: string | null;
+	modelReasoningEffort: string | null;
+	modelReasoningSummary: string | null;
+	modelVerbosity: string | null;
+	approvalPolicy: string | null;
+	sandboxMode: string | null;
+}
+
+export interface CodexConfigProfile extends CodexConfigChatSettings {
+	name: string;
+}
+
+export interface CodexConfigModelProvider {
+	id: string;
+	name: string | null;
+	baseUrl: string | null;
+	envKey: string | null;
+	wireApi: string | null;
+	queryParams: Record<string, string>;
+	httpHeaders: Record<string, string>;
+	requestMaxRetries: number | null;
+	streamMaxRetries: number | null;
+	streamIdleTimeoutMs: number | null;
+}
+
+export interface CodexConfigMcpServer {
+	name: string;
+	command: string | null;
+	args: string[];
+	env: Record<string, string>;
+	cwd: string | null;
+	url: string | null;
+	bearerTokenEnvVar: string | null;
+	enabled: boolean | null;
+	startupTimeoutSec: number | null;
+	toolTimeoutSec: number | null;
+}
+
+export interface CodexConfigModel extends CodexConfigChatSettings {
+	profile: string | null;
+	profiles: CodexConfigProfile[];
+	modelProviders: CodexConfigModelProvider[];
+	mcpServers: CodexConfigMcpServer[];
+	sandboxWorkspaceWrite: {
+		writableRoots: string[];
+		networkAccess: boolean | null;
+		excludeTmpdirEnvVar: boolean | null;
+		excludeSlashTmp: boolean | null;
+	};
+	shellEnvironmentPolicy: {
+		inherit: string | null;
+		ignoreDefaultExcludes: boolean | null;
+		exclude: string[];
+		includeOnly: string[];
+		set: Record<string, string>;
+	};
+}
+
+/** Sets one key; `null` removes it. Objects merge into existing tables. */
+export interface CodexConfigEdit {
+	keyPath: string[];
+	value: unknown;
+}
+
+export interface CodexConfigDocument {
+	path: string;
+	exists: boolean;
+	content: string;
+	revision: string;
+	config: CodexConfigModel | null;
+	issues: CodexConfigIssue[];
+}
+
+export interface CodexConfigPlan {
+	revision: string;
+	content: string;
+	changed: boolean;
+	diff: string;
+	config: CodexConfigModel | null;
+	issues: CodexConfigIssue[];
+}
+
+export interface CodexConfigBackup {
+	id: string;
+	createdAtMs: number;
+	size: number;
+}
+
+export interface CodexConfigWriteResult {
+	revision: string;
+	changed: boolean;
+	diff: string;
+	backup: CodexConfigBackup | null;
+	issues: CodexConfigIssue[];
+}
+
+// ============================================================================
+// Per-turn workspace checkpoints (restore on rollback)
+// ============================================================================
+
+export interface CodexCheckpoint {
+	turnId: string;
+	commit: string;
+	cwd: string;
+	createdAtMs: number;
+}
+
+export interface CodexCheckpointFileChange {
+	/** Relative to the checkpoint's cwd. */
+	path: string;
+	/** What changed since the checkpoint; restoring undoes it. */
+	status: 'added' | 'modified' | 'deleted';
+}
+
+export interface CodexCheckpointPreview {
+	checkpoint: CodexCheckpoint;
+	files: CodexCheckpointFileChange[];
+}
+
+export interface CodexCheckpointRestore extends CodexCheckpointPreview {
+	backupCommit: string;
+}
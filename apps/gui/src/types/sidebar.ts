@@ -21,6 +21,9 @@ export interface TreeNodeData {
 		threadId?: string;
 		path?: string;
 		wtLabel?: string;
+		pinned?: boolean;
+		tags?: string[];
+		note?: string;
 	};
 }
 
@@ -42,6 +42,28 @@ export interface TaskConfig {
 	maxConcurrentAgents: number;
 	timeoutSeconds: number;
 	autoApprove: boolean;
+	workerTimeoutSeconds?: number;
+	retry?: RetryPolicy;
+	budget?: TaskBudget;
+}
+
+export type BudgetAction = 'gate' | 'cancel';
+
+export interface TaskBudget {
+	maxTokens?: number;
+	maxCostUsd?: number;
+	maxWallClockSeconds?: number;
+	maxSpawns?: number;
+	softLimitRatio: number;
+	onExceed: BudgetAction;
+}
+
+export type RetryClass = 'spawn-error' | 'timeout' | 'invalid-output' | 'rate-limit' | 'crash';
+
+export interface RetryPolicy {
+	maxAttempts: number;
+	backoffSeconds: number;
+	retryOn: RetryClass[];
 }
 
 export interface Task {
@@ -84,11 +106,22 @@ export interface CreateTaskResponse {
 	message: string;
 }
 
+export interface TokenUsage {
+	inputTokens: number;
+	cachedInputTokens: number;
+	outputTokens: number;
+	reasoningOutputTokens: number;
+}
+
 export interface ClusterStatus {
 	orchestrator: string;
 	codexAdapter: string;
 	activeAgents: number;
 	maxAgents: number;
+	// Sum over every task's shared/usage.json
+	usage: TokenUsage;
+	// Present only when .coco/prices.yaml priced at least one worker
+	costUsd?: number;
 }
 
 // ============ Subagent / Sessions (GUI only) ============
@@ -145,3 +178,60 @@ export interface SharedArtifactContent {
 	content: string;
 	updatedAtMs: number | null;
 }
+
+export type EvidenceSource =
+	| { type: 'fileAnchor'; path: string; startLine: number; endLine: number }
+	| { type: 'commandExecution'; command: string; cwd: string; exitCode?: number; stdoutRef?: string; stderrRef?: string }
+	| { type: 'runtimeEventRange'; eventsRef: string; startLine?: number; endLine?: number };
+
+export interface EvidenceEntry {
+	id: string;
+	kind: string;
+	title: string;
+	summary: string;
+	createdAt: string;
+	sources: EvidenceSource[];
+	artifactRefs?: string[];
+}
+
+export interface EvidenceReference {
+	id: string;
+	file: string;
+	line: number;
+	column: number;
+}
+
+export interface EvidenceCheckReport {
+	scannedFiles: string[];
+	references: EvidenceReference[];
+	dangling: EvidenceReference[];
+	unused: string[];
+	duplicates: string[];
+}
+
+export type EvidenceLink =
+	| { type: 'fileRange'; path: string; startLine: number; endLine: number }
+	| { type: 'commandOutput'; command: string; cwd: string; exitCode?: number; stdoutPath?: string; stderrPath?: string }
+	| { type: 'eventRange'; path: string; startLine?: number; endLine?: number };
+
+export interface ResolvedEvidence {
+	entry: EvidenceEntry;
+	links: EvidenceLink[];
+}
+
+export type TaskChangeKind = 'agentEvents' | 'agentStderr' | 'agentFinal' | 'agentStatus' | 'sharedArtifact' | 'task';
+
+export interface TaskChange {
+	kind: TaskChangeKind;
+	agentInstance?: string;
+	/** First directory under `shared/`, e.g. `reports`. */
+	category?: string;
+	/** Task-relative, `/`-separated. */
+	path: string;
+}
+
+/** Payload of `coco_task_fs_update`, batched per task after a short quiet period. */
+export interface TaskWatchEvent {
+	taskId: string;
+	changes: TaskChange[];
+}
@@ -2,16 +2,30 @@ use clap::Args;
 use clap::Parser;
 use clap::Subcommand;
 use clap::ValueEnum;
+use coco_core::agent_spec::AgentSpecError;
+use coco_core::evidence::EvidenceLink;
 use coco_core::task::CreateTaskRequest;
 use coco_core::task::TaskTopology;
+use coco_core::usage::format_cost_usd;
+use coco_orchestrator::ControllerOptions;
+use coco_orchestrator::ControllerOutcome;
+use coco_orchestrator::ControllerRoundOptions;
 use coco_orchestrator::Orchestrator;
+use coco_orchestrator::PlannerOptions;
+use coco_orchestrator::SynthesizerOptions;
+use coco_server::HttpServer;
+use coco_server::McpServer;
+use coco_server::ServeOptions;
+use coco_server::DEFAULT_SERVE_PORT;
 use directories::ProjectDirs;
-use std::path::Path;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 const EXIT_CODE_NOT_FOUND: u8 = 3;
 const EXIT_CODE_USAGE: u8 = 2;
 const EXIT_CODE_TIMEOUT: u8 = 4;
+const EXIT_CODE_CHECK_FAILED: u8 = 5;
 
 #[derive(Debug, thiserror::Error)]
 enum CliError {
@@ -27,6 +41,18 @@ enum CliError {
     InvalidTaskId { task_id: String },
     #[error("json encode error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error(
+        "evidence check failed: dangling={dangling}, duplicates={duplicates}, unused={unused}"
+    )]
+    EvidenceCheckFailed {
+        dangling: usize,
+        duplicates: usize,
+        unused: usize,
+    },
+    #[error("agent spec validation failed: invalid={invalid}")]
+    AgentSpecInvalid { invalid: usize },
+    #[error("{0}")]
+    Server(#[from] coco_server::ServerError),
 }
 
 #[derive(Parser, Debug)]
@@ -50,6 +76,30 @@ enum Commands {
         #[command(subcommand)]
         command: SubagentCommand,
     },
+    Evidence {
+        #[command(subcommand)]
+        command: EvidenceCommand,
+    },
+    Report {
+        #[command(subcommand)]
+        command: ReportCommand,
+    },
+    Plan {
+        #[command(subcommand)]
+        command: PlanCommand,
+    },
+    Agent {
+        #[command(subcommand)]
+        command: AgentCommand,
+    },
+    Budget {
+        #[command(subcommand)]
+        command: BudgetCommand,
+    },
+    /// Serve the orchestrator as a localhost HTTP/JSON API (see docs/coco/subagents.md).
+    Serve(ServeArgs),
+    /// Serve the orchestrator as an MCP server on stdio (tools `coco_*`).
+    Mcp(McpArgs),
 }
 
 #[derive(Subcommand, Debug)]
@@ -58,6 +108,8 @@ enum TaskCommand {
     List(TaskListArgs),
     Show(TaskShowArgs),
     Events(TaskEventsArgs),
+    /// Synthesize the joined worker outputs into `shared/reports/synthesis.{json,md}`.
+    Synthesize(TaskSynthesizeArgs),
 }
 
 #[derive(Subcommand, Debug)]
@@ -68,6 +120,41 @@ enum SubagentCommand {
     Cancel(SubagentCancelArgs),
 }
 
+#[derive(Subcommand, Debug)]
+enum EvidenceCommand {
+    Check(EvidenceCheckArgs),
+    Show(EvidenceShowArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum ReportCommand {
+    Render(ReportRenderArgs),
+    Templates(ReportTemplatesArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum PlanCommand {
+    Create(PlanCreateArgs),
+    Show(PlanShowArgs),
+    Approve(PlanResolveArgs),
+    Reject(PlanResolveArgs),
+    Run(PlanRunArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum AgentCommand {
+    List(AgentListArgs),
+    Show(AgentShowArgs),
+    Validate(AgentValidateArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum BudgetCommand {
+    Show(BudgetShowArgs),
+    Approve(BudgetResolveArgs),
+    Reject(BudgetResolveArgs),
+}
+
 #[derive(Args, Debug)]
 struct TaskCreateArgs {
     #[arg(long)]
@@ -88,6 +175,27 @@ struct TaskShowArgs {
     task_id: String,
 }
 
+#[derive(Args, Debug)]
+struct TaskSynthesizeArgs {
+    task_id: String,
+
+    /// Session goal the synthesis is judged against.
+    #[arg(long, required_unless_present = "plan", conflicts_with = "plan")]
+    goal: Option<String>,
+
+    /// Take the session goal from `shared/plans/<n>.json`.
+    #[arg(long, value_name = "N")]
+    plan: Option<u32>,
+
+    /// Working directory for the synthesizer (defaults to current directory).
+    #[arg(long, default_value = ".")]
+    cwd: PathBuf,
+
+    /// Codex binary to use (defaults to `codex` on PATH).
+    #[arg(long, default_value = "codex")]
+    codex_bin: PathBuf,
+}
+
 #[derive(Args, Debug)]
 struct TaskEventsArgs {
     task_id: String,
@@ -120,6 +228,10 @@ struct SubagentSpawnArgs {
     #[arg(long, default_value = "codex")]
     codex_bin: PathBuf,
 
+    /// Wall-clock limit in seconds (defaults to `task.yaml.config.workerTimeoutSeconds`).
+    #[arg(long)]
+    timeout_seconds: Option<u32>,
+
     /// Prompt passed to `codex exec`.
     prompt: String,
 }
@@ -144,6 +256,165 @@ struct SubagentCancelArgs {
     agent_instance: String,
 }
 
+#[derive(Args, Debug)]
+struct EvidenceCheckArgs {
+    task_id: String,
+
+    /// Also fail when the index contains entries that no report cites.
+    #[arg(long)]
+    strict: bool,
+}
+
+#[derive(Args, Debug)]
+struct EvidenceShowArgs {
+    task_id: String,
+    evidence_id: String,
+}
+
+#[derive(Args, Debug)]
+struct ReportRenderArgs {
+    task_id: String,
+
+    /// Template name (built-in: JoinedSummary, DiagnosticReport, APIContract; overrides in `.coco/templates/`).
+    #[arg(long, default_value = coco_orchestrator::JOINED_SUMMARY_TEMPLATE_NAME)]
+    template: String,
+
+    /// Output path (defaults to `shared/reports/<template-kebab>.md`).
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Print the rendered report instead of its path (the file is still written).
+    #[arg(long)]
+    print: bool,
+}
+
+#[derive(Args, Debug)]
+struct ReportTemplatesArgs {}
+
+#[derive(Args, Debug)]
+struct PlanCreateArgs {
+    task_id: String,
+
+    /// Planner adapter (`codex-exec` or `codex-app-server`).
+    #[arg(long, default_value = "codex-exec")]
+    adapter: String,
+
+    /// Planner execution directory (defaults to current directory).
+    #[arg(long, default_value = ".")]
+    cwd: PathBuf,
+
+    /// Codex binary to use (defaults to `codex` on PATH).
+    #[arg(long, default_value = "codex")]
+    codex_bin: PathBuf,
+
+    /// Always open a `human-approval` gate (default: unless `config.autoApprove`).
+    #[arg(long, conflicts_with = "no_approval")]
+    require_approval: bool,
+
+    /// Never open a `human-approval` gate.
+    #[arg(long)]
+    no_approval: bool,
+}
+
+#[derive(Args, Debug)]
+struct AgentListArgs {}
+
+#[derive(Args, Debug)]
+struct AgentShowArgs {
+    name: String,
+}
+
+#[derive(Args, Debug)]
+struct AgentValidateArgs {
+    /// Validate a single spec (defaults to all specs).
+    name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct PlanShowArgs {
+    task_id: String,
+    plan_number: u32,
+}
+
+#[derive(Args, Debug)]
+struct BudgetShowArgs {
+    task_id: String,
+}
+
+#[derive(Args, Debug)]
+struct BudgetResolveArgs {
+    task_id: String,
+
+    /// Recorded as `resolvedBy` on the gate.
+    #[arg(long, default_value = "human")]
+    by: String,
+}
+
+#[derive(Args, Debug)]
+struct PlanResolveArgs {
+    task_id: String,
+    plan_number: u32,
+
+    /// Recorded as `resolvedBy` on the gate.
+    #[arg(long, default_value = "human")]
+    by: String,
+}
+
+#[derive(Args, Debug)]
+struct PlanRunArgs {
+    task_id: String,
+    plan_number: u32,
+
+    /// Default worker execution directory (defaults to current directory).
+    #[arg(long, default_value = ".")]
+    cwd: PathBuf,
+
+    /// Codex binary to use (defaults to `codex` on PATH).
+    #[arg(long, default_value = "codex")]
+    codex_bin: PathBuf,
+
+    /// Restart running codex-exec workers when `shared/human-notes.md` changes (default: only
+    /// mark them as having stale context).
+    #[arg(long)]
+    restart_on_notes: bool,
+
+    /// Round mode: after each join a round planner decides whether to dispatch another batch,
+    /// for at most N rounds (including the first).
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    max_rounds: Option<u32>,
+
+    /// Round mode: cap on workers dispatched across all rounds (a count; token and cost limits
+    /// are `config.budget`).
+    #[arg(long, value_name = "N", requires = "max_rounds")]
+    max_dispatched_workers: Option<u32>,
+
+    /// Run the synthesizer after the final join (`shared/reports/synthesis.{json,md}`).
+    #[arg(long)]
+    synthesize: bool,
+}
+
+#[derive(Args, Debug)]
+struct ServeArgs {
+    /// Listen address; must be a loopback address.
+    #[arg(long, default_value_t = SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_SERVE_PORT)))]
+    addr: SocketAddr,
+
+    /// Bearer token clients must present (defaults to `COCO_SERVE_TOKEN`, else a random one).
+    #[arg(long)]
+    token: Option<String>,
+
+    /// Codex binary for workers spawned through the API (defaults to `codex` on PATH).
+    #[arg(long, default_value = "codex")]
+    codex_bin: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct McpArgs {
+    /// Codex binary for workers spawned through `coco_subagent_spawn` (defaults to `codex` on PATH).
+    #[arg(long, default_value = "codex")]
+    codex_bin: PathBuf,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 enum TopologyArg {
     Swarm,
@@ -181,6 +452,7 @@ fn run() -> Result<(), CliError> {
             TaskCommand::List(args) => cmd_task_list(&orchestrator, cli.json, args),
             TaskCommand::Show(args) => cmd_task_show(&orchestrator, cli.json, args),
             TaskCommand::Events(args) => cmd_task_events(&orchestrator, cli.json, args),
+            TaskCommand::Synthesize(args) => cmd_task_synthesize(&orchestrator, cli.json, args),
         },
         Commands::Subagent { command } => match command {
             SubagentCommand::Spawn(args) => cmd_subagent_spawn(&orchestrator, cli.json, args),
@@ -188,6 +460,33 @@ fn run() -> Result<(), CliError> {
             SubagentCommand::WaitAny(args) => cmd_subagent_wait_any(&orchestrator, cli.json, args),
             SubagentCommand::Cancel(args) => cmd_subagent_cancel(&orchestrator, cli.json, args),
         },
+        Commands::Evidence { command } => match command {
+            EvidenceCommand::Check(args) => cmd_evidence_check(&orchestrator, cli.json, args),
+            EvidenceCommand::Show(args) => cmd_evidence_show(&orchestrator, cli.json, args),
+        },
+        Commands::Report { command } => match command {
+            ReportCommand::Render(args) => cmd_report_render(&orchestrator, cli.json, args),
+            ReportCommand::Templates(args) => cmd_report_templates(&orchestrator, cli.json, args),
+        },
+        Commands::Plan { command } => match command {
+            PlanCommand::Create(args) => cmd_plan_create(&orchestrator, cli.json, args),
+            PlanCommand::Show(args) => cmd_plan_show(&orchestrator, cli.json, args),
+            PlanCommand::Approve(args) => cmd_plan_resolve(&orchestrator, cli.json, args, true),
+            PlanCommand::Reject(args) => cmd_plan_resolve(&orchestrator, cli.json, args, false),
+            PlanCommand::Run(args) => cmd_plan_run(&orchestrator, cli.json, args),
+        },
+        Commands::Agent { command } => match command {
+            AgentCommand::List(args) => cmd_agent_list(&orchestrator, cli.json, args),
+            AgentCommand::Show(args) => cmd_agent_show(&orchestrator, cli.json, args),
+            AgentCommand::Validate(args) => cmd_agent_validate(&orchestrator, cli.json, args),
+        },
+        Commands::Budget { command } => match command {
+            BudgetCommand::Show(args) => cmd_budget_show(&orchestrator, cli.json, args),
+            BudgetCommand::Approve(args) => cmd_budget_resolve(&orchestrator, cli.json, args, true),
+            BudgetCommand::Reject(args) => cmd_budget_resolve(&orchestrator, cli.json, args, false),
+        },
+        Commands::Serve(args) => cmd_serve(orchestrator, cli.json, args),
+        Commands::Mcp(args) => cmd_mcp(orchestrator, args),
     }
 }
 
@@ -245,9 +544,12 @@ fn cmd_task_show(
 ) -> Result<(), CliError> {
     validate_task_id(&args.task_id)?;
     let task = orchestrator.get_task(&args.task_id)?;
+    let usage = orchestrator.task_usage(&args.task_id)?;
 
     if json {
-        println!("{}", serde_json::to_string(&task)?);
+        let mut value = serde_json::to_value(&task)?;
+        value["usage"] = serde_json::to_value(&usage)?;
+        println!("{}", serde_json::to_string(&value)?);
         return Ok(());
     }
 
@@ -263,6 +565,19 @@ fn cmd_task_show(
     println!("milestones: {}", task.milestones.len());
     println!("roster: {}", task.roster.len());
     println!("gates: {}", task.gates.len());
+    println!("tokens: {}", usage.usage);
+    if let Some(cost) = usage.cost_usd {
+        println!("cost: {}", format_cost_usd(cost));
+    }
+    for w in usage.workers.iter().filter(|w| !w.usage.is_empty()) {
+        println!(
+            "  {}\tmodel={}\ttokens={}\tcost={}",
+            w.agent_instance,
+            w.model.as_deref().unwrap_or("unknown"),
+            w.usage.total_tokens(),
+            w.cost_usd.map(format_cost_usd).as_deref().unwrap_or("-")
+        );
+    }
     Ok(())
 }
 
@@ -294,6 +609,40 @@ fn cmd_task_events(
     Ok(())
 }
 
+fn cmd_task_synthesize(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: TaskSynthesizeArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    // clap makes `--goal` and `--plan` mutually exclusive and requires one of them.
+    let session_goal = match args.plan {
+        Some(plan_number) => {
+            orchestrator
+                .plan_read(&args.task_id, plan_number)?
+                .session_goal
+        }
+        None => args.goal.unwrap_or_default(),
+    };
+    let mut opts = ControllerOptions::new(orchestrator.workspace_root());
+    opts.codex_bin = args.codex_bin;
+    opts.default_cwd = args.cwd;
+    let synth = SynthesizerOptions::new(orchestrator.workspace_root());
+
+    let result = orchestrator.task_synthesize(&args.task_id, &session_goal, &opts, &synth)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&result)?);
+        return Ok(());
+    }
+
+    println!("synthesisMd: {}", result.synthesis_md.display());
+    println!("synthesisJson: {}", result.synthesis_json.display());
+    println!("uncitedClaims: {}", result.uncited_claims);
+    Ok(())
+}
+
 fn cmd_subagent_spawn(
     orchestrator: &Orchestrator,
     json: bool,
@@ -303,7 +652,7 @@ fn cmd_subagent_spawn(
     // Ensure consistent exit code when the task id does not exist.
     let _ = orchestrator.get_task(&args.task_id)?;
 
-    let output_schema_path = default_output_schema_path(orchestrator.workspace_root());
+    let output_schema_path = orchestrator.worker_output_schema_path(&args.agent)?;
 
     let resp = orchestrator.subagent_spawn(coco_orchestrator::SubagentSpawnRequest {
         task_id: args.task_id,
@@ -313,6 +662,7 @@ fn cmd_subagent_spawn(
         cwd: args.cwd,
         codex_bin: args.codex_bin,
         output_schema_path,
+        timeout_seconds: args.timeout_seconds,
     })?;
 
     if json {
@@ -349,6 +699,8 @@ fn cmd_subagent_list(
                     "agentInstance": a.agent_instance,
                     "agent": a.agent,
                     "status": a.status.as_str(),
+                    "exitCode": a.exit_code,
+                    "staleContext": a.stale_context,
                 })
             })
             .collect::<Vec<_>>();
@@ -357,7 +709,14 @@ fn cmd_subagent_list(
     }
 
     for a in subagents {
-        println!("{}\t{}\t{}", a.agent_instance, a.status.as_str(), a.agent);
+        let mut line = format!("{}\t{}\t{}", a.agent_instance, a.status.as_str(), a.agent);
+        if let Some(code) = a.exit_code {
+            line.push_str(&format!("\texit={code}"));
+        }
+        if a.stale_context {
+            line.push_str("\tstale-context");
+        }
+        println!("{line}");
     }
     Ok(())
 }
@@ -414,6 +773,482 @@ fn cmd_subagent_cancel(
     Ok(())
 }
 
+fn cmd_evidence_check(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: EvidenceCheckArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let report = orchestrator.evidence_check(&args.task_id)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&report)?);
+    } else {
+        println!(
+            "scanned {} file(s), {} reference(s)",
+            report.scanned_files.len(),
+            report.references.len()
+        );
+        for r in &report.dangling {
+            println!(
+                "dangling\t{}:{}:{}\tevidence:{}",
+                r.file, r.line, r.column, r.id
+            );
+        }
+        for id in &report.duplicates {
+            println!("duplicate\t{id}");
+        }
+        for id in &report.unused {
+            println!("unused\t{id}");
+        }
+    }
+
+    let failed = !report.is_ok() || (args.strict && !report.unused.is_empty());
+    if failed {
+        return Err(CliError::EvidenceCheckFailed {
+            dangling: report.dangling.len(),
+            duplicates: report.duplicates.len(),
+            unused: report.unused.len(),
+        });
+    }
+    Ok(())
+}
+
+fn cmd_evidence_show(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: EvidenceShowArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let resolved = orchestrator.evidence_resolve(&args.task_id, &args.evidence_id)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&resolved)?);
+        return Ok(());
+    }
+
+    println!("id: {}", resolved.entry.id);
+    println!("kind: {}", resolved.entry.kind);
+    println!("title: {}", resolved.entry.title);
+    if !resolved.entry.summary.trim().is_empty() {
+        println!("summary: {}", resolved.entry.summary);
+    }
+    for link in &resolved.links {
+        match link {
+            EvidenceLink::FileRange {
+                path,
+                start_line,
+                end_line,
+            } => println!("file\t{}:{start_line}-{end_line}", path.display()),
+            EvidenceLink::CommandOutput {
+                command,
+                stdout_path,
+                stderr_path,
+                ..
+            } => {
+                println!("command\t{command}");
+                if let Some(path) = stdout_path {
+                    println!("stdout\t{}", path.display());
+                }
+                if let Some(path) = stderr_path {
+                    println!("stderr\t{}", path.display());
+                }
+            }
+            EvidenceLink::EventRange {
+                path,
+                start_line,
+                end_line,
+            } => match (start_line, end_line) {
+                (Some(start), Some(end)) => println!("events\t{}:{start}-{end}", path.display()),
+                (Some(start), None) => println!("events\t{}:{start}", path.display()),
+                _ => println!("events\t{}", path.display()),
+            },
+        }
+    }
+    Ok(())
+}
+
+fn cmd_report_render(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: ReportRenderArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let report =
+        orchestrator.report_render(&args.task_id, &args.template, args.output.as_deref())?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(&serde_json::json!({
+                "template": report.template,
+                "path": report.path,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if args.print {
+        print!("{}", report.content);
+    } else {
+        println!("{}", report.path.display());
+    }
+    Ok(())
+}
+
+fn cmd_report_templates(
+    orchestrator: &Orchestrator,
+    json: bool,
+    _args: ReportTemplatesArgs,
+) -> Result<(), CliError> {
+    let templates = orchestrator.report_templates()?;
+
+    if json {
+        println!("{}", serde_json::to_string(&templates)?);
+        return Ok(());
+    }
+
+    for t in templates {
+        match t.override_path {
+            Some(path) => println!("{}\t{}", t.name, path.display()),
+            None => println!("{}\tbuilt-in", t.name),
+        }
+    }
+    Ok(())
+}
+
+fn cmd_plan_create(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: PlanCreateArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let workspace_root = orchestrator.workspace_root();
+    let mut opts = ControllerOptions::new(workspace_root);
+    opts.codex_bin = args.codex_bin;
+    opts.default_cwd = args.cwd;
+    let mut planner = PlannerOptions::new(workspace_root);
+    planner.adapter = args.adapter;
+    if args.require_approval {
+        planner.require_approval = Some(true);
+    } else if args.no_approval {
+        planner.require_approval = Some(false);
+    }
+
+    let plan = orchestrator.plan(&args.task_id, &opts, &planner)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&plan)?);
+        return Ok(());
+    }
+
+    println!("plan: {}", plan.plan_number);
+    println!("path: {}", plan.path.display());
+    println!("tasks: {}", plan.actions.tasks.len());
+    if let Some(gate_id) = plan.gate_id {
+        println!("gate: {gate_id} (blocked)");
+    }
+    Ok(())
+}
+
+fn cmd_plan_show(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: PlanShowArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let actions = orchestrator.plan_read(&args.task_id, args.plan_number)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&actions)?);
+        return Ok(());
+    }
+
+    println!("sessionGoal: {}", actions.session_goal);
+    for t in &actions.tasks {
+        println!(
+            "{}\t{}\t{}\t{}",
+            t.resolved_agent_instance(),
+            t.agent,
+            t.adapter,
+            t.title
+        );
+    }
+    Ok(())
+}
+
+fn cmd_plan_resolve(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: PlanResolveArgs,
+    approved: bool,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    orchestrator.plan_resolve(&args.task_id, args.plan_number, approved, Some(&args.by))?;
+
+    let state = if approved { "approved" } else { "rejected" };
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(&serde_json::json!({
+                "planNumber": args.plan_number,
+                "state": state,
+            }))?
+        );
+        return Ok(());
+    }
+
+    println!("{state}\tplan {}", args.plan_number);
+    Ok(())
+}
+
+fn cmd_budget_show(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: BudgetShowArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let status = orchestrator.budget_status(&args.task_id)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&status)?);
+        return Ok(());
+    }
+
+    let Some(status) = status else {
+        println!("no budget configured");
+        return Ok(());
+    };
+    println!("level: {}", status.level().as_str());
+    println!("onExceed: {}", status.on_exceed.as_str());
+    println!("extensions: {}", status.extensions);
+    for m in &status.metrics {
+        println!("{}\t{}\t{}", m.name, m.display(), m.level.as_str());
+    }
+    Ok(())
+}
+
+fn cmd_budget_resolve(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: BudgetResolveArgs,
+    approved: bool,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let gate_id = orchestrator.budget_resolve(&args.task_id, approved, Some(&args.by))?;
+
+    let state = if approved { "approved" } else { "rejected" };
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(&serde_json::json!({
+                "gateId": gate_id,
+                "state": state,
+            }))?
+        );
+        return Ok(());
+    }
+
+    println!("{state}\t{gate_id}");
+    Ok(())
+}
+
+fn cmd_plan_run(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: PlanRunArgs,
+) -> Result<(), CliError> {
+    validate_task_id(&args.task_id)?;
+
+    let mut opts = ControllerOptions::new(orchestrator.workspace_root());
+    opts.codex_bin = args.codex_bin;
+    opts.default_cwd = args.cwd;
+    opts.restart_stale_workers = args.restart_on_notes;
+    if let Some(max_rounds) = args.max_rounds {
+        let mut rounds = ControllerRoundOptions::new(orchestrator.workspace_root());
+        rounds.max_rounds = max_rounds;
+        rounds.max_dispatched_workers = args.max_dispatched_workers;
+        opts.rounds = Some(rounds);
+    }
+    if args.synthesize {
+        opts.synthesizer = Some(SynthesizerOptions::new(orchestrator.workspace_root()));
+    }
+
+    let result = orchestrator.controller_run_plan(&args.task_id, args.plan_number, opts)?;
+    let outcome = match result.outcome {
+        ControllerOutcome::Done => "done",
+        ControllerOutcome::Blocked => "blocked",
+        ControllerOutcome::BudgetExhausted => "budget-exhausted",
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(&serde_json::json!({
+                "outcome": outcome,
+                "joinedSummaryMd": result.joined_summary.as_ref().map(|j| &j.joined_summary_md),
+                "rounds": result.rounds,
+                "synthesisMd": result.synthesis.as_ref().map(|s| &s.synthesis_md),
+            }))?
+        );
+        return Ok(());
+    }
+
+    println!("outcome: {outcome}");
+    for round in &result.rounds {
+        println!(
+            "round {}\t{}\t{}",
+            round.round,
+            round.decision.as_str(),
+            round.reason
+        );
+    }
+    if let Some(joined) = result.joined_summary {
+        println!("joinedSummaryMd: {}", joined.joined_summary_md.display());
+    }
+    if let Some(synthesis) = result.synthesis {
+        println!("synthesisMd: {}", synthesis.synthesis_md.display());
+    }
+    Ok(())
+}
+
+fn cmd_serve(orchestrator: Orchestrator, json: bool, args: ServeArgs) -> Result<(), CliError> {
+    let server = HttpServer::bind(
+        orchestrator,
+        ServeOptions {
+            addr: args.addr,
+            token: args
+                .token
+                .or_else(|| std::env::var("COCO_SERVE_TOKEN").ok()),
+            codex_bin: args.codex_bin,
+        },
+    )?;
+    let info_path = server.write_info_file()?;
+    let info = server.info()?;
+
+    if json {
+        println!("{}", serde_json::to_string(&info)?);
+    } else {
+        println!("listening\t{}", info.url);
+        println!("token\t{}", info.token);
+        println!("info\t{}", info_path.display());
+    }
+
+    server.run()?;
+    Ok(())
+}
+
+fn cmd_mcp(orchestrator: Orchestrator, args: McpArgs) -> Result<(), CliError> {
+    // stdout carries the JSON-RPC stream only.
+    McpServer::new(orchestrator)
+        .with_codex_bin(args.codex_bin)
+        .serve(std::io::stdin().lock(), std::io::stdout().lock())?;
+    Ok(())
+}
+
+fn cmd_agent_list(
+    orchestrator: &Orchestrator,
+    json: bool,
+    _args: AgentListArgs,
+) -> Result<(), CliError> {
+    let specs = orchestrator.agent_specs()?;
+
+    if json {
+        println!("{}", serde_json::to_string(&specs)?);
+        return Ok(());
+    }
+
+    for spec in specs {
+        println!(
+            "{}\t{}\t{}",
+            spec.name,
+            spec.file.adapter.as_deref().unwrap_or("-"),
+            spec.file.description.trim()
+        );
+    }
+    Ok(())
+}
+
+fn cmd_agent_show(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: AgentShowArgs,
+) -> Result<(), CliError> {
+    let spec = orchestrator.agent_spec(&args.name)?;
+
+    if json {
+        println!("{}", serde_json::to_string(&spec)?);
+        return Ok(());
+    }
+
+    println!("name: {}", spec.name);
+    println!("dir: {}", spec.dir.display());
+    if !spec.file.description.trim().is_empty() {
+        println!("description: {}", spec.file.description.trim());
+    }
+    if let Some(adapter) = &spec.file.adapter {
+        println!("adapter: {adapter}");
+    }
+    if let Some(model) = &spec.file.model {
+        println!("model: {model}");
+    }
+    if let Some(sandbox) = spec.file.sandbox {
+        println!("sandbox: {}", sandbox.as_str());
+    }
+    if let Some(policy) = spec.file.approval_policy {
+        println!("approvalPolicy: {}", policy.as_str());
+    }
+    if let Some(schema) = &spec.file.output_schema {
+        println!("outputSchema: {schema}");
+    }
+    if !spec.file.skills.is_empty() {
+        println!("skills: {}", spec.file.skills.join(", "));
+    }
+    if !spec.file.allowed_tools.is_empty() {
+        println!("allowedTools: {}", spec.file.allowed_tools.join(", "));
+    }
+    if !spec.system_prompt.is_empty() {
+        println!();
+        println!("{}", spec.system_prompt);
+    }
+    Ok(())
+}
+
+fn cmd_agent_validate(
+    orchestrator: &Orchestrator,
+    json: bool,
+    args: AgentValidateArgs,
+) -> Result<(), CliError> {
+    let results = orchestrator.agent_spec_validate(args.name.as_deref())?;
+    let invalid = results.iter().filter(|r| !r.is_ok()).count();
+
+    if json {
+        println!("{}", serde_json::to_string(&results)?);
+    } else {
+        for r in &results {
+            if r.is_ok() {
+                println!("ok\t{}", r.name);
+            }
+            for issue in &r.issues {
+                println!("invalid\t{}\t{issue}", r.name);
+            }
+        }
+    }
+
+    if invalid > 0 {
+        return Err(CliError::AgentSpecInvalid { invalid });
+    }
+    Ok(())
+}
+
 fn resolve_workspace_root() -> Result<PathBuf, CliError> {
     if let Ok(root) = std::env::var("COCO_WORKSPACE_ROOT") {
         let path = PathBuf::from(root);
@@ -462,27 +1297,35 @@ fn exit_code_for_error(err: &CliError) -> u8 {
         CliError::Orchestrator(coco_orchestrator::OrchestratorError::SubagentNotFound {
             ..
         }) => EXIT_CODE_NOT_FOUND,
+        CliError::Orchestrator(coco_orchestrator::OrchestratorError::EvidenceNotFound {
+            ..
+        }) => EXIT_CODE_NOT_FOUND,
+        CliError::Orchestrator(coco_orchestrator::OrchestratorError::TemplateNotFound {
+            ..
+        }) => EXIT_CODE_NOT_FOUND,
+        CliError::Orchestrator(coco_orchestrator::OrchestratorError::PlanNotFound { .. }) => {
+            EXIT_CODE_NOT_FOUND
+        }
+        CliError::Orchestrator(coco_orchestrator::OrchestratorError::GateNotFound { .. }) => {
+            EXIT_CODE_NOT_FOUND
+        }
+        CliError::Orchestrator(coco_orchestrator::OrchestratorError::AgentSpec(
+            AgentSpecError::NotFound { .. },
+        )) => EXIT_CODE_NOT_FOUND,
         CliError::Orchestrator(coco_orchestrator::OrchestratorError::WaitAnyTimeout { .. }) => {
             EXIT_CODE_TIMEOUT
         }
+        CliError::EvidenceCheckFailed { .. } => EXIT_CODE_CHECK_FAILED,
+        CliError::AgentSpecInvalid { .. } => EXIT_CODE_CHECK_FAILED,
+        CliError::Orchestrator(coco_orchestrator::OrchestratorError::AgentSpec(
+            AgentSpecError::InvalidName { .. },
+        )) => EXIT_CODE_USAGE,
         CliError::InvalidTaskId { .. } => EXIT_CODE_USAGE,
+        CliError::Server(coco_server::ServerError::NonLoopback { .. }) => EXIT_CODE_USAGE,
         _ => 1,
     }
 }
 
-fn default_output_schema_path(workspace_root: &Path) -> PathBuf {
-    let candidate = workspace_root
-        .join("schemas")
-        .join("worker-output.schema.json");
-    if candidate.exists() {
-        return candidate;
-    }
-    std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join("schemas")
-        .join("worker-output.schema.json")
-}
-
 fn format_task_topology(topology: TaskTopology) -> &'static str {
     match topology {
         TaskTopology::Swarm => "swarm",
@@ -5,6 +5,7 @@
 
 mod app_server_client;
 mod exec_runner;
+mod usage;
 
 pub use exec_runner::CodexExecError;
 pub use exec_runner::CodexExecStartRequest;
@@ -17,3 +18,6 @@ pub use app_server_client::CodexAppServerClient;
 pub use app_server_client::CodexAppServerError;
 pub use app_server_client::CodexAppServerSpawnRequest;
 pub use app_server_client::CodexJsonRpcEvent;
+
+pub use usage::parse_events_usage;
+pub use usage::EventsUsage;
@@ -0,0 +1,135 @@
+use coco_core::usage::TokenUsage;
+use serde_json::Value;
+
+/// Token usage and model extracted from a worker's `runtime/events.jsonl`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventsUsage {
+    pub model: Option<String>,
+    pub usage: TokenUsage,
+}
+
+/// Aggregate usage from either stream format:
+/// - `codex exec --json`: per-turn `usage` on `turn.completed` events, summed;
+/// - app-server: cumulative thread totals (`thread/tokenUsage/updated`, or the legacy
+///   `codex/event/token_count`), last one wins.
+///
+/// Unparseable lines are skipped; recordings are best-effort.
+pub fn parse_events_usage(content: &str) -> EventsUsage {
+    let mut model = None;
+    let mut per_turn = TokenUsage::default();
+    let mut thread_total = None;
+    let mut legacy_total = None;
+
+    for line in content.lines() {
+        let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+            continue;
+        };
+        if let Some(m) = model_of(&value) {
+            model = Some(m.to_string());
+        }
+
+        if value.get("type").and_then(Value::as_str) == Some("turn.completed") {
+            if let Some(usage) = value.get("usage") {
+                per_turn += token_usage(usage);
+            }
+            continue;
+        }
+
+        let params = value.get("params");
+        match value.get("method").and_then(Value::as_str) {
+            Some("thread/tokenUsage/updated") => {
+                if let Some(total) = params
+                    .and_then(|p| p.get("tokenUsage"))
+                    .and_then(|u| u.get("total"))
+                {
+                    thread_total = Some(token_usage(total));
+                }
+            }
+            Some("codex/event/token_count") => {
+                if let Some(total) = params
+                    .and_then(|p| p.get("msg"))
+                    .and_then(|m| m.get("info"))
+                    .and_then(|i| i.get("total_token_usage"))
+                {
+                    legacy_total = Some(token_usage(total));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    EventsUsage {
+        model,
+        usage: thread_total.or(legacy_total).unwrap_or(per_turn),
+    }
+}
+
+/// Model announced by a thread start/resume response or a session-configured notification.
+fn model_of(value: &Value) -> Option<&str> {
+    [
+        value.get("model"),
+        value.get("result").and_then(|r| r.get("model")),
+        value.get("params").and_then(|p| p.get("model")),
+        value
+            .get("params")
+            .and_then(|p| p.get("msg"))
+            .and_then(|m| m.get("model")),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(Value::as_str)
+    .filter(|m| !m.is_empty())
+}
+
+/// Accepts both the snake_case (exec/legacy) and camelCase (app-server v2) field names.
+fn token_usage(value: &Value) -> TokenUsage {
+    let field = |snake: &str, camel: &str| {
+        value
+            .get(snake)
+            .or_else(|| value.get(camel))
+            .and_then(Value::as_u64)
+            .unwrap_or(0)
+    };
+    TokenUsage {
+        input_tokens: field("input_tokens", "inputTokens"),
+        cached_input_tokens: field("cached_input_tokens", "cachedInputTokens"),
+        output_tokens: field("output_tokens", "outputTokens"),
+        reasoning_output_tokens: field("reasoning_output_tokens", "reasoningOutputTokens"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_exec_turns() {
+        let events = r#"{"type":"thread.started","thread_id":"thr_1"}
+{"type":"turn.completed","usage":{"input_tokens":100,"cached_input_tokens":40,"output_tokens":10}}
+not json
+{"type":"turn.completed","usage":{"input_tokens":50,"cached_input_tokens":0,"output_tokens":5}}"#;
+        let parsed = parse_events_usage(events);
+        assert_eq!(parsed.model, None);
+        assert_eq!(parsed.usage.input_tokens, 150);
+        assert_eq!(parsed.usage.cached_input_tokens, 40);
+        assert_eq!(parsed.usage.output_tokens, 15);
+    }
+
+    #[test]
+    fn app_server_totals_are_cumulative() {
+        let events = r#"{"id":1,"result":{"thread":{"id":"thr_1"},"model":"gpt-5-codex"}}
+{"method":"thread/tokenUsage/updated","params":{"tokenUsage":{"total":{"inputTokens":10,"cachedInputTokens":0,"outputTokens":2,"reasoningOutputTokens":1}}}}
+{"method":"thread/tokenUsage/updated","params":{"tokenUsage":{"total":{"inputTokens":30,"cachedInputTokens":20,"outputTokens":6,"reasoningOutputTokens":3}}}}"#;
+        let parsed = parse_events_usage(events);
+        assert_eq!(parsed.model.as_deref(), Some("gpt-5-codex"));
+        assert_eq!(
+            parsed.usage,
+            TokenUsage {
+                input_tokens: 30,
+                cached_input_tokens: 20,
+                output_tokens: 6,
+                reasoning_output_tokens: 3,
+            }
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Structured half of a spec: `agents/<name>/agent.yaml`.
+pub const AGENT_SPEC_FILE_NAME: &str = "agent.yaml";
+/// System prompt half of a spec: `agents/<name>/agents.md`.
+pub const AGENT_PROMPT_FILE_NAME: &str = "agents.md";
+
+const KNOWN_ADAPTERS: &[&str] = &["codex-exec", "codex-app-server"];
+
+#[derive(Debug, Error)]
+pub enum AgentSpecError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid {path}: {source}")]
+    Yaml {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+    #[error("agent spec not found: {name}")]
+    NotFound { name: String },
+    #[error("invalid agent name: {name}")]
+    InvalidName { name: String },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SandboxMode {
+    ReadOnly,
+    WorkspaceWrite,
+    DangerFullAccess,
+}
+
+impl SandboxMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SandboxMode::ReadOnly => "read-only",
+            SandboxMode::WorkspaceWrite => "workspace-write",
+            SandboxMode::DangerFullAccess => "danger-full-access",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalPolicy {
+    Untrusted,
+    OnFailure,
+    OnRequest,
+    Never,
+}
+
+impl ApprovalPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalPolicy::Untrusted => "untrusted",
+            ApprovalPolicy::OnFailure => "on-failure",
+            ApprovalPolicy::OnRequest => "on-request",
+            ApprovalPolicy::Never => "never",
+        }
+    }
+}
+
+/// `agent.yaml` as written on disk. Every field is optional so a spec can start as a bare
+/// `agents.md`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AgentSpecFile {
+    #[serde(default)]
+    pub description: String,
+    /// Default skills recorded on the roster entry at spawn.
+    #[serde(default)]
+    pub skills: Vec<String>,
+    /// Worker output schema, relative to the workspace root.
+    pub output_schema: Option<String>,
+    /// Adapter used when a subtask does not name one.
+    pub adapter: Option<String>,
+    pub model: Option<String>,
+    pub sandbox: Option<SandboxMode>,
+    pub approval_policy: Option<ApprovalPolicy>,
+    /// Tools the worker is told it may use (Codex has no hard allowlist; this is prompt-level).
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSpec {
+    pub name: String,
+    pub dir: PathBuf,
+    #[serde(flatten)]
+    pub file: AgentSpecFile,
+    /// Contents of `agents.md` (trimmed; empty when the file is missing).
+    pub system_prompt: String,
+}
+
+impl AgentSpec {
+    /// Wrap a subtask prompt with the spec's system prompt, skills and tool constraints.
+    /// Returns the prompt unchanged when the spec adds nothing.
+    pub fn compose_prompt(&self, task_prompt: &str) -> String {
+        let mut sections = Vec::new();
+        if !self.system_prompt.is_empty() {
+            sections.push(self.system_prompt.clone());
+        }
+        if !self.file.skills.is_empty() {
+            sections.push(format!("## Skills\n\n- {}", self.file.skills.join("\n- ")));
+        }
+        if !self.file.allowed_tools.is_empty() {
+            sections.push(format!(
+                "## Allowed Tools\n\nOnly use these tools: {}.",
+                self.file.allowed_tools.join(", ")
+            ));
+        }
+        if sections.is_empty() {
+            return task_prompt.to_string();
+        }
+        sections.push(format!("# Task\n\n{}", task_prompt.trim()));
+        sections.join("\n\n") + "\n"
+    }
+
+    /// Output schema resolved against the workspace root.
+    pub fn output_schema_path(&self, workspace_root: &Path) -> Option<PathBuf> {
+        self.file
+            .output_schema
+            .as_deref()
+            .map(|p| workspace_root.join(p))
+    }
+}
+
+/// Names of the `agents/<name>/` directories that hold an `agent.yaml` or `agents.md`.
+pub fn list_agent_spec_names(agents_dir: &Path) -> Result<Vec<String>, AgentSpecError> {
+    if !agents_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut names = Vec::new();
+    for entry in fs::read_dir(agents_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if !path.join(AGENT_SPEC_FILE_NAME).is_file()
+            && !path.join(AGENT_PROMPT_FILE_NAME).is_file()
+        {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if validate_agent_name(&name).is_ok() {
+            names.push(name);
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+pub fn load_agent_spec(agents_dir: &Path, name: &str) -> Result<AgentSpec, AgentSpecError> {
+    validate_agent_name(name)?;
+    let dir = agents_dir.join(name);
+    let yaml_path = dir.join(AGENT_SPEC_FILE_NAME);
+    let prompt_path = dir.join(AGENT_PROMPT_FILE_NAME);
+    if !yaml_path.is_file() && !prompt_path.is_file() {
+        return Err(AgentSpecError::NotFound {
+            name: name.to_string(),
+        });
+    }
+
+    let file = if yaml_path.is_file() {
+        let content = fs::read_to_string(&yaml_path)?;
+        if content.trim().is_empty() {
+            AgentSpecFile::default()
+        } else {
+            serde_yaml::from_str(&content).map_err(|source| AgentSpecError::Yaml {
+                path: yaml_path.clone(),
+                source,
+            })?
+        }
+    } else {
+        AgentSpecFile::default()
+    };
+    let system_prompt = if prompt_path.is_file() {
+        fs::read_to_string(&prompt_path)?.trim().to_string()
+    } else {
+        String::new()
+    };
+
+    Ok(AgentSpec {
+        name: name.to_string(),
+        dir,
+        file,
+        system_prompt,
+    })
+}
+
+/// Problems that would make a spawn misbehave (empty list means valid).
+pub fn validate_agent_spec(spec: &AgentSpec, workspace_root: &Path) -> Vec<String> {
+    let mut issues = Vec::new();
+    if spec.system_prompt.is_empty() {
+        issues.push(format!("{AGENT_PROMPT_FILE_NAME} is missing or empty"));
+    }
+    if let Some(adapter) = spec.file.adapter.as_deref() {
+        if !KNOWN_ADAPTERS.contains(&adapter) {
+            issues.push(format!("unsupported adapter: {adapter}"));
+        }
+    }
+    if let Some(path) = spec.output_schema_path(workspace_root) {
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                if serde_json::from_str::<serde_json::Value>(&content).is_err() {
+                    issues.push(format!(
+                        "outputSchema is not valid JSON: {}",
+                        path.display()
+                    ));
+                }
+            }
+            Err(_) => issues.push(format!("outputSchema not found: {}", path.display())),
+        }
+    }
+    if spec
+        .file
+        .model
+        .as_deref()
+        .is_some_and(|m| m.trim().is_empty())
+    {
+        issues.push("model is empty".to_string());
+    }
+    for (field, values) in [
+        ("skills", &spec.file.skills),
+        ("allowedTools", &spec.file.allowed_tools),
+    ] {
+        if values.iter().any(|v| v.trim().is_empty()) {
+            issues.push(format!("{field} contains an empty entry"));
+        }
+    }
+    issues
+}
+
+fn validate_agent_name(name: &str) -> Result<(), AgentSpecError> {
+    let is_ok = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_ok {
+        Ok(())
+    } else {
+        Err(AgentSpecError::InvalidName {
+            name: name.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_spec_and_composes_prompt() {
+        let root = std::env::temp_dir().join(format!("coco-agent-spec-{}", uuid::Uuid::new_v4()));
+        let agents_dir = root.join("agents");
+        fs::create_dir_all(agents_dir.join("qa")).unwrap();
+        fs::create_dir_all(agents_dir.join("empty")).unwrap();
+        fs::write(
+            agents_dir.join("qa").join(AGENT_SPEC_FILE_NAME),
+            "skills: [testing]\nsandbox: read-only\napprovalPolicy: never\nallowedTools: [shell]\noutputSchema: schemas/missing.json\n",
+        )
+        .unwrap();
+        fs::write(
+            agents_dir.join("qa").join(AGENT_PROMPT_FILE_NAME),
+            "You are QA.\n",
+        )
+        .unwrap();
+
+        assert_eq!(list_agent_spec_names(&agents_dir).unwrap(), vec!["qa"]);
+        assert!(matches!(
+            load_agent_spec(&agents_dir, "empty"),
+            Err(AgentSpecError::NotFound { .. })
+        ));
+
+        let spec = load_agent_spec(&agents_dir, "qa").unwrap();
+        assert_eq!(spec.file.sandbox, Some(SandboxMode::ReadOnly));
+        assert_eq!(spec.file.approval_policy, Some(ApprovalPolicy::Never));
+        assert_eq!(
+            spec.compose_prompt("run the suite"),
+            "You are QA.\n\n## Skills\n\n- testing\n\n## Allowed Tools\n\nOnly use these tools: shell.\n\n# Task\n\nrun the suite\n"
+        );
+
+        let issues = validate_agent_spec(&spec, &root);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("outputSchema not found"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
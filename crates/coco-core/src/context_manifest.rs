@@ -0,0 +1,707 @@
+use crate::evidence::EvidenceEntry;
+use crate::evidence::EvidenceSource;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Default budget for inline context (bytes of attachment content, headers not counted).
+pub const DEFAULT_CONTEXT_MAX_BYTES: usize = 64 * 1024;
+/// `attachmentId` recorded for the human notes entry in `context.json`.
+pub const HUMAN_NOTES_ATTACHMENT_ID: &str = "human-notes";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ContextManifestError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid {path}: {source}")]
+    Yaml {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+}
+
+/// `shared/context-manifest.yaml`: what each worker sees besides its prompt.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextManifest {
+    /// Inline budget per worker; defaults to [`DEFAULT_CONTEXT_MAX_BYTES`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+    #[serde(default)]
+    pub attachments: Vec<ContextAttachment>,
+}
+
+impl ContextManifest {
+    /// Load `path`; a missing or empty file is an empty manifest.
+    pub fn load(path: &Path) -> Result<Self, ContextManifestError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&content).map_err(|source| ContextManifestError::Yaml {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContextAttachmentKind {
+    /// `path`: one workspace file.
+    File,
+    /// `pattern`: workspace-relative glob, matches in path order.
+    Glob,
+    /// `url`: a local document (`file://` URL or workspace path).
+    Doc,
+    /// `evidenceId`: an entry of `shared/evidence/index.json`.
+    Evidence,
+    /// `path` + `range` (`start:end`, 1-based, inclusive).
+    Snippet,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContextDelivery {
+    /// Content goes into the prompt preamble and counts against the budget.
+    #[default]
+    Inline,
+    /// Content is copied to `agents/<instance>/context/<id>/`; the preamble only lists the path.
+    File,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextAttachment {
+    pub id: String,
+    pub kind: ContextAttachmentKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Agent instances or agent spec names allowed to see this attachment; empty = everyone.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    #[serde(default)]
+    pub delivery: ContextDelivery,
+    /// Per-attachment cap on inline bytes (on top of the manifest budget).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+}
+
+impl ContextAttachment {
+    pub fn visible_to(&self, agent_instance: &str, agent: &str) -> bool {
+        self.agents.is_empty()
+            || self
+                .agents
+                .iter()
+                .any(|a| a == agent_instance || a == agent)
+    }
+}
+
+/// Everything needed to resolve a manifest for one worker.
+#[derive(Debug, Clone, Copy)]
+pub struct ContextTarget<'a> {
+    pub workspace_root: &'a Path,
+    /// `agents/<instance>/context/`, where `delivery: file` content is copied.
+    pub attach_dir: &'a Path,
+    pub agent_instance: &'a str,
+    pub agent: &'a str,
+    pub evidence: &'a [EvidenceEntry],
+    /// `shared/human-notes.md`, placed first and exempt from the budget; empty to leave it out.
+    pub human_notes: &'a str,
+}
+
+/// One injected (or skipped) source. A glob yields one entry per matched file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectedContext {
+    pub attachment_id: String,
+    pub kind: ContextAttachmentKind,
+    /// Workspace-relative path, document path or `evidence:<id>`.
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+    pub delivery: ContextDelivery,
+    /// Bytes placed in the preamble (0 for `delivery: file`).
+    pub bytes: usize,
+    pub original_bytes: usize,
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attached_path: Option<PathBuf>,
+    /// Why nothing was injected (missing file, binary, budget exhausted, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+}
+
+/// `agents/<instance>/context.json`: the exact preamble a worker was given and where it came from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedContext {
+    pub resolved_at: DateTime<Utc>,
+    pub agent_instance: String,
+    pub agent: String,
+    pub max_bytes: usize,
+    pub used_bytes: usize,
+    pub entries: Vec<InjectedContext>,
+    /// Empty when nothing applies to this worker.
+    pub preamble: String,
+}
+
+/// Resolve the attachments visible to `target` into a prompt preamble.
+///
+/// Per-source problems never fail resolution; they are recorded as `skipped` so the worker still
+/// starts and the record shows what it did not get.
+pub fn resolve_context(manifest: &ContextManifest, target: ContextTarget<'_>) -> ResolvedContext {
+    let max_bytes = manifest.max_bytes.unwrap_or(DEFAULT_CONTEXT_MAX_BYTES);
+    let mut used_bytes = 0;
+    let mut entries = Vec::new();
+    let mut sections = Vec::new();
+
+    if !target.human_notes.trim().is_empty() {
+        sections.push(format!(
+            "## Human notes (shared/human-notes.md, highest priority)\n\n{}\n",
+            target.human_notes.trim_end()
+        ));
+        entries.push(InjectedContext {
+            attachment_id: HUMAN_NOTES_ATTACHMENT_ID.to_string(),
+            kind: ContextAttachmentKind::File,
+            source: "shared/human-notes.md".to_string(),
+            range: None,
+            delivery: ContextDelivery::Inline,
+            bytes: target.human_notes.len(),
+            original_bytes: target.human_notes.len(),
+            truncated: false,
+            attached_path: None,
+            skipped: None,
+        });
+    }
+
+    for attachment in manifest
+        .attachments
+        .iter()
+        .filter(|a| a.visible_to(target.agent_instance, target.agent))
+    {
+        for source in collect_sources(attachment, &target) {
+            let mut entry = InjectedContext {
+                attachment_id: attachment.id.clone(),
+                kind: attachment.kind,
+                source: source.label.clone(),
+                range: source.range.clone(),
+                delivery: attachment.delivery,
+                bytes: 0,
+                original_bytes: 0,
+                truncated: false,
+                attached_path: None,
+                skipped: None,
+            };
+            let content = match &source.content {
+                Ok(content) => content.clone(),
+                Err(reason) => {
+                    entry.skipped = Some(reason.clone());
+                    entries.push(entry);
+                    continue;
+                }
+            };
+            entry.original_bytes = content.len();
+
+            let mut section = format!("## {} ({})\n\n", attachment.id, source.heading());
+            if let Some(reason) = &attachment.reason {
+                section.push_str(&format!("Reason: {}\n\n", reason.trim()));
+            }
+            match attachment.delivery {
+                ContextDelivery::File => {
+                    let path = target
+                        .attach_dir
+                        .join(&attachment.id)
+                        .join(source.file_name());
+                    if let Err(err) = write_attached(&path, &content) {
+                        entry.skipped = Some(format!("cannot attach: {err}"));
+                        entries.push(entry);
+                        continue;
+                    }
+                    section.push_str(&format!("Attached file: `{}`\n", path.display()));
+                    entry.attached_path = Some(path);
+                }
+                ContextDelivery::Inline => {
+                    let remaining = max_bytes - used_bytes;
+                    let limit = attachment
+                        .max_bytes
+                        .map_or(remaining, |cap| cap.min(remaining));
+                    if limit == 0 {
+                        entry.skipped = Some("context budget exhausted".to_string());
+                        entries.push(entry);
+                        continue;
+                    }
+                    let kept = truncate_text(&content, limit);
+                    entry.bytes = kept.len();
+                    entry.truncated = kept.len() < content.len();
+                    used_bytes += kept.len();
+                    section.push_str("```\n");
+                    section.push_str(kept);
+                    if !kept.ends_with('\n') {
+                        section.push('\n');
+                    }
+                    section.push_str("```\n");
+                    if entry.truncated {
+                        section.push_str(&format!(
+                            "\n[truncated: {} of {} bytes omitted]\n",
+                            content.len() - kept.len(),
+                            content.len()
+                        ));
+                    }
+                }
+            }
+            sections.push(section);
+            entries.push(entry);
+        }
+    }
+
+    let skipped = entries
+        .iter()
+        .filter_map(|e| {
+            e.skipped
+                .as_ref()
+                .map(|reason| format!("- {} ({}): {}", e.attachment_id, e.source, reason))
+        })
+        .collect::<Vec<_>>();
+    if !skipped.is_empty() {
+        sections.push(format!("## Not included\n\n{}\n", skipped.join("\n")));
+    }
+
+    let preamble = if sections.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "# Context\n\nShared by the task's context manifest; treat it as read-only reference.\n\n{}",
+            sections.join("\n")
+        )
+    };
+
+    ResolvedContext {
+        resolved_at: Utc::now(),
+        agent_instance: target.agent_instance.to_string(),
+        agent: target.agent.to_string(),
+        max_bytes,
+        used_bytes,
+        entries,
+        preamble,
+    }
+}
+
+struct ContextSource {
+    label: String,
+    range: Option<String>,
+    content: Result<String, String>,
+}
+
+impl ContextSource {
+    fn heading(&self) -> String {
+        match &self.range {
+            Some(range) => format!("{} lines {}", self.label, range.replace(':', "-")),
+            None => self.label.clone(),
+        }
+    }
+
+    /// Flat file name under `context/<id>/`.
+    fn file_name(&self) -> String {
+        let name = self
+            .label
+            .trim_start_matches("./")
+            .replace(['/', '\\', ':'], "_");
+        if name.is_empty() {
+            "context.txt".to_string()
+        } else {
+            name
+        }
+    }
+}
+
+fn collect_sources(
+    attachment: &ContextAttachment,
+    target: &ContextTarget<'_>,
+) -> Vec<ContextSource> {
+    let missing = |field: &str| {
+        vec![ContextSource {
+            label: attachment.id.clone(),
+            range: None,
+            content: Err(format!("missing `{field}`")),
+        }]
+    };
+    match attachment.kind {
+        ContextAttachmentKind::File => match &attachment.path {
+            Some(path) => vec![file_source(target.workspace_root, path, None)],
+            None => missing("path"),
+        },
+        ContextAttachmentKind::Snippet => match (&attachment.path, &attachment.range) {
+            (Some(path), Some(range)) => {
+                vec![file_source(target.workspace_root, path, Some(range))]
+            }
+            (None, _) => missing("path"),
+            (_, None) => missing("range"),
+        },
+        ContextAttachmentKind::Doc => match &attachment.url {
+            Some(url) => vec![doc_source(target.workspace_root, url)],
+            None => missing("url"),
+        },
+        ContextAttachmentKind::Glob => match &attachment.pattern {
+            Some(pattern) => glob_sources(target.workspace_root, pattern),
+            None => missing("pattern"),
+        },
+        ContextAttachmentKind::Evidence => match &attachment.evidence_id {
+            Some(id) => vec![evidence_source(target.evidence, id)],
+            None => missing("evidenceId"),
+        },
+    }
+}
+
+fn file_source(workspace_root: &Path, rel: &str, range: Option<&String>) -> ContextSource {
+    let content = workspace_file(workspace_root, rel)
+        .and_then(|path| read_text(&path))
+        .and_then(|text| match range {
+            Some(range) => slice_lines(&text, range),
+            None => Ok(text),
+        });
+    ContextSource {
+        label: rel.to_string(),
+        range: range.cloned(),
+        content,
+    }
+}
+
+fn doc_source(workspace_root: &Path, url: &str) -> ContextSource {
+    let content = if url.contains("://") {
+        Err("only workspace documents are supported".to_string())
+    } else {
+        workspace_file(workspace_root, url).and_then(|path| read_text(&path))
+    };
+    ContextSource {
+        label: url.to_string(),
+        range: None,
+        content,
+    }
+}
+
+fn glob_sources(workspace_root: &Path, pattern: &str) -> Vec<ContextSource> {
+    if !is_relative_inside(Path::new(pattern)) {
+        return vec![ContextSource {
+            label: pattern.to_string(),
+            range: None,
+            content: Err(OUTSIDE_WORKSPACE.to_string()),
+        }];
+    }
+    let full = workspace_root.join(pattern);
+    let paths = match glob::glob(&full.to_string_lossy()) {
+        Ok(paths) => paths.filter_map(Result::ok).filter(|p| p.is_file()),
+        Err(err) => {
+            return vec![ContextSource {
+                label: pattern.to_string(),
+                range: None,
+                content: Err(format!("invalid pattern: {err}")),
+            }]
+        }
+    };
+    let sources = paths
+        .map(|path| {
+            let label = path
+                .strip_prefix(workspace_root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            ContextSource {
+                label: label.clone(),
+                range: None,
+                content: workspace_file(workspace_root, &label).and_then(|path| read_text(&path)),
+            }
+        })
+        .collect::<Vec<_>>();
+    if sources.is_empty() {
+        return vec![ContextSource {
+            label: pattern.to_string(),
+            range: None,
+            content: Err("no files matched".to_string()),
+        }];
+    }
+    sources
+}
+
+const OUTSIDE_WORKSPACE: &str = "path must stay inside the workspace";
+
+/// Attachments are inlined into worker prompts, so they may only name files inside the
+/// workspace: absolute paths, `..` and symlinks that lead out of it are refused.
+fn workspace_file(workspace_root: &Path, rel: &str) -> Result<PathBuf, String> {
+    let rel_path = Path::new(rel);
+    if !is_relative_inside(rel_path) {
+        return Err(OUTSIDE_WORKSPACE.to_string());
+    }
+    let root = fs::canonicalize(workspace_root)
+        .map_err(|err| format!("cannot read {}: {err}", workspace_root.display()))?;
+    let path =
+        fs::canonicalize(root.join(rel_path)).map_err(|err| format!("cannot read {rel}: {err}"))?;
+    if path.starts_with(&root) {
+        Ok(path)
+    } else {
+        Err(OUTSIDE_WORKSPACE.to_string())
+    }
+}
+
+fn is_relative_inside(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+fn evidence_source(evidence: &[EvidenceEntry], id: &str) -> ContextSource {
+    let content = match evidence.iter().find(|e| e.id == id) {
+        Some(entry) => Ok(render_evidence(entry)),
+        None => Err("not in shared/evidence/index.json".to_string()),
+    };
+    ContextSource {
+        label: format!("evidence:{id}"),
+        range: None,
+        content,
+    }
+}
+
+fn render_evidence(entry: &EvidenceEntry) -> String {
+    let mut out = format!(
+        "{} [{}]\n{}\n",
+        entry.title.trim(),
+        entry.kind,
+        entry.summary.trim()
+    );
+    for source in &entry.sources {
+        let line = match source {
+            EvidenceSource::FileAnchor {
+                path,
+                start_line,
+                end_line,
+            } => format!("file {path}:{start_line}-{end_line}"),
+            EvidenceSource::CommandExecution {
+                command, exit_code, ..
+            } => match exit_code {
+                Some(code) => format!("command `{command}` (exit {code})"),
+                None => format!("command `{command}`"),
+            },
+            EvidenceSource::RuntimeEventRange { events_ref, .. } => format!("events {events_ref}"),
+        };
+        out.push_str(&format!("- {line}\n"));
+    }
+    out
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+    if bytes.contains(&0) {
+        return Err("binary file".to_string());
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// `start:end`, 1-based and inclusive; `end` past the file is clamped.
+fn slice_lines(text: &str, range: &str) -> Result<String, String> {
+    let parsed = range.split_once(':').and_then(|(s, e)| {
+        Some((
+            s.trim().parse::<usize>().ok()?,
+            e.trim().parse::<usize>().ok()?,
+        ))
+    });
+    let Some((start, end)) = parsed.filter(|(s, e)| *s >= 1 && s <= e) else {
+        return Err(format!("invalid range `{range}` (expected start:end)"));
+    };
+    let lines = text
+        .lines()
+        .skip(start - 1)
+        .take(end - start + 1)
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        return Err(format!("range `{range}` is past the end of the file"));
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Longest prefix within `limit` bytes, cut at the last line break when there is one.
+fn truncate_text(text: &str, limit: usize) -> &str {
+    if text.len() <= limit {
+        return text;
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    match text[..end].rfind('\n') {
+        Some(newline) if newline > 0 => &text[..=newline],
+        _ => &text[..end],
+    }
+}
+
+fn write_attached(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_visible_attachments_within_budget() {
+        let nanos = Utc::now().timestamp_nanos_opt().unwrap();
+        let root = std::env::temp_dir().join(format!("coco-context-{nanos}"));
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs").join("a.md"), "alpha\n").unwrap();
+        fs::write(root.join("docs").join("b.md"), "beta\n").unwrap();
+        fs::write(root.join("src.rs"), "l1\nl2\nl3\nl4\n").unwrap();
+        fs::write(root.join("big.txt"), "0123456789\n".repeat(10)).unwrap();
+
+        let manifest: ContextManifest = serde_yaml::from_str(
+            r#"
+maxBytes: 60
+attachments:
+  - id: docs
+    kind: glob
+    pattern: "docs/*.md"
+  - id: snip
+    kind: snippet
+    path: src.rs
+    range: "2:3"
+    agents: [backend]
+  - id: hidden
+    kind: file
+    path: src.rs
+    agents: [frontend]
+  - id: ev
+    kind: evidence
+    evidenceId: missing
+  - id: big
+    kind: file
+    path: big.txt
+  - id: copy
+    kind: file
+    path: big.txt
+    delivery: file
+"#,
+        )
+        .unwrap();
+        let attach_dir = root.join("context");
+        let resolved = resolve_context(
+            &manifest,
+            ContextTarget {
+                workspace_root: &root,
+                attach_dir: &attach_dir,
+                agent_instance: "be-1",
+                agent: "backend",
+                evidence: &[],
+                human_notes: "",
+            },
+        );
+
+        let sources = resolved
+            .entries
+            .iter()
+            .map(|e| (e.attachment_id.as_str(), e.source.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            vec![
+                ("docs", "docs/a.md"),
+                ("docs", "docs/b.md"),
+                ("snip", "src.rs"),
+                ("ev", "evidence:missing"),
+                ("big", "big.txt"),
+                ("copy", "big.txt"),
+            ]
+        );
+        assert!(resolved.preamble.contains("## snip (src.rs lines 2-3)"));
+        assert!(resolved.preamble.contains("```\nl2\nl3\n```"));
+        assert!(resolved.entries[3].skipped.is_some());
+
+        let big = &resolved.entries[4];
+        assert!(big.truncated);
+        // 43 bytes were left; the cut falls back to the last full line.
+        assert_eq!(big.bytes, 33);
+        assert_eq!(resolved.used_bytes, 6 + 5 + 6 + 33);
+        assert!(resolved
+            .preamble
+            .contains("[truncated: 77 of 110 bytes omitted]"));
+
+        let copy = &resolved.entries[5];
+        assert_eq!(copy.bytes, 0);
+        assert_eq!(
+            fs::read_to_string(copy.attached_path.as_ref().unwrap()).unwrap(),
+            "0123456789\n".repeat(10)
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn refuses_attachments_outside_the_workspace() {
+        let nanos = Utc::now().timestamp_nanos_opt().unwrap();
+        let base = std::env::temp_dir().join(format!("coco-context-escape-{nanos}"));
+        let root = base.join("workspace");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(base.join("secret.txt"), "secret\n").unwrap();
+        fs::write(root.join("ok.md"), "ok\n").unwrap();
+        let secret = base.join("secret.txt").to_string_lossy().to_string();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(base.join("secret.txt"), root.join("link.txt")).unwrap();
+
+        let manifest: ContextManifest = serde_yaml::from_str(&format!(
+            r#"
+attachments:
+  - {{ id: parent, kind: file, path: "../secret.txt" }}
+  - {{ id: absolute, kind: file, path: "{secret}" }}
+  - {{ id: url, kind: doc, url: "file://{secret}" }}
+  - {{ id: doc-parent, kind: doc, url: "../secret.txt" }}
+  - {{ id: glob-parent, kind: glob, pattern: "../*.txt" }}
+  - {{ id: link, kind: file, path: "link.txt" }}
+  - {{ id: ok, kind: doc, url: "ok.md" }}
+"#
+        ))
+        .unwrap();
+        let resolved = resolve_context(
+            &manifest,
+            ContextTarget {
+                workspace_root: &root,
+                attach_dir: &root.join("context"),
+                agent_instance: "be-1",
+                agent: "backend",
+                evidence: &[],
+                human_notes: "",
+            },
+        );
+
+        assert!(!resolved.preamble.contains("secret\n"));
+        for entry in &resolved.entries {
+            if entry.attachment_id == "ok" {
+                assert!(entry.skipped.is_none());
+            } else {
+                assert!(entry.skipped.is_some(), "{}", entry.attachment_id);
+            }
+        }
+
+        let _ = fs::remove_dir_all(base);
+    }
+}
@@ -0,0 +1,421 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Token prefix used by reports/decisions to cite an evidence entry: `evidence:<id>`.
+pub const EVIDENCE_TOKEN_PREFIX: &str = "evidence:";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvidenceEntry {
+    pub id: String,
+    pub kind: String,
+    pub title: String,
+    pub summary: String,
+    pub created_at: String,
+    pub sources: Vec<EvidenceSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifact_refs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum EvidenceSource {
+    FileAnchor {
+        path: String,
+        start_line: u32,
+        end_line: u32,
+    },
+    CommandExecution {
+        command: String,
+        cwd: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stdout_ref: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stderr_ref: Option<String>,
+    },
+    RuntimeEventRange {
+        events_ref: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        start_line: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        end_line: Option<u32>,
+    },
+}
+
+/// One `evidence:<id>` occurrence inside a Markdown file (1-based line/column).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvidenceReference {
+    pub id: String,
+    /// Task-directory relative path of the file containing the token (e.g. `shared/reports/x.md`).
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvidenceCheckReport {
+    pub scanned_files: Vec<String>,
+    pub references: Vec<EvidenceReference>,
+    /// References whose id is not present in `shared/evidence/index.json`.
+    pub dangling: Vec<EvidenceReference>,
+    /// Index ids that no scanned Markdown file cites.
+    pub unused: Vec<String>,
+    /// Ids that appear more than once in the index.
+    pub duplicates: Vec<String>,
+}
+
+impl EvidenceCheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.dangling.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+/// A clickable target for a single evidence source, resolved to an absolute path on disk.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum EvidenceLink {
+    FileRange {
+        path: PathBuf,
+        start_line: u32,
+        end_line: u32,
+    },
+    CommandOutput {
+        command: String,
+        cwd: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stdout_path: Option<PathBuf>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stderr_path: Option<PathBuf>,
+    },
+    EventRange {
+        path: PathBuf,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        start_line: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        end_line: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedEvidence {
+    pub entry: EvidenceEntry,
+    pub links: Vec<EvidenceLink>,
+}
+
+/// Scan a Markdown document for `evidence:<id>` tokens.
+///
+/// Ids use the same character set as evidence ids (`[A-Za-z0-9_.-]`); trailing `.` is treated as
+/// sentence punctuation. The prefix must not be glued to a preceding word (`myevidence:x`).
+pub fn scan_evidence_tokens(file: &str, text: &str) -> Vec<EvidenceReference> {
+    let mut out = Vec::new();
+    for (line_idx, line) in text.lines().enumerate() {
+        let mut search_from = 0;
+        while let Some(found) = line[search_from..].find(EVIDENCE_TOKEN_PREFIX) {
+            let start = search_from + found;
+            let id_start = start + EVIDENCE_TOKEN_PREFIX.len();
+            search_from = id_start;
+
+            let glued = line[..start]
+                .chars()
+                .next_back()
+                .is_some_and(is_evidence_id_char);
+            if glued {
+                continue;
+            }
+
+            let id_len = line[id_start..]
+                .find(|c: char| !is_evidence_id_char(c))
+                .unwrap_or(line.len() - id_start);
+            let id = line[id_start..id_start + id_len].trim_end_matches('.');
+            if id.is_empty() {
+                continue;
+            }
+
+            out.push(EvidenceReference {
+                id: id.to_string(),
+                file: file.to_string(),
+                line: (line_idx + 1) as u32,
+                column: (line[..start].chars().count() + 1) as u32,
+            });
+        }
+    }
+    out
+}
+
+/// Ids must survive a round-trip through `evidence:<id>` tokens (see `scan_evidence_tokens`).
+pub fn is_valid_evidence_id(id: &str) -> bool {
+    !id.is_empty() && !id.ends_with('.') && id.chars().all(is_evidence_id_char)
+}
+
+fn is_evidence_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'
+}
+
+pub fn read_evidence_index(path: &Path) -> io::Result<Vec<EvidenceEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&content).map_err(io::Error::from)
+}
+
+/// Cross-check every `evidence:<id>` token in the task's shared Markdown files
+/// (reports, decisions, contracts, human-notes.md, state-board.md, ...) against the index.
+pub fn check_evidence_references(
+    task_dir: &Path,
+    index: &[EvidenceEntry],
+) -> io::Result<EvidenceCheckReport> {
+    let shared_dir = task_dir.join("shared");
+    let mut files = Vec::new();
+    collect_markdown_files(&shared_dir, &mut files)?;
+    files.sort();
+
+    let mut report = EvidenceCheckReport::default();
+    for path in files {
+        let rel = path
+            .strip_prefix(task_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let text = fs::read_to_string(&path)?;
+        report.references.extend(scan_evidence_tokens(&rel, &text));
+        report.scanned_files.push(rel);
+    }
+
+    let mut known = HashSet::new();
+    let mut duplicates = BTreeSet::new();
+    for entry in index {
+        if !known.insert(entry.id.as_str()) {
+            duplicates.insert(entry.id.clone());
+        }
+    }
+    report.duplicates = duplicates.into_iter().collect();
+
+    let cited = report
+        .references
+        .iter()
+        .map(|r| r.id.as_str())
+        .collect::<HashSet<_>>();
+    report.dangling = report
+        .references
+        .iter()
+        .filter(|r| !known.contains(r.id.as_str()))
+        .cloned()
+        .collect();
+    report.unused = index
+        .iter()
+        .filter(|e| !cited.contains(e.id.as_str()))
+        .map(|e| e.id.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Ok(report)
+}
+
+fn collect_markdown_files(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_markdown_files(&path, out)?;
+        } else if file_type.is_file() && path.extension().is_some_and(|ext| ext == "md") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the sources of an evidence entry into absolute link targets.
+///
+/// `fileAnchor.path` is workspace-relative; `stdoutRef`/`stderrRef`/`eventsRef` are
+/// task-directory relative (e.g. `./agents/<instance>/runtime/events.jsonl`). A path that is
+/// absolute or climbs out with `..` is rejected (`InvalidInput`).
+pub fn resolve_evidence_links(
+    entry: &EvidenceEntry,
+    workspace_root: &Path,
+    task_dir: &Path,
+) -> io::Result<Vec<EvidenceLink>> {
+    entry
+        .sources
+        .iter()
+        .map(|source| -> io::Result<EvidenceLink> {
+            Ok(match source {
+                EvidenceSource::FileAnchor {
+                    path,
+                    start_line,
+                    end_line,
+                } => EvidenceLink::FileRange {
+                    path: join_relative(workspace_root, path)?,
+                    start_line: *start_line,
+                    end_line: *end_line,
+                },
+                EvidenceSource::CommandExecution {
+                    command,
+                    cwd,
+                    exit_code,
+                    stdout_ref,
+                    stderr_ref,
+                } => EvidenceLink::CommandOutput {
+                    command: command.clone(),
+                    cwd: cwd.clone(),
+                    exit_code: *exit_code,
+                    stdout_path: stdout_ref
+                        .as_deref()
+                        .map(|r| join_relative(task_dir, r))
+                        .transpose()?,
+                    stderr_path: stderr_ref
+                        .as_deref()
+                        .map(|r| join_relative(task_dir, r))
+                        .transpose()?,
+                },
+                EvidenceSource::RuntimeEventRange {
+                    events_ref,
+                    start_line,
+                    end_line,
+                } => EvidenceLink::EventRange {
+                    path: join_relative(task_dir, events_ref)?,
+                    start_line: *start_line,
+                    end_line: *end_line,
+                },
+            })
+        })
+        .collect()
+}
+
+/// `base.join(rel)`, refusing anything but plain relative components so the result stays
+/// under `base`.
+fn join_relative(base: &Path, rel: &str) -> io::Result<PathBuf> {
+    let rel = Path::new(rel);
+    if !rel
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("evidence path must stay under its base: {}", rel.display()),
+        ));
+    }
+    Ok(base.join(
+        rel.components()
+            .filter(|c| *c != Component::CurDir)
+            .collect::<PathBuf>(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str) -> EvidenceEntry {
+        EvidenceEntry {
+            id: id.to_string(),
+            kind: "runtime-event-range".to_string(),
+            title: id.to_string(),
+            summary: String::new(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            sources: vec![EvidenceSource::RuntimeEventRange {
+                events_ref: "./agents/w1/runtime/events.jsonl".to_string(),
+                start_line: Some(3),
+                end_line: None,
+            }],
+            artifact_refs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn scan_finds_tokens_and_skips_placeholders() {
+        let text =
+            "See evidence:worker-w1.\n`evidence:<id>` and myevidence:nope, (evidence:cmd_2)\n";
+        let refs = scan_evidence_tokens("shared/reports/a.md", text);
+        let ids = refs.iter().map(|r| r.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["worker-w1", "cmd_2"]);
+        assert_eq!(refs[0].line, 1);
+        assert_eq!(refs[0].column, 5);
+        assert_eq!(refs[1].line, 2);
+    }
+
+    #[test]
+    fn check_reports_dangling_unused_and_links() {
+        let task_dir = std::env::temp_dir().join(format!(
+            "coco-evidence-test-{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let reports_dir = task_dir.join("shared").join("reports");
+        fs::create_dir_all(&reports_dir).unwrap();
+        fs::write(
+            reports_dir.join("joined-summary.md"),
+            "- evidence:worker-w1\n- evidence:missing\n",
+        )
+        .unwrap();
+        fs::write(
+            task_dir.join("shared").join("human-notes.md"),
+            "cite evidence:worker-w1\n",
+        )
+        .unwrap();
+
+        let index = vec![entry("worker-w1"), entry("worker-w2")];
+        let report = check_evidence_references(&task_dir, &index).unwrap();
+        assert_eq!(report.scanned_files.len(), 2);
+        assert_eq!(report.references.len(), 3);
+        assert_eq!(report.dangling.len(), 1);
+        assert_eq!(report.dangling[0].id, "missing");
+        assert_eq!(report.dangling[0].file, "shared/reports/joined-summary.md");
+        assert_eq!(report.unused, vec!["worker-w2".to_string()]);
+        assert!(!report.is_ok());
+
+        let links = resolve_evidence_links(&index[0], Path::new("/ws"), &task_dir).unwrap();
+        assert_eq!(
+            links,
+            vec![EvidenceLink::EventRange {
+                path: task_dir.join("agents/w1/runtime/events.jsonl"),
+                start_line: Some(3),
+                end_line: None,
+            }]
+        );
+
+        for bad in ["../../etc/passwd", "/etc/passwd", "agents/../../../x"] {
+            let mut escaping = entry("escaping");
+            escaping.sources.push(EvidenceSource::CommandExecution {
+                command: "cat".to_string(),
+                cwd: ".".to_string(),
+                exit_code: None,
+                stdout_ref: Some(bad.to_string()),
+                stderr_ref: None,
+            });
+            let err = resolve_evidence_links(&escaping, Path::new("/ws"), &task_dir).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{bad}");
+        }
+
+        fs::remove_dir_all(task_dir).unwrap();
+    }
+}
@@ -1,2 +1,8 @@
+pub mod agent_spec;
+pub mod context_manifest;
+pub mod evidence;
+pub mod prompt_library;
 pub mod task;
 pub mod task_store;
+pub mod template;
+pub mod usage;
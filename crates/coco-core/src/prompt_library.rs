@@ -0,0 +1,480 @@
+//! Custom prompt library shared by chat turns and orchestrator subtasks.
+//!
+//! Prompts are `<name>.md` files discovered in (highest precedence first):
+//!
+//! - `<workspace>/.coco/prompts/`
+//! - `<workspace>/.codex/prompts/`
+//! - `$CODEX_HOME/prompts/` (default `~/.codex/prompts/`)
+//!
+//! A prompt may start with `description:` / `argument-hint:` frontmatter. Rendering substitutes
+//! `$1`..`$9`, `$ARGUMENTS` (all positional arguments) and `$NAME` (from `NAME=value`
+//! arguments) in the prompt's own body, where `$$` is a literal `$` and a placeholder without a
+//! value is an error; `{{> relative/path}}` includes (relative to the including file, confined
+//! to the prompt directory or the workspace) are pasted in verbatim, so their `$HOME` or `$1`
+//! stay as written.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use thiserror::Error;
+
+pub const PROMPTS_DIR_NAME: &str = "prompts";
+
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum PromptError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("prompt not found: {name}")]
+    NotFound { name: String },
+    #[error("missing argument ${name} (pass {name}=value)")]
+    MissingArgument { name: String },
+    #[error("missing positional argument ${index}")]
+    MissingPositional { index: usize },
+    #[error("unterminated quote in prompt arguments")]
+    UnterminatedQuote,
+    #[error("cannot include {path}: {message}")]
+    Include { path: String, message: String },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptScope {
+    /// `<workspace>/.coco/prompts`
+    Workspace,
+    /// `<workspace>/.codex/prompts`
+    WorkspaceCodex,
+    /// `$CODEX_HOME/prompts`
+    User,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptEntry {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+    pub path: PathBuf,
+    pub scope: PromptScope,
+    /// Lower-precedence prompts with the same name.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shadows: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedPrompt {
+    pub name: String,
+    pub path: PathBuf,
+    pub scope: PromptScope,
+    pub text: String,
+    /// Files pulled in through `{{> ...}}`, in include order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<PathBuf>,
+}
+
+/// Arguments as typed after a prompt name: shell-style words, where `NAME=value` words fill
+/// named placeholders and the rest are positional.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptArgs {
+    pub positional: Vec<String>,
+    pub named: BTreeMap<String, String>,
+}
+
+impl PromptArgs {
+    pub fn parse(raw: &str) -> Result<Self, PromptError> {
+        let mut args = PromptArgs::default();
+        for word in split_words(raw)? {
+            match word.split_once('=') {
+                Some((key, value)) if is_placeholder_name(key) => {
+                    args.named.insert(key.to_string(), value.to_string());
+                }
+                _ => args.positional.push(word),
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn split_words(raw: &str) -> Result<Vec<String>, PromptError> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => match chars.next() {
+                Some(next @ ('"' | '\\')) => current.push(next),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => current.push('\\'),
+            },
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(PromptError::UnterminatedQuote);
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Splits `description` / `argument-hint` out of leading `---` frontmatter and returns the body.
+fn split_frontmatter(content: &str) -> (Option<String>, Option<String>, &str) {
+    let trimmed = content.trim_start();
+    let Some(after_start) = trimmed.strip_prefix("---") else {
+        return (None, None, content);
+    };
+    let Some(end) = after_start.find("\n---") else {
+        return (None, None, content);
+    };
+    let frontmatter = &after_start[..end];
+    let rest = &after_start[end + "\n---".len()..];
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+
+    let unquote = |v: &str| v.trim().trim_matches('"').trim_matches('\'').to_string();
+    let mut description = None;
+    let mut argument_hint = None;
+    for line in frontmatter.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("description:") {
+            description = Some(unquote(rest));
+        } else if let Some(rest) = line.strip_prefix("argument-hint:") {
+            argument_hint = Some(unquote(rest));
+        }
+    }
+    (description, argument_hint, body)
+}
+
+pub struct PromptLibrary {
+    workspace_root: Option<PathBuf>,
+    /// Highest precedence first.
+    sources: Vec<(PromptScope, PathBuf)>,
+}
+
+impl PromptLibrary {
+    pub fn new(workspace_root: Option<&Path>, user_prompts_dir: Option<PathBuf>) -> Self {
+        let mut sources = Vec::new();
+        if let Some(root) = workspace_root {
+            sources.push((
+                PromptScope::Workspace,
+                root.join(".coco").join(PROMPTS_DIR_NAME),
+            ));
+            sources.push((
+                PromptScope::WorkspaceCodex,
+                root.join(".codex").join(PROMPTS_DIR_NAME),
+            ));
+        }
+        if let Some(dir) = user_prompts_dir {
+            sources.push((PromptScope::User, dir));
+        }
+        Self {
+            workspace_root: workspace_root.map(Path::to_path_buf),
+            sources,
+        }
+    }
+
+    /// Workspace prompts plus the user's `$CODEX_HOME/prompts` (or `~/.codex/prompts`).
+    pub fn discover(workspace_root: Option<&Path>) -> Self {
+        Self::new(workspace_root, user_prompts_dir())
+    }
+
+    /// One entry per name (the highest-precedence file wins), sorted by name.
+    pub fn list(&self) -> Vec<PromptEntry> {
+        let mut by_name: HashMap<String, PromptEntry> = HashMap::new();
+        for (scope, dir) in &self.sources {
+            for path in prompt_files(dir) {
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if let Some(winner) = by_name.get_mut(name) {
+                    winner.shadows.push(path);
+                    continue;
+                }
+                let Ok(content) = fs::read_to_string(&path) else {
+                    continue;
+                };
+                let (description, argument_hint, _) = split_frontmatter(&content);
+                by_name.insert(
+                    name.to_string(),
+                    PromptEntry {
+                        name: name.to_string(),
+                        description,
+                        argument_hint,
+                        path,
+                        scope: *scope,
+                        shadows: Vec::new(),
+                    },
+                );
+            }
+        }
+        let mut prompts = by_name.into_values().collect::<Vec<_>>();
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts
+    }
+
+    pub fn find(&self, name: &str) -> Result<PromptEntry, PromptError> {
+        self.list()
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| PromptError::NotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Expands includes and placeholders of prompt `name`; `args` is parsed with
+    /// [`PromptArgs::parse`].
+    pub fn render(&self, name: &str, args: &str) -> Result<RenderedPrompt, PromptError> {
+        let entry = self.find(name)?;
+        let args = PromptArgs::parse(args)?;
+        let source_dir = self
+            .sources
+            .iter()
+            .find(|(scope, _)| *scope == entry.scope)
+            .map(|(_, dir)| dir.clone());
+        let roots = source_dir
+            .iter()
+            .chain(self.workspace_root.iter())
+            .filter_map(|dir| fs::canonicalize(dir).ok())
+            .collect::<Vec<_>>();
+
+        let mut includes = Vec::new();
+        let mut stack = Vec::new();
+        let text = expand_includes(&entry.path, &roots, Some(&args), &mut stack, &mut includes)?;
+        Ok(RenderedPrompt {
+            name: entry.name,
+            path: entry.path,
+            scope: entry.scope,
+            text: text.trim().to_string(),
+            includes,
+        })
+    }
+}
+
+pub fn user_prompts_dir() -> Option<PathBuf> {
+    let codex_home = match std::env::var_os("CODEX_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            PathBuf::from(std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?)
+                .join(".codex")
+        }
+    };
+    Some(codex_home.join(PROMPTS_DIR_NAME))
+}
+
+fn prompt_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files = read_dir
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// Expands the includes of `path`. Only the text of the file itself goes through `substitute`
+/// (when `args` is given); included files are pasted in as written.
+fn expand_includes(
+    path: &Path,
+    roots: &[PathBuf],
+    args: Option<&PromptArgs>,
+    stack: &mut Vec<PathBuf>,
+    includes: &mut Vec<PathBuf>,
+) -> Result<String, PromptError> {
+    let content = fs::read_to_string(path)?;
+    let (_, _, body) = split_frontmatter(&content);
+    let base = path.parent().unwrap_or(Path::new("."));
+    let canonical = fs::canonicalize(path)?;
+    stack.push(canonical);
+
+    let own_text = |text: &str| match args {
+        Some(args) => substitute(text, args),
+        None => Ok(text.to_string()),
+    };
+    let mut out = String::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{>") {
+        out.push_str(&own_text(&rest[..start])?);
+        let after = &rest[start + 3..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&own_text(&rest[start..])?);
+            rest = "";
+            break;
+        };
+        let target = after[..end].trim();
+        rest = &after[end + 2..];
+
+        let include_error = |message: &str| PromptError::Include {
+            path: target.to_string(),
+            message: message.to_string(),
+        };
+        if stack.len() > MAX_INCLUDE_DEPTH {
+            return Err(include_error("includes are nested too deeply"));
+        }
+        let resolved =
+            fs::canonicalize(base.join(target)).map_err(|e| include_error(&e.to_string()))?;
+        if !roots.iter().any(|root| resolved.starts_with(root)) {
+            return Err(include_error(
+                "outside the prompt directory and the workspace",
+            ));
+        }
+        if stack.contains(&resolved) {
+            return Err(include_error("include cycle"));
+        }
+        includes.push(resolved.clone());
+        let included = expand_includes(&resolved, roots, None, stack, includes)?;
+        out.push_str(included.trim_end_matches('\n'));
+    }
+    out.push_str(&own_text(rest)?);
+    stack.pop();
+    Ok(out)
+}
+
+fn substitute(text: &str, args: &PromptArgs) -> Result<String, PromptError> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(tail) = after.strip_prefix('$') {
+            out.push('$');
+            rest = tail;
+            continue;
+        }
+        if let Some(digit) = after.chars().next().filter(|c| ('1'..='9').contains(c)) {
+            let index = digit as usize - '1' as usize;
+            let value = args
+                .positional
+                .get(index)
+                .ok_or(PromptError::MissingPositional { index: index + 1 })?;
+            out.push_str(value);
+            rest = &after[1..];
+            continue;
+        }
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+            .unwrap_or(after.len());
+        let name = &after[..name_len];
+        if !is_placeholder_name(name) {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        if name == "ARGUMENTS" {
+            out.push_str(&args.positional.join(" "));
+        } else {
+            let value = args
+                .named
+                .get(name)
+                .ok_or_else(|| PromptError::MissingArgument {
+                    name: name.to_string(),
+                })?;
+            out.push_str(value);
+        }
+        rest = &after[name_len..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_prompts_win_and_render_arguments_and_includes() {
+        let root = std::env::temp_dir().join(format!("coco-prompts-{}", uuid::Uuid::new_v4()));
+        let user = root.join("home").join(PROMPTS_DIR_NAME);
+        let coco = root.join(".coco").join(PROMPTS_DIR_NAME);
+        fs::create_dir_all(&user).unwrap();
+        fs::create_dir_all(&coco).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(user.join("review.md"), "user review\n").unwrap();
+        fs::write(user.join("other.md"), "other\n").unwrap();
+        fs::write(
+            root.join("docs").join("style.md"),
+            "Follow the style guide; run `ls $HOME` and pass $1 through.\n",
+        )
+        .unwrap();
+        fs::write(
+            coco.join("review.md"),
+            "---\ndescription: \"Review a change\"\nargument-hint: FILE=<path> [focus]\n---\nReview $FILE for $1 ($ARGUMENTS), costs $$5.\n{{> ../../docs/style.md}}\nThen $2.\n",
+        )
+        .unwrap();
+        fs::write(coco.join("escape.md"), "{{> ../../../outside.md}}\n").unwrap();
+
+        let library = PromptLibrary::new(Some(&root), Some(user.clone()));
+        let prompts = library.list();
+        assert_eq!(
+            prompts.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            vec!["escape", "other", "review"]
+        );
+        let review = &prompts[2];
+        assert_eq!(review.scope, PromptScope::Workspace);
+        assert_eq!(review.description.as_deref(), Some("Review a change"));
+        assert_eq!(review.shadows, vec![user.join("review.md")]);
+
+        let rendered = library
+            .render("review", "FILE=src/lib.rs \"error handling\" tests")
+            .unwrap();
+        assert_eq!(
+            rendered.text,
+            "Review src/lib.rs for error handling (error handling tests), costs $5.\nFollow the style guide; run `ls $HOME` and pass $1 through.\nThen tests."
+        );
+        assert_eq!(rendered.includes.len(), 1);
+
+        assert!(matches!(
+            library.render("review", "focus"),
+            Err(PromptError::MissingArgument { name }) if name == "FILE"
+        ));
+        assert!(matches!(
+            library.render("review", "FILE=a focus"),
+            Err(PromptError::MissingPositional { index: 2 })
+        ));
+        assert!(matches!(
+            library.render("escape", ""),
+            Err(PromptError::Include { .. })
+        ));
+        assert!(matches!(
+            library.render("missing", ""),
+            Err(PromptError::NotFound { .. })
+        ));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,7 +1,9 @@
+use crate::usage::TokenUsage;
 use chrono::DateTime;
 use chrono::Utc;
 use serde::Deserialize;
 use serde::Serialize;
+use std::time::Duration;
 
 fn now() -> DateTime<Utc> {
     Utc::now()
@@ -106,12 +108,22 @@ pub struct Gate {
     pub resolved_by: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskConfig {
     pub max_concurrent_agents: u32,
     pub timeout_seconds: u32,
     pub auto_approve: bool,
+    /// Wall-clock limit for each worker (seconds), enforced during reconcile. None means
+    /// unlimited; subtasks may override it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_timeout_seconds: Option<u32>,
+    /// Default retry policy for failed workers. None disables retries; subtasks may override it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+    /// Task-wide limits enforced while the controller monitors workers. None means unlimited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<TaskBudget>,
 }
 
 impl Default for TaskConfig {
@@ -120,12 +132,146 @@ impl Default for TaskConfig {
             max_concurrent_agents: 3,
             timeout_seconds: 3600,
             auto_approve: false,
+            worker_timeout_seconds: None,
+            retry: None,
+            budget: None,
         }
     }
 }
 
+/// Why a worker attempt failed, as far as retries are concerned.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum RetryClass {
+    /// The worker process could not be started.
+    SpawnError,
+    /// The worker ran past its wall-clock deadline.
+    Timeout,
+    /// `final.json` is not valid worker output.
+    InvalidOutput,
+    /// The model provider rate-limited the worker (seen in `stderr.log` / error events).
+    RateLimit,
+    /// The process exited without writing `final.json`.
+    Crash,
+}
+
+impl RetryClass {
+    pub const ALL: [RetryClass; 5] = [
+        RetryClass::SpawnError,
+        RetryClass::Timeout,
+        RetryClass::InvalidOutput,
+        RetryClass::RateLimit,
+        RetryClass::Crash,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetryClass::SpawnError => "spawn-error",
+            RetryClass::Timeout => "timeout",
+            RetryClass::InvalidOutput => "invalid-output",
+            RetryClass::RateLimit => "rate-limit",
+            RetryClass::Crash => "crash",
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_backoff_seconds() -> u32 {
+    5
+}
+
+fn default_retry_on() -> Vec<RetryClass> {
+    RetryClass::ALL.to_vec()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the second attempt; doubles for every further attempt.
+    #[serde(default = "default_backoff_seconds")]
+    pub backoff_seconds: u32,
+    /// Failure classes that are retried. Failures the worker reports itself
+    /// (`status: failed`) are never retried.
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<RetryClass>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_seconds: default_backoff_seconds(),
+            retry_on: default_retry_on(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether attempt `attempt` (1-based) failing with `class` earns another attempt.
+    pub fn should_retry(&self, class: RetryClass, attempt: u32) -> bool {
+        attempt < self.max_attempts && self.retry_on.contains(&class)
+    }
+
+    /// Delay before attempt `attempt + 1`.
+    pub fn backoff_after(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_secs(u64::from(self.backoff_seconds).saturating_mul(factor))
+    }
+}
+
+/// What the controller does once a hard budget limit is reached.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum BudgetAction {
+    /// Open a `human-approval` gate asking to extend the budget; running workers are left alone.
+    #[default]
+    Gate,
+    /// Cancel every running worker.
+    Cancel,
+}
+
+impl BudgetAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetAction::Gate => "gate",
+            BudgetAction::Cancel => "cancel",
+        }
+    }
+}
+
+fn default_soft_limit_ratio() -> f64 {
+    0.8
+}
+
+/// Hard limits per task. Each approved budget gate grants one more allotment of every limit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskBudget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_usd: Option<f64>,
+    /// Measured from the first `agent.started` event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wall_clock_seconds: Option<u64>,
+    /// Worker spawns, retries included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_spawns: Option<u32>,
+    /// Fraction of a hard limit at which `budget.warning` is emitted.
+    #[serde(default = "default_soft_limit_ratio")]
+    pub soft_limit_ratio: f64,
+    #[serde(default)]
+    pub on_exceed: BudgetAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct TaskFile {
     pub id: String,
     pub title: String,
@@ -147,7 +293,7 @@ pub struct TaskFile {
     pub config: TaskConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateTaskRequest {
     pub title: String,
@@ -183,13 +329,18 @@ pub struct TaskEvent {
     pub path: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ClusterStatus {
     pub orchestrator: String,
     pub codex_adapter: String,
     pub active_agents: u32,
     pub max_agents: u32,
+    /// Token totals over every task's last `shared/usage.json`.
+    #[serde(default)]
+    pub usage: TokenUsage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
 }
 
 #[cfg(test)]
@@ -214,6 +365,19 @@ mod tests {
         assert_eq!(state, TaskState::InputRequired);
     }
 
+    #[test]
+    fn retry_policy_defaults_and_backoff() {
+        let policy: RetryPolicy = serde_yaml::from_str("maxAttempts: 3").unwrap();
+        assert_eq!(policy.retry_on, RetryClass::ALL.to_vec());
+        assert!(policy.should_retry(RetryClass::Crash, 2));
+        assert!(!policy.should_retry(RetryClass::Crash, 3));
+        assert_eq!(policy.backoff_after(1), Duration::from_secs(5));
+        assert_eq!(policy.backoff_after(2), Duration::from_secs(10));
+
+        let policy: RetryPolicy = serde_yaml::from_str("retryOn: [rate-limit]").unwrap();
+        assert!(!policy.should_retry(RetryClass::InvalidOutput, 1));
+    }
+
     #[test]
     fn task_state_accepts_legacy_cancelled() {
         let state: TaskState = serde_yaml::from_str("cancelled").unwrap();
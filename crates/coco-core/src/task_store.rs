@@ -1,3 +1,5 @@
+use crate::evidence::EvidenceCheckReport;
+use crate::evidence::EvidenceEntry;
 use crate::task::CreateTaskRequest;
 use crate::task::CreateTaskResponse;
 use crate::task::TaskEvent;
@@ -24,6 +26,9 @@ pub enum TaskStoreError {
     InvalidTaskId { task_id: String },
 }
 
+/// Seed content of `shared/human-notes.md`; workers are not given the notes while unchanged.
+pub const HUMAN_NOTES_TEMPLATE: &str = "# Human Notes\n\n- 在这里记录人工补充、约束与纠错。\n";
+
 #[derive(Debug, Clone)]
 pub struct TaskStore {
     workspace_root: PathBuf,
@@ -104,6 +109,52 @@ impl TaskStore {
         Ok(())
     }
 
+    pub fn evidence_index_path(&self, task_id: &str) -> PathBuf {
+        self.task_dir(task_id)
+            .join("shared")
+            .join("evidence")
+            .join("index.json")
+    }
+
+    pub fn read_evidence_index(&self, task_id: &str) -> Result<Vec<EvidenceEntry>, TaskStoreError> {
+        self.ensure_task_exists(task_id)?;
+        Ok(crate::evidence::read_evidence_index(
+            &self.evidence_index_path(task_id),
+        )?)
+    }
+
+    pub fn write_evidence_index(
+        &self,
+        task_id: &str,
+        entries: &[EvidenceEntry],
+    ) -> Result<(), TaskStoreError> {
+        let path = self.evidence_index_path(task_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(entries)?)?;
+        Ok(())
+    }
+
+    /// Scan the task's shared Markdown for `evidence:<id>` tokens and cross-check the index.
+    pub fn check_evidence(&self, task_id: &str) -> Result<EvidenceCheckReport, TaskStoreError> {
+        let index = self.read_evidence_index(task_id)?;
+        Ok(crate::evidence::check_evidence_references(
+            &self.task_dir(task_id),
+            &index,
+        )?)
+    }
+
+    fn ensure_task_exists(&self, task_id: &str) -> Result<(), TaskStoreError> {
+        if self.task_dir(task_id).join("task.yaml").exists() {
+            Ok(())
+        } else {
+            Err(TaskStoreError::TaskNotFound {
+                task_id: task_id.to_string(),
+            })
+        }
+    }
+
     pub fn read_task_events(
         &self,
         task_id: &str,
@@ -185,10 +236,7 @@ impl TaskStore {
 
         let human_notes_path = shared_dir.join("human-notes.md");
         if !human_notes_path.exists() {
-            fs::write(
-                human_notes_path,
-                "# Human Notes\n\n- 在这里记录人工补充、约束与纠错。\n",
-            )?;
+            fs::write(human_notes_path, HUMAN_NOTES_TEMPLATE)?;
         }
 
         let context_manifest_path = shared_dir.join("context-manifest.yaml");
@@ -0,0 +1,668 @@
+//! Minimal Handlebars-style template engine used for task reports.
+//!
+//! Supported syntax (rendered over a `serde_json::Value` context):
+//!
+//! - `{{path}}` / `{{helper path}}`: value lookup with dotted paths (`task.title`), `this`,
+//!   `../parent`, and loop metadata (`@index`, `@number`, `@first`, `@last`).
+//! - `{{join path}}` / `{{join path "sep"}}`: trimmed, non-blank list items joined with `, `
+//!   (or `sep`).
+//! - `{{#each path}}...{{else}}...{{/each}}`: iterate arrays (or object values).
+//! - `{{#if path}}...{{else}}...{{/if}}` and `{{#unless path}}...{{/unless}}`.
+//! - `{{! comment }}` / `{{!-- comment --}}`.
+//!
+//! Lookups fall back to enclosing scopes (Mustache-style), missing values render as empty
+//! strings, and block tags standing alone on a line do not leave blank lines behind.
+
+use serde_json::Value;
+use thiserror::Error;
+
+const DEFAULT_JOIN_SEPARATOR: &str = ", ";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("template parse error at line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Helper {
+    Yaml,
+    Json,
+    Trim,
+    Cell,
+    Join { separator: String },
+}
+
+impl Helper {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "yaml" => Some(Helper::Yaml),
+            "json" => Some(Helper::Json),
+            "trim" => Some(Helper::Trim),
+            "cell" => Some(Helper::Cell),
+            "join" => Some(Helper::Join {
+                separator: DEFAULT_JOIN_SEPARATOR.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockKind {
+    Each,
+    If,
+    Unless,
+}
+
+impl BlockKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "each" => Some(BlockKind::Each),
+            "if" => Some(BlockKind::If),
+            "unless" => Some(BlockKind::Unless),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            BlockKind::Each => "each",
+            BlockKind::If => "if",
+            BlockKind::Unless => "unless",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Var {
+        helper: Option<Helper>,
+        path: String,
+    },
+    Block {
+        kind: BlockKind,
+        path: String,
+        body: Vec<Node>,
+        else_body: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tag {
+    Var {
+        helper: Option<Helper>,
+        path: String,
+    },
+    Open {
+        kind: BlockKind,
+        path: String,
+    },
+    Close {
+        kind: BlockKind,
+    },
+    Else,
+    Comment,
+}
+
+impl Tag {
+    fn is_standalone_candidate(&self) -> bool {
+        !matches!(self, Tag::Var { .. })
+    }
+}
+
+#[derive(Debug)]
+struct OpenBlock {
+    kind: BlockKind,
+    path: String,
+    outer: Vec<Node>,
+    body: Option<Vec<Node>>,
+    line: usize,
+}
+
+#[derive(Debug)]
+struct LexedTag {
+    tag: Tag,
+    line: usize,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let (mut texts, tags) = lex(source)?;
+        strip_standalone_lines(&mut texts, &tags);
+
+        let mut stack: Vec<OpenBlock> = Vec::new();
+        let mut current: Vec<Node> = Vec::new();
+
+        for (idx, text) in texts.into_iter().enumerate() {
+            if !text.is_empty() {
+                current.push(Node::Text(text));
+            }
+            let Some(lexed) = tags.get(idx) else {
+                continue;
+            };
+            match &lexed.tag {
+                Tag::Var { helper, path } => current.push(Node::Var {
+                    helper: helper.clone(),
+                    path: path.clone(),
+                }),
+                Tag::Comment => {}
+                Tag::Open { kind, path } => {
+                    let outer = std::mem::take(&mut current);
+                    stack.push(OpenBlock {
+                        kind: *kind,
+                        path: path.clone(),
+                        outer,
+                        body: None,
+                        line: lexed.line,
+                    });
+                }
+                Tag::Else => {
+                    let Some(frame) = stack.last_mut() else {
+                        return Err(parse_error(lexed.line, "{{else}} outside of a block"));
+                    };
+                    if frame.body.is_some() {
+                        return Err(parse_error(lexed.line, "duplicate {{else}} in block"));
+                    }
+                    frame.body = Some(std::mem::take(&mut current));
+                }
+                Tag::Close { kind } => {
+                    let Some(open) = stack.pop() else {
+                        return Err(parse_error(
+                            lexed.line,
+                            &format!("unexpected {{{{/{}}}}}", kind.as_str()),
+                        ));
+                    };
+                    if open.kind != *kind {
+                        return Err(parse_error(
+                            lexed.line,
+                            &format!(
+                                "expected {{{{/{}}}}}, found {{{{/{}}}}}",
+                                open.kind.as_str(),
+                                kind.as_str()
+                            ),
+                        ));
+                    }
+                    let tail = std::mem::replace(&mut current, open.outer);
+                    let (body, else_body) = match open.body {
+                        Some(body) => (body, tail),
+                        None => (tail, Vec::new()),
+                    };
+                    current.push(Node::Block {
+                        kind: open.kind,
+                        path: open.path,
+                        body,
+                        else_body,
+                    });
+                }
+            }
+        }
+
+        if let Some(open) = stack.pop() {
+            return Err(parse_error(
+                open.line,
+                &format!("unclosed {{{{#{}}}}}", open.kind.as_str()),
+            ));
+        }
+
+        Ok(Self { nodes: current })
+    }
+
+    pub fn render(&self, context: &Value) -> String {
+        let mut out = String::new();
+        let mut scopes = vec![Scope {
+            value: context.clone(),
+            meta: None,
+        }];
+        render_nodes(&self.nodes, &mut scopes, &mut out);
+        out
+    }
+}
+
+/// Parse and render in one step.
+pub fn render_template(source: &str, context: &Value) -> Result<String, TemplateError> {
+    Ok(Template::parse(source)?.render(context))
+}
+
+fn parse_error(line: usize, message: &str) -> TemplateError {
+    TemplateError::Parse {
+        line,
+        message: message.to_string(),
+    }
+}
+
+/// Split the source into `texts.len() == tags.len() + 1` interleaved text segments and tags.
+fn lex(source: &str) -> Result<(Vec<String>, Vec<LexedTag>), TemplateError> {
+    let mut texts = Vec::new();
+    let mut tags = Vec::new();
+    let mut rest = source;
+    let mut line = 1;
+
+    while let Some(start) = rest.find("{{") {
+        let text = &rest[..start];
+        line += text.matches('\n').count();
+        texts.push(text.to_string());
+
+        let after_open = &rest[start + 2..];
+        let (inner, consumed) = if let Some(comment) = after_open.strip_prefix("!--") {
+            let end = comment
+                .find("--}}")
+                .ok_or_else(|| parse_error(line, "unterminated comment"))?;
+            (&after_open[..end + 3], end + 3 + 4)
+        } else {
+            let end = after_open
+                .find("}}")
+                .ok_or_else(|| parse_error(line, "unterminated tag"))?;
+            (&after_open[..end], end + 2)
+        };
+
+        tags.push(LexedTag {
+            tag: parse_tag(inner.trim(), line)?,
+            line,
+        });
+        line += inner.matches('\n').count();
+        rest = &after_open[consumed..];
+    }
+    texts.push(rest.to_string());
+
+    Ok((texts, tags))
+}
+
+fn parse_tag(inner: &str, line: usize) -> Result<Tag, TemplateError> {
+    if inner.starts_with('!') {
+        return Ok(Tag::Comment);
+    }
+    if inner == "else" {
+        return Ok(Tag::Else);
+    }
+    if let Some(rest) = inner.strip_prefix('#') {
+        let mut parts = rest.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        let kind = BlockKind::parse(name)
+            .ok_or_else(|| parse_error(line, &format!("unknown block helper: {name}")))?;
+        let path = parts
+            .next()
+            .ok_or_else(|| parse_error(line, &format!("{{{{#{name}}}}} requires a path")))?;
+        if parts.next().is_some() {
+            return Err(parse_error(line, &format!("too many arguments: {inner}")));
+        }
+        return Ok(Tag::Open {
+            kind,
+            path: path.to_string(),
+        });
+    }
+    if let Some(rest) = inner.strip_prefix('/') {
+        let kind = BlockKind::parse(rest.trim())
+            .ok_or_else(|| parse_error(line, &format!("unknown block helper: {rest}")))?;
+        return Ok(Tag::Close { kind });
+    }
+
+    // `join` is the only helper with an extra argument: a double-quoted separator.
+    if let Some((head, separator)) = inner.split_once('"') {
+        let separator = separator
+            .strip_suffix('"')
+            .filter(|sep| !sep.contains('"'))
+            .ok_or_else(|| parse_error(line, &format!("invalid separator: {{{{{inner}}}}}")))?;
+        return match head.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["join", path] => Ok(Tag::Var {
+                helper: Some(Helper::Join {
+                    separator: separator.to_string(),
+                }),
+                path: path.to_string(),
+            }),
+            _ => Err(parse_error(line, &format!("invalid tag: {{{{{inner}}}}}"))),
+        };
+    }
+
+    let parts = inner.split_whitespace().collect::<Vec<_>>();
+    match parts.as_slice() {
+        [path] => Ok(Tag::Var {
+            helper: None,
+            path: path.to_string(),
+        }),
+        [helper, path] => {
+            let helper = Helper::parse(helper)
+                .ok_or_else(|| parse_error(line, &format!("unknown helper: {helper}")))?;
+            Ok(Tag::Var {
+                helper: Some(helper),
+                path: path.to_string(),
+            })
+        }
+        _ => Err(parse_error(line, &format!("invalid tag: {{{{{inner}}}}}"))),
+    }
+}
+
+/// Mustache "standalone" rule: a block/comment tag that is the only thing on its line
+/// removes that line (its indentation and trailing newline) from the output.
+fn strip_standalone_lines(texts: &mut [String], tags: &[LexedTag]) {
+    let last_text = texts.len() - 1;
+    let standalone = tags
+        .iter()
+        .enumerate()
+        .map(|(idx, lexed)| {
+            if !lexed.tag.is_standalone_candidate() {
+                return false;
+            }
+            let before = &texts[idx];
+            let after = &texts[idx + 1];
+            let line_start = match before.rfind('\n') {
+                Some(pos) => is_blank(&before[pos + 1..]),
+                None => idx == 0 && is_blank(before),
+            };
+            let line_end = match after.find('\n') {
+                Some(pos) => is_blank(&after[..pos]),
+                None => idx + 1 == last_text && is_blank(after),
+            };
+            line_start && line_end
+        })
+        .collect::<Vec<_>>();
+
+    for (idx, is_standalone) in standalone.into_iter().enumerate() {
+        if !is_standalone {
+            continue;
+        }
+        let before = &mut texts[idx];
+        let keep = before.rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+        before.truncate(keep);
+
+        let after = &mut texts[idx + 1];
+        let drop = after.find('\n').map(|pos| pos + 1).unwrap_or(after.len());
+        after.drain(..drop);
+    }
+}
+
+fn is_blank(value: &str) -> bool {
+    value.chars().all(|c| c == ' ' || c == '\t' || c == '\r')
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LoopMeta {
+    index: usize,
+    len: usize,
+}
+
+struct Scope {
+    value: Value,
+    meta: Option<LoopMeta>,
+}
+
+fn render_nodes(nodes: &[Node], scopes: &mut Vec<Scope>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { helper, path } => {
+                let value = lookup(scopes, path);
+                out.push_str(&apply_helper(helper.as_ref(), value.as_ref()));
+            }
+            Node::Block {
+                kind: BlockKind::Each,
+                path,
+                body,
+                else_body,
+            } => {
+                let items = match lookup(scopes, path) {
+                    Some(Value::Array(items)) => items,
+                    Some(Value::Object(map)) => map.into_iter().map(|(_, v)| v).collect(),
+                    _ => Vec::new(),
+                };
+                if items.is_empty() {
+                    render_nodes(else_body, scopes, out);
+                    continue;
+                }
+                let len = items.len();
+                for (index, value) in items.into_iter().enumerate() {
+                    scopes.push(Scope {
+                        value,
+                        meta: Some(LoopMeta { index, len }),
+                    });
+                    render_nodes(body, scopes, out);
+                    scopes.pop();
+                }
+            }
+            Node::Block {
+                kind,
+                path,
+                body,
+                else_body,
+            } => {
+                let truthy = lookup(scopes, path).as_ref().is_some_and(is_truthy);
+                let take_body = if *kind == BlockKind::Unless {
+                    !truthy
+                } else {
+                    truthy
+                };
+                if take_body {
+                    render_nodes(body, scopes, out);
+                } else {
+                    render_nodes(else_body, scopes, out);
+                }
+            }
+        }
+    }
+}
+
+fn lookup(scopes: &[Scope], path: &str) -> Option<Value> {
+    let mut depth = scopes.len();
+    let mut path = path;
+    let mut explicit_scope = false;
+    while let Some(rest) = path.strip_prefix("../") {
+        depth = depth.saturating_sub(1);
+        path = rest;
+        explicit_scope = true;
+    }
+    if depth == 0 {
+        return None;
+    }
+    let visible = &scopes[..depth];
+
+    if let Some(name) = path.strip_prefix('@') {
+        let meta = visible.iter().rev().find_map(|s| s.meta)?;
+        return match name {
+            "index" => Some(Value::from(meta.index)),
+            "number" => Some(Value::from(meta.index + 1)),
+            "first" => Some(Value::Bool(meta.index == 0)),
+            "last" => Some(Value::Bool(meta.index + 1 == meta.len)),
+            _ => None,
+        };
+    }
+
+    let current = &visible[depth - 1].value;
+    if path == "this" || path == "." {
+        return Some(current.clone());
+    }
+    let path = path.strip_prefix("this.").unwrap_or(path);
+    let segments = path.split('.').collect::<Vec<_>>();
+
+    if explicit_scope {
+        return resolve_segments(current, &segments).cloned();
+    }
+    visible
+        .iter()
+        .rev()
+        .find_map(|scope| resolve_segments(&scope.value, &segments))
+        .cloned()
+}
+
+fn resolve_segments<'a>(value: &'a Value, segments: &[&str]) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in segments {
+        current = match current {
+            Value::Object(map) => map.get(*segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|v| v != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(display_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Object(_) => value.to_string(),
+    }
+}
+
+fn apply_helper(helper: Option<&Helper>, value: Option<&Value>) -> String {
+    let Some(value) = value else {
+        return String::new();
+    };
+    match helper {
+        None => display_value(value),
+        Some(Helper::Join { separator }) => join_items(value, separator),
+        Some(Helper::Trim) => display_value(value).trim().to_string(),
+        Some(Helper::Yaml) => yaml_quoted(&display_value(value)),
+        Some(Helper::Json) => value.to_string(),
+        Some(Helper::Cell) => display_value(value)
+            .trim()
+            .replace('|', "\\|")
+            .replace("\r\n", " ")
+            .replace('\n', " "),
+    }
+}
+
+/// Arrays (and object values) as trimmed, non-blank items; a scalar is a one-item list.
+fn join_items(value: &Value, separator: &str) -> String {
+    let items = match value {
+        Value::Array(items) => items.iter().collect::<Vec<_>>(),
+        Value::Object(map) => map.values().collect(),
+        other => vec![other],
+    };
+    items
+        .into_iter()
+        .map(|item| display_value(item).trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Escape `text` for the inside of a YAML double-quoted scalar (`"{{yaml x}}"`).
+fn yaml_quoted(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // YAML also breaks lines on U+2028/U+2029.
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                out.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_sections_helpers_and_standalone_lines() {
+        let source = r#"# {{task.title}}
+{{!-- comment --}}
+title: "{{yaml task.title}}"
+{{#each workers}}
+- {{@number}}. {{name}} in {{task.id}}
+  {{#if questions}}
+  - q: {{join questions}}
+  {{else}}
+  - none
+  {{/if}}
+{{else}}
+- no workers
+{{/each}}
+| {{cell task.note}} |
+"#;
+        let ctx = json!({
+            "task": {"id": "t1", "title": "Say \"hi\"", "note": "a|b\nc"},
+            "workers": [
+                {"name": "w1", "questions": ["x", "y"]},
+                {"name": "w2", "questions": []}
+            ]
+        });
+
+        let out = render_template(source, &ctx).unwrap();
+        assert_eq!(
+            out,
+            r#"# Say "hi"
+title: "Say \"hi\""
+- 1. w1 in t1
+  - q: x, y
+- 2. w2 in t1
+  - none
+| a\|b c |
+"#
+        );
+
+        let empty = render_template(source, &json!({"task": {"title": "t"}})).unwrap();
+        assert!(empty.contains("- no workers\n"));
+    }
+
+    #[test]
+    fn yaml_helper_output_round_trips_through_a_yaml_parser() {
+        let title = "C:\\tmp \"x\"\nsecond line\ttab\u{7}bell";
+        let out = render_template(
+            "title: \"{{yaml task.title}}\"\n",
+            &json!({"task": {"title": title}}),
+        )
+        .unwrap();
+        assert_eq!(out.lines().count(), 1);
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&out).unwrap();
+        assert_eq!(parsed["title"].as_str(), Some(title));
+    }
+
+    #[test]
+    fn reports_unbalanced_blocks() {
+        let err = Template::parse("{{#each a}}\n{{/if}}\n").unwrap_err();
+        assert_eq!(
+            err,
+            TemplateError::Parse {
+                line: 2,
+                message: "expected {{/each}}, found {{/if}}".to_string(),
+            }
+        );
+        assert!(Template::parse("{{#if a}}").is_err());
+        assert!(Template::parse("{{shout a}}").is_err());
+        assert!(Template::parse("{{trim a \", \"}}").is_err());
+        assert!(Template::parse("{{join a \"x}}").is_err());
+    }
+
+    #[test]
+    fn join_skips_blank_items_and_takes_a_separator() {
+        let ctx = json!({"items": [" a ", "", "b", null, 3], "one": " x ", "none": []});
+        let out = render_template(
+            "[{{join items}}] [{{join items \" / \"}}] [{{items}}] [{{join one}}] [{{join none}}]",
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(out, "[a, b, 3] [a / b / 3] [ a , , b, , 3] [x] []");
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::AddAssign;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Token counters as reported by Codex. `cached_input_tokens` is a subset of `input_tokens`
+/// and `reasoning_output_tokens` a subset of `output_tokens`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub cached_input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub reasoning_output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_tokens() == 0
+    }
+}
+
+impl AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_output_tokens += other.reasoning_output_tokens;
+    }
+}
+
+impl fmt::Display for TokenUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "input={} cached={} output={} reasoning={} total={}",
+            self.input_tokens,
+            self.cached_input_tokens,
+            self.output_tokens,
+            self.reasoning_output_tokens,
+            self.total_tokens()
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PriceTableError {
+    #[error("io error reading {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid price table {path}: {source}")]
+    Yaml {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+}
+
+/// USD per million tokens for one model.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    /// Defaults to `input_per_million` when the provider has no cache discount.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_million: Option<f64>,
+    pub output_per_million: f64,
+}
+
+/// `.coco/prices.yaml`: optional price table keyed by model name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceTable {
+    #[serde(default)]
+    pub models: BTreeMap<String, ModelPrice>,
+}
+
+impl PriceTable {
+    /// Load `path`; a missing file is an empty table (nothing gets priced).
+    pub fn load(path: &Path) -> Result<Self, PriceTableError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(PriceTableError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        serde_yaml::from_str(&content).map_err(|source| PriceTableError::Yaml {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Cost in USD, or None when the model is unknown or has no price.
+    pub fn cost(&self, model: Option<&str>, usage: &TokenUsage) -> Option<f64> {
+        let price = self.models.get(model?)?;
+        let cached = usage.cached_input_tokens.min(usage.input_tokens);
+        let uncached = usage.input_tokens - cached;
+        let cached_rate = price
+            .cached_input_per_million
+            .unwrap_or(price.input_per_million);
+        Some(
+            (uncached as f64 * price.input_per_million
+                + cached as f64 * cached_rate
+                + usage.output_tokens as f64 * price.output_per_million)
+                / 1_000_000.0,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerUsage {
+    pub agent_instance: String,
+    pub agent: String,
+    pub model: Option<String>,
+    /// Summed over every attempt, including archived ones.
+    pub usage: TokenUsage,
+    /// None when the model has no entry in the price table.
+    pub cost_usd: Option<f64>,
+}
+
+/// `shared/usage.json`: per-worker totals rolled up for the task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskUsage {
+    pub task_id: String,
+    pub updated_at: DateTime<Utc>,
+    pub usage: TokenUsage,
+    /// Sum over priced workers; None when no worker could be priced.
+    pub cost_usd: Option<f64>,
+    pub workers: Vec<WorkerUsage>,
+}
+
+impl TaskUsage {
+    pub fn new(task_id: String, workers: Vec<WorkerUsage>) -> Self {
+        let mut usage = TokenUsage::default();
+        let mut cost_usd = None;
+        for w in &workers {
+            usage += w.usage;
+            if let Some(cost) = w.cost_usd {
+                *cost_usd.get_or_insert(0.0) += cost;
+            }
+        }
+        Self {
+            task_id,
+            updated_at: Utc::now(),
+            usage,
+            cost_usd,
+            workers,
+        }
+    }
+}
+
+/// `$1.2345`-style rendering shared by the CLI and Markdown views.
+pub fn format_cost_usd(cost: f64) -> String {
+    format!("${cost:.4}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_table_discounts_cached_input() {
+        let table: PriceTable = serde_yaml::from_str(
+            "models:\n  gpt-test:\n    inputPerMillion: 2\n    cachedInputPerMillion: 0.5\n    outputPerMillion: 10\n",
+        )
+        .unwrap();
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            cached_input_tokens: 400_000,
+            output_tokens: 100_000,
+            reasoning_output_tokens: 50_000,
+        };
+        let cost = table.cost(Some("gpt-test"), &usage).unwrap();
+        assert!((cost - (1.2 + 0.2 + 1.0)).abs() < 1e-9);
+        assert_eq!(table.cost(Some("other"), &usage), None);
+        assert_eq!(table.cost(None, &usage), None);
+
+        let task = TaskUsage::new(
+            "t1".to_string(),
+            vec![WorkerUsage {
+                agent_instance: "a1".to_string(),
+                agent: "worker".to_string(),
+                model: Some("gpt-test".to_string()),
+                usage,
+                cost_usd: Some(cost),
+            }],
+        );
+        assert_eq!(task.usage.total_tokens(), 1_100_000);
+        assert_eq!(task.cost_usd, Some(cost));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use coco_core::agent_spec::list_agent_spec_names;
+use coco_core::agent_spec::load_agent_spec;
+use coco_core::agent_spec::validate_agent_spec;
+use coco_core::agent_spec::AgentSpec;
+use coco_core::agent_spec::AgentSpecError;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Spec lookup order: `<workspace>/.coco/agents/<name>/` overrides `<workspace>/agents/<name>/`.
+const WORKSPACE_AGENTS_DIR_NAME: &str = "agents";
+
+/// `<workspace>/schemas/worker-output.schema.json`, used when the agent spec names no schema.
+pub(crate) fn default_output_schema_path(workspace_root: &Path) -> PathBuf {
+    workspace_root
+        .join("schemas")
+        .join("worker-output.schema.json")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSpecValidation {
+    pub name: String,
+    pub dir: PathBuf,
+    pub issues: Vec<String>,
+}
+
+impl AgentSpecValidation {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Orchestrator {
+    /// All agent specs, sorted by name (`.coco/agents/` entries shadow `agents/` ones).
+    pub fn agent_specs(&self) -> Result<Vec<AgentSpec>, OrchestratorError> {
+        self.agent_spec_names()?
+            .iter()
+            .map(|name| self.agent_spec(name))
+            .collect()
+    }
+
+    pub fn agent_spec(&self, name: &str) -> Result<AgentSpec, OrchestratorError> {
+        for dir in self.agent_spec_dirs() {
+            match load_agent_spec(&dir, name) {
+                Err(AgentSpecError::NotFound { .. }) => continue,
+                other => return Ok(other?),
+            }
+        }
+        Err(AgentSpecError::NotFound {
+            name: name.to_string(),
+        }
+        .into())
+    }
+
+    /// Validate one spec (or every spec when `name` is None). Parse errors are reported as
+    /// issues rather than failing the whole run.
+    pub fn agent_spec_validate(
+        &self,
+        name: Option<&str>,
+    ) -> Result<Vec<AgentSpecValidation>, OrchestratorError> {
+        let names = match name {
+            Some(name) => vec![name.to_string()],
+            None => self.agent_spec_names()?,
+        };
+        let mut out = Vec::new();
+        for name in names {
+            let validation = match self.agent_spec(&name) {
+                Ok(spec) => AgentSpecValidation {
+                    issues: validate_agent_spec(&spec, self.workspace_root()),
+                    name,
+                    dir: spec.dir,
+                },
+                Err(OrchestratorError::AgentSpec(AgentSpecError::Yaml { path, source })) => {
+                    AgentSpecValidation {
+                        name,
+                        dir: path.parent().map(|p| p.to_path_buf()).unwrap_or_default(),
+                        issues: vec![format!("invalid {}: {source}", path.display())],
+                    }
+                }
+                Err(err) => return Err(err),
+            };
+            out.push(validation);
+        }
+        Ok(out)
+    }
+
+    /// The spec for `agent`, or None when the name is only a label (no spec on disk).
+    pub(crate) fn find_agent_spec(
+        &self,
+        agent: &str,
+    ) -> Result<Option<AgentSpec>, OrchestratorError> {
+        match self.agent_spec(agent) {
+            Ok(spec) => Ok(Some(spec)),
+            Err(OrchestratorError::AgentSpec(AgentSpecError::NotFound { .. })) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Output schema for a worker of `agent`: the spec's `outputSchema`, else the workspace default.
+    pub fn worker_output_schema_path(&self, agent: &str) -> Result<PathBuf, OrchestratorError> {
+        Ok(self
+            .find_agent_spec(agent)?
+            .and_then(|spec| spec.output_schema_path(self.workspace_root()))
+            .unwrap_or_else(|| default_output_schema_path(self.workspace_root())))
+    }
+
+    fn agent_spec_names(&self) -> Result<Vec<String>, OrchestratorError> {
+        let mut names = Vec::new();
+        for dir in self.agent_spec_dirs() {
+            names.extend(list_agent_spec_names(&dir)?);
+        }
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    fn agent_spec_dirs(&self) -> [PathBuf; 2] {
+        let root = self.workspace_root();
+        [
+            root.join(".coco").join(WORKSPACE_AGENTS_DIR_NAME),
+            root.join(WORKSPACE_AGENTS_DIR_NAME),
+        ]
+    }
+}
@@ -0,0 +1,372 @@
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::SubagentStatus;
+use chrono::Utc;
+use coco_core::task::BudgetAction;
+use coco_core::task::Gate;
+use coco_core::task::GateState;
+use coco_core::task::GateType;
+use coco_core::task::TaskEvent;
+use coco_core::task::TaskState;
+use coco_core::usage::format_cost_usd;
+use serde::Serialize;
+use serde_json::json;
+
+/// Budget gates are `budget-1`, `budget-2`, …; every approved one extends the limits.
+const BUDGET_GATE_PREFIX: &str = "budget-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BudgetLevel {
+    Ok,
+    Warning,
+    Exceeded,
+}
+
+impl BudgetLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetLevel::Ok => "ok",
+            BudgetLevel::Warning => "warning",
+            BudgetLevel::Exceeded => "exceeded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetMetric {
+    /// `tokens`, `costUsd`, `wallClockSeconds` or `spawns`.
+    pub name: &'static str,
+    pub used: f64,
+    /// Effective limit (configured limit × (extensions + 1)).
+    pub limit: f64,
+    pub level: BudgetLevel,
+}
+
+impl BudgetMetric {
+    fn new(name: &'static str, used: f64, limit: f64, soft_limit_ratio: f64) -> Self {
+        let level = if used > limit {
+            BudgetLevel::Exceeded
+        } else if used >= limit * soft_limit_ratio {
+            BudgetLevel::Warning
+        } else {
+            BudgetLevel::Ok
+        };
+        Self {
+            name,
+            used,
+            limit,
+            level,
+        }
+    }
+
+    /// `used/limit` with the cost metric rendered in dollars.
+    pub fn display(&self) -> String {
+        if self.name == "costUsd" {
+            format!(
+                "{}/{}",
+                format_cost_usd(self.used),
+                format_cost_usd(self.limit)
+            )
+        } else {
+            format!("{}/{}", self.used, self.limit)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    /// Approved budget gates so far.
+    pub extensions: u32,
+    pub on_exceed: BudgetAction,
+    pub metrics: Vec<BudgetMetric>,
+}
+
+impl BudgetStatus {
+    pub fn level(&self) -> BudgetLevel {
+        self.metrics
+            .iter()
+            .map(|m| m.level)
+            .max()
+            .unwrap_or(BudgetLevel::Ok)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BudgetEnforcement {
+    Within,
+    /// A budget gate is blocked (or the last one was rejected); stop dispatching.
+    Gated,
+    /// Running workers were cancelled.
+    Cancelled,
+}
+
+impl Orchestrator {
+    /// Current usage against `config.budget`, or None when the task has no budget.
+    pub fn budget_status(&self, task_id: &str) -> Result<Option<BudgetStatus>, OrchestratorError> {
+        let task = self.store.read_task(task_id)?;
+        let Some(budget) = task.config.budget else {
+            return Ok(None);
+        };
+        let extensions = task
+            .gates
+            .iter()
+            .filter(|g| is_budget_gate(&g.id) && g.state == GateState::Approved)
+            .count() as u32;
+        let factor = f64::from(extensions + 1);
+        let ratio = budget.soft_limit_ratio;
+
+        let started = self
+            .store
+            .read_task_events(task_id, Some("agent.started"), usize::MAX, 0)?;
+        let mut metrics = Vec::new();
+        if budget.max_tokens.is_some() || budget.max_cost_usd.is_some() {
+            let usage = self.task_usage(task_id)?;
+            if let Some(max_tokens) = budget.max_tokens {
+                metrics.push(BudgetMetric::new(
+                    "tokens",
+                    usage.usage.total_tokens() as f64,
+                    max_tokens as f64 * factor,
+                    ratio,
+                ));
+            }
+            if let Some(max_cost_usd) = budget.max_cost_usd {
+                metrics.push(BudgetMetric::new(
+                    "costUsd",
+                    usage.cost_usd.unwrap_or(0.0),
+                    max_cost_usd * factor,
+                    ratio,
+                ));
+            }
+        }
+        if let Some(max_seconds) = budget.max_wall_clock_seconds {
+            let elapsed = started
+                .iter()
+                .map(|e| e.ts)
+                .min()
+                .map(|first| (Utc::now() - first).num_seconds().max(0))
+                .unwrap_or(0);
+            metrics.push(BudgetMetric::new(
+                "wallClockSeconds",
+                elapsed as f64,
+                max_seconds as f64 * factor,
+                ratio,
+            ));
+        }
+        if let Some(max_spawns) = budget.max_spawns {
+            metrics.push(BudgetMetric::new(
+                "spawns",
+                started.len() as f64,
+                f64::from(max_spawns) * factor,
+                ratio,
+            ));
+        }
+
+        Ok(Some(BudgetStatus {
+            extensions,
+            on_exceed: budget.on_exceed,
+            metrics,
+        }))
+    }
+
+    /// Approve (extend the budget by one more allotment) or reject the blocked budget gate.
+    pub fn budget_resolve(
+        &self,
+        task_id: &str,
+        approved: bool,
+        by: Option<&str>,
+    ) -> Result<String, OrchestratorError> {
+        let task = self.store.read_task(task_id)?;
+        let Some(gate) = task
+            .gates
+            .iter()
+            .find(|g| is_budget_gate(&g.id) && g.state == GateState::Blocked)
+        else {
+            return Err(OrchestratorError::GateNotFound {
+                gate_id: format!("{BUDGET_GATE_PREFIX}*"),
+            });
+        };
+        let gate_id = gate.id.clone();
+        self.resolve_gate(task_id, &gate_id, approved, by, json!({ "by": by }))?;
+        Ok(gate_id)
+    }
+
+    /// Check the budget before dispatching `planned_spawns` more workers (0 while monitoring):
+    /// warn once per metric at the soft threshold, and at the hard limit gate or cancel.
+    pub(crate) fn enforce_budget(
+        &self,
+        task_id: &str,
+        planned_spawns: u32,
+    ) -> Result<BudgetEnforcement, OrchestratorError> {
+        let Some(mut status) = self.budget_status(task_id)? else {
+            return Ok(BudgetEnforcement::Within);
+        };
+        if let Some(spawns) = status.metrics.iter_mut().find(|m| m.name == "spawns") {
+            if spawns.used + f64::from(planned_spawns) > spawns.limit {
+                spawns.level = BudgetLevel::Exceeded;
+            }
+        }
+
+        let warned = self
+            .store
+            .read_task_events(task_id, Some("budget.warning"), usize::MAX, 0)?;
+        for m in status
+            .metrics
+            .iter()
+            .filter(|m| m.level == BudgetLevel::Warning)
+        {
+            let already = warned.iter().any(|e| {
+                e.payload["metric"] == m.name && e.payload["extensions"] == status.extensions
+            });
+            if !already {
+                self.append_budget_event(
+                    task_id,
+                    "budget.warning",
+                    json!({
+                        "metric": m.name,
+                        "used": m.used,
+                        "limit": m.limit,
+                        "extensions": status.extensions,
+                    }),
+                )?;
+            }
+        }
+
+        let exceeded = status
+            .metrics
+            .iter()
+            .filter(|m| m.level == BudgetLevel::Exceeded)
+            .collect::<Vec<_>>();
+        if exceeded.is_empty() {
+            return Ok(BudgetEnforcement::Within);
+        }
+        let summary = exceeded
+            .iter()
+            .map(|m| format!("{} {}", m.name, m.display()))
+            .collect::<Vec<_>>();
+
+        match status.on_exceed {
+            BudgetAction::Gate => {
+                let task = self.store.read_task(task_id)?;
+                let budget_gates = task
+                    .gates
+                    .iter()
+                    .filter(|g| is_budget_gate(&g.id))
+                    .collect::<Vec<_>>();
+                // Blocked: still waiting. Rejected: stays stopped until task.yaml raises the limits.
+                if budget_gates
+                    .iter()
+                    .any(|g| matches!(g.state, GateState::Blocked | GateState::Rejected))
+                {
+                    return Ok(BudgetEnforcement::Gated);
+                }
+                let gate_id = format!("{BUDGET_GATE_PREFIX}{}", budget_gates.len() + 1);
+                self.open_budget_gate(task_id, &gate_id, &summary)?;
+                self.append_budget_event(
+                    task_id,
+                    "budget.exceeded",
+                    json!({
+                        "exceeded": summary,
+                        "action": status.on_exceed.as_str(),
+                        "gateId": gate_id,
+                        "extensions": status.extensions,
+                    }),
+                )?;
+                Ok(BudgetEnforcement::Gated)
+            }
+            BudgetAction::Cancel => {
+                let mut cancelled = Vec::new();
+                for s in self.subagent_list(task_id)? {
+                    if s.status == SubagentStatus::Running {
+                        self.subagent_cancel(task_id, &s.agent_instance)?;
+                        cancelled.push(s.agent_instance);
+                    }
+                }
+                // Limits stay exceeded after the cancel; record it once unless workers were stopped.
+                let reported = self
+                    .store
+                    .read_task_events(task_id, Some("budget.exceeded"), usize::MAX, 0)?
+                    .iter()
+                    .any(|e| e.payload["extensions"] == status.extensions);
+                if reported && cancelled.is_empty() {
+                    return Ok(BudgetEnforcement::Cancelled);
+                }
+                self.append_budget_event(
+                    task_id,
+                    "budget.exceeded",
+                    json!({
+                        "exceeded": summary,
+                        "action": status.on_exceed.as_str(),
+                        "cancelled": cancelled,
+                        "extensions": status.extensions,
+                    }),
+                )?;
+                Ok(BudgetEnforcement::Cancelled)
+            }
+        }
+    }
+
+    fn open_budget_gate(
+        &self,
+        task_id: &str,
+        gate_id: &str,
+        exceeded: &[String],
+    ) -> Result<(), OrchestratorError> {
+        let reason = format!(
+            "Task budget exceeded ({}); approve to extend every limit by one more allotment",
+            exceeded.join(", ")
+        );
+        let now = Utc::now();
+
+        let mut task = self.store.read_task(task_id)?;
+        task.gates.push(Gate {
+            id: gate_id.to_string(),
+            gate_type: GateType::HumanApproval,
+            state: GateState::Blocked,
+            reason: reason.clone(),
+            instructions_ref: Some("./task.yaml".to_string()),
+            blocked_at: Some(now),
+            resolved_at: None,
+            resolved_by: None,
+        });
+        if matches!(task.state, TaskState::Created | TaskState::Working) {
+            task.state = TaskState::InputRequired;
+        }
+        task.updated_at = now;
+        self.store.write_task(&task)?;
+
+        self.append_gate_event(
+            task_id,
+            gate_id,
+            None,
+            "gate.blocked",
+            json!({ "reason": reason }),
+        )
+    }
+
+    fn append_budget_event(
+        &self,
+        task_id: &str,
+        event_type: &str,
+        payload: serde_json::Value,
+    ) -> Result<(), OrchestratorError> {
+        let event = TaskEvent {
+            ts: Utc::now(),
+            event_type: event_type.to_string(),
+            task_id: task_id.to_string(),
+            agent_instance: None,
+            turn_id: None,
+            payload,
+            by: Some("controller".to_string()),
+            path: None,
+        };
+        self.store.append_task_event(task_id, &event)?;
+        Ok(())
+    }
+}
+
+fn is_budget_gate(gate_id: &str) -> bool {
+    gate_id.starts_with(BUDGET_GATE_PREFIX)
+}
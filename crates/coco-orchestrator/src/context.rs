@@ -0,0 +1,67 @@
+use crate::agent_dir;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::TASK_SHARED_DIR_NAME;
+use coco_core::context_manifest::resolve_context;
+use coco_core::context_manifest::ContextManifest;
+use coco_core::context_manifest::ContextTarget;
+use coco_core::task_store::HUMAN_NOTES_TEMPLATE;
+use std::fs;
+
+pub(crate) const CONTEXT_MANIFEST_FILE_NAME: &str = "context-manifest.yaml";
+/// Per-worker record of what the manifest injected: `agents/<instance>/context.json`.
+const CONTEXT_RECORD_FILE_NAME: &str = "context.json";
+/// `delivery: file` attachments are copied under `agents/<instance>/context/`.
+const CONTEXT_ATTACH_DIR_NAME: &str = "context";
+
+impl Orchestrator {
+    /// Resolve `shared/context-manifest.yaml` (plus the human notes) for one worker, prefix
+    /// `prompt` with the resulting preamble and record what was injected in `context.json`.
+    pub(crate) fn inject_worker_context(
+        &self,
+        task_id: &str,
+        agent_instance: &str,
+        agent: &str,
+        prompt: String,
+    ) -> Result<String, OrchestratorError> {
+        let task_dir = self.store.task_dir(task_id);
+        let manifest = ContextManifest::load(
+            &task_dir
+                .join(TASK_SHARED_DIR_NAME)
+                .join(CONTEXT_MANIFEST_FILE_NAME),
+        )?;
+        let evidence = self.store.read_evidence_index(task_id)?;
+        let notes = self.read_human_notes(task_id);
+        let agent_dir = agent_dir(&task_dir, agent_instance);
+        let attach_dir = agent_dir.join(CONTEXT_ATTACH_DIR_NAME);
+        // Stale copies from an earlier attempt must not look like part of this one.
+        let _ = fs::remove_dir_all(&attach_dir);
+
+        let resolved = resolve_context(
+            &manifest,
+            ContextTarget {
+                workspace_root: self.workspace_root(),
+                attach_dir: &attach_dir,
+                agent_instance,
+                agent,
+                evidence: &evidence,
+                human_notes: if notes == HUMAN_NOTES_TEMPLATE {
+                    ""
+                } else {
+                    &notes
+                },
+            },
+        );
+        fs::create_dir_all(&agent_dir)?;
+        fs::write(
+            agent_dir.join(CONTEXT_RECORD_FILE_NAME),
+            serde_json::to_string_pretty(&resolved)?,
+        )?;
+        self.record_notes_seen(task_id, agent_instance, &notes)?;
+
+        if resolved.preamble.is_empty() {
+            return Ok(prompt);
+        }
+        Ok(format!("{}\n{prompt}", resolved.preamble))
+    }
+}
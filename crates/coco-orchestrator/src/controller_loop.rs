@@ -1,13 +1,30 @@
+use crate::agent_registry::default_output_schema_path;
+use crate::budget::BudgetEnforcement;
+use crate::notes::notes_steering_prompt;
+use crate::notes::NotesSteering;
+use crate::report::task_relative_path;
+use crate::rounds::RoundBoard;
+use crate::rounds::RoundStep;
+use crate::usage::USAGE_FILE_NAME;
+use crate::ControllerRound;
+use crate::ControllerRoundOptions;
 use crate::JoinTaskResponse;
 use crate::Orchestrator;
 use crate::OrchestratorError;
 use crate::SubagentSpawnRequest;
 use crate::SubagentStatus;
+use crate::SynthesisResult;
+use crate::SynthesizerOptions;
 use chrono::Utc;
+use coco_core::agent_spec::AgentSpec;
+use coco_core::agent_spec::AgentSpecFile;
+use coco_core::prompt_library::PromptLibrary;
 use coco_core::task::AgentInstance;
 use coco_core::task::AgentInstanceState;
+use coco_core::task::RetryPolicy;
 use coco_core::task::TaskEvent;
 use coco_core::task::TaskState;
+use coco_core::usage::format_cost_usd;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::json;
@@ -17,12 +34,22 @@ use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
+use std::time::Instant;
 
 const STATE_BOARD_FILE_NAME: &str = "state-board.md";
 const HUMAN_NOTES_FILE_NAME: &str = "human-notes.md";
 const EVIDENCE_INDEX_REL: &str = "./shared/evidence/index.json";
 const JOINED_SUMMARY_MD_REL: &str = "./shared/reports/joined-summary.md";
 const JOINED_SUMMARY_JSON_REL: &str = "./shared/reports/joined-summary.json";
+const SYNTHESIS_MD_REL: &str = "./shared/reports/synthesis.md";
+
+/// Prepended to app-server worker prompts, which have no CLI-level `--output-schema`.
+const WORKER_OUTPUT_CONTRACT: &str = "# Output Contract\nReturn ONLY valid JSON matching schemas/worker-output.schema.json.\nRequired keys: status, summary. Optional: questions, nextActions, errors.\n";
+
+const DEFAULT_ADAPTER: &str = "codex-exec";
+
+/// How often an app-server turn checks `shared/human-notes.md` for edits to steer with.
+const NOTES_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 const STATEBOARD_BEGIN: &str = "<!-- COCO:STATEBOARD:START -->";
 const STATEBOARD_END: &str = "<!-- COCO:STATEBOARD:END -->";
@@ -32,6 +59,8 @@ pub enum ControllerState {
     Dispatching,
     Monitoring,
     Joining,
+    Evaluating,
+    Synthesizing,
     Blocked,
     Done,
 }
@@ -42,6 +71,8 @@ impl ControllerState {
             ControllerState::Dispatching => "dispatching",
             ControllerState::Monitoring => "monitoring",
             ControllerState::Joining => "joining",
+            ControllerState::Evaluating => "evaluating",
+            ControllerState::Synthesizing => "synthesizing",
             ControllerState::Blocked => "blocked",
             ControllerState::Done => "done",
         }
@@ -52,6 +83,9 @@ impl ControllerState {
 pub enum ControllerOutcome {
     Done,
     Blocked,
+    /// Round mode: the goal was not met within `max_rounds`/`max_dispatched_workers`. Also returned when
+    /// the task budget (`config.budget`, `onExceed: cancel`) cancelled the workers.
+    BudgetExhausted,
 }
 
 #[derive(Debug, Clone)]
@@ -66,18 +100,27 @@ pub struct ControllerOptions {
     pub poll_interval: Duration,
     /// Timeout for wait-any calls (seconds). When None, uses task.yaml config.
     pub timeout_seconds: Option<u32>,
+    /// Optional synthesizer stage run after the join. When None, the loop ends at the join.
+    pub synthesizer: Option<SynthesizerOptions>,
+    /// Round mode: after each join a round planner decides whether to stop or dispatch another
+    /// batch. When None, the loop is single-shot.
+    pub rounds: Option<ControllerRoundOptions>,
+    /// When `shared/human-notes.md` changes, restart running codex-exec workers with the new
+    /// notes instead of only marking them as having stale context.
+    pub restart_stale_workers: bool,
 }
 
 impl ControllerOptions {
     pub fn new(workspace_root: &Path) -> Self {
         Self {
             codex_bin: PathBuf::from("codex"),
-            output_schema_path: workspace_root
-                .join("schemas")
-                .join("worker-output.schema.json"),
+            output_schema_path: default_output_schema_path(workspace_root),
             default_cwd: workspace_root.to_path_buf(),
             poll_interval: Duration::from_millis(250),
             timeout_seconds: None,
+            synthesizer: None,
+            rounds: None,
+            restart_stale_workers: false,
         }
     }
 }
@@ -86,33 +129,60 @@ impl ControllerOptions {
 pub struct ControllerRunResult {
     pub outcome: ControllerOutcome,
     pub joined_summary: Option<JoinTaskResponse>,
+    pub synthesis: Option<SynthesisResult>,
+    /// Completed rounds (round mode only; empty for single-shot runs).
+    pub rounds: Vec<ControllerRound>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrchestratorActions {
     pub session_goal: String,
     pub tasks: Vec<OrchestratorSubtask>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrchestratorSubtask {
     pub task_id: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub agent_instance: Option<String>,
     pub title: String,
     pub agent: String,
+    /// Empty means "use the agent spec's adapter" (falling back to `codex-exec`).
+    #[serde(default)]
     pub adapter: String,
-    pub prompt: String,
+    /// May be empty when `prompt_template` supplies the instructions.
     #[serde(default)]
+    pub prompt: String,
+    /// Custom prompt rendered at dispatch and placed before `prompt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<SubtaskPromptTemplate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub forked_from_thread_id: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cwd: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_schema_path: Option<String>,
+    /// Worker wall-clock limit (seconds); overrides `config.workerTimeoutSeconds`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u32>,
+    /// Retry policy for failed attempts; overrides `config.retry`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+}
+
+/// A prompt from the custom prompt library (`.coco/prompts`, `.codex/prompts`,
+/// `$CODEX_HOME/prompts`).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtaskPromptTemplate {
+    pub name: String,
+    /// Raw arguments, as typed after the prompt name in chat (`NAME=value` words fill `$NAME`).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub args: String,
 }
 
 impl OrchestratorSubtask {
@@ -137,6 +207,8 @@ impl Orchestrator {
     /// - dispatch subtasks
     /// - monitor until done/blocked
     /// - join and write shared reports
+    /// - in round mode, let a round planner re-plan until the goal is met or the budget runs out
+    /// - optionally synthesize the join into `shared/reports/synthesis.md`
     /// - maintain `shared/state-board.md`
     pub fn controller_run_actions(
         &self,
@@ -149,39 +221,128 @@ impl Orchestrator {
 
         self.set_task_state_working(task_id)?;
 
-        self.write_controller_state(
-            task_id,
-            ControllerState::Dispatching,
-            Some(&actions.session_goal),
-        )?;
-        self.write_state_board(task_id, &actions, ControllerState::Dispatching, None)?;
-
-        self.dispatch_actions(task_id, &actions, &opts)?;
+        let mut actions = actions;
+        let mut board = opts.rounds.as_ref().map(|r| RoundBoard::new(r.max_rounds));
+        let (outcome, joined) = loop {
+            self.write_controller_state(
+                task_id,
+                ControllerState::Dispatching,
+                Some(&actions.session_goal),
+            )?;
+            self.write_state_board(
+                task_id,
+                &actions,
+                ControllerState::Dispatching,
+                None,
+                board.as_ref(),
+            )?;
+
+            let outcome = match self.enforce_budget(task_id, actions.tasks.len() as u32)? {
+                BudgetEnforcement::Gated => ControllerOutcome::Blocked,
+                enforcement => {
+                    if enforcement == BudgetEnforcement::Within {
+                        self.dispatch_actions(task_id, &actions, &opts)?;
+                        if let Some(board) = board.as_mut() {
+                            board.dispatched_workers += actions.tasks.len() as u32;
+                        }
+                    }
 
-        self.write_controller_state(task_id, ControllerState::Monitoring, None)?;
-        self.write_state_board(task_id, &actions, ControllerState::Monitoring, None)?;
+                    self.write_controller_state(task_id, ControllerState::Monitoring, None)?;
+                    self.write_state_board(
+                        task_id,
+                        &actions,
+                        ControllerState::Monitoring,
+                        None,
+                        board.as_ref(),
+                    )?;
+
+                    match self.monitor_until_terminal(task_id, &actions, &opts)? {
+                        ControllerOutcome::Done if enforcement == BudgetEnforcement::Cancelled => {
+                            ControllerOutcome::BudgetExhausted
+                        }
+                        outcome => outcome,
+                    }
+                }
+            };
+            if outcome == ControllerOutcome::Blocked {
+                self.write_controller_state(task_id, ControllerState::Blocked, None)?;
+                self.write_state_board(
+                    task_id,
+                    &actions,
+                    ControllerState::Blocked,
+                    None,
+                    board.as_ref(),
+                )?;
+                return Ok(ControllerRunResult {
+                    outcome,
+                    joined_summary: None,
+                    synthesis: None,
+                    rounds: board.map(|b| b.history).unwrap_or_default(),
+                });
+            }
 
-        let outcome = self.monitor_until_terminal(task_id, &actions, &opts)?;
-        if outcome == ControllerOutcome::Blocked {
-            self.write_controller_state(task_id, ControllerState::Blocked, None)?;
-            self.write_state_board(task_id, &actions, ControllerState::Blocked, None)?;
-            return Ok(ControllerRunResult {
-                outcome,
-                joined_summary: None,
-            });
-        }
+            self.write_controller_state(task_id, ControllerState::Joining, None)?;
+            self.write_state_board(
+                task_id,
+                &actions,
+                ControllerState::Joining,
+                None,
+                board.as_ref(),
+            )?;
+
+            let joined = self.task_join(task_id)?;
+            if outcome == ControllerOutcome::BudgetExhausted {
+                break (outcome, joined);
+            }
 
-        self.write_controller_state(task_id, ControllerState::Joining, None)?;
-        self.write_state_board(task_id, &actions, ControllerState::Joining, None)?;
+            let (Some(round_opts), Some(board)) = (opts.rounds.as_ref(), board.as_mut()) else {
+                break (ControllerOutcome::Done, joined);
+            };
+            self.write_controller_state(task_id, ControllerState::Evaluating, None)?;
+            self.write_state_board(
+                task_id,
+                &actions,
+                ControllerState::Evaluating,
+                Some(&joined),
+                Some(board),
+            )?;
+            match self
+                .controller_round_step(task_id, &actions, &joined, round_opts, board, &opts)?
+            {
+                RoundStep::Finished(outcome) => break (outcome, joined),
+                RoundStep::Next(next) => actions = next,
+            }
+        };
 
-        let joined = self.task_join(task_id)?;
+        let synthesis = match &opts.synthesizer {
+            Some(synth) => {
+                self.write_controller_state(task_id, ControllerState::Synthesizing, None)?;
+                self.write_state_board(
+                    task_id,
+                    &actions,
+                    ControllerState::Synthesizing,
+                    Some(&joined),
+                    board.as_ref(),
+                )?;
+                Some(self.task_synthesize(task_id, &actions.session_goal, &opts, synth)?)
+            }
+            None => None,
+        };
 
         self.write_controller_state(task_id, ControllerState::Done, None)?;
-        self.write_state_board(task_id, &actions, ControllerState::Done, Some(&joined))?;
+        self.write_state_board(
+            task_id,
+            &actions,
+            ControllerState::Done,
+            Some(&joined),
+            board.as_ref(),
+        )?;
 
         Ok(ControllerRunResult {
-            outcome: ControllerOutcome::Done,
+            outcome,
             joined_summary: Some(joined),
+            synthesis,
+            rounds: board.map(|b| b.history).unwrap_or_default(),
         })
     }
 
@@ -203,6 +364,7 @@ impl Orchestrator {
         opts: &ControllerOptions,
     ) -> Result<(), OrchestratorError> {
         let mut remaining = actions.tasks.clone();
+        let task_retry = self.store.read_task(task_id)?.config.retry;
 
         // Skip tasks that already exist in the roster (supports "resume" by re-running the loop).
         let existing = self
@@ -216,7 +378,8 @@ impl Orchestrator {
         // and then continue.
         let mut idx = 0usize;
         while idx < remaining.len() {
-            let subtask = remaining[idx].clone();
+            let mut subtask = remaining[idx].clone();
+            subtask.adapter = self.resolve_adapter(&subtask)?;
             match subtask.adapter.as_str() {
                 "codex-exec" => match self.dispatch_codex_exec(task_id, &subtask, opts) {
                     Ok(()) => idx += 1,
@@ -224,6 +387,13 @@ impl Orchestrator {
                         // Wait for any running worker to finish, then retry this subtask.
                         let _ = self.subagent_wait_any(task_id, opts.timeout_seconds)?;
                     }
+                    Err(err @ (OrchestratorError::CodexNotFound | OrchestratorError::Io(_))) => {
+                        let policy = subtask.retry.as_ref().or(task_retry.as_ref());
+                        match self.schedule_spawn_retry(task_id, &subtask, policy)? {
+                            Some(backoff) => std::thread::sleep(backoff),
+                            None => return Err(err),
+                        }
+                    }
                     Err(err) => return Err(err),
                 },
                 "codex-app-server" => {
@@ -231,44 +401,78 @@ impl Orchestrator {
                     self.dispatch_codex_app_server(task_id, &subtask, opts)?;
                     idx += 1;
                 }
-                other => {
-                    return Err(OrchestratorError::Io(std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        format!("unsupported adapter: {other}"),
-                    )))
-                }
+                other => return Err(unsupported_adapter(other)),
             }
         }
 
         Ok(())
     }
 
+    /// The subtask's adapter, falling back to the agent spec's and then `codex-exec`.
+    fn resolve_adapter(&self, subtask: &OrchestratorSubtask) -> Result<String, OrchestratorError> {
+        if !subtask.adapter.is_empty() {
+            return Ok(subtask.adapter.clone());
+        }
+        Ok(self
+            .find_agent_spec(&subtask.agent)?
+            .and_then(|spec| spec.file.adapter)
+            .unwrap_or_else(|| DEFAULT_ADAPTER.to_string()))
+    }
+
     fn dispatch_codex_exec(
         &self,
         task_id: &str,
         subtask: &OrchestratorSubtask,
         opts: &ControllerOptions,
     ) -> Result<(), OrchestratorError> {
+        let req = self.exec_spawn_request(task_id, subtask, opts)?;
+        let _ = self.subagent_spawn(req)?;
+        Ok(())
+    }
+
+    /// The subtask's instructions, with its prompt template (if any) rendered in front.
+    fn subtask_prompt(&self, subtask: &OrchestratorSubtask) -> Result<String, OrchestratorError> {
+        let Some(template) = &subtask.prompt_template else {
+            return Ok(subtask.prompt.clone());
+        };
+        let rendered = PromptLibrary::discover(Some(self.workspace_root()))
+            .render(&template.name, &template.args)?;
+        let extra = subtask.prompt.trim();
+        if extra.is_empty() {
+            Ok(rendered.text)
+        } else {
+            Ok(format!("{}\n\n{extra}", rendered.text))
+        }
+    }
+
+    pub(crate) fn exec_spawn_request(
+        &self,
+        task_id: &str,
+        subtask: &OrchestratorSubtask,
+        opts: &ControllerOptions,
+    ) -> Result<SubagentSpawnRequest, OrchestratorError> {
         let agent_instance = subtask.resolved_agent_instance();
 
         let cwd = resolve_optional_path(&subtask.cwd, &opts.default_cwd);
-        let output_schema_path = subtask
-            .output_schema_path
-            .as_deref()
-            .map(|p| resolve_optional_path(&Some(p.to_string()), &opts.default_cwd))
-            .unwrap_or_else(|| opts.output_schema_path.clone());
+        // Precedence: subtask `outputSchemaPath` > agent spec `outputSchema` > controller default.
+        let output_schema_path = match subtask.output_schema_path.as_deref() {
+            Some(p) => resolve_optional_path(&Some(p.to_string()), &opts.default_cwd),
+            None => self
+                .find_agent_spec(&subtask.agent)?
+                .and_then(|spec| spec.output_schema_path(self.workspace_root()))
+                .unwrap_or_else(|| opts.output_schema_path.clone()),
+        };
 
-        let _ = self.subagent_spawn(SubagentSpawnRequest {
+        Ok(SubagentSpawnRequest {
             task_id: task_id.to_string(),
             agent_instance,
             agent: subtask.agent.clone(),
-            prompt: subtask.prompt.clone(),
+            prompt: self.subtask_prompt(subtask)?,
             cwd,
             codex_bin: opts.codex_bin.clone(),
             output_schema_path,
-        })?;
-
-        Ok(())
+            timeout_seconds: subtask.timeout_seconds,
+        })
     }
 
     fn dispatch_codex_app_server(
@@ -281,7 +485,14 @@ impl Orchestrator {
         // spawn a long-running process.
         let agent_instance = subtask.resolved_agent_instance();
         let cwd = resolve_optional_path(&subtask.cwd, &opts.default_cwd);
-        self.ensure_agent_instance(task_id, &agent_instance, &subtask.agent, &cwd)?;
+        let spec = self.find_agent_spec(&subtask.agent)?;
+        self.ensure_agent_instance(
+            task_id,
+            &agent_instance,
+            &subtask.agent,
+            &cwd,
+            spec.as_ref(),
+        )?;
 
         let task_dir = self.store.task_dir(task_id);
         let agent_dir = task_dir.join("agents").join(&agent_instance);
@@ -296,6 +507,16 @@ impl Orchestrator {
         let forked_from = subtask.forked_from_thread_id.clone();
 
         let final_path = agent_dir.join("artifacts").join("final.json");
+        let task_prompt = self.subtask_prompt(subtask)?;
+        let prompt = match &spec {
+            Some(spec) => spec.compose_prompt(&task_prompt),
+            None => task_prompt,
+        };
+        let prompt =
+            self.inject_worker_context(task_id, &agent_instance, &subtask.agent, prompt)?;
+        let thread_params = spec
+            .as_ref()
+            .and_then(|s| app_server_thread_params(&s.file));
 
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -308,8 +529,15 @@ impl Orchestrator {
                 &opts.codex_bin,
                 mode,
                 forked_from.as_deref(),
-                &subtask.prompt,
+                thread_params,
+                WORKER_OUTPUT_CONTRACT,
+                &prompt,
                 &final_path,
+                Some(&NotesSteering {
+                    orchestrator: self,
+                    task_id,
+                    agent_instance: &agent_instance,
+                }),
             )
             .await
         })?;
@@ -323,33 +551,78 @@ impl Orchestrator {
         actions: &OrchestratorActions,
         opts: &ControllerOptions,
     ) -> Result<ControllerOutcome, OrchestratorError> {
-        let expected_instances = actions
+        let expected = actions
             .tasks
             .iter()
-            .map(|t| t.resolved_agent_instance())
-            .collect::<std::collections::HashSet<_>>();
+            .map(|t| (t.resolved_agent_instance(), t))
+            .collect::<HashMap<_, _>>();
+        let task_retry = self.store.read_task(task_id)?.config.retry;
+        // Failed codex-exec attempts waiting out their backoff before a respawn.
+        let mut pending_retries: HashMap<String, Instant> = HashMap::new();
+        let mut budget_cancelled = false;
 
         loop {
+            match self.enforce_budget(task_id, 0)? {
+                BudgetEnforcement::Within => {}
+                BudgetEnforcement::Gated => return Ok(ControllerOutcome::Blocked),
+                BudgetEnforcement::Cancelled => {
+                    budget_cancelled = true;
+                    pending_retries.clear();
+                }
+            }
+
             let subagents = self.subagent_list(task_id)?;
+            self.sync_human_notes(task_id, &subagents, &expected, opts)?;
             let mut all_terminal = true;
             let mut any_blocked = false;
 
             for s in &subagents {
-                if !expected_instances.contains(&s.agent_instance) {
+                let Some(subtask) = expected.get(&s.agent_instance) else {
                     continue;
-                }
+                };
                 match s.status {
                     SubagentStatus::Running => all_terminal = false,
                     SubagentStatus::Blocked => any_blocked = true,
+                    SubagentStatus::Failed if pending_retries.contains_key(&s.agent_instance) => {
+                        all_terminal = false;
+                    }
+                    // app-server subtasks run synchronously during dispatch and are not retried.
+                    SubagentStatus::Failed if self.resolve_adapter(subtask)? == "codex-exec" => {
+                        let policy = subtask.retry.as_ref().or(task_retry.as_ref());
+                        if let Some(backoff) = self.schedule_retry(task_id, subtask, policy)? {
+                            pending_retries
+                                .insert(s.agent_instance.clone(), Instant::now() + backoff);
+                            all_terminal = false;
+                        }
+                    }
                     SubagentStatus::Completed
                     | SubagentStatus::Failed
                     | SubagentStatus::Cancelled => {}
                 }
             }
 
+            let ready = pending_retries
+                .iter()
+                .filter(|(_, at)| Instant::now() >= **at)
+                .map(|(instance, _)| instance.clone())
+                .collect::<Vec<_>>();
+            for instance in ready {
+                match self.respawn_worker(task_id, expected[&instance], opts) {
+                    Ok(()) => {
+                        pending_retries.remove(&instance);
+                    }
+                    // Try again on the next poll once a slot frees up.
+                    Err(OrchestratorError::ConcurrencyLimit { .. }) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+
             if any_blocked {
                 return Ok(ControllerOutcome::Blocked);
             }
+            if all_terminal && budget_cancelled {
+                return Ok(ControllerOutcome::BudgetExhausted);
+            }
             if all_terminal {
                 return Ok(ControllerOutcome::Done);
             }
@@ -376,6 +649,7 @@ impl Orchestrator {
         agent_instance: &str,
         agent_name: &str,
         cwd: &Path,
+        spec: Option<&AgentSpec>,
     ) -> Result<(), OrchestratorError> {
         let mut task = self.store.read_task(task_id)?;
         if task.roster.iter().any(|a| a.instance == agent_instance) {
@@ -387,7 +661,7 @@ impl Orchestrator {
             agent: agent_name.to_string(),
             state: AgentInstanceState::Active,
             assigned_milestone: None,
-            skills: Vec::new(),
+            skills: spec.map(|s| s.file.skills.clone()).unwrap_or_default(),
         });
         task.updated_at = Utc::now();
         self.store.write_task(&task)?;
@@ -402,6 +676,9 @@ impl Orchestrator {
             payload: json!({
                 "cwd": cwd.to_string_lossy(),
                 "adapter": "codex-app-server",
+                "agentSpec": spec.map(|s| s.dir.to_string_lossy()),
+                // App-server instances are added once and never retried.
+                "attempt": 1,
             }),
             by: Some("controller".to_string()),
             path: None,
@@ -442,6 +719,7 @@ impl Orchestrator {
         actions: &OrchestratorActions,
         controller_state: ControllerState,
         joined: Option<&JoinTaskResponse>,
+        rounds: Option<&RoundBoard>,
     ) -> Result<(), OrchestratorError> {
         let task = self.store.read_task(task_id)?;
         let subagents = self.subagent_list(task_id).unwrap_or_default();
@@ -464,6 +742,12 @@ impl Orchestrator {
             "- controllerState: `{}`\n",
             controller_state.as_str()
         ));
+        if let Some(rounds) = rounds {
+            managed.push_str(&format!(
+                "- round: `{}/{}`\n",
+                rounds.current, rounds.max_rounds
+            ));
+        }
         managed.push_str(&format!("- updatedAt: `{}`\n", now));
         managed.push_str(&format!(
             "- sessionGoal: {}\n\n",
@@ -488,13 +772,75 @@ impl Orchestrator {
         }
         managed.push('\n');
 
+        if let Some(rounds) = rounds.filter(|r| !r.history.is_empty()) {
+            let task_dir = self.store.task_dir(task_id);
+            managed.push_str("## Rounds\n\n");
+            for r in &rounds.history {
+                managed.push_str(&format!(
+                    "- round `{}` workers=`{}` decision=`{}` report=`{}` reason=\"{}\"\n",
+                    r.round,
+                    r.agent_instances.join(", "),
+                    r.decision.as_str(),
+                    task_relative_path(&task_dir, &r.report_dir),
+                    escape_markdown_inline(&r.reason)
+                ));
+            }
+            managed.push('\n');
+        }
+
+        if let Ok(usage) = self.task_usage(task_id) {
+            managed.push_str("## Usage\n\n");
+            managed.push_str(&format!("- total: `{}`\n", usage.usage));
+            if let Some(cost) = usage.cost_usd {
+                managed.push_str(&format!("- cost: `{}`\n", format_cost_usd(cost)));
+            }
+            for w in usage.workers.iter().filter(|w| !w.usage.is_empty()) {
+                managed.push_str(&format!(
+                    "- `{}` model=`{}` tokens=`{}` cost=`{}`\n",
+                    w.agent_instance,
+                    w.model.as_deref().unwrap_or("unknown"),
+                    w.usage.total_tokens(),
+                    w.cost_usd.map(format_cost_usd).as_deref().unwrap_or("-")
+                ));
+            }
+            managed.push('\n');
+        }
+
+        if let Some(budget) = self.budget_status(task_id).ok().flatten() {
+            managed.push_str("## Budget\n\n");
+            managed.push_str(&format!(
+                "- level: `{}` onExceed: `{}` extensions: `{}`\n",
+                budget.level().as_str(),
+                budget.on_exceed.as_str(),
+                budget.extensions
+            ));
+            for m in &budget.metrics {
+                managed.push_str(&format!(
+                    "- {}: `{}` ({})\n",
+                    m.name,
+                    m.display(),
+                    m.level.as_str()
+                ));
+            }
+            managed.push('\n');
+        }
+
         managed.push_str("## Key Artifacts\n\n");
         managed.push_str(&format!("- joinedSummaryMd: `{}`\n", JOINED_SUMMARY_MD_REL));
         managed.push_str(&format!(
             "- joinedSummaryJson: `{}`\n",
             JOINED_SUMMARY_JSON_REL
         ));
+        if self
+            .store
+            .task_dir(task_id)
+            .join(SYNTHESIS_MD_REL)
+            .is_file()
+        {
+            managed.push_str(&format!("- synthesisMd: `{}`\n", SYNTHESIS_MD_REL));
+        }
         managed.push_str(&format!("- evidenceIndex: `{}`\n", EVIDENCE_INDEX_REL));
+        managed.push_str(&format!("- usage: `./shared/{}`\n", USAGE_FILE_NAME));
         managed.push_str(&format!(
             "- humanNotes: `./shared/{}`\n",
             HUMAN_NOTES_FILE_NAME
@@ -517,14 +863,18 @@ impl Orchestrator {
     }
 }
 
-async fn run_app_server_one_turn(
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_app_server_one_turn(
     agent_dir: &Path,
     cwd: &Path,
     codex_bin: &Path,
     mode: &str,
     forked_from_thread_id: Option<&str>,
+    thread_params: Option<Value>,
+    output_contract: &str,
     prompt: &str,
     final_output_path: &Path,
+    steering: Option<&NotesSteering<'_>>,
 ) -> Result<(), OrchestratorError> {
     use coco_codex::CodexAppServerClient;
     use coco_codex::CodexAppServerSpawnRequest;
@@ -543,30 +893,43 @@ async fn run_app_server_one_turn(
                     "mode=fork requires forkedFromThreadId",
                 ))
             })?;
-            client.thread_fork(parent, None).await?
+            client.thread_fork(parent, thread_params).await?
         }
-        _ => client.thread_start(None).await?,
+        _ => client.thread_start(thread_params).await?,
     };
 
-    let wrapped_prompt = format!(
-        "{}\n\n{}\n",
-        "# Output Contract\nReturn ONLY valid JSON matching schemas/worker-output.schema.json.\nRequired keys: status, summary. Optional: questions, nextActions, errors.\n",
-        prompt
-    );
+    let wrapped_prompt = format!("{output_contract}\n\n{prompt}\n");
 
     let params = json!({
         "threadId": thread_id,
         "input": [{ "type": "text", "text": wrapped_prompt }],
     });
 
-    let _ = client.turn_start(params).await?;
+    let mut turn_id = turn_id_of(&client.turn_start(params).await?);
 
     let mut last_agent_message_text: Option<String> = None;
     let mut blocked_reason: Option<String> = None;
     let mut turn_done = false;
+    // Notes edit waiting for the interrupted turn to finish before it is sent as a new turn.
+    let mut pending_steer: Option<(String, String)> = None;
+    let mut notes_poll = tokio::time::interval(NOTES_POLL_INTERVAL);
 
     while !turn_done {
-        let evt = match events.recv().await {
+        let received = tokio::select! {
+            received = events.recv() => received,
+            _ = notes_poll.tick(), if steering.is_some() && pending_steer.is_none() => {
+                if let (Some(steering), Some(current)) = (steering, turn_id.as_deref()) {
+                    if let Some(pending) = steering.pending()? {
+                        client
+                            .turn_interrupt(json!({ "threadId": thread_id, "turnId": current }))
+                            .await?;
+                        pending_steer = Some(pending);
+                    }
+                }
+                continue;
+            }
+        };
+        let evt = match received {
             Ok(evt) => evt,
             Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
             Err(_) => break,
@@ -601,8 +964,25 @@ async fn run_app_server_one_turn(
                     }
                 }
 
+                if method == "turn/started" {
+                    turn_id = params.get("turn").and_then(turn_id_of).or(turn_id);
+                }
                 if method == "turn/completed" {
-                    turn_done = true;
+                    match (pending_steer.take(), steering) {
+                        (Some((diff, notes)), Some(steering)) => {
+                            let params = json!({
+                                "threadId": thread_id,
+                                "input": [{
+                                    "type": "text",
+                                    "text": notes_steering_prompt(&diff, output_contract),
+                                }],
+                            });
+                            turn_id = turn_id_of(&client.turn_start(params).await?);
+                            steering.delivered(&diff, &notes)?;
+                            last_agent_message_text = None;
+                        }
+                        _ => turn_done = true,
+                    }
                 }
                 if method == "error" {
                     blocked_reason = Some("app-server error".to_string());
@@ -651,6 +1031,38 @@ async fn run_app_server_one_turn(
     Ok(())
 }
 
+/// `turn/start` responses and `turn/started` notifications carry `{ turn: { id } }`.
+fn turn_id_of(value: &Value) -> Option<String> {
+    value
+        .get("turn")
+        .unwrap_or(value)
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// `thread/start` / `thread/fork` overrides for an agent spec's model/sandbox/approval defaults.
+fn app_server_thread_params(spec: &AgentSpecFile) -> Option<Value> {
+    let mut params = serde_json::Map::new();
+    if let Some(model) = spec.model.as_deref() {
+        params.insert("model".to_string(), json!(model));
+    }
+    if let Some(sandbox) = spec.sandbox {
+        params.insert("sandbox".to_string(), json!(sandbox.as_str()));
+    }
+    if let Some(policy) = spec.approval_policy {
+        params.insert("approvalPolicy".to_string(), json!(policy.as_str()));
+    }
+    (!params.is_empty()).then_some(Value::Object(params))
+}
+
+pub(crate) fn unsupported_adapter(adapter: &str) -> OrchestratorError {
+    OrchestratorError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("unsupported adapter: {adapter}"),
+    ))
+}
+
 fn resolve_optional_path(value: &Option<String>, default_base: &Path) -> PathBuf {
     match value.as_deref() {
         None => default_base.to_path_buf(),
@@ -703,7 +1115,7 @@ fn escape_markdown_inline(value: &str) -> String {
     value.replace('`', "\\`")
 }
 
-fn extract_json_from_text(text: &str) -> Option<String> {
+pub(crate) fn extract_json_from_text(text: &str) -> Option<String> {
     let trimmed = text.trim();
     if trimmed.starts_with('{') && trimmed.ends_with('}') {
         return Some(trimmed.to_string());
@@ -0,0 +1,134 @@
+use crate::validate_agent_instance;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use chrono::Utc;
+use coco_core::task::Gate;
+use coco_core::task::GateState;
+use coco_core::task::GateType;
+use coco_core::task::TaskState;
+use serde_json::json;
+
+/// Gates opened through `gate_request` without an explicit id are `request-1`, `request-2`, …
+const REQUESTED_GATE_PREFIX: &str = "request-";
+
+impl Orchestrator {
+    /// Open a blocked `human-approval` gate on behalf of a worker or tool (the task moves to
+    /// `input-required` until it is resolved).
+    pub fn gate_request(
+        &self,
+        task_id: &str,
+        gate_id: Option<&str>,
+        reason: &str,
+        instructions_ref: Option<&str>,
+        by: Option<&str>,
+    ) -> Result<Gate, OrchestratorError> {
+        let mut task = self.store.read_task(task_id)?;
+        let gate_id = match gate_id {
+            Some(gate_id) => {
+                if validate_agent_instance(gate_id).is_err() {
+                    return Err(OrchestratorError::InvalidGateId {
+                        gate_id: gate_id.to_string(),
+                    });
+                }
+                gate_id.to_string()
+            }
+            None => {
+                let last = task
+                    .gates
+                    .iter()
+                    .filter_map(|g| g.id.strip_prefix(REQUESTED_GATE_PREFIX))
+                    .filter_map(|n| n.parse::<u32>().ok())
+                    .max()
+                    .unwrap_or(0);
+                format!("{REQUESTED_GATE_PREFIX}{}", last + 1)
+            }
+        };
+        if task.gates.iter().any(|g| g.id == gate_id) {
+            return Err(OrchestratorError::GateAlreadyExists { gate_id });
+        }
+
+        let now = Utc::now();
+        let gate = Gate {
+            id: gate_id.clone(),
+            gate_type: GateType::HumanApproval,
+            state: GateState::Blocked,
+            reason: reason.to_string(),
+            instructions_ref: instructions_ref.map(|v| v.to_string()),
+            blocked_at: Some(now),
+            resolved_at: None,
+            resolved_by: None,
+        };
+        task.gates.push(gate.clone());
+        if matches!(task.state, TaskState::Created | TaskState::Working) {
+            task.state = TaskState::InputRequired;
+        }
+        task.updated_at = now;
+        self.store.write_task(&task)?;
+
+        self.append_gate_event(
+            task_id,
+            &gate_id,
+            None,
+            "gate.blocked",
+            json!({ "reason": reason, "requestedBy": by }),
+        )?;
+        Ok(gate)
+    }
+
+    /// Approve or reject any gate of the task by id (plan, budget or otherwise).
+    pub fn gate_resolve(
+        &self,
+        task_id: &str,
+        gate_id: &str,
+        approved: bool,
+        by: Option<&str>,
+    ) -> Result<(), OrchestratorError> {
+        self.resolve_gate(task_id, gate_id, approved, by, json!({ "by": by }))
+    }
+
+    /// Record the decision on the gate, leave `input-required` once nothing is blocked any more
+    /// and emit `gate.approved` / `gate.rejected` with `payload`. Only blocked gates can be
+    /// resolved; a decision is never overwritten.
+    pub(crate) fn resolve_gate(
+        &self,
+        task_id: &str,
+        gate_id: &str,
+        approved: bool,
+        by: Option<&str>,
+        payload: serde_json::Value,
+    ) -> Result<(), OrchestratorError> {
+        let mut task = self.store.read_task(task_id)?;
+        let Some(gate) = task.gates.iter_mut().find(|g| g.id == gate_id) else {
+            return Err(OrchestratorError::GateNotFound {
+                gate_id: gate_id.to_string(),
+            });
+        };
+        if gate.state != GateState::Blocked {
+            return Err(OrchestratorError::GateNotPending {
+                gate_id: gate_id.to_string(),
+            });
+        }
+        let now = Utc::now();
+        gate.state = if approved {
+            GateState::Approved
+        } else {
+            GateState::Rejected
+        };
+        gate.resolved_at = Some(now);
+        gate.resolved_by = by.map(|v| v.to_string());
+
+        let still_blocked = task.gates.iter().any(|g| g.state == GateState::Blocked);
+        if task.state == TaskState::InputRequired && !still_blocked {
+            task.state = TaskState::Working;
+        }
+        task.updated_at = now;
+        self.store.write_task(&task)?;
+
+        let event_type = if approved {
+            "gate.approved"
+        } else {
+            "gate.rejected"
+        };
+        self.append_gate_event(task_id, gate_id, None, event_type, payload)
+    }
+}
@@ -1,4 +1,10 @@
+use chrono::DateTime;
 use chrono::Utc;
+use coco_core::agent_spec::AgentSpecFile;
+use coco_core::evidence::EvidenceCheckReport;
+use coco_core::evidence::EvidenceEntry;
+use coco_core::evidence::EvidenceSource;
+use coco_core::evidence::ResolvedEvidence;
 use coco_core::task::AgentInstance;
 use coco_core::task::AgentInstanceState;
 use coco_core::task::ClusterStatus;
@@ -12,9 +18,12 @@ use coco_core::task::TaskFile;
 use coco_core::task::TaskState;
 use coco_core::task_store::TaskStore;
 use coco_core::task_store::TaskStoreError;
+use serde::Deserialize;
+use serde::Serialize;
 use serde_json::json;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fs;
 use std::io;
 use std::io::Write;
@@ -26,9 +35,26 @@ use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 
+mod agent_registry;
+mod budget;
 mod codex_app_server_adapter;
+mod context;
 mod controller_loop;
-
+mod gates;
+mod notes;
+mod oneshot;
+mod planner;
+mod process;
+mod report;
+mod retry;
+mod rounds;
+mod synthesizer;
+mod usage;
+
+pub use agent_registry::AgentSpecValidation;
+pub use budget::BudgetLevel;
+pub use budget::BudgetMetric;
+pub use budget::BudgetStatus;
 pub use codex_app_server_adapter::CodexAppServerAdapter;
 pub use controller_loop::ControllerOptions;
 pub use controller_loop::ControllerOutcome;
@@ -36,6 +62,20 @@ pub use controller_loop::ControllerRunResult;
 pub use controller_loop::ControllerState;
 pub use controller_loop::OrchestratorActions;
 pub use controller_loop::OrchestratorSubtask;
+pub use controller_loop::SubtaskPromptTemplate;
+pub use planner::PlanResult;
+pub use planner::PlannerOptions;
+pub use planner::PLANNER_AGENT_INSTANCE;
+pub use report::RenderedReport;
+pub use report::ReportTemplateInfo;
+pub use report::JOINED_SUMMARY_TEMPLATE_NAME;
+pub use rounds::ControllerRound;
+pub use rounds::ControllerRoundOptions;
+pub use rounds::RoundDecision;
+pub use rounds::ROUND_PLANNER_AGENT_INSTANCE;
+pub use synthesizer::SynthesisResult;
+pub use synthesizer::SynthesizerOptions;
+pub use synthesizer::SYNTHESIZER_AGENT_INSTANCE;
 
 #[derive(Debug, Clone)]
 pub struct Orchestrator {
@@ -64,6 +104,43 @@ pub enum OrchestratorError {
     WaitAnyTimeout { timeout_seconds: u32 },
     #[error("codex binary not found on PATH")]
     CodexNotFound,
+    #[error("evidence not found: {evidence_id}")]
+    EvidenceNotFound { evidence_id: String },
+    #[error("invalid evidence id: {evidence_id}")]
+    InvalidEvidenceId { evidence_id: String },
+    #[error("evidence already exists: {evidence_id}")]
+    EvidenceAlreadyExists { evidence_id: String },
+    #[error("{0}")]
+    Template(#[from] coco_core::template::TemplateError),
+    #[error("{0}")]
+    AgentSpec(#[from] coco_core::agent_spec::AgentSpecError),
+    #[error("{0}")]
+    PriceTable(#[from] coco_core::usage::PriceTableError),
+    #[error("{0}")]
+    ContextManifest(#[from] coco_core::context_manifest::ContextManifestError),
+    #[error("{0}")]
+    Prompt(#[from] coco_core::prompt_library::PromptError),
+    #[error("report template not found: {template}")]
+    TemplateNotFound { template: String },
+    #[error("plan not found: {plan_number}")]
+    PlanNotFound { plan_number: u32 },
+    #[error("invalid plan: {message}")]
+    InvalidPlan { message: String },
+    #[error("gate not found: {gate_id}")]
+    GateNotFound { gate_id: String },
+    #[error("gate not approved: {gate_id}")]
+    GateNotApproved { gate_id: String },
+    #[error("gate is not pending: {gate_id}")]
+    GateNotPending { gate_id: String },
+    #[error("invalid gate id: {gate_id}")]
+    InvalidGateId { gate_id: String },
+    #[error("gate already exists: {gate_id}")]
+    GateAlreadyExists { gate_id: String },
+    #[error("invalid output from {agent_instance}: {message}")]
+    InvalidWorkerOutput {
+        agent_instance: String,
+        message: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -92,6 +169,10 @@ pub struct SubagentInfo {
     pub agent_instance: String,
     pub agent: String,
     pub status: SubagentStatus,
+    /// Worker exit code recorded by the supervisor (`runtime/exit-status`), once it exited.
+    pub exit_code: Option<i32>,
+    /// `shared/human-notes.md` changed after this (running codex-exec) worker started.
+    pub stale_context: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -103,6 +184,8 @@ pub struct SubagentSpawnRequest {
     pub cwd: PathBuf,
     pub codex_bin: PathBuf,
     pub output_schema_path: PathBuf,
+    /// Wall-clock limit (seconds). Overrides `task.yaml` `config.workerTimeoutSeconds`.
+    pub timeout_seconds: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -120,8 +203,6 @@ pub struct SubagentWaitAnyResult {
 const TASK_AGENTS_DIR_NAME: &str = "agents";
 const TASK_SHARED_DIR_NAME: &str = "shared";
 const TASK_SHARED_REPORTS_DIR_NAME: &str = "reports";
-const TASK_SHARED_EVIDENCE_DIR_NAME: &str = "evidence";
-const TASK_SHARED_EVIDENCE_INDEX_FILE_NAME: &str = "index.json";
 const RUNTIME_DIR_NAME: &str = "runtime";
 const ARTIFACTS_DIR_NAME: &str = "artifacts";
 const CODEX_HOME_DIR_NAME: &str = "codex_home";
@@ -129,10 +210,15 @@ const CODEX_HOME_DIR_NAME: &str = "codex_home";
 const RUNTIME_EVENTS_FILE_NAME: &str = "events.jsonl";
 const RUNTIME_STDERR_FILE_NAME: &str = "stderr.log";
 const RUNTIME_PID_FILE_NAME: &str = "pid";
+const RUNTIME_DEADLINE_FILE_NAME: &str = "deadline.json";
+/// `runtime/prompt.md`: the composed worker prompt, fed to `codex exec -` on stdin so it stays
+/// out of the process command line (and `process.json`).
+const RUNTIME_PROMPT_FILE_NAME: &str = "prompt.md";
 const FINAL_OUTPUT_FILE_NAME: &str = "final.json";
 const SESSION_FILE_NAME: &str = "session.json";
 const JOINED_SUMMARY_MD_FILE_NAME: &str = "joined-summary.md";
 const JOINED_SUMMARY_JSON_FILE_NAME: &str = "joined-summary.json";
+const WORKER_EVIDENCE_ID_PREFIX: &str = "worker-";
 
 const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
 const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(3);
@@ -144,50 +230,6 @@ pub struct JoinTaskResponse {
     pub joined_summary_json: PathBuf,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-struct EvidenceEntry {
-    id: String,
-    kind: String,
-    title: String,
-    summary: String,
-    created_at: String,
-    sources: Vec<EvidenceSource>,
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    artifact_refs: Vec<String>,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
-#[serde(
-    tag = "type",
-    rename_all = "camelCase",
-    rename_all_fields = "camelCase"
-)]
-enum EvidenceSource {
-    FileAnchor {
-        path: String,
-        start_line: u32,
-        end_line: u32,
-    },
-    CommandExecution {
-        command: String,
-        cwd: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        exit_code: Option<i32>,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        stdout_ref: Option<String>,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        stderr_ref: Option<String>,
-    },
-    RuntimeEventRange {
-        events_ref: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        start_line: Option<u32>,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        end_line: Option<u32>,
-    },
-}
-
 impl Orchestrator {
     pub fn new(workspace_root: PathBuf) -> Self {
         Self {
@@ -226,26 +268,40 @@ impl Orchestrator {
     }
 
     pub fn cluster_status(&self) -> ClusterStatus {
+        let (usage, cost_usd) = self.recorded_usage_totals();
         ClusterStatus {
             orchestrator: "online".to_string(),
             codex_adapter: "disconnected".to_string(),
             active_agents: 0,
             max_agents: 8,
+            usage,
+            cost_usd,
         }
     }
 
     pub fn subagent_spawn(
         &self,
         req: SubagentSpawnRequest,
+    ) -> Result<SubagentSpawnResponse, OrchestratorError> {
+        self.spawn_exec_worker(req, false)
+    }
+
+    /// With `respawn`, an instance already in the roster is started again (its previous attempt
+    /// must have been archived under `attempts/<n>/`).
+    fn spawn_exec_worker(
+        &self,
+        req: SubagentSpawnRequest,
+        respawn: bool,
     ) -> Result<SubagentSpawnResponse, OrchestratorError> {
         validate_agent_instance(&req.agent_instance)?;
         validate_agent_instance(&req.agent)?;
 
         let reconcile = self.reconcile_subagents(&req.task_id)?;
-        if reconcile
-            .subagents
-            .iter()
-            .any(|a| a.agent_instance == req.agent_instance)
+        if !respawn
+            && reconcile
+                .subagents
+                .iter()
+                .any(|a| a.agent_instance == req.agent_instance)
         {
             return Err(OrchestratorError::SubagentAlreadyExists {
                 agent_instance: req.agent_instance,
@@ -264,11 +320,13 @@ impl Orchestrator {
 
         let task_dir = self.store.task_dir(&req.task_id);
         let agent_dir = agent_dir(&task_dir, &req.agent_instance);
-        if agent_dir.exists() {
+        // Only `attempts/` (and `codex_home/`) may survive from earlier attempts.
+        if agent_dir.join(RUNTIME_DIR_NAME).exists() {
             return Err(OrchestratorError::SubagentAlreadyExists {
                 agent_instance: req.agent_instance,
             });
         }
+        let attempt = retry::current_attempt(&agent_dir)?;
 
         let runtime_dir = agent_dir.join(RUNTIME_DIR_NAME);
         let artifacts_dir = agent_dir.join(ARTIFACTS_DIR_NAME);
@@ -280,13 +338,34 @@ impl Orchestrator {
         let events_path = runtime_dir.join(RUNTIME_EVENTS_FILE_NAME);
         let stderr_path = runtime_dir.join(RUNTIME_STDERR_FILE_NAME);
         let pid_path = runtime_dir.join(RUNTIME_PID_FILE_NAME);
+        let process_path = runtime_dir.join(process::RUNTIME_PROCESS_FILE_NAME);
+        let exit_status_path = runtime_dir.join(process::RUNTIME_EXIT_STATUS_FILE_NAME);
+        let deadline_path = runtime_dir.join(RUNTIME_DEADLINE_FILE_NAME);
+        let prompt_path = runtime_dir.join(RUNTIME_PROMPT_FILE_NAME);
         let session_path = agent_dir.join(SESSION_FILE_NAME);
         let final_output_path = artifacts_dir.join(FINAL_OUTPUT_FILE_NAME);
 
         let _ = fs::remove_file(&final_output_path);
+        let _ = fs::remove_file(&exit_status_path);
+        let Some(codex_bin) = process::resolve_program(&req.codex_bin) else {
+            return Err(OrchestratorError::CodexNotFound);
+        };
+        let timeout_seconds = req
+            .timeout_seconds
+            .or(reconcile.task.config.worker_timeout_seconds);
 
         write_session_file(&session_path, &req.cwd, &codex_home_dir)?;
 
+        let spec = self.find_agent_spec(&req.agent)?;
+        let prompt = match &spec {
+            Some(spec) => spec.compose_prompt(&req.prompt),
+            None => req.prompt.clone(),
+        };
+        let prompt =
+            self.inject_worker_context(&req.task_id, &req.agent_instance, &req.agent, prompt)?;
+        fs::write(&prompt_path, &prompt)?;
+
+        let prompt_file = fs::File::open(&prompt_path)?;
         let events_file = fs::OpenOptions::new()
             .create(true)
             .write(true)
@@ -298,21 +377,20 @@ impl Orchestrator {
             .truncate(true)
             .open(&stderr_path)?;
 
-        let mut cmd = Command::new(&req.codex_bin);
-        cmd.arg("exec")
-            .arg("--json")
-            .arg("-C")
-            .arg(&req.cwd)
-            .arg("--output-schema")
-            .arg(&req.output_schema_path)
-            .arg("--output-last-message")
-            .arg(&final_output_path)
-            .arg(&req.prompt)
-            .env("CODEX_HOME", &codex_home_dir)
-            .stdin(Stdio::null())
-            .stdout(Stdio::from(events_file))
-            .stderr(Stdio::from(stderr_file))
-            .current_dir(&req.cwd);
+        let mut cmd = codex_exec_command(
+            &CodexExecInvocation {
+                codex_bin: &codex_bin,
+                cwd: &req.cwd,
+                output_schema_path: &req.output_schema_path,
+                final_output_path: &final_output_path,
+                codex_home: &codex_home_dir,
+                spec: spec.as_ref().map(|s| &s.file),
+                exit_status_path: Some(&exit_status_path),
+            },
+            prompt_file,
+            events_file,
+            stderr_file,
+        );
 
         let child = match cmd.spawn() {
             Ok(child) => child,
@@ -325,6 +403,12 @@ impl Orchestrator {
         let pid = child.id();
         drop(child);
 
+        let identity = process::ProcessIdentity {
+            prompt_path: Some(prompt_path),
+            ..process::ProcessIdentity::capture(pid, &cmd, process::SUPERVISOR_PROCESS_GROUP)
+        };
+        fs::write(&process_path, serde_json::to_string_pretty(&identity)?)?;
+        // Bare pid kept for older readers; liveness checks use `process.json`.
         fs::OpenOptions::new()
             .create(true)
             .write(true)
@@ -332,14 +416,35 @@ impl Orchestrator {
             .open(&pid_path)?
             .write_all(format!("{pid}\n").as_bytes())?;
 
+        if let Some(timeout_seconds) = timeout_seconds {
+            let started_at = Utc::now();
+            let deadline = WorkerDeadline {
+                started_at,
+                timeout_seconds,
+                deadline: started_at + chrono::Duration::seconds(i64::from(timeout_seconds)),
+            };
+            fs::write(&deadline_path, serde_json::to_string_pretty(&deadline)?)?;
+        }
+
         let mut task = reconcile.task;
-        task.roster.push(AgentInstance {
+        let entry = AgentInstance {
             instance: req.agent_instance.clone(),
             agent: req.agent,
             state: AgentInstanceState::Active,
             assigned_milestone: None,
-            skills: Vec::new(),
-        });
+            skills: spec
+                .as_ref()
+                .map(|s| s.file.skills.clone())
+                .unwrap_or_default(),
+        };
+        match task
+            .roster
+            .iter_mut()
+            .find(|a| a.instance == req.agent_instance)
+        {
+            Some(existing) => *existing = entry,
+            None => task.roster.push(entry),
+        }
         task.updated_at = Utc::now();
         self.store.write_task(&task)?;
 
@@ -350,6 +455,9 @@ impl Orchestrator {
             json!({
                 "cwd": req.cwd.to_string_lossy(),
                 "adapter": "codex-exec",
+                "agentSpec": spec.as_ref().map(|s| s.dir.to_string_lossy()),
+                "timeoutSeconds": timeout_seconds,
+                "attempt": attempt,
             }),
         )?;
 
@@ -415,13 +523,12 @@ impl Orchestrator {
         let agent_dir = agent_dir(&task_dir, agent_instance);
         let runtime_dir = agent_dir.join(RUNTIME_DIR_NAME);
         let pid_path = runtime_dir.join(RUNTIME_PID_FILE_NAME);
-        let pid = read_pid(&pid_path)?;
 
-        if let Some(pid) = pid {
-            cancel_pid(pid)?;
+        if let Some(process) = process::read_process_identity(&runtime_dir)? {
+            process.cancel()?;
 
             // If we can confirm exit, remove pid to avoid stale "running" counts.
-            if !pid_is_alive(pid)? {
+            if !process.is_alive()? {
                 let _ = fs::remove_file(&pid_path);
             }
         }
@@ -453,71 +560,23 @@ impl Orchestrator {
             .join(TASK_SHARED_REPORTS_DIR_NAME);
         fs::create_dir_all(&reports_dir)?;
 
-        let evidence_dir = task_dir
-            .join(TASK_SHARED_DIR_NAME)
-            .join(TASK_SHARED_EVIDENCE_DIR_NAME);
-        fs::create_dir_all(&evidence_dir)?;
-        let evidence_index_path = evidence_dir.join(TASK_SHARED_EVIDENCE_INDEX_FILE_NAME);
-
-        let mut workers = Vec::new();
-        for agent in &task.roster {
-            let final_path = agent_dir(&task_dir, &agent.instance)
-                .join(ARTIFACTS_DIR_NAME)
-                .join(FINAL_OUTPUT_FILE_NAME);
-            let final_output = read_worker_final_output(&final_path)?;
-            workers.push(JoinedWorkerSummary {
-                agent_instance: agent.instance.clone(),
-                agent: agent.agent.clone(),
-                status: final_output.status,
-                summary: final_output.summary,
-                questions: final_output.questions,
-                next_actions: final_output.next_actions,
-            });
-        }
+        let workers = report::collect_worker_summaries(&task, &task_dir)?;
 
         let generated_at = Utc::now();
 
-        // Evidence Index: keep the evidence entries small and reference the raw recordings/artifacts.
-        // For now, join produces a minimal per-worker evidence entry so reports can cite it.
-        let evidence_entries = workers
-            .iter()
-            .map(|w| {
-                let evidence_id = evidence_id_for_agent_instance(&w.agent_instance);
-                EvidenceEntry {
-                    id: evidence_id.clone(),
-                    kind: "runtime-event-range".to_string(),
-                    title: format!("Worker {} runtime", w.agent_instance),
-                    summary: format!("[{}] {}", w.status, w.summary.trim())
-                        .trim()
-                        .to_string(),
-                    created_at: generated_at.to_rfc3339(),
-                    sources: vec![EvidenceSource::RuntimeEventRange {
-                        events_ref: format!(
-                            "./agents/{}/runtime/{}",
-                            w.agent_instance, RUNTIME_EVENTS_FILE_NAME
-                        ),
-                        start_line: None,
-                        end_line: None,
-                    }],
-                    artifact_refs: vec![
-                        format!(
-                            "./agents/{}/artifacts/{}",
-                            w.agent_instance, FINAL_OUTPUT_FILE_NAME
-                        ),
-                        format!(
-                            "./agents/{}/runtime/{}",
-                            w.agent_instance, RUNTIME_EVENTS_FILE_NAME
-                        ),
-                    ],
-                }
-            })
-            .collect::<Vec<_>>();
-        fs::write(
-            &evidence_index_path,
-            serde_json::to_string_pretty(&evidence_entries)?,
+        let evidence_entries = self.worker_evidence_entries(task_id, &workers, generated_at)?;
+        self.store
+            .write_evidence_index(task_id, &evidence_entries)?;
+        let usage = self.record_task_usage(task_id)?;
+
+        let markdown = self.render_report(
+            JOINED_SUMMARY_TEMPLATE_NAME,
+            &task,
+            generated_at,
+            &workers,
+            &evidence_entries,
+            &usage,
         )?;
-
-        let markdown = render_joined_summary_markdown(&task, generated_at, &workers);
         let md_path = reports_dir.join(JOINED_SUMMARY_MD_FILE_NAME);
         fs::write(&md_path, markdown)?;
 
@@ -532,8 +591,14 @@ impl Orchestrator {
                     "summary": w.summary,
                     "questions": w.questions,
                     "nextActions": w.next_actions,
+                    "attempt": w.attempt,
+                    "usage": usage.workers.iter().find(|u| u.agent_instance == w.agent_instance),
                 })
             }).collect::<Vec<_>>(),
+            "usage": {
+                "tokens": usage.usage,
+                "costUsd": usage.cost_usd,
+            },
         });
         let json_path = reports_dir.join(JOINED_SUMMARY_JSON_FILE_NAME);
         fs::write(&json_path, serde_json::to_string_pretty(&json_value)?)?;
@@ -544,6 +609,125 @@ impl Orchestrator {
         })
     }
 
+    /// Evidence Index: keep the evidence entries small and reference the raw recordings/artifacts.
+    /// The index with one minimal `worker-<instance>` entry per worker (re)built in memory so
+    /// reports can cite it; entries added by other producers are kept as-is. Join writes it back.
+    pub(crate) fn worker_evidence_entries(
+        &self,
+        task_id: &str,
+        workers: &[JoinedWorkerSummary],
+        generated_at: chrono::DateTime<Utc>,
+    ) -> Result<Vec<EvidenceEntry>, OrchestratorError> {
+        let mut entries = self.store.read_evidence_index(task_id)?;
+        for w in workers {
+            let entry = EvidenceEntry {
+                id: evidence_id_for_agent_instance(&w.agent_instance),
+                kind: "runtime-event-range".to_string(),
+                title: format!("Worker {} runtime", w.agent_instance),
+                summary: format!("[{}] {}", w.status, w.summary.trim())
+                    .trim()
+                    .to_string(),
+                created_at: generated_at.to_rfc3339(),
+                sources: vec![EvidenceSource::RuntimeEventRange {
+                    events_ref: format!(
+                        "./agents/{}/runtime/{}",
+                        w.agent_instance, RUNTIME_EVENTS_FILE_NAME
+                    ),
+                    start_line: None,
+                    end_line: None,
+                }],
+                artifact_refs: vec![
+                    format!(
+                        "./agents/{}/artifacts/{}",
+                        w.agent_instance, FINAL_OUTPUT_FILE_NAME
+                    ),
+                    format!(
+                        "./agents/{}/runtime/{}",
+                        w.agent_instance, RUNTIME_EVENTS_FILE_NAME
+                    ),
+                ],
+            };
+            match entries.iter_mut().find(|e| e.id == entry.id) {
+                Some(existing) => *existing = entry,
+                None => entries.push(entry),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Entries of the task's evidence index, in file order.
+    pub fn evidence_list(&self, task_id: &str) -> Result<Vec<EvidenceEntry>, OrchestratorError> {
+        Ok(self.store.read_evidence_index(task_id)?)
+    }
+
+    /// Append an entry produced outside join (e.g. by a worker through MCP) to the evidence index.
+    /// `worker-*` ids are reserved: join rewrites them. An empty `created_at` is set to now.
+    pub fn evidence_add(
+        &self,
+        task_id: &str,
+        mut entry: EvidenceEntry,
+    ) -> Result<EvidenceEntry, OrchestratorError> {
+        if !coco_core::evidence::is_valid_evidence_id(&entry.id)
+            || entry.id.starts_with(WORKER_EVIDENCE_ID_PREFIX)
+        {
+            return Err(OrchestratorError::InvalidEvidenceId {
+                evidence_id: entry.id,
+            });
+        }
+        self.store.read_task(task_id)?;
+        let mut entries = self.store.read_evidence_index(task_id)?;
+        if entries.iter().any(|e| e.id == entry.id) {
+            return Err(OrchestratorError::EvidenceAlreadyExists {
+                evidence_id: entry.id,
+            });
+        }
+        if entry.created_at.is_empty() {
+            entry.created_at = Utc::now().to_rfc3339();
+        }
+        entries.push(entry.clone());
+        self.store.write_evidence_index(task_id, &entries)?;
+
+        let event = TaskEvent {
+            ts: Utc::now(),
+            event_type: "evidence.added".to_string(),
+            task_id: task_id.to_string(),
+            agent_instance: None,
+            turn_id: None,
+            payload: json!({ "evidenceId": entry.id, "kind": entry.kind }),
+            by: Some("orchestrator".to_string()),
+            path: None,
+        };
+        self.store.append_task_event(task_id, &event)?;
+        Ok(entry)
+    }
+
+    /// Cross-check `evidence:<id>` tokens in the task's shared Markdown against the evidence index.
+    pub fn evidence_check(&self, task_id: &str) -> Result<EvidenceCheckReport, OrchestratorError> {
+        Ok(self.store.check_evidence(task_id)?)
+    }
+
+    /// Look up an evidence entry and resolve its sources into link targets for the GUI/CLI.
+    pub fn evidence_resolve(
+        &self,
+        task_id: &str,
+        evidence_id: &str,
+    ) -> Result<ResolvedEvidence, OrchestratorError> {
+        let entry = self
+            .store
+            .read_evidence_index(task_id)?
+            .into_iter()
+            .find(|e| e.id == evidence_id)
+            .ok_or_else(|| OrchestratorError::EvidenceNotFound {
+                evidence_id: evidence_id.to_string(),
+            })?;
+        let links = coco_core::evidence::resolve_evidence_links(
+            &entry,
+            self.workspace_root(),
+            &self.store.task_dir(task_id),
+        )?;
+        Ok(ResolvedEvidence { entry, links })
+    }
+
     fn reconcile_subagents(
         &self,
         task_id: &str,
@@ -553,17 +737,6 @@ impl Orchestrator {
         let mut gate_event_index = self.load_gate_event_index(task_id)?;
 
         let task_dir = self.store.task_dir(task_id);
-        let cancelled = agent_event_index
-            .by_agent
-            .iter()
-            .filter_map(|(agent, events)| {
-                if events.contains("agent.cancelled") {
-                    Some(agent.clone())
-                } else {
-                    None
-                }
-            })
-            .collect::<HashSet<_>>();
 
         let mut roster_changed = false;
         let mut gates_changed = false;
@@ -583,13 +756,13 @@ impl Orchestrator {
             let events_path = runtime_dir.join(RUNTIME_EVENTS_FILE_NAME);
             let session_path = agent_dir.join(SESSION_FILE_NAME);
             let pid_path = runtime_dir.join(RUNTIME_PID_FILE_NAME);
+            let deadline_path = runtime_dir.join(RUNTIME_DEADLINE_FILE_NAME);
             let final_output_path = artifacts_dir.join(FINAL_OUTPUT_FILE_NAME);
 
             if events_path.exists() && session_path.exists() {
                 maybe_update_session_thread_id(&events_path, &session_path)?;
             }
-
-            let status = if cancelled.contains(&agent_instance) {
+            let status = if agent_event_index.contains(&agent_instance, "agent.cancelled") {
                 SubagentStatus::Cancelled
             } else if let Some(final_status) = read_final_status(&final_output_path)? {
                 match final_status.as_str() {
@@ -598,11 +771,24 @@ impl Orchestrator {
                     "failed" => SubagentStatus::Failed,
                     _ => SubagentStatus::Failed,
                 }
-            } else if let Some(pid) = read_pid(&pid_path)? {
-                if pid_is_alive(pid)? {
-                    SubagentStatus::Running
-                } else {
+            } else if let Some(process) = process::read_process_identity(&runtime_dir)? {
+                if !process.is_alive()? {
+                    SubagentStatus::Failed
+                } else if let Some(deadline) =
+                    read_deadline(&deadline_path)?.filter(|d| Utc::now() >= d.deadline)
+                {
+                    self.time_out_worker(
+                        &mut agent_event_index,
+                        task_id,
+                        &agent_instance,
+                        &process,
+                        &deadline,
+                        &pid_path,
+                        &final_output_path,
+                    )?;
                     SubagentStatus::Failed
+                } else {
+                    SubagentStatus::Running
                 }
             } else {
                 match agent_state {
@@ -615,6 +801,9 @@ impl Orchestrator {
                     }
                 }
             };
+            // Read only once the supervisor is seen gone: it writes the status right before it
+            // exits, so an earlier read can miss it.
+            let exit_code = process::read_exit_status(&runtime_dir)?;
 
             match status {
                 SubagentStatus::Running => {
@@ -667,7 +856,10 @@ impl Orchestrator {
                         task_id,
                         &agent_instance,
                         "agent.failed",
-                        json!({}),
+                        match exit_code {
+                            Some(code) => json!({ "exitCode": code }),
+                            None => json!({}),
+                        },
                     )?;
                 }
                 SubagentStatus::Cancelled => {
@@ -689,6 +881,9 @@ impl Orchestrator {
                 agent_instance,
                 agent: agent_name,
                 status,
+                exit_code,
+                stale_context: status == SubagentStatus::Running
+                    && runtime_dir.join(notes::STALE_CONTEXT_FILE_NAME).exists(),
             });
         }
 
@@ -709,10 +904,59 @@ impl Orchestrator {
             task.updated_at = Utc::now();
             self.store.write_task(&task)?;
         }
+        if roster_changed {
+            self.record_task_usage(task_id)?;
+        }
 
         Ok(ReconcileSubagentsOutput { task, subagents })
     }
 
+    /// Stop a worker that ran past its deadline: SIGINT→SIGTERM→SIGKILL, then record a failed
+    /// `final.json` so join/report see a terminal status.
+    #[allow(clippy::too_many_arguments)]
+    fn time_out_worker(
+        &self,
+        agent_event_index: &mut AgentEventIndex,
+        task_id: &str,
+        agent_instance: &str,
+        process: &process::ProcessIdentity,
+        deadline: &WorkerDeadline,
+        pid_path: &Path,
+        final_output_path: &Path,
+    ) -> Result<(), OrchestratorError> {
+        process.cancel()?;
+        if !process.is_alive()? {
+            let _ = fs::remove_file(pid_path);
+        }
+
+        let final_output = json!({
+            "status": "failed",
+            "summary": format!("worker timed out after {}s", deadline.timeout_seconds),
+            "errors": [format!(
+                "timeout: deadline {} exceeded; process was cancelled",
+                deadline.deadline.to_rfc3339()
+            )],
+        });
+        if let Some(parent) = final_output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            final_output_path,
+            serde_json::to_string_pretty(&final_output)?,
+        )?;
+
+        self.ensure_agent_event(
+            agent_event_index,
+            task_id,
+            agent_instance,
+            "agent.timed_out",
+            json!({
+                "timeoutSeconds": deadline.timeout_seconds,
+                "deadline": deadline.deadline.to_rfc3339(),
+            }),
+        )
+    }
+
     fn append_agent_event(
         &self,
         task_id: &str,
@@ -735,24 +979,29 @@ impl Orchestrator {
         Ok(())
     }
 
+    /// Append `event_type` unless the agent's latest attempt (from its last `agent.started`)
+    /// already has one. The attempt number goes into the payload, so a retry that fails the same
+    /// way still gets its own event.
     fn ensure_agent_event(
         &self,
         index: &mut AgentEventIndex,
         task_id: &str,
         agent_instance: &str,
         event_type: &str,
-        payload: serde_json::Value,
+        mut payload: serde_json::Value,
     ) -> Result<(), OrchestratorError> {
-        let entry = index
-            .by_agent
-            .entry(agent_instance.to_string())
-            .or_default();
-        if entry.contains(event_type) {
+        let attempt = index.attempt(agent_instance);
+        if index.contains(agent_instance, event_type) {
             return Ok(());
         }
 
+        if let Some(map) = payload.as_object_mut() {
+            map.insert("attempt".to_string(), json!(attempt));
+        }
         self.append_agent_event(task_id, agent_instance, event_type, payload)?;
-        entry.insert(event_type.to_string());
+        index
+            .seen
+            .insert((agent_instance.to_string(), event_type.to_string(), attempt));
         Ok(())
     }
 
@@ -761,18 +1010,34 @@ impl Orchestrator {
             .store
             .read_task_events(task_id, Some("agent."), usize::MAX, 0)?;
 
-        let mut by_agent: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut index = AgentEventIndex {
+            started: HashMap::new(),
+            seen: HashSet::new(),
+        };
         for event in events {
             let Some(agent_instance) = event.agent_instance else {
                 continue;
             };
-            by_agent
-                .entry(agent_instance)
-                .or_default()
-                .insert(event.event_type);
+            let payload_attempt = event
+                .payload
+                .get("attempt")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32);
+            let attempt = if event.event_type == "agent.started" {
+                // Starts without an attempt number (app-server workers) still begin a new one.
+                let attempt = payload_attempt.unwrap_or(index.attempt(&agent_instance) + 1);
+                index.started.insert(agent_instance.clone(), attempt);
+                attempt
+            } else {
+                // Events written before attempts were recorded belong to the latest start.
+                payload_attempt.unwrap_or(index.attempt(&agent_instance))
+            };
+            index
+                .seen
+                .insert((agent_instance, event.event_type, attempt));
         }
 
-        Ok(AgentEventIndex { by_agent })
+        Ok(index)
     }
 
     fn ensure_blocked_gate(
@@ -921,9 +1186,28 @@ struct ReconcileSubagentsOutput {
     subagents: Vec<SubagentInfo>,
 }
 
+/// Lifecycle events seen per (agent, event type, attempt).
 #[derive(Debug)]
 struct AgentEventIndex {
-    by_agent: HashMap<String, HashSet<String>>,
+    /// Attempt number of each agent's latest `agent.started`.
+    started: HashMap<String, u32>,
+    seen: HashSet<(String, String, u32)>,
+}
+
+impl AgentEventIndex {
+    fn attempt(&self, agent_instance: &str) -> u32 {
+        self.started.get(agent_instance).copied().unwrap_or(1)
+    }
+
+    /// Whether the agent's latest attempt already has `event_type`.
+    fn contains(&self, agent_instance: &str, event_type: &str) -> bool {
+        let key = (
+            agent_instance.to_string(),
+            event_type.to_string(),
+            self.attempt(agent_instance),
+        );
+        self.seen.contains(&key)
+    }
 }
 
 #[derive(Debug)]
@@ -943,6 +1227,17 @@ struct JoinedWorkerSummary {
     summary: String,
     questions: Vec<String>,
     next_actions: Vec<String>,
+    /// 1-based attempt that produced `final.json` (greater than 1 after retries).
+    attempt: u32,
+}
+
+/// `agents/<instance>/runtime/deadline.json`: written at spawn when a wall-clock limit applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkerDeadline {
+    started_at: DateTime<Utc>,
+    timeout_seconds: u32,
+    deadline: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -953,6 +1248,69 @@ struct WorkerFinalOutputSnapshot {
     next_actions: Vec<String>,
 }
 
+struct CodexExecInvocation<'a> {
+    codex_bin: &'a Path,
+    cwd: &'a Path,
+    output_schema_path: &'a Path,
+    final_output_path: &'a Path,
+    codex_home: &'a Path,
+    /// Model/sandbox/approval defaults from the agent spec, when one exists.
+    spec: Option<&'a AgentSpecFile>,
+    /// Run under the exit-status supervisor (detached workers only).
+    exit_status_path: Option<&'a Path>,
+}
+
+/// `codex exec -` reading its prompt from `prompt_file`.
+fn codex_exec_command(
+    inv: &CodexExecInvocation<'_>,
+    prompt_file: fs::File,
+    events_file: fs::File,
+    stderr_file: fs::File,
+) -> Command {
+    let mut args: Vec<OsString> = vec![
+        "exec".into(),
+        "--json".into(),
+        "-C".into(),
+        inv.cwd.into(),
+        "--output-schema".into(),
+        inv.output_schema_path.into(),
+        "--output-last-message".into(),
+        inv.final_output_path.into(),
+    ];
+    if let Some(spec) = inv.spec {
+        if let Some(model) = spec.model.as_deref() {
+            args.extend(["--model".into(), model.into()]);
+        }
+        if let Some(sandbox) = spec.sandbox {
+            args.extend(["--sandbox".into(), sandbox.as_str().into()]);
+        }
+        if let Some(policy) = spec.approval_policy {
+            args.extend([
+                "--config".into(),
+                format!("approval_policy=\"{}\"", policy.as_str()).into(),
+            ]);
+        }
+    }
+    args.push("-".into());
+
+    let mut cmd = match inv.exit_status_path {
+        Some(exit_status_path) => {
+            process::supervised_command(inv.codex_bin, &args, exit_status_path)
+        }
+        None => {
+            let mut cmd = Command::new(inv.codex_bin);
+            cmd.args(&args);
+            cmd
+        }
+    };
+    cmd.env("CODEX_HOME", inv.codex_home)
+        .stdin(Stdio::from(prompt_file))
+        .stdout(Stdio::from(events_file))
+        .stderr(Stdio::from(stderr_file))
+        .current_dir(inv.cwd);
+    cmd
+}
+
 fn read_worker_final_output(path: &Path) -> Result<WorkerFinalOutputSnapshot, OrchestratorError> {
     if !path.exists() {
         return Ok(WorkerFinalOutputSnapshot {
@@ -964,7 +1322,14 @@ fn read_worker_final_output(path: &Path) -> Result<WorkerFinalOutputSnapshot, Or
     }
 
     let content = fs::read_to_string(path)?;
-    let value: serde_json::Value = serde_json::from_str(&content)?;
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Ok(WorkerFinalOutputSnapshot {
+            status: "failed".to_string(),
+            summary: "final output is not valid JSON".to_string(),
+            questions: Vec::new(),
+            next_actions: Vec::new(),
+        });
+    };
 
     let status = value
         .get("status")
@@ -1023,72 +1388,9 @@ fn blocked_gate_reason(agent_instance: &str, output: &WorkerFinalOutputSnapshot)
     format!("blocked by {agent_instance}")
 }
 
-fn render_joined_summary_markdown(
-    task: &TaskFile,
-    generated_at: chrono::DateTime<Utc>,
-    workers: &[JoinedWorkerSummary],
-) -> String {
-    let mut out = String::new();
-    out.push_str("---\n");
-    out.push_str(&format!(
-        "title: \"Joined Summary: {}\"\n",
-        escape_yaml_string(&task.title)
-    ));
-    out.push_str("purpose: \"汇总多个 subagent 的最终输出与阻塞点\"\n");
-    out.push_str("tags: [\"joined-summary\", \"subagents\"]\n");
-    out.push_str(&format!("task_id: \"{}\"\n", escape_yaml_string(&task.id)));
-    out.push_str(&format!(
-        "generated_at: \"{}\"\n",
-        generated_at.to_rfc3339()
-    ));
-    out.push_str("---\n\n");
-
-    out.push_str(&format!("# Joined Summary: {}\n\n", task.title));
-    out.push_str(&format!("- task: `{}`\n", task.id));
-    out.push_str(&format!(
-        "- generatedAt: `{}`\n\n",
-        generated_at.to_rfc3339()
-    ));
-
-    out.push_str("## Workers\n\n");
-    for w in workers {
-        out.push_str(&format!("### {} ({})\n\n", w.agent_instance, w.agent));
-        out.push_str(&format!("- status: `{}`\n", w.status));
-        out.push_str(&format!(
-            "- evidence: evidence:{}\n",
-            evidence_id_for_agent_instance(&w.agent_instance)
-        ));
-        if !w.summary.trim().is_empty() {
-            out.push_str(&format!("- summary: {}\n", w.summary.trim()));
-        }
-
-        if !w.questions.is_empty() {
-            out.push_str("- questions:\n");
-            for q in &w.questions {
-                out.push_str(&format!("  - {}\n", q.trim()));
-            }
-        }
-
-        if !w.next_actions.is_empty() {
-            out.push_str("- nextActions:\n");
-            for a in &w.next_actions {
-                out.push_str(&format!("  - {}\n", a.trim()));
-            }
-        }
-
-        out.push('\n');
-    }
-
-    out
-}
-
 fn evidence_id_for_agent_instance(agent_instance: &str) -> String {
     let normalized = agent_instance.replace('_', "-");
-    format!("worker-{normalized}")
-}
-
-fn escape_yaml_string(value: &str) -> String {
-    value.replace('"', "\\\"")
+    format!("{WORKER_EVIDENCE_ID_PREFIX}{normalized}")
 }
 
 fn validate_agent_instance(value: &str) -> Result<(), OrchestratorError> {
@@ -1227,7 +1529,10 @@ fn read_final_status(path: &Path) -> Result<Option<String>, OrchestratorError> {
         return Ok(None);
     }
     let content = fs::read_to_string(path)?;
-    let value: serde_json::Value = serde_json::from_str(&content)?;
+    // Unparseable output counts as a failed attempt (see `RetryClass::InvalidOutput`).
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Ok(Some("invalid".to_string()));
+    };
     Ok(value
         .get("status")
         .and_then(|v| v.as_str())
@@ -1249,50 +1554,12 @@ fn read_pid(path: &Path) -> Result<Option<i32>, OrchestratorError> {
     Ok(Some(pid))
 }
 
-fn cancel_pid(pid: i32) -> Result<(), OrchestratorError> {
-    if !pid_is_alive(pid)? {
-        return Ok(());
-    }
-
-    send_signal(pid, libc::SIGINT)?;
-    let start = Instant::now();
-    while start.elapsed() < CANCEL_GRACE_PERIOD {
-        if !pid_is_alive(pid)? {
-            return Ok(());
-        }
-        thread::sleep(Duration::from_millis(100));
-    }
-
-    if pid_is_alive(pid)? {
-        send_signal(pid, libc::SIGTERM)?;
-    }
-
-    let start = Instant::now();
-    while start.elapsed() < CANCEL_TERMINATE_PERIOD {
-        if !pid_is_alive(pid)? {
-            return Ok(());
-        }
-        thread::sleep(Duration::from_millis(100));
-    }
-
-    #[cfg(unix)]
-    {
-        if pid_is_alive(pid)? {
-            send_signal(pid, libc::SIGKILL)?;
-        }
-    }
-
-    #[cfg(not(unix))]
-    {
-        let _ = Command::new("taskkill")
-            .arg("/PID")
-            .arg(pid.to_string())
-            .arg("/T")
-            .arg("/F")
-            .status();
+fn read_deadline(path: &Path) -> Result<Option<WorkerDeadline>, OrchestratorError> {
+    if !path.exists() {
+        return Ok(None);
     }
-
-    Ok(())
+    let content = fs::read_to_string(path)?;
+    Ok(Some(serde_json::from_str(&content)?))
 }
 
 fn pid_is_alive(pid: i32) -> Result<bool, OrchestratorError> {
@@ -1358,7 +1625,9 @@ fn send_signal(pid: i32, signal: i32) -> Result<(), OrchestratorError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use coco_core::task::BudgetAction;
     use coco_core::task::CreateTaskRequest;
+    use coco_core::task::TaskBudget;
     use coco_core::task::TaskConfig;
     use coco_core::task::TaskTopology;
     use std::time::SystemTime;
@@ -1402,6 +1671,9 @@ mod tests {
                     max_concurrent_agents: 2,
                     timeout_seconds: 1,
                     auto_approve: false,
+                    worker_timeout_seconds: None,
+                    retry: None,
+                    budget: None,
                 }),
             })
             .unwrap();
@@ -1441,6 +1713,7 @@ mod tests {
             cwd: std::env::current_dir().unwrap(),
             codex_bin: PathBuf::from("codex"),
             output_schema_path: root.join("schemas").join("worker-output.schema.json"),
+            timeout_seconds: None,
         };
 
         let err = orchestrator.subagent_spawn(req).unwrap_err();
@@ -1452,86 +1725,451 @@ mod tests {
         let _ = child2.wait();
     }
 
+    #[cfg(unix)]
     #[test]
-    fn cancel_appends_cancelled_event() {
+    fn worker_past_deadline_is_cancelled_and_failed() {
+        use std::os::unix::fs::PermissionsExt;
+
         let root = new_temp_workspace_root();
         let _guard = TempDirGuard(root.clone());
 
+        let codex_bin = root.join("hung-codex.sh");
+        fs::write(&codex_bin, "#!/bin/sh\nexec sleep 60\n").unwrap();
+        fs::set_permissions(&codex_bin, fs::Permissions::from_mode(0o755)).unwrap();
+
         let orchestrator = Orchestrator::new(root.clone());
-        let resp = orchestrator
+        let task_id = orchestrator
             .create_task(CreateTaskRequest {
-                title: "test".to_string(),
+                title: "timeout".to_string(),
                 description: "".to_string(),
                 topology: TaskTopology::Swarm,
                 milestones: Vec::new(),
                 roster: Vec::new(),
                 config: None,
             })
-            .unwrap();
+            .unwrap()
+            .id;
 
-        let task_id = resp.id;
-        let mut task = orchestrator.get_task(&task_id).unwrap();
+        orchestrator
+            .subagent_spawn(SubagentSpawnRequest {
+                task_id: task_id.clone(),
+                agent_instance: "a1".to_string(),
+                agent: "worker".to_string(),
+                prompt: "hang".to_string(),
+                cwd: root.clone(),
+                codex_bin,
+                output_schema_path: root.join("schemas").join("worker-output.schema.json"),
+                timeout_seconds: Some(1),
+            })
+            .unwrap();
 
-        let mut child = spawn_sleep_process();
-        task.roster.push(AgentInstance {
-            instance: "a1".to_string(),
-            agent: "worker".to_string(),
-            state: AgentInstanceState::Active,
-            assigned_milestone: None,
-            skills: Vec::new(),
-        });
-        orchestrator.store.write_task(&task).unwrap();
+        let found = orchestrator.subagent_wait_any(&task_id, Some(20)).unwrap();
+        assert_eq!(found.agent_instance, "a1");
+        assert_eq!(found.status, SubagentStatus::Failed);
 
-        let agent_dir = orchestrator
+        let a1_dir = orchestrator
             .store
             .task_dir(&task_id)
             .join("agents")
             .join("a1");
-        let runtime_dir = agent_dir.join("runtime");
-        fs::create_dir_all(&runtime_dir).unwrap();
-        fs::write(runtime_dir.join("pid"), format!("{}\n", child.id())).unwrap();
-
-        orchestrator.subagent_cancel(&task_id, "a1").unwrap();
+        let final_status = read_final_status(&a1_dir.join("artifacts").join("final.json")).unwrap();
+        assert_eq!(final_status.as_deref(), Some("failed"));
+        assert!(!a1_dir.join("runtime").join("pid").exists());
 
         let events = orchestrator
             .store
             .read_task_events(&task_id, Some("agent."), usize::MAX, 0)
             .unwrap();
-        assert!(events.iter().any(|e| {
-            e.event_type == "agent.cancelled" && e.agent_instance.as_deref() == Some("a1")
-        }));
-
-        // Best-effort cleanup: the cancel path may reap the child process.
-        let _ = child.kill();
-        let _ = child.wait();
+        let types = events
+            .iter()
+            .map(|e| e.event_type.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec!["agent.started", "agent.timed_out", "agent.failed"]
+        );
     }
 
+    #[cfg(unix)]
     #[test]
-    fn join_includes_all_worker_fields() {
+    fn human_notes_edit_marks_exec_worker_stale_then_restarts_it() {
+        use std::os::unix::fs::PermissionsExt;
+
         let root = new_temp_workspace_root();
         let _guard = TempDirGuard(root.clone());
 
+        let codex_bin = root.join("hung-codex.sh");
+        fs::write(&codex_bin, "#!/bin/sh\nexec sleep 60\n").unwrap();
+        fs::set_permissions(&codex_bin, fs::Permissions::from_mode(0o755)).unwrap();
+
         let orchestrator = Orchestrator::new(root.clone());
-        let resp = orchestrator
+        let task_id = orchestrator
             .create_task(CreateTaskRequest {
-                title: "join test".to_string(),
+                title: "notes".to_string(),
                 description: "".to_string(),
                 topology: TaskTopology::Swarm,
                 milestones: Vec::new(),
                 roster: Vec::new(),
                 config: None,
             })
+            .unwrap()
+            .id;
+        let subtask = OrchestratorSubtask {
+            task_id: "t1".to_string(),
+            agent_instance: Some("a1".to_string()),
+            title: "hang".to_string(),
+            agent: "worker".to_string(),
+            adapter: "codex-exec".to_string(),
+            prompt: "hang".to_string(),
+            prompt_template: None,
+            mode: None,
+            forked_from_thread_id: None,
+            cwd: None,
+            output_schema_path: None,
+            timeout_seconds: None,
+            retry: None,
+        };
+        let mut opts = ControllerOptions::new(&root);
+        opts.codex_bin = codex_bin;
+        orchestrator
+            .subagent_spawn(
+                orchestrator
+                    .exec_spawn_request(&task_id, &subtask, &opts)
+                    .unwrap(),
+            )
             .unwrap();
 
-        let task_id = resp.id;
-        let mut task = orchestrator.get_task(&task_id).unwrap();
-        task.roster.push(AgentInstance {
-            instance: "w1".to_string(),
-            agent: "worker".to_string(),
-            state: AgentInstanceState::Completed,
-            assigned_milestone: None,
-            skills: Vec::new(),
-        });
+        let task_dir = orchestrator.store.task_dir(&task_id);
+        let a1_dir = task_dir.join("agents").join("a1");
+        let context = |dir: &Path| -> serde_json::Value {
+            serde_json::from_str(&fs::read_to_string(dir.join("context.json")).unwrap()).unwrap()
+        };
+        // The untouched seed notes are not worth a preamble.
+        assert_eq!(context(&a1_dir)["preamble"], "");
+
+        fs::write(
+            task_dir.join("shared").join("human-notes.md"),
+            "# Human Notes\n\n- do not touch the schema\n",
+        )
+        .unwrap();
+        let expected = HashMap::from([("a1".to_string(), &subtask)]);
+        for _ in 0..2 {
+            let subagents = orchestrator.subagent_list(&task_id).unwrap();
+            orchestrator
+                .sync_human_notes(&task_id, &subagents, &expected, &opts)
+                .unwrap();
+        }
+        let subagents = orchestrator.subagent_list(&task_id).unwrap();
+        assert_eq!(subagents[0].status, SubagentStatus::Running);
+        assert!(subagents[0].stale_context);
+        let delivered = orchestrator
+            .store
+            .read_task_events(&task_id, Some("notes.delivered"), usize::MAX, 0)
+            .unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].payload["via"], "stale");
+        assert_eq!(
+            delivered[0].payload["diff"],
+            "- - 在这里记录人工补充、约束与纠错。\n+ - do not touch the schema\n"
+        );
+
+        opts.restart_stale_workers = true;
+        orchestrator
+            .sync_human_notes(&task_id, &subagents, &expected, &opts)
+            .unwrap();
+        let subagents = orchestrator.subagent_list(&task_id).unwrap();
+        assert_eq!(subagents[0].status, SubagentStatus::Running);
+        assert!(!subagents[0].stale_context);
+        assert!(a1_dir.join("attempts").join("1").join("runtime").is_dir());
+        assert!(context(&a1_dir)["preamble"]
+            .as_str()
+            .unwrap()
+            .contains("do not touch the schema"));
+        let delivered = orchestrator
+            .store
+            .read_task_events(&task_id, Some("notes.delivered"), usize::MAX, 0)
+            .unwrap();
+        assert_eq!(delivered.len(), 2);
+        assert_eq!(delivered[1].payload["via"], "restart");
+        assert_eq!(delivered[1].payload["archivedAttempt"], 1);
+
+        orchestrator.subagent_cancel(&task_id, "a1").unwrap();
+    }
+
+    #[test]
+    fn subtask_prompt_template_is_rendered_from_workspace_prompts() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+        let prompts_dir = root.join(".coco").join("prompts");
+        fs::create_dir_all(&prompts_dir).unwrap();
+        fs::write(prompts_dir.join("fix.md"), "Fix $1 in $AREA.\n").unwrap();
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let mut subtask = OrchestratorSubtask {
+            task_id: "t1".to_string(),
+            agent_instance: None,
+            title: "fix".to_string(),
+            agent: "worker".to_string(),
+            adapter: "codex-exec".to_string(),
+            prompt: "Keep the diff small.".to_string(),
+            prompt_template: Some(SubtaskPromptTemplate {
+                name: "fix".to_string(),
+                args: "AREA=parser \"the crash\"".to_string(),
+            }),
+            mode: None,
+            forked_from_thread_id: None,
+            cwd: None,
+            output_schema_path: None,
+            timeout_seconds: None,
+            retry: None,
+        };
+        let opts = ControllerOptions::new(&root);
+        let req = orchestrator
+            .exec_spawn_request("task", &subtask, &opts)
+            .unwrap();
+        assert_eq!(
+            req.prompt,
+            "Fix the crash in parser.\n\nKeep the diff small."
+        );
+
+        let mut actions = OrchestratorActions {
+            session_goal: "fix".to_string(),
+            tasks: vec![subtask.clone()],
+        };
+        crate::planner::validate_actions(&actions, &root).unwrap();
+
+        subtask.prompt_template = Some(SubtaskPromptTemplate {
+            name: "fix".to_string(),
+            args: String::new(),
+        });
+        assert!(matches!(
+            orchestrator.exec_spawn_request("task", &subtask, &opts),
+            Err(OrchestratorError::Prompt(_))
+        ));
+
+        // Plans are rejected up front when their template cannot be rendered.
+        actions.tasks = vec![subtask.clone()];
+        assert!(matches!(
+            crate::planner::validate_actions(&actions, &root),
+            Err(OrchestratorError::InvalidPlan { message }) if message.contains("promptTemplate fix")
+        ));
+        subtask.prompt_template = Some(SubtaskPromptTemplate {
+            name: "no-such-prompt".to_string(),
+            args: String::new(),
+        });
+        actions.tasks = vec![subtask];
+        assert!(matches!(
+            crate::planner::validate_actions(&actions, &root),
+            Err(OrchestratorError::InvalidPlan { .. })
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn supervisor_records_worker_exit_code() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let codex_bin = root.join("crashing-codex.sh");
+        fs::write(&codex_bin, "#!/bin/sh\nexit 3\n").unwrap();
+        fs::set_permissions(&codex_bin, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let task_id = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "exit code".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: None,
+            })
+            .unwrap()
+            .id;
+
+        orchestrator
+            .subagent_spawn(SubagentSpawnRequest {
+                task_id: task_id.clone(),
+                agent_instance: "a1".to_string(),
+                agent: "worker".to_string(),
+                prompt: "crash".to_string(),
+                cwd: root.clone(),
+                codex_bin,
+                output_schema_path: root.join("schemas").join("worker-output.schema.json"),
+                timeout_seconds: None,
+            })
+            .unwrap();
+
+        let runtime_dir = orchestrator
+            .store
+            .task_dir(&task_id)
+            .join("agents")
+            .join("a1")
+            .join("runtime");
+        let identity = process::read_process_identity(&runtime_dir)
+            .unwrap()
+            .unwrap();
+        assert!(identity.start_time.is_some());
+        assert!(identity.boot_id.is_some());
+        assert!(identity.process_group);
+        // The prompt is passed on stdin from a file, not on the command line.
+        let prompt_path = runtime_dir.join(RUNTIME_PROMPT_FILE_NAME);
+        assert_eq!(identity.prompt_path.as_ref(), Some(&prompt_path));
+        let prompt = fs::read_to_string(&prompt_path).unwrap();
+        assert!(prompt.contains("crash"));
+        assert_eq!(identity.cmdline.last().map(String::as_str), Some("-"));
+        assert!(!identity.cmdline.contains(&prompt));
+
+        let found = orchestrator.subagent_wait_any(&task_id, Some(20)).unwrap();
+        assert_eq!(found.status, SubagentStatus::Failed);
+
+        let subagents = orchestrator.subagent_list(&task_id).unwrap();
+        assert_eq!(subagents[0].exit_code, Some(3));
+
+        let events = orchestrator
+            .store
+            .read_task_events(&task_id, Some("agent.failed"), usize::MAX, 0)
+            .unwrap();
+        assert_eq!(events[0].payload["exitCode"], json!(3));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn recycled_pid_is_not_mistaken_for_worker() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let task_id = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "recycled pid".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: None,
+            })
+            .unwrap()
+            .id;
+
+        let mut task = orchestrator.get_task(&task_id).unwrap();
+        task.roster.push(AgentInstance {
+            instance: "a1".to_string(),
+            agent: "worker".to_string(),
+            state: AgentInstanceState::Active,
+            assigned_milestone: None,
+            skills: Vec::new(),
+        });
+        orchestrator.store.write_task(&task).unwrap();
+
+        // A live process that merely reuses the recorded pid.
+        let mut child = spawn_sleep_process();
+        let runtime_dir = orchestrator
+            .store
+            .task_dir(&task_id)
+            .join("agents")
+            .join("a1")
+            .join("runtime");
+        fs::create_dir_all(&runtime_dir).unwrap();
+        let mut identity =
+            process::ProcessIdentity::capture(child.id(), Command::new("sleep").arg("60"), false);
+        assert!(identity.is_alive().unwrap());
+        identity.start_time = identity.start_time.map(|t| t + 1);
+        fs::write(
+            runtime_dir.join(process::RUNTIME_PROCESS_FILE_NAME),
+            serde_json::to_string(&identity).unwrap(),
+        )
+        .unwrap();
+
+        let subagents = orchestrator.subagent_list(&task_id).unwrap();
+        assert_eq!(subagents[0].status, SubagentStatus::Failed);
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn cancel_appends_cancelled_event() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let resp = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "test".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: None,
+            })
+            .unwrap();
+
+        let task_id = resp.id;
+        let mut task = orchestrator.get_task(&task_id).unwrap();
+
+        let mut child = spawn_sleep_process();
+        task.roster.push(AgentInstance {
+            instance: "a1".to_string(),
+            agent: "worker".to_string(),
+            state: AgentInstanceState::Active,
+            assigned_milestone: None,
+            skills: Vec::new(),
+        });
+        orchestrator.store.write_task(&task).unwrap();
+
+        let agent_dir = orchestrator
+            .store
+            .task_dir(&task_id)
+            .join("agents")
+            .join("a1");
+        let runtime_dir = agent_dir.join("runtime");
+        fs::create_dir_all(&runtime_dir).unwrap();
+        fs::write(runtime_dir.join("pid"), format!("{}\n", child.id())).unwrap();
+
+        orchestrator.subagent_cancel(&task_id, "a1").unwrap();
+
+        let events = orchestrator
+            .store
+            .read_task_events(&task_id, Some("agent."), usize::MAX, 0)
+            .unwrap();
+        assert!(events.iter().any(|e| {
+            e.event_type == "agent.cancelled" && e.agent_instance.as_deref() == Some("a1")
+        }));
+
+        // Best-effort cleanup: the cancel path may reap the child process.
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn join_includes_all_worker_fields() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let resp = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "join test".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: None,
+            })
+            .unwrap();
+
+        let task_id = resp.id;
+        let mut task = orchestrator.get_task(&task_id).unwrap();
+        task.roster.push(AgentInstance {
+            instance: "w1".to_string(),
+            agent: "worker".to_string(),
+            state: AgentInstanceState::Completed,
+            assigned_milestone: None,
+            skills: Vec::new(),
+        });
         task.roster.push(AgentInstance {
             instance: "w2".to_string(),
             agent: "worker".to_string(),
@@ -1608,6 +2246,380 @@ mod tests {
         );
     }
 
+    #[test]
+    fn task_usage_rolls_up_attempts_and_prices_workers() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let task_id = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "usage".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: None,
+            })
+            .unwrap()
+            .id;
+        let mut task = orchestrator.get_task(&task_id).unwrap();
+        for instance in ["w1", "w2"] {
+            task.roster.push(AgentInstance {
+                instance: instance.to_string(),
+                agent: "worker".to_string(),
+                state: AgentInstanceState::Completed,
+                assigned_milestone: None,
+                skills: Vec::new(),
+            });
+        }
+        orchestrator.store.write_task(&task).unwrap();
+
+        fs::write(
+            root.join(".coco").join("prices.yaml"),
+            "models:\n  gpt-test:\n    inputPerMillion: 1\n    outputPerMillion: 10\n",
+        )
+        .unwrap();
+        let task_dir = orchestrator.store.task_dir(&task_id);
+        let w1_dir = task_dir.join("agents").join("w1");
+        for (runtime_dir, events) in [
+            (
+                w1_dir.join("attempts").join("1").join("runtime"),
+                r#"{"type":"turn.completed","usage":{"input_tokens":100,"output_tokens":10}}"#,
+            ),
+            (
+                w1_dir.join("runtime"),
+                "{\"model\":\"gpt-test\"}\n{\"type\":\"turn.completed\",\"usage\":{\"input_tokens\":900000,\"output_tokens\":99990}}",
+            ),
+            (
+                task_dir.join("agents").join("w2").join("runtime"),
+                r#"{"type":"turn.completed","usage":{"input_tokens":5,"output_tokens":5}}"#,
+            ),
+            (
+                task_dir
+                    .join("agents")
+                    .join(PLANNER_AGENT_INSTANCE)
+                    .join("attempts")
+                    .join("1")
+                    .join("runtime"),
+                r#"{"type":"turn.completed","usage":{"input_tokens":7,"output_tokens":3}}"#,
+            ),
+        ] {
+            fs::create_dir_all(&runtime_dir).unwrap();
+            fs::write(runtime_dir.join("events.jsonl"), events).unwrap();
+        }
+        write_worker_final_json(
+            &task_dir,
+            "w1",
+            json!({"status": "success", "summary": "ok"}),
+        );
+        write_worker_final_json(
+            &task_dir,
+            "w2",
+            json!({"status": "success", "summary": "ok"}),
+        );
+
+        let usage = orchestrator.task_usage(&task_id).unwrap();
+        let w1 = &usage.workers[0];
+        assert_eq!(w1.model.as_deref(), Some("gpt-test"));
+        assert_eq!(w1.usage.input_tokens, 900_100);
+        assert_eq!(w1.usage.output_tokens, 100_000);
+        assert!((w1.cost_usd.unwrap() - 1.9001).abs() < 1e-9);
+        assert_eq!(usage.workers[1].cost_usd, None);
+        // Earlier helper runs are archived like attempts and still count.
+        assert_eq!(usage.workers[2].agent_instance, PLANNER_AGENT_INSTANCE);
+        assert_eq!(usage.workers[2].usage.total_tokens(), 10);
+        assert_eq!(usage.usage.total_tokens(), 1_000_120);
+        assert_eq!(usage.cost_usd, w1.cost_usd);
+        // Reading usage has no side effects; the join records it.
+        assert!(!task_dir.join("shared").join("usage.json").exists());
+
+        let joined = orchestrator.task_join(&task_id).unwrap();
+        assert!(task_dir.join("shared").join("usage.json").is_file());
+        let status = orchestrator.cluster_status();
+        assert_eq!(status.usage, usage.usage);
+        let md = fs::read_to_string(joined.joined_summary_md).unwrap();
+        assert!(md.contains("- cost: `$1.9001`"));
+        assert!(md.contains("（model `gpt-test`）"));
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(joined.joined_summary_json).unwrap()).unwrap();
+        assert_eq!(json["usage"]["tokens"]["inputTokens"], json!(900_112));
+    }
+
+    #[test]
+    fn budget_gate_blocks_dispatch_until_approved() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let task_id = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "budget".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: Some(TaskConfig {
+                    budget: Some(TaskBudget {
+                        max_tokens: Some(100),
+                        max_cost_usd: None,
+                        max_wall_clock_seconds: None,
+                        max_spawns: None,
+                        soft_limit_ratio: 0.8,
+                        on_exceed: BudgetAction::Gate,
+                    }),
+                    ..TaskConfig::default()
+                }),
+            })
+            .unwrap()
+            .id;
+        let mut task = orchestrator.get_task(&task_id).unwrap();
+        task.roster.push(AgentInstance {
+            instance: "w1".to_string(),
+            agent: "worker".to_string(),
+            state: AgentInstanceState::Completed,
+            assigned_milestone: None,
+            skills: Vec::new(),
+        });
+        orchestrator.store.write_task(&task).unwrap();
+
+        let runtime_dir = orchestrator
+            .store
+            .task_dir(&task_id)
+            .join("agents")
+            .join("w1")
+            .join("runtime");
+        fs::create_dir_all(&runtime_dir).unwrap();
+        let write_usage = |input: u64| {
+            fs::write(
+                runtime_dir.join("events.jsonl"),
+                format!(r#"{{"type":"turn.completed","usage":{{"input_tokens":{input},"output_tokens":0}}}}"#),
+            )
+            .unwrap();
+        };
+
+        write_usage(90);
+        for _ in 0..2 {
+            assert_eq!(
+                orchestrator.enforce_budget(&task_id, 0).unwrap(),
+                budget::BudgetEnforcement::Within
+            );
+        }
+        let warnings = orchestrator
+            .store
+            .read_task_events(&task_id, Some("budget.warning"), usize::MAX, 0)
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+
+        write_usage(150);
+        for _ in 0..2 {
+            assert_eq!(
+                orchestrator.enforce_budget(&task_id, 0).unwrap(),
+                budget::BudgetEnforcement::Gated
+            );
+        }
+        let task = orchestrator.get_task(&task_id).unwrap();
+        assert_eq!(task.state, TaskState::InputRequired);
+        assert_eq!(task.gates.len(), 1);
+        assert_eq!(task.gates[0].id, "budget-1");
+        let status = orchestrator.budget_status(&task_id).unwrap().unwrap();
+        assert_eq!(status.level(), BudgetLevel::Exceeded);
+
+        assert_eq!(
+            orchestrator
+                .budget_resolve(&task_id, true, Some("tester"))
+                .unwrap(),
+            "budget-1"
+        );
+        let status = orchestrator.budget_status(&task_id).unwrap().unwrap();
+        assert_eq!(status.extensions, 1);
+        assert_eq!(status.metrics[0].limit, 200.0);
+        assert_eq!(
+            orchestrator.enforce_budget(&task_id, 0).unwrap(),
+            budget::BudgetEnforcement::Within
+        );
+        assert_eq!(
+            orchestrator.get_task(&task_id).unwrap().state,
+            TaskState::Working
+        );
+    }
+
+    #[test]
+    fn budget_counts_helper_spend_and_status_is_read_only() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let task_id = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "helper budget".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: Some(TaskConfig {
+                    budget: Some(TaskBudget {
+                        max_tokens: Some(100),
+                        max_cost_usd: None,
+                        max_wall_clock_seconds: None,
+                        max_spawns: None,
+                        soft_limit_ratio: 0.8,
+                        on_exceed: BudgetAction::Gate,
+                    }),
+                    ..TaskConfig::default()
+                }),
+            })
+            .unwrap()
+            .id;
+
+        // No roster worker has run; only the synthesizer spent tokens.
+        let task_dir = orchestrator.store.task_dir(&task_id);
+        let runtime_dir = task_dir
+            .join("agents")
+            .join(SYNTHESIZER_AGENT_INSTANCE)
+            .join("runtime");
+        fs::create_dir_all(&runtime_dir).unwrap();
+        fs::write(
+            runtime_dir.join("events.jsonl"),
+            r#"{"type":"turn.completed","usage":{"input_tokens":150,"output_tokens":0}}"#,
+        )
+        .unwrap();
+
+        let events_before = orchestrator
+            .get_task_events(&task_id, None, usize::MAX, 0)
+            .unwrap()
+            .len();
+        for _ in 0..2 {
+            let status = orchestrator.budget_status(&task_id).unwrap().unwrap();
+            assert_eq!(status.metrics[0].used, 150.0);
+            assert_eq!(status.level(), BudgetLevel::Exceeded);
+        }
+        assert!(!task_dir.join("shared").join("usage.json").exists());
+        assert_eq!(
+            orchestrator
+                .get_task_events(&task_id, None, usize::MAX, 0)
+                .unwrap()
+                .len(),
+            events_before
+        );
+        assert!(orchestrator.get_task(&task_id).unwrap().gates.is_empty());
+
+        assert_eq!(
+            orchestrator.enforce_budget(&task_id, 0).unwrap(),
+            budget::BudgetEnforcement::Gated
+        );
+    }
+
+    #[test]
+    fn report_render_prefers_workspace_template_override() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let task_id = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "report test".to_string(),
+                description: "".to_string(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: None,
+            })
+            .unwrap()
+            .id;
+        let mut task = orchestrator.get_task(&task_id).unwrap();
+        task.roster.push(AgentInstance {
+            instance: "w1".to_string(),
+            agent: "worker".to_string(),
+            state: AgentInstanceState::Completed,
+            assigned_milestone: None,
+            skills: Vec::new(),
+        });
+        orchestrator.store.write_task(&task).unwrap();
+        let task_dir = orchestrator.store.task_dir(&task_id);
+        write_worker_final_json(
+            &task_dir,
+            "w1",
+            json!({"status": "success", "summary": "found it", "nextActions": ["ship"]}),
+        );
+
+        let builtin = orchestrator
+            .report_render(&task_id, "DiagnosticReport", None)
+            .unwrap();
+        assert!(builtin
+            .path
+            .ends_with("shared/reports/diagnostic-report.md"));
+        assert!(builtin.content.contains("# 诊断报告：report test"));
+        assert!(builtin.content.contains("- [ ] ship（w1）"));
+
+        let override_dir = root.join(".coco").join("templates");
+        fs::create_dir_all(&override_dir).unwrap();
+        fs::write(
+            override_dir.join("DiagnosticReport.md"),
+            "{{#each workers}}{{agentInstance}}={{status}} evidence:{{evidenceId}}\n{{/each}}",
+        )
+        .unwrap();
+        let overridden = orchestrator
+            .report_render(&task_id, "DiagnosticReport", None)
+            .unwrap();
+        assert_eq!(overridden.content, "w1=success evidence:worker-w1\n");
+        // Rendering leaves the evidence index alone; join is what records the worker entries.
+        assert!(orchestrator.evidence_list(&task_id).unwrap().is_empty());
+        orchestrator.task_join(&task_id).unwrap();
+        assert!(orchestrator.evidence_check(&task_id).unwrap().is_ok());
+
+        let err = orchestrator
+            .report_render(&task_id, "Missing", None)
+            .unwrap_err();
+        assert!(matches!(err, OrchestratorError::TemplateNotFound { .. }));
+    }
+
+    #[test]
+    fn workspace_agent_specs_shadow_repo_specs() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+        for (dir, prompt) in [
+            (root.join("agents").join("qa"), "repo qa"),
+            (root.join("agents").join("db"), "repo db"),
+            (root.join(".coco").join("agents").join("qa"), "workspace qa"),
+        ] {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("agents.md"), prompt).unwrap();
+        }
+
+        let orchestrator = Orchestrator::new(root.clone());
+        let specs = orchestrator.agent_specs().unwrap();
+        let prompts = specs
+            .iter()
+            .map(|s| (s.name.as_str(), s.system_prompt.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(prompts, vec![("db", "repo db"), ("qa", "workspace qa")]);
+        assert!(orchestrator.find_agent_spec("backend").unwrap().is_none());
+    }
+
+    #[test]
+    fn worker_output_schema_prefers_the_agent_spec() {
+        let root = new_temp_workspace_root();
+        let _guard = TempDirGuard(root.clone());
+        let qa = root.join("agents").join("qa");
+        fs::create_dir_all(&qa).unwrap();
+        fs::write(
+            qa.join("agent.yaml"),
+            "outputSchema: schemas/qa.schema.json\n",
+        )
+        .unwrap();
+
+        let orchestrator = Orchestrator::new(root.clone());
+        assert_eq!(
+            orchestrator.worker_output_schema_path("qa").unwrap(),
+            root.join("schemas").join("qa.schema.json")
+        );
+        assert_eq!(
+            orchestrator.worker_output_schema_path("backend").unwrap(),
+            root.join("schemas").join("worker-output.schema.json")
+        );
+    }
+
     fn write_worker_final_json(task_dir: &Path, agent_instance: &str, value: serde_json::Value) {
         let artifacts_dir = task_dir
             .join("agents")
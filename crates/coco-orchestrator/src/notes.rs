@@ -0,0 +1,221 @@
+use crate::agent_dir;
+use crate::ControllerOptions;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::OrchestratorSubtask;
+use crate::SubagentInfo;
+use crate::SubagentStatus;
+use crate::RUNTIME_DIR_NAME;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+/// The notes as last given to a worker: `agents/<instance>/human-notes.seen.md`.
+const NOTES_SEEN_FILE_NAME: &str = "human-notes.seen.md";
+/// Set on a running codex-exec worker whose notes are out of date: `runtime/stale-context.json`.
+pub(crate) const STALE_CONTEXT_FILE_NAME: &str = "stale-context.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StaleContext {
+    marked_at: DateTime<Utc>,
+    /// Notes diff against what the worker saw at spawn.
+    diff: String,
+}
+
+/// Lets an app-server turn steer its thread when `shared/human-notes.md` changes mid-turn.
+pub(crate) struct NotesSteering<'a> {
+    pub orchestrator: &'a Orchestrator,
+    pub task_id: &'a str,
+    pub agent_instance: &'a str,
+}
+
+impl NotesSteering<'_> {
+    /// Diff since the worker last saw the notes, with the current notes.
+    pub fn pending(&self) -> Result<Option<(String, String)>, OrchestratorError> {
+        self.orchestrator
+            .pending_notes_diff(self.task_id, self.agent_instance)
+    }
+
+    pub fn delivered(&self, diff: &str, notes: &str) -> Result<(), OrchestratorError> {
+        self.orchestrator
+            .record_notes_seen(self.task_id, self.agent_instance, notes)?;
+        self.orchestrator.append_notes_delivered(
+            self.task_id,
+            self.agent_instance,
+            "interrupt-turn",
+            diff,
+            json!({}),
+        )
+    }
+}
+
+impl Orchestrator {
+    pub(crate) fn record_notes_seen(
+        &self,
+        task_id: &str,
+        agent_instance: &str,
+        notes: &str,
+    ) -> Result<(), OrchestratorError> {
+        let agent_dir = agent_dir(&self.store.task_dir(task_id), agent_instance);
+        fs::create_dir_all(&agent_dir)?;
+        fs::write(agent_dir.join(NOTES_SEEN_FILE_NAME), notes)?;
+        Ok(())
+    }
+
+    /// None when the notes are unchanged, or the worker never recorded what it saw.
+    pub(crate) fn pending_notes_diff(
+        &self,
+        task_id: &str,
+        agent_instance: &str,
+    ) -> Result<Option<(String, String)>, OrchestratorError> {
+        let seen_path =
+            agent_dir(&self.store.task_dir(task_id), agent_instance).join(NOTES_SEEN_FILE_NAME);
+        let seen = match fs::read_to_string(seen_path) {
+            Ok(seen) => seen,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let notes = self.read_human_notes(task_id);
+        Ok(diff_lines(&seen, &notes).map(|diff| (diff, notes)))
+    }
+
+    /// Called on every monitor poll: running codex-exec workers cannot take new input, so they
+    /// are marked stale (or, with `restart_stale_workers`, restarted with the current notes).
+    pub(crate) fn sync_human_notes(
+        &self,
+        task_id: &str,
+        subagents: &[SubagentInfo],
+        expected: &HashMap<String, &OrchestratorSubtask>,
+        opts: &ControllerOptions,
+    ) -> Result<(), OrchestratorError> {
+        for s in subagents
+            .iter()
+            .filter(|s| s.status == SubagentStatus::Running)
+        {
+            let Some((diff, _)) = self.pending_notes_diff(task_id, &s.agent_instance)? else {
+                continue;
+            };
+
+            if let Some(subtask) = expected
+                .get(&s.agent_instance)
+                .filter(|_| opts.restart_stale_workers)
+            {
+                let archived = self.restart_worker(task_id, subtask, opts)?;
+                self.append_notes_delivered(
+                    task_id,
+                    &s.agent_instance,
+                    "restart",
+                    &diff,
+                    json!({ "archivedAttempt": archived }),
+                )?;
+                continue;
+            }
+
+            let stale_path = agent_dir(&self.store.task_dir(task_id), &s.agent_instance)
+                .join(RUNTIME_DIR_NAME)
+                .join(STALE_CONTEXT_FILE_NAME);
+            let already_marked = fs::read_to_string(&stale_path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<StaleContext>(&content).ok())
+                .is_some_and(|stale| stale.diff == diff);
+            if already_marked {
+                continue;
+            }
+            let stale = StaleContext {
+                marked_at: Utc::now(),
+                diff: diff.clone(),
+            };
+            fs::write(&stale_path, serde_json::to_string_pretty(&stale)?)?;
+            self.append_notes_delivered(task_id, &s.agent_instance, "stale", &diff, json!({}))?;
+        }
+        Ok(())
+    }
+
+    fn append_notes_delivered(
+        &self,
+        task_id: &str,
+        agent_instance: &str,
+        via: &str,
+        diff: &str,
+        extra: serde_json::Value,
+    ) -> Result<(), OrchestratorError> {
+        let mut payload = json!({ "via": via, "diff": diff });
+        if let (Some(payload), serde_json::Value::Object(extra)) = (payload.as_object_mut(), extra)
+        {
+            payload.extend(extra);
+        }
+        self.append_agent_event(task_id, agent_instance, "notes.delivered", payload)
+    }
+}
+
+/// Steering message for an app-server worker whose notes changed mid-turn.
+pub(crate) fn notes_steering_prompt(diff: &str, output_contract: &str) -> String {
+    format!(
+        "# Human notes updated\n\nshared/human-notes.md changed while you were working \
+         (`+` added, `-` removed). These notes take priority over earlier instructions; \
+         adjust and continue the task.\n\n```diff\n{diff}```\n\n{output_contract}"
+    )
+}
+
+/// Line diff (`+ `/`- ` prefixed, unchanged lines omitted); None when equal.
+fn diff_lines(old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // Longest common subsequence table, filled from the end.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    // Only whitespace at line ends changed (e.g. a trailing newline).
+    (!out.is_empty()).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lines_reports_added_and_removed_lines() {
+        assert_eq!(diff_lines("a\nb\n", "a\nb\n"), None);
+        assert_eq!(diff_lines("a\nb", "a\nb\n"), None);
+        assert_eq!(
+            diff_lines(
+                "# Notes\n- keep v1\n- be quick\n",
+                "# Notes\n- use v2\n- be quick\n- add tests\n"
+            )
+            .as_deref(),
+            Some("- - keep v1\n+ - use v2\n+ - add tests\n")
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::agent_dir;
+use crate::codex_exec_command;
+use crate::controller_loop::extract_json_from_text;
+use crate::controller_loop::run_app_server_one_turn;
+use crate::controller_loop::unsupported_adapter;
+use crate::retry::current_attempt;
+use crate::validate_agent_instance;
+use crate::write_session_file;
+use crate::CodexExecInvocation;
+use crate::ControllerOptions;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::ARTIFACTS_DIR_NAME;
+use crate::CODEX_HOME_DIR_NAME;
+use crate::DEFAULT_POLL_INTERVAL;
+use crate::FINAL_OUTPUT_FILE_NAME;
+use crate::RUNTIME_DIR_NAME;
+use crate::RUNTIME_EVENTS_FILE_NAME;
+use crate::RUNTIME_PROMPT_FILE_NAME;
+use crate::RUNTIME_STDERR_FILE_NAME;
+use crate::SESSION_FILE_NAME;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+
+/// A helper worker (synthesizer, planner, ...) that runs one turn to completion and returns its
+/// JSON output. Unlike subagents it is not added to the task roster, so joins ignore it; its
+/// recordings still live under `agents/<agent_instance>/` for auditing.
+pub(crate) struct OneShotWorker<'a> {
+    pub agent_instance: &'a str,
+    pub adapter: &'a str,
+    pub prompt: String,
+    pub output_schema_path: &'a Path,
+    /// Prepended to the prompt for adapters without `--output-schema` support (app-server).
+    pub output_contract: &'a str,
+}
+
+impl Orchestrator {
+    pub(crate) fn run_oneshot_worker(
+        &self,
+        task_id: &str,
+        worker: &OneShotWorker<'_>,
+        opts: &ControllerOptions,
+    ) -> Result<Value, OrchestratorError> {
+        validate_agent_instance(worker.agent_instance)?;
+        let task = self.store.read_task(task_id)?;
+        let agent_dir = agent_dir(&self.store.task_dir(task_id), worker.agent_instance);
+
+        // Each run starts from fresh recordings; earlier runs are archived like retried attempts
+        // so their token usage still counts, and codex_home is kept so auth/config survive.
+        let runtime_dir = agent_dir.join(RUNTIME_DIR_NAME);
+        let artifacts_dir = agent_dir.join(ARTIFACTS_DIR_NAME);
+        let codex_home_dir = agent_dir.join(CODEX_HOME_DIR_NAME);
+        if runtime_dir.exists() || artifacts_dir.exists() {
+            self.archive_attempt(&agent_dir, current_attempt(&agent_dir)?, None)?;
+        }
+        for dir in [&runtime_dir, &artifacts_dir] {
+            fs::create_dir_all(dir)?;
+        }
+        fs::create_dir_all(&codex_home_dir)?;
+        write_session_file(
+            &agent_dir.join(SESSION_FILE_NAME),
+            &opts.default_cwd,
+            &codex_home_dir,
+        )?;
+
+        let final_output_path = artifacts_dir.join(FINAL_OUTPUT_FILE_NAME);
+        match worker.adapter {
+            "codex-exec" => {
+                let prompt_path = runtime_dir.join(RUNTIME_PROMPT_FILE_NAME);
+                fs::write(&prompt_path, &worker.prompt)?;
+                let prompt_file = fs::File::open(&prompt_path)?;
+                let events_file = fs::File::create(runtime_dir.join(RUNTIME_EVENTS_FILE_NAME))?;
+                let stderr_file = fs::File::create(runtime_dir.join(RUNTIME_STDERR_FILE_NAME))?;
+                let mut cmd = codex_exec_command(
+                    &CodexExecInvocation {
+                        codex_bin: &opts.codex_bin,
+                        cwd: &opts.default_cwd,
+                        output_schema_path: worker.output_schema_path,
+                        final_output_path: &final_output_path,
+                        codex_home: &codex_home_dir,
+                        spec: None,
+                        exit_status_path: None,
+                    },
+                    prompt_file,
+                    events_file,
+                    stderr_file,
+                );
+                let mut child = match cmd.spawn() {
+                    Ok(child) => child,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        return Err(OrchestratorError::CodexNotFound)
+                    }
+                    Err(err) => return Err(OrchestratorError::Io(err)),
+                };
+
+                let timeout_seconds = opts.timeout_seconds.unwrap_or(task.config.timeout_seconds);
+                let deadline = Instant::now() + Duration::from_secs(u64::from(timeout_seconds));
+                while child.try_wait()?.is_none() {
+                    if Instant::now() >= deadline {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        return Err(OrchestratorError::WaitAnyTimeout { timeout_seconds });
+                    }
+                    std::thread::sleep(DEFAULT_POLL_INTERVAL);
+                }
+            }
+            "codex-app-server" => {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(OrchestratorError::Io)?;
+                rt.block_on(async {
+                    run_app_server_one_turn(
+                        &agent_dir,
+                        &opts.default_cwd,
+                        &opts.codex_bin,
+                        "spawn",
+                        None,
+                        None,
+                        worker.output_contract,
+                        &worker.prompt,
+                        &final_output_path,
+                        None,
+                    )
+                    .await
+                })?;
+            }
+            other => return Err(unsupported_adapter(other)),
+        }
+
+        self.record_task_usage(task_id)?;
+
+        let content = fs::read_to_string(&final_output_path).map_err(|err| {
+            OrchestratorError::InvalidWorkerOutput {
+                agent_instance: worker.agent_instance.to_string(),
+                message: format!("missing {FINAL_OUTPUT_FILE_NAME}: {err}"),
+            }
+        })?;
+        extract_json_from_text(&content)
+            .as_deref()
+            .and_then(|s| serde_json::from_str::<Value>(s).ok())
+            .ok_or_else(|| OrchestratorError::InvalidWorkerOutput {
+                agent_instance: worker.agent_instance.to_string(),
+                message: "final output is not valid JSON".to_string(),
+            })
+    }
+}
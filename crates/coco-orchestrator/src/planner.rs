@@ -0,0 +1,423 @@
+use crate::context::CONTEXT_MANIFEST_FILE_NAME;
+use crate::oneshot::OneShotWorker;
+use crate::report::task_relative_path;
+use crate::validate_agent_instance;
+use crate::ControllerOptions;
+use crate::ControllerOutcome;
+use crate::ControllerRunResult;
+use crate::Orchestrator;
+use crate::OrchestratorActions;
+use crate::OrchestratorError;
+use crate::TASK_SHARED_DIR_NAME;
+use chrono::Utc;
+use coco_core::prompt_library::PromptLibrary;
+use coco_core::task::Gate;
+use coco_core::task::GateState;
+use coco_core::task::GateType;
+use coco_core::task::TaskEvent;
+use coco_core::task::TaskState;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The planner records under `agents/planner/` but is never added to the roster.
+pub const PLANNER_AGENT_INSTANCE: &str = "planner";
+
+const PLANS_DIR_NAME: &str = "plans";
+const HUMAN_NOTES_FILE_NAME: &str = "human-notes.md";
+
+const PLANNER_OUTPUT_CONTRACT: &str = "# Output Contract\nReturn ONLY valid JSON matching schemas/orchestrator-actions.schema.json.\nRequired keys: sessionGoal, tasks[] (taskId, title, agent, adapter, prompt). Optional per task: agentInstance, mode, forkedFromThreadId, cwd, outputSchemaPath, promptTemplate.\n";
+
+#[derive(Debug, Clone)]
+pub struct PlannerOptions {
+    /// `codex-exec` (default) or `codex-app-server`.
+    pub adapter: String,
+    /// JSON schema for the planner output (used by codex-exec).
+    pub output_schema_path: PathBuf,
+    /// Open a `human-approval` gate before the plan may be dispatched.
+    /// When None, follows `task.yaml` (`config.autoApprove: false` requires approval).
+    pub require_approval: Option<bool>,
+}
+
+impl PlannerOptions {
+    pub fn new(workspace_root: &Path) -> Self {
+        Self {
+            adapter: "codex-exec".to_string(),
+            output_schema_path: workspace_root
+                .join("schemas")
+                .join("orchestrator-actions.schema.json"),
+            require_approval: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanResult {
+    /// 1-based plan number; the plan is stored in `shared/plans/<n>.json`.
+    pub plan_number: u32,
+    pub path: PathBuf,
+    pub actions: OrchestratorActions,
+    /// Set when dispatch waits on a `human-approval` gate.
+    pub gate_id: Option<String>,
+}
+
+impl Orchestrator {
+    /// Run the planner worker over the task description, human notes, agent specs and context
+    /// manifest, then store the resulting actions in `shared/plans/<n>.json` for review.
+    pub fn plan(
+        &self,
+        task_id: &str,
+        opts: &ControllerOptions,
+        planner: &PlannerOptions,
+    ) -> Result<PlanResult, OrchestratorError> {
+        let task = self.store.read_task(task_id)?;
+        let task_dir = self.store.task_dir(task_id);
+        let shared_dir = task_dir.join(TASK_SHARED_DIR_NAME);
+
+        let human_notes = self.read_human_notes(task_id);
+        let context_manifest =
+            fs::read_to_string(shared_dir.join(CONTEXT_MANIFEST_FILE_NAME)).unwrap_or_default();
+        let agent_specs = self.workspace_agent_specs()?;
+        let roster = task
+            .roster
+            .iter()
+            .map(|a| format!("- `{}` (agent `{}`)", a.instance, a.agent))
+            .collect::<Vec<_>>();
+
+        let prompt = planner_prompt(&PlannerInput {
+            task_id: &task.id,
+            title: &task.title,
+            description: &task.description,
+            human_notes: &human_notes,
+            context_manifest: &context_manifest,
+            agent_specs: &agent_specs,
+            roster: &roster,
+        });
+        let value = self.run_oneshot_worker(
+            task_id,
+            &OneShotWorker {
+                agent_instance: PLANNER_AGENT_INSTANCE,
+                adapter: &planner.adapter,
+                prompt,
+                output_schema_path: &planner.output_schema_path,
+                output_contract: PLANNER_OUTPUT_CONTRACT,
+            },
+            opts,
+        )?;
+        let actions: OrchestratorActions = serde_json::from_value(value).map_err(|err| {
+            OrchestratorError::InvalidWorkerOutput {
+                agent_instance: PLANNER_AGENT_INSTANCE.to_string(),
+                message: err.to_string(),
+            }
+        })?;
+        validate_actions(&actions, self.workspace_root())?;
+
+        let plans_dir = shared_dir.join(PLANS_DIR_NAME);
+        fs::create_dir_all(&plans_dir)?;
+        let plan_number = next_plan_number(&plans_dir)?;
+        let path = plans_dir.join(format!("{plan_number}.json"));
+        fs::write(&path, serde_json::to_string_pretty(&actions)?)?;
+        let rel_path = task_relative_path(&task_dir, &path);
+
+        let event = TaskEvent {
+            ts: Utc::now(),
+            event_type: "plan.created".to_string(),
+            task_id: task_id.to_string(),
+            agent_instance: Some(PLANNER_AGENT_INSTANCE.to_string()),
+            turn_id: None,
+            payload: json!({
+                "planNumber": plan_number,
+                "sessionGoal": actions.session_goal,
+                "tasks": actions.tasks.len(),
+            }),
+            by: Some("controller".to_string()),
+            path: Some(rel_path.clone()),
+        };
+        self.store.append_task_event(task_id, &event)?;
+
+        let require_approval = planner
+            .require_approval
+            .unwrap_or(!task.config.auto_approve);
+        let gate_id = if require_approval {
+            Some(self.open_plan_gate(task_id, plan_number, &rel_path)?)
+        } else {
+            None
+        };
+
+        Ok(PlanResult {
+            plan_number,
+            path,
+            actions,
+            gate_id,
+        })
+    }
+
+    /// Read `shared/plans/<n>.json`, including any edits made during review.
+    pub fn plan_read(
+        &self,
+        task_id: &str,
+        plan_number: u32,
+    ) -> Result<OrchestratorActions, OrchestratorError> {
+        let path = self.plan_path(task_id, plan_number);
+        if !path.is_file() {
+            return Err(OrchestratorError::PlanNotFound { plan_number });
+        }
+        let actions: OrchestratorActions = serde_json::from_str(&fs::read_to_string(path)?)?;
+        validate_actions(&actions, self.workspace_root())?;
+        Ok(actions)
+    }
+
+    /// Approve or reject the `human-approval` gate of a plan.
+    pub fn plan_resolve(
+        &self,
+        task_id: &str,
+        plan_number: u32,
+        approved: bool,
+        by: Option<&str>,
+    ) -> Result<(), OrchestratorError> {
+        self.resolve_gate(
+            task_id,
+            &plan_gate_id(plan_number),
+            approved,
+            by,
+            json!({ "planNumber": plan_number, "by": by }),
+        )
+    }
+
+    /// Dispatch a stored plan through the controller loop. Fails while its gate is unresolved.
+    pub fn controller_run_plan(
+        &self,
+        task_id: &str,
+        plan_number: u32,
+        opts: ControllerOptions,
+    ) -> Result<ControllerRunResult, OrchestratorError> {
+        let task = self.store.read_task(task_id)?;
+        let gate_id = plan_gate_id(plan_number);
+        if let Some(gate) = task.gates.iter().find(|g| g.id == gate_id) {
+            if gate.state != GateState::Approved {
+                return Err(OrchestratorError::GateNotApproved { gate_id });
+            }
+        }
+        let actions = self.plan_read(task_id, plan_number)?;
+        self.controller_run_actions(task_id, actions, opts)
+    }
+
+    /// Plan, then run the plan right away unless it waits on a `human-approval` gate (in which
+    /// case the result is `Blocked`; approve and call `controller_run_plan` to continue).
+    pub fn controller_plan_and_run(
+        &self,
+        task_id: &str,
+        opts: ControllerOptions,
+        planner: &PlannerOptions,
+    ) -> Result<ControllerRunResult, OrchestratorError> {
+        let plan = self.plan(task_id, &opts, planner)?;
+        if plan.gate_id.is_some() {
+            return Ok(ControllerRunResult {
+                outcome: ControllerOutcome::Blocked,
+                joined_summary: None,
+                synthesis: None,
+                rounds: Vec::new(),
+            });
+        }
+        self.controller_run_actions(task_id, plan.actions, opts)
+    }
+
+    fn plan_path(&self, task_id: &str, plan_number: u32) -> PathBuf {
+        self.store
+            .task_dir(task_id)
+            .join(TASK_SHARED_DIR_NAME)
+            .join(PLANS_DIR_NAME)
+            .join(format!("{plan_number}.json"))
+    }
+
+    fn open_plan_gate(
+        &self,
+        task_id: &str,
+        plan_number: u32,
+        plan_rel_path: &str,
+    ) -> Result<String, OrchestratorError> {
+        let gate_id = plan_gate_id(plan_number);
+        let reason = format!("Review plan {plan_number} before dispatch");
+        let now = Utc::now();
+
+        let mut task = self.store.read_task(task_id)?;
+        task.gates.retain(|g| g.id != gate_id);
+        task.gates.push(Gate {
+            id: gate_id.clone(),
+            gate_type: GateType::HumanApproval,
+            state: GateState::Blocked,
+            reason: reason.clone(),
+            instructions_ref: Some(plan_rel_path.to_string()),
+            blocked_at: Some(now),
+            resolved_at: None,
+            resolved_by: None,
+        });
+        if matches!(task.state, TaskState::Created | TaskState::Working) {
+            task.state = TaskState::InputRequired;
+        }
+        task.updated_at = now;
+        self.store.write_task(&task)?;
+
+        self.append_gate_event(
+            task_id,
+            &gate_id,
+            None,
+            "gate.blocked",
+            json!({ "reason": reason, "planNumber": plan_number }),
+        )?;
+        Ok(gate_id)
+    }
+
+    pub(crate) fn read_human_notes(&self, task_id: &str) -> String {
+        let path = self
+            .store
+            .task_dir(task_id)
+            .join(TASK_SHARED_DIR_NAME)
+            .join(HUMAN_NOTES_FILE_NAME);
+        fs::read_to_string(path).unwrap_or_default()
+    }
+
+    /// Agent specs (see `agent_specs`) rendered as prompt sections.
+    pub(crate) fn workspace_agent_specs(&self) -> Result<Vec<String>, OrchestratorError> {
+        let specs = self.agent_specs()?;
+        Ok(specs
+            .iter()
+            .map(|spec| {
+                let mut section = format!("## {}\n", spec.name);
+                if !spec.file.description.trim().is_empty() {
+                    section.push_str(&format!("\n{}\n", spec.file.description.trim()));
+                }
+                if let Some(adapter) = spec.file.adapter.as_deref() {
+                    section.push_str(&format!("\n- default adapter: {adapter}"));
+                }
+                if !spec.file.skills.is_empty() {
+                    section.push_str(&format!("\n- skills: {}", spec.file.skills.join(", ")));
+                }
+                if !spec.system_prompt.is_empty() {
+                    section.push_str(&format!("\n\n{}\n", spec.system_prompt));
+                }
+                section
+            })
+            .collect())
+    }
+}
+
+struct PlannerInput<'a> {
+    task_id: &'a str,
+    title: &'a str,
+    description: &'a str,
+    human_notes: &'a str,
+    context_manifest: &'a str,
+    agent_specs: &'a [String],
+    roster: &'a [String],
+}
+
+fn planner_prompt(input: &PlannerInput<'_>) -> String {
+    let or_none = |value: &str| {
+        if value.trim().is_empty() {
+            "(none)".to_string()
+        } else {
+            value.trim().to_string()
+        }
+    };
+    format!(
+        "You are the planner for Coco task `{task_id}`.\n\
+         Break the task into subtasks that workers can run in parallel, and pick an agent for \
+         each one from the Agents section. Each subtask prompt must be self-contained: workers \
+         only see their own prompt.\n\n\
+         {PLANNER_OUTPUT_CONTRACT}\n\
+         # Task\n\n\
+         - title: {title}\n\
+         - description: {description}\n\n\
+         # Human Notes (shared/{HUMAN_NOTES_FILE_NAME}; highest priority)\n\n\
+         {notes}\n\n\
+         # Agents\n\n\
+         {agents}\n\n\
+         # Current Roster\n\n\
+         {roster}\n\n\
+         # Context Manifest (shared/{CONTEXT_MANIFEST_FILE_NAME})\n\n\
+         ```yaml\n{manifest}\n```\n",
+        task_id = input.task_id,
+        title = input.title.trim(),
+        description = or_none(input.description),
+        notes = or_none(input.human_notes),
+        agents = or_none(&input.agent_specs.join("\n")),
+        roster = or_none(&input.roster.join("\n")),
+        manifest = input.context_manifest.trim(),
+    )
+}
+
+/// Checks the schema can't express: unique agent instances and known adapters.
+/// Structural checks on a plan, plus rendering each `promptTemplate` against the workspace's
+/// prompt library so a missing prompt or argument is caught before approval, not at dispatch.
+pub(crate) fn validate_actions(
+    actions: &OrchestratorActions,
+    workspace_root: &Path,
+) -> Result<(), OrchestratorError> {
+    let invalid = |message: String| Err(OrchestratorError::InvalidPlan { message });
+    if actions.session_goal.trim().is_empty() {
+        return invalid("sessionGoal is empty".to_string());
+    }
+    if actions.tasks.is_empty() {
+        return invalid("tasks is empty".to_string());
+    }
+    let mut seen = HashSet::new();
+    let mut library = None;
+    for task in &actions.tasks {
+        let instance = task.resolved_agent_instance();
+        validate_agent_instance(&instance)?;
+        if !seen.insert(instance.clone()) {
+            return invalid(format!("duplicate agent instance: {instance}"));
+        }
+        // An empty adapter defers to the agent spec at dispatch.
+        if !matches!(
+            task.adapter.as_str(),
+            "" | "codex-exec" | "codex-app-server"
+        ) {
+            return invalid(format!("unsupported adapter: {}", task.adapter));
+        }
+        if task.prompt.trim().is_empty() && task.prompt_template.is_none() {
+            return invalid(format!("{instance}: prompt or promptTemplate is required"));
+        }
+        if let Some(template) = &task.prompt_template {
+            let library =
+                library.get_or_insert_with(|| PromptLibrary::discover(Some(workspace_root)));
+            if let Err(err) = library.render(&template.name, &template.args) {
+                return invalid(format!(
+                    "{instance}: promptTemplate {}: {err}",
+                    template.name
+                ));
+            }
+        }
+        if task.mode.as_deref() == Some("fork") && task.forked_from_thread_id.is_none() {
+            return invalid(format!("{instance}: mode=fork requires forkedFromThreadId"));
+        }
+    }
+    Ok(())
+}
+
+fn next_plan_number(plans_dir: &Path) -> Result<u32, OrchestratorError> {
+    let mut max = 0;
+    for entry in fs::read_dir(plans_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|v| v.to_str()) != Some("json") {
+            continue;
+        }
+        if let Some(n) = path
+            .file_stem()
+            .and_then(|v| v.to_str())
+            .and_then(|v| v.parse::<u32>().ok())
+        {
+            max = max.max(n);
+        }
+    }
+    Ok(max + 1)
+}
+
+fn plan_gate_id(plan_number: u32) -> String {
+    format!("gate-plan-{plan_number}")
+}
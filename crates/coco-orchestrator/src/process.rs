@@ -0,0 +1,256 @@
+use crate::pid_is_alive;
+use crate::read_pid;
+use crate::send_signal;
+use crate::OrchestratorError;
+use crate::CANCEL_GRACE_PERIOD;
+use crate::CANCEL_TERMINATE_PERIOD;
+use crate::RUNTIME_PID_FILE_NAME;
+use serde::Deserialize;
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+/// `runtime/process.json`: who the worker process is, so a recycled pid is not mistaken for it.
+pub(crate) const RUNTIME_PROCESS_FILE_NAME: &str = "process.json";
+/// `runtime/exit-status`: exit code written by the supervisor once the worker exits.
+pub(crate) const RUNTIME_EXIT_STATUS_FILE_NAME: &str = "exit-status";
+
+/// `sh -c` supervisor: runs the worker in the foreground and records its exit code.
+/// INT/TERM are trapped (not ignored) so the worker still receives them from the process
+/// group while the shell survives long enough to write the status.
+#[cfg(unix)]
+const SUPERVISOR_SCRIPT: &str = r#"exit_file="$1"
+shift
+trap ':' INT TERM
+"$@"
+code=$?
+printf '%s\n' "$code" > "$exit_file.tmp" && mv "$exit_file.tmp" "$exit_file"
+exit "$code""#;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProcessIdentity {
+    pub pid: i32,
+    /// Start time in clock ticks since boot (`/proc/<pid>/stat` field 22).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<u64>,
+    /// `/proc/sys/kernel/random/boot_id` at spawn time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boot_id: Option<String>,
+    /// The worker's argv; the prompt is not part of it but read from `prompt_path`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cmdline: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_path: Option<PathBuf>,
+    /// The supervisor leads its own process group; cancellation signals the whole group.
+    #[serde(default)]
+    pub process_group: bool,
+}
+
+impl ProcessIdentity {
+    /// Identity of a freshly spawned child. Fields `/proc` cannot provide stay empty and are
+    /// not checked later.
+    pub(crate) fn capture(pid: u32, cmd: &Command, process_group: bool) -> Self {
+        let pid = pid as i32;
+        let cmdline = std::iter::once(cmd.get_program())
+            .chain(cmd.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        Self {
+            pid,
+            start_time: proc_start_time(pid),
+            boot_id: boot_id(),
+            cmdline,
+            prompt_path: None,
+            process_group,
+        }
+    }
+
+    /// Alive only when the pid exists and, where recorded, boot id, start time and command
+    /// line still match.
+    pub(crate) fn is_alive(&self) -> Result<bool, OrchestratorError> {
+        if !pid_is_alive(self.pid)? {
+            return Ok(false);
+        }
+        if !procfs_available() {
+            return Ok(true);
+        }
+        if self.boot_id.is_some() && self.boot_id != boot_id() {
+            return Ok(false);
+        }
+        if self.start_time.is_some() && self.start_time != proc_start_time(self.pid) {
+            return Ok(false);
+        }
+        // `/proc/<pid>/cmdline` reads empty right after exec and for zombies; skip it then.
+        if let Some(cmdline) = proc_cmdline(self.pid).filter(|c| !c.is_empty()) {
+            if !self.cmdline.is_empty() && cmdline != self.cmdline {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// SIGINT → SIGTERM → SIGKILL with grace periods; a no-op once the process is gone.
+    pub(crate) fn cancel(&self) -> Result<(), OrchestratorError> {
+        if !self.is_alive()? {
+            return Ok(());
+        }
+
+        self.signal(libc::SIGINT)?;
+        if self.wait_exit(CANCEL_GRACE_PERIOD)? {
+            return Ok(());
+        }
+
+        self.signal(libc::SIGTERM)?;
+        if self.wait_exit(CANCEL_TERMINATE_PERIOD)? {
+            return Ok(());
+        }
+
+        #[cfg(unix)]
+        {
+            self.signal(libc::SIGKILL)?;
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = Command::new("taskkill")
+                .arg("/PID")
+                .arg(self.pid.to_string())
+                .arg("/T")
+                .arg("/F")
+                .status();
+        }
+
+        Ok(())
+    }
+
+    fn signal(&self, signal: i32) -> Result<(), OrchestratorError> {
+        if self.process_group {
+            send_signal(-self.pid, signal)
+        } else {
+            send_signal(self.pid, signal)
+        }
+    }
+
+    fn wait_exit(&self, period: Duration) -> Result<bool, OrchestratorError> {
+        let start = Instant::now();
+        while start.elapsed() < period {
+            if !self.is_alive()? {
+                return Ok(true);
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        Ok(!self.is_alive()?)
+    }
+}
+
+/// `process.json`, falling back to the bare `pid` file written by older versions.
+pub(crate) fn read_process_identity(
+    runtime_dir: &Path,
+) -> Result<Option<ProcessIdentity>, OrchestratorError> {
+    let path = runtime_dir.join(RUNTIME_PROCESS_FILE_NAME);
+    if path.exists() {
+        let content = fs::read_to_string(&path)?;
+        return Ok(Some(serde_json::from_str(&content)?));
+    }
+    Ok(
+        read_pid(&runtime_dir.join(RUNTIME_PID_FILE_NAME))?.map(|pid| ProcessIdentity {
+            pid,
+            start_time: None,
+            boot_id: None,
+            cmdline: Vec::new(),
+            prompt_path: None,
+            process_group: false,
+        }),
+    )
+}
+
+pub(crate) fn read_exit_status(runtime_dir: &Path) -> Result<Option<i32>, OrchestratorError> {
+    let path = runtime_dir.join(RUNTIME_EXIT_STATUS_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(fs::read_to_string(path)?.trim().parse().ok())
+}
+
+/// Supervised workers lead their own process group (Unix only).
+pub(crate) const SUPERVISOR_PROCESS_GROUP: bool = cfg!(unix);
+
+/// Wrap `program args…` in the supervisor (Unix) so its exit code lands in
+/// `runtime/exit-status`.
+pub(crate) fn supervised_command(
+    program: &Path,
+    args: &[OsString],
+    exit_status_path: &Path,
+) -> Command {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        let mut cmd = Command::new("/bin/sh");
+        cmd.arg("-c")
+            .arg(SUPERVISOR_SCRIPT)
+            .arg("coco-supervisor")
+            .arg(exit_status_path)
+            .arg(program)
+            .args(args)
+            .process_group(0);
+        cmd
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = exit_status_path;
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        cmd
+    }
+}
+
+/// Resolve `program` like the shell would (`PATH` lookup for bare names). The supervisor
+/// always starts, so a missing binary has to be detected before spawning.
+pub(crate) fn resolve_program(program: &Path) -> Option<PathBuf> {
+    if program.components().count() > 1 {
+        return program.is_file().then(|| program.to_path_buf());
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+fn procfs_available() -> bool {
+    Path::new("/proc/self/stat").exists()
+}
+
+fn boot_id() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .ok()
+        .map(|id| id.trim().to_string())
+}
+
+fn proc_start_time(pid: i32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // `comm` (field 2) may contain spaces and parentheses; fields after it are plain.
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(19)?.parse().ok()
+}
+
+fn proc_cmdline(pid: i32) -> Option<Vec<String>> {
+    let raw = fs::read(format!("/proc/{pid}/cmdline")).ok()?;
+    if raw.is_empty() {
+        return Some(Vec::new());
+    }
+    let raw = raw.strip_suffix(&[0]).unwrap_or(&raw);
+    Some(
+        raw.split(|b| *b == 0)
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect(),
+    )
+}
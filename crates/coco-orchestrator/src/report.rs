@@ -0,0 +1,392 @@
+use crate::agent_dir;
+use crate::evidence_id_for_agent_instance;
+use crate::read_worker_final_output;
+use crate::retry::current_attempt;
+use crate::JoinedWorkerSummary;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::ARTIFACTS_DIR_NAME;
+use crate::FINAL_OUTPUT_FILE_NAME;
+use crate::TASK_SHARED_DIR_NAME;
+use crate::TASK_SHARED_REPORTS_DIR_NAME;
+use chrono::DateTime;
+use chrono::Utc;
+use coco_core::evidence::EvidenceEntry;
+use coco_core::evidence::EvidenceSource;
+use coco_core::task::GateState;
+use coco_core::task::TaskEvent;
+use coco_core::task::TaskFile;
+use coco_core::template::Template;
+use coco_core::usage::format_cost_usd;
+use coco_core::usage::TaskUsage;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub const JOINED_SUMMARY_TEMPLATE_NAME: &str = "JoinedSummary";
+
+/// Workspace-level overrides live in `<workspace>/.coco/templates/<Name>.md`.
+const TEMPLATE_OVERRIDE_DIR_NAME: &str = "templates";
+const TEMPLATE_FILE_EXTENSION: &str = "md";
+
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        JOINED_SUMMARY_TEMPLATE_NAME,
+        include_str!("../../../templates/JoinedSummary.md"),
+    ),
+    (
+        "DiagnosticReport",
+        include_str!("../../../templates/DiagnosticReport.md"),
+    ),
+    (
+        "APIContract",
+        include_str!("../../../templates/APIContract.md"),
+    ),
+    ("Synthesis", include_str!("../../../templates/Synthesis.md")),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportTemplateInfo {
+    pub name: String,
+    /// `None` for templates bundled with Coco; otherwise the workspace override path.
+    pub override_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedReport {
+    pub template: String,
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl Orchestrator {
+    /// Built-in report templates plus any `.coco/templates/*.md` overrides/additions.
+    pub fn report_templates(&self) -> Result<Vec<ReportTemplateInfo>, OrchestratorError> {
+        let mut out = BUILTIN_TEMPLATES
+            .iter()
+            .map(|(name, _)| ReportTemplateInfo {
+                name: name.to_string(),
+                override_path: None,
+            })
+            .collect::<Vec<_>>();
+
+        let override_dir = self.template_override_dir();
+        if override_dir.is_dir() {
+            for entry in fs::read_dir(&override_dir)? {
+                let path = entry?.path();
+                if path.extension().and_then(|v| v.to_str()) != Some(TEMPLATE_FILE_EXTENSION) {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|v| v.to_str()) else {
+                    continue;
+                };
+                if validate_template_name(name).is_err() {
+                    continue;
+                }
+                match out.iter_mut().find(|t| t.name == name) {
+                    Some(existing) => existing.override_path = Some(path),
+                    None => out.push(ReportTemplateInfo {
+                        name: name.to_string(),
+                        override_path: Some(path),
+                    }),
+                }
+            }
+        }
+
+        out.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(out)
+    }
+
+    /// Render `template` over the task's join data model and write it under `shared/reports/`
+    /// (or `output`, when given). Workers are re-read from their `final.json`; their evidence
+    /// entries are only merged in memory, the index on disk is left to `task_join`.
+    pub fn report_render(
+        &self,
+        task_id: &str,
+        template: &str,
+        output: Option<&Path>,
+    ) -> Result<RenderedReport, OrchestratorError> {
+        validate_template_name(template)?;
+        let task = self.reconcile_subagents(task_id)?.task;
+        let task_dir = self.store.task_dir(task_id);
+        let workers = collect_worker_summaries(&task, &task_dir)?;
+        let generated_at = Utc::now();
+        let evidence = self.worker_evidence_entries(task_id, &workers, generated_at)?;
+        let usage = self.task_usage(task_id)?;
+
+        let content =
+            self.render_report(template, &task, generated_at, &workers, &evidence, &usage)?;
+        let path = match output {
+            Some(path) => path.to_path_buf(),
+            None => task_dir
+                .join(TASK_SHARED_DIR_NAME)
+                .join(TASK_SHARED_REPORTS_DIR_NAME)
+                .join(report_file_name(template)),
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &content)?;
+
+        let event = TaskEvent {
+            ts: Utc::now(),
+            event_type: "report.rendered".to_string(),
+            task_id: task_id.to_string(),
+            agent_instance: None,
+            turn_id: None,
+            payload: json!({ "template": template }),
+            by: Some("orchestrator".to_string()),
+            path: Some(task_relative_path(&task_dir, &path)),
+        };
+        self.store.append_task_event(task_id, &event)?;
+
+        Ok(RenderedReport {
+            template: template.to_string(),
+            path,
+            content,
+        })
+    }
+
+    pub(crate) fn render_report(
+        &self,
+        template: &str,
+        task: &TaskFile,
+        generated_at: DateTime<Utc>,
+        workers: &[JoinedWorkerSummary],
+        evidence: &[EvidenceEntry],
+        usage: &TaskUsage,
+    ) -> Result<String, OrchestratorError> {
+        let context = report_context(task, generated_at, workers, evidence, usage);
+        self.render_template_with_context(template, &context)
+    }
+
+    /// Render a (possibly overridden) template over a caller-built context, e.g. the join data
+    /// model extended with stage-specific keys.
+    pub(crate) fn render_template_with_context(
+        &self,
+        template: &str,
+        context: &serde_json::Value,
+    ) -> Result<String, OrchestratorError> {
+        let source = self.load_report_template(template)?;
+        let template = Template::parse(&source)?;
+        Ok(template.render(context))
+    }
+
+    fn load_report_template(&self, name: &str) -> Result<String, OrchestratorError> {
+        let override_path = self
+            .template_override_dir()
+            .join(format!("{name}.{TEMPLATE_FILE_EXTENSION}"));
+        if override_path.is_file() {
+            return Ok(fs::read_to_string(override_path)?);
+        }
+        BUILTIN_TEMPLATES
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, source)| source.to_string())
+            .ok_or_else(|| OrchestratorError::TemplateNotFound {
+                template: name.to_string(),
+            })
+    }
+
+    fn template_override_dir(&self) -> PathBuf {
+        self.workspace_root()
+            .join(".coco")
+            .join(TEMPLATE_OVERRIDE_DIR_NAME)
+    }
+}
+
+pub(crate) fn collect_worker_summaries(
+    task: &TaskFile,
+    task_dir: &Path,
+) -> Result<Vec<JoinedWorkerSummary>, OrchestratorError> {
+    let mut workers = Vec::new();
+    for agent in &task.roster {
+        let agent_dir = agent_dir(task_dir, &agent.instance);
+        let final_path = agent_dir
+            .join(ARTIFACTS_DIR_NAME)
+            .join(FINAL_OUTPUT_FILE_NAME);
+        let final_output = read_worker_final_output(&final_path)?;
+        workers.push(JoinedWorkerSummary {
+            agent_instance: agent.instance.clone(),
+            agent: agent.agent.clone(),
+            status: final_output.status,
+            summary: final_output.summary,
+            questions: final_output.questions,
+            next_actions: final_output.next_actions,
+            attempt: current_attempt(&agent_dir)?,
+        });
+    }
+    Ok(workers)
+}
+
+/// Join data model exposed to report templates.
+pub(crate) fn report_context(
+    task: &TaskFile,
+    generated_at: DateTime<Utc>,
+    workers: &[JoinedWorkerSummary],
+    evidence: &[EvidenceEntry],
+    usage: &TaskUsage,
+) -> serde_json::Value {
+    let trimmed = |items: &[String]| {
+        items
+            .iter()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>()
+    };
+    let count_status = |status: &str| workers.iter().filter(|w| w.status == status).count();
+    let worker_usage = |agent_instance: &str| {
+        usage
+            .workers
+            .iter()
+            .find(|w| w.agent_instance == agent_instance)
+    };
+
+    json!({
+        "task": task,
+        "generatedAt": generated_at.to_rfc3339(),
+        "workers": workers.iter().map(|w| json!({
+            "agentInstance": w.agent_instance,
+            "agent": w.agent,
+            "status": w.status,
+            "summary": w.summary.trim(),
+            "questions": trimmed(&w.questions),
+            "nextActions": trimmed(&w.next_actions),
+            "attempt": w.attempt,
+            "retried": w.attempt > 1,
+            // model/tokens/cost: empty strings (not missing keys) so lookups never fall back to
+            // the task-level values.
+            "model": worker_usage(&w.agent_instance).and_then(|u| u.model.clone()).unwrap_or_default(),
+            "tokens": worker_usage(&w.agent_instance)
+                .filter(|u| !u.usage.is_empty())
+                .map(|u| u.usage.to_string())
+                .unwrap_or_default(),
+            "cost": worker_usage(&w.agent_instance)
+                .and_then(|u| u.cost_usd)
+                .map(format_cost_usd)
+                .unwrap_or_default(),
+            "evidenceId": evidence_id_for_agent_instance(&w.agent_instance),
+        })).collect::<Vec<_>>(),
+        "evidence": evidence.iter().map(|e| {
+            let mut value = json!(e);
+            value["refs"] = json!(evidence_refs(e));
+            value
+        }).collect::<Vec<_>>(),
+        "usage": {
+            "totals": usage.usage,
+            "tokens": if usage.usage.is_empty() { String::new() } else { usage.usage.to_string() },
+            "cost": usage.cost_usd.map(format_cost_usd).unwrap_or_default(),
+        },
+        "milestones": task.milestones,
+        "gates": task.gates,
+        "blockedGates": task
+            .gates
+            .iter()
+            .filter(|g| g.state == GateState::Blocked)
+            .collect::<Vec<_>>(),
+        "counts": {
+            "workers": workers.len(),
+            "success": count_status("success"),
+            "blocked": count_status("blocked"),
+            "failed": count_status("failed"),
+            "nextActions": workers.iter().map(|w| w.next_actions.len()).sum::<usize>(),
+        },
+    })
+}
+
+/// Short, human-readable pointers for each evidence source (used in report tables).
+fn evidence_refs(entry: &EvidenceEntry) -> Vec<String> {
+    entry
+        .sources
+        .iter()
+        .map(|source| match source {
+            EvidenceSource::FileAnchor {
+                path,
+                start_line,
+                end_line,
+            } => format!("{path}#L{start_line}-L{end_line}"),
+            EvidenceSource::CommandExecution {
+                command,
+                stdout_ref,
+                ..
+            } => stdout_ref.clone().unwrap_or_else(|| command.clone()),
+            EvidenceSource::RuntimeEventRange {
+                events_ref,
+                start_line,
+                end_line,
+            } => match (start_line, end_line) {
+                (Some(start), Some(end)) => format!("{events_ref}#L{start}-L{end}"),
+                (Some(start), None) => format!("{events_ref}#L{start}"),
+                _ => events_ref.clone(),
+            },
+        })
+        .collect()
+}
+
+fn validate_template_name(name: &str) -> Result<(), OrchestratorError> {
+    let is_ok = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_ok {
+        Ok(())
+    } else {
+        Err(OrchestratorError::TemplateNotFound {
+            template: name.to_string(),
+        })
+    }
+}
+
+/// `DiagnosticReport` -> `diagnostic-report.md`, `APIContract` -> `api-contract.md`.
+fn report_file_name(template: &str) -> String {
+    let chars = template.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    for (idx, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev_lower = idx > 0 && chars[idx - 1].is_ascii_lowercase();
+            let next_lower = chars.get(idx + 1).is_some_and(|n| n.is_ascii_lowercase());
+            let prev_upper = idx > 0 && chars[idx - 1].is_ascii_uppercase();
+            if prev_lower || (prev_upper && next_lower) {
+                out.push('-');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if *c == '_' {
+            out.push('-');
+        } else {
+            out.push(*c);
+        }
+    }
+    format!("{out}.{TEMPLATE_FILE_EXTENSION}")
+}
+
+pub(crate) fn task_relative_path(task_dir: &Path, path: &Path) -> String {
+    match path.strip_prefix(task_dir) {
+        Ok(rel) => format!("./{}", rel.to_string_lossy()),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_file_names_are_kebab_case() {
+        assert_eq!(report_file_name("JoinedSummary"), "joined-summary.md");
+        assert_eq!(report_file_name("DiagnosticReport"), "diagnostic-report.md");
+        assert_eq!(report_file_name("APIContract"), "api-contract.md");
+    }
+
+    #[test]
+    fn builtin_templates_parse() {
+        for (name, source) in BUILTIN_TEMPLATES {
+            assert!(
+                Template::parse(source).is_ok(),
+                "template {name} must parse"
+            );
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::agent_dir;
+use crate::process::read_process_identity;
+use crate::read_deadline;
+use crate::read_final_status;
+use crate::ControllerOptions;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::OrchestratorSubtask;
+use crate::ARTIFACTS_DIR_NAME;
+use crate::FINAL_OUTPUT_FILE_NAME;
+use crate::RUNTIME_DEADLINE_FILE_NAME;
+use crate::RUNTIME_DIR_NAME;
+use crate::RUNTIME_EVENTS_FILE_NAME;
+use crate::RUNTIME_STDERR_FILE_NAME;
+use crate::SESSION_FILE_NAME;
+use chrono::DateTime;
+use chrono::Utc;
+use coco_core::task::RetryClass;
+use coco_core::task::RetryPolicy;
+use serde::Serialize;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Failed attempts are moved to `agents/<instance>/attempts/<n>/` before a respawn.
+const ATTEMPTS_DIR_NAME: &str = "attempts";
+const ATTEMPT_FILE_NAME: &str = "attempt.json";
+
+/// Lower-cased substrings that mark a provider rate limit in `stderr.log` or error events.
+const RATE_LIMIT_MARKERS: &[&str] = &["rate limit", "rate_limit", "ratelimit", "too many requests"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchivedAttempt {
+    attempt: u32,
+    /// None when a running attempt was stopped on purpose (restart after a human-notes edit) or
+    /// for an earlier run of a helper worker.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure_class: Option<RetryClass>,
+    archived_at: DateTime<Utc>,
+}
+
+impl Orchestrator {
+    /// Decide whether a failed codex-exec worker gets another attempt. If so, the failed attempt
+    /// is archived, `agent.retrying` is emitted and the backoff before the respawn is returned.
+    pub(crate) fn schedule_retry(
+        &self,
+        task_id: &str,
+        subtask: &OrchestratorSubtask,
+        policy: Option<&RetryPolicy>,
+    ) -> Result<Option<Duration>, OrchestratorError> {
+        let Some(policy) = policy else {
+            return Ok(None);
+        };
+        let agent_instance = subtask.resolved_agent_instance();
+        let agent_dir = agent_dir(&self.store.task_dir(task_id), &agent_instance);
+        if !agent_dir.join(RUNTIME_DIR_NAME).exists() {
+            // Archived but not respawned yet (e.g. the controller restarted in between).
+            return Ok((archived_attempts(&agent_dir)? > 0).then_some(Duration::ZERO));
+        }
+        let Some(class) = classify_failure(&agent_dir)? else {
+            return Ok(None);
+        };
+        self.archive_failed_attempt(task_id, &agent_instance, class, policy)
+    }
+
+    /// Spawn failures leave `runtime/` behind without a process; archive them like any other
+    /// failed attempt.
+    pub(crate) fn schedule_spawn_retry(
+        &self,
+        task_id: &str,
+        subtask: &OrchestratorSubtask,
+        policy: Option<&RetryPolicy>,
+    ) -> Result<Option<Duration>, OrchestratorError> {
+        let Some(policy) = policy else {
+            return Ok(None);
+        };
+        let agent_instance = subtask.resolved_agent_instance();
+        let agent_dir = agent_dir(&self.store.task_dir(task_id), &agent_instance);
+        if !agent_dir.join(RUNTIME_DIR_NAME).exists() {
+            return Ok(None);
+        }
+        self.archive_failed_attempt(task_id, &agent_instance, RetryClass::SpawnError, policy)
+    }
+
+    /// Start the next attempt of an instance whose failed attempt was archived.
+    pub(crate) fn respawn_worker(
+        &self,
+        task_id: &str,
+        subtask: &OrchestratorSubtask,
+        opts: &ControllerOptions,
+    ) -> Result<(), OrchestratorError> {
+        let req = self.exec_spawn_request(task_id, subtask, opts)?;
+        self.spawn_exec_worker(req, true)?;
+        Ok(())
+    }
+
+    /// Stop a running codex-exec worker, archive its attempt and start the next one, so that it
+    /// picks up the current context. Returns the archived attempt number.
+    pub(crate) fn restart_worker(
+        &self,
+        task_id: &str,
+        subtask: &OrchestratorSubtask,
+        opts: &ControllerOptions,
+    ) -> Result<u32, OrchestratorError> {
+        let agent_instance = subtask.resolved_agent_instance();
+        let agent_dir = agent_dir(&self.store.task_dir(task_id), &agent_instance);
+        if let Some(process) = read_process_identity(&agent_dir.join(RUNTIME_DIR_NAME))? {
+            process.cancel()?;
+        }
+        let attempt = current_attempt(&agent_dir)?;
+        self.archive_attempt(&agent_dir, attempt, None)?;
+        self.respawn_worker(task_id, subtask, opts)?;
+        Ok(attempt)
+    }
+
+    fn archive_failed_attempt(
+        &self,
+        task_id: &str,
+        agent_instance: &str,
+        class: RetryClass,
+        policy: &RetryPolicy,
+    ) -> Result<Option<Duration>, OrchestratorError> {
+        let agent_dir = agent_dir(&self.store.task_dir(task_id), agent_instance);
+        let attempt = current_attempt(&agent_dir)?;
+        if !policy.should_retry(class, attempt) {
+            return Ok(None);
+        }
+        self.archive_attempt(&agent_dir, attempt, Some(class))?;
+
+        let backoff = policy.backoff_after(attempt);
+        self.append_agent_event(
+            task_id,
+            agent_instance,
+            "agent.retrying",
+            json!({
+                "failedAttempt": attempt,
+                "attempt": attempt + 1,
+                "failureClass": class.as_str(),
+                "backoffSeconds": backoff.as_secs(),
+                "archivedTo": format!("./{ATTEMPTS_DIR_NAME}/{attempt}"),
+            }),
+        )?;
+        Ok(Some(backoff))
+    }
+
+    pub(crate) fn archive_attempt(
+        &self,
+        agent_dir: &Path,
+        attempt: u32,
+        failure_class: Option<RetryClass>,
+    ) -> Result<(), OrchestratorError> {
+        let archived = agent_dir.join(ATTEMPTS_DIR_NAME).join(attempt.to_string());
+        fs::create_dir_all(&archived)?;
+        for name in [RUNTIME_DIR_NAME, ARTIFACTS_DIR_NAME] {
+            let src = agent_dir.join(name);
+            if src.exists() {
+                fs::rename(&src, archived.join(name))?;
+            }
+        }
+        let session_path = agent_dir.join(SESSION_FILE_NAME);
+        if session_path.exists() {
+            fs::copy(&session_path, archived.join(SESSION_FILE_NAME))?;
+        }
+        let record = ArchivedAttempt {
+            attempt,
+            failure_class,
+            archived_at: Utc::now(),
+        };
+        fs::write(
+            archived.join(ATTEMPT_FILE_NAME),
+            serde_json::to_string_pretty(&record)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// 1-based number of the attempt that owns `runtime/` and `artifacts/`.
+pub(crate) fn current_attempt(agent_dir: &Path) -> Result<u32, OrchestratorError> {
+    Ok(archived_attempts(agent_dir)? + 1)
+}
+
+/// `runtime/` of every archived attempt followed by the current one (existing dirs only).
+pub(crate) fn attempt_runtime_dirs(agent_dir: &Path) -> Result<Vec<PathBuf>, OrchestratorError> {
+    let mut dirs = Vec::new();
+    for attempt in 1..=archived_attempts(agent_dir)? {
+        dirs.push(
+            agent_dir
+                .join(ATTEMPTS_DIR_NAME)
+                .join(attempt.to_string())
+                .join(RUNTIME_DIR_NAME),
+        );
+    }
+    dirs.push(agent_dir.join(RUNTIME_DIR_NAME));
+    dirs.retain(|d| d.is_dir());
+    Ok(dirs)
+}
+
+fn archived_attempts(agent_dir: &Path) -> Result<u32, OrchestratorError> {
+    let dir = agent_dir.join(ATTEMPTS_DIR_NAME);
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.path().is_dir() && entry.file_name().to_string_lossy().parse::<u32>().is_ok() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Retryable cause of a failed attempt, or None when the worker reported `status: failed` itself.
+fn classify_failure(agent_dir: &Path) -> Result<Option<RetryClass>, OrchestratorError> {
+    let runtime_dir = agent_dir.join(RUNTIME_DIR_NAME);
+    if read_deadline(&runtime_dir.join(RUNTIME_DEADLINE_FILE_NAME))?
+        .is_some_and(|d| Utc::now() >= d.deadline)
+    {
+        return Ok(Some(RetryClass::Timeout));
+    }
+    if saw_rate_limit(&runtime_dir)? {
+        return Ok(Some(RetryClass::RateLimit));
+    }
+    let final_path = agent_dir
+        .join(ARTIFACTS_DIR_NAME)
+        .join(FINAL_OUTPUT_FILE_NAME);
+    Ok(match read_final_status(&final_path)?.as_deref() {
+        None => Some(RetryClass::Crash),
+        Some("success" | "blocked" | "failed") => None,
+        Some(_) => Some(RetryClass::InvalidOutput),
+    })
+}
+
+fn saw_rate_limit(runtime_dir: &Path) -> Result<bool, OrchestratorError> {
+    let mentions_rate_limit = |text: &str| {
+        let text = text.to_lowercase();
+        RATE_LIMIT_MARKERS.iter().any(|m| text.contains(m))
+    };
+
+    let stderr_path = runtime_dir.join(RUNTIME_STDERR_FILE_NAME);
+    if stderr_path.exists() && mentions_rate_limit(&fs::read_to_string(&stderr_path)?) {
+        return Ok(true);
+    }
+
+    let events_path = runtime_dir.join(RUNTIME_EVENTS_FILE_NAME);
+    if !events_path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&events_path)?;
+    Ok(content.lines().any(|line| {
+        let is_error = serde_json::from_str::<serde_json::Value>(line)
+            .ok()
+            .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .is_some_and(|t| t == "error" || t == "turn.failed");
+        is_error && mentions_rate_limit(line)
+    }))
+}
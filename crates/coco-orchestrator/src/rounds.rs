@@ -0,0 +1,320 @@
+use crate::oneshot::OneShotWorker;
+use crate::planner::validate_actions;
+use crate::report::task_relative_path;
+use crate::ControllerOptions;
+use crate::ControllerOutcome;
+use crate::JoinTaskResponse;
+use crate::Orchestrator;
+use crate::OrchestratorActions;
+use crate::OrchestratorError;
+use crate::OrchestratorSubtask;
+use crate::TASK_SHARED_DIR_NAME;
+use crate::TASK_SHARED_REPORTS_DIR_NAME;
+use chrono::Utc;
+use coco_core::task::TaskEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The round planner records under `agents/round-planner/` but is never added to the roster.
+pub const ROUND_PLANNER_AGENT_INSTANCE: &str = "round-planner";
+
+const ROUNDS_DIR_NAME: &str = "rounds";
+const ROUND_DECISION_FILE_NAME: &str = "decision.json";
+
+const ROUND_DECISION_OUTPUT_CONTRACT: &str = "# Output Contract\nReturn ONLY valid JSON matching schemas/round-decision.schema.json.\nRequired keys: status (done|continue), reason, tasks[]. When status=continue, tasks use the orchestrator-actions subtask shape (taskId, title, agent, adapter, prompt).\n";
+
+#[derive(Debug, Clone)]
+pub struct ControllerRoundOptions {
+    /// Upper bound on dispatch/join rounds, including the first one.
+    pub max_rounds: u32,
+    /// Cap on workers dispatched across all rounds (a count, not a cost). None means unlimited.
+    /// Token and cost limits are `config.budget` (`maxTokens` / `maxCostUsd`).
+    pub max_dispatched_workers: Option<u32>,
+    /// Round planner adapter: `codex-exec` (default) or `codex-app-server`.
+    pub adapter: String,
+    /// JSON schema for round decisions (used by codex-exec).
+    pub output_schema_path: PathBuf,
+}
+
+impl ControllerRoundOptions {
+    pub fn new(workspace_root: &Path) -> Self {
+        Self {
+            max_rounds: 3,
+            max_dispatched_workers: None,
+            adapter: "codex-exec".to_string(),
+            output_schema_path: workspace_root
+                .join("schemas")
+                .join("round-decision.schema.json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundDecision {
+    /// The planner judged `sessionGoal` met.
+    Done,
+    /// The planner emitted another batch of subtasks.
+    Continue,
+    /// The planner wanted another round but `max_rounds` was reached.
+    MaxRounds,
+    /// The planner wanted another round but it would exceed `max_dispatched_workers`.
+    MaxWorkers,
+}
+
+impl RoundDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundDecision::Done => "done",
+            RoundDecision::Continue => "continue",
+            RoundDecision::MaxRounds => "max-rounds",
+            RoundDecision::MaxWorkers => "max-workers",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControllerRound {
+    /// 1-based round number.
+    pub round: u32,
+    pub agent_instances: Vec<String>,
+    pub decision: RoundDecision,
+    pub reason: String,
+    /// `shared/reports/rounds/<n>/`: this round's joined summary and decision.
+    pub report_dir: PathBuf,
+}
+
+/// Round progress threaded through the controller loop (and rendered in `state-board.md`).
+#[derive(Debug, Clone)]
+pub(crate) struct RoundBoard {
+    pub current: u32,
+    pub max_rounds: u32,
+    pub dispatched_workers: u32,
+    pub history: Vec<ControllerRound>,
+}
+
+impl RoundBoard {
+    pub(crate) fn new(max_rounds: u32) -> Self {
+        Self {
+            current: 1,
+            max_rounds: max_rounds.max(1),
+            dispatched_workers: 0,
+            history: Vec::new(),
+        }
+    }
+}
+
+pub(crate) enum RoundStep {
+    Finished(ControllerOutcome),
+    Next(OrchestratorActions),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RoundPlannerOutput {
+    status: String,
+    reason: String,
+    #[serde(default)]
+    tasks: Vec<OrchestratorSubtask>,
+}
+
+impl Orchestrator {
+    /// Archive the round's reports, ask the round planner whether `sessionGoal` is met and
+    /// either finish or return the next batch (subject to `max_rounds`/`max_dispatched_workers`).
+    pub(crate) fn controller_round_step(
+        &self,
+        task_id: &str,
+        actions: &OrchestratorActions,
+        joined: &JoinTaskResponse,
+        rounds: &ControllerRoundOptions,
+        board: &mut RoundBoard,
+        opts: &ControllerOptions,
+    ) -> Result<RoundStep, OrchestratorError> {
+        let task_dir = self.store.task_dir(task_id);
+        let report_dir = task_dir
+            .join(TASK_SHARED_DIR_NAME)
+            .join(TASK_SHARED_REPORTS_DIR_NAME)
+            .join(ROUNDS_DIR_NAME)
+            .join(board.current.to_string());
+        fs::create_dir_all(&report_dir)?;
+        for path in [&joined.joined_summary_md, &joined.joined_summary_json] {
+            if let Some(file_name) = path.file_name() {
+                fs::copy(path, report_dir.join(file_name))?;
+            }
+        }
+
+        let output = self.evaluate_round(task_id, actions, joined, rounds, board, opts)?;
+        fs::write(
+            report_dir.join(ROUND_DECISION_FILE_NAME),
+            serde_json::to_string_pretty(&output)?,
+        )?;
+
+        let mut next = OrchestratorActions {
+            session_goal: actions.session_goal.clone(),
+            tasks: output.tasks,
+        };
+        let (decision, step) = if output.status == "done" || next.tasks.is_empty() {
+            (
+                RoundDecision::Done,
+                RoundStep::Finished(ControllerOutcome::Done),
+            )
+        } else if board.current >= board.max_rounds {
+            (
+                RoundDecision::MaxRounds,
+                RoundStep::Finished(ControllerOutcome::BudgetExhausted),
+            )
+        } else if rounds
+            .max_dispatched_workers
+            .is_some_and(|max| board.dispatched_workers + next.tasks.len() as u32 > max)
+        {
+            (
+                RoundDecision::MaxWorkers,
+                RoundStep::Finished(ControllerOutcome::BudgetExhausted),
+            )
+        } else {
+            self.rename_reused_instances(task_id, &mut next, board.current + 1)?;
+            validate_actions(&next, self.workspace_root())?;
+            (RoundDecision::Continue, RoundStep::Next(next))
+        };
+
+        let round = ControllerRound {
+            round: board.current,
+            agent_instances: actions
+                .tasks
+                .iter()
+                .map(|t| t.resolved_agent_instance())
+                .collect(),
+            decision,
+            reason: output.reason.trim().to_string(),
+            report_dir: report_dir.clone(),
+        };
+        let event = TaskEvent {
+            ts: Utc::now(),
+            event_type: "controller.round.completed".to_string(),
+            task_id: task_id.to_string(),
+            agent_instance: None,
+            turn_id: None,
+            payload: json!({
+                "round": round.round,
+                "decision": decision.as_str(),
+                "reason": round.reason,
+                "agentInstances": round.agent_instances,
+                "dispatchedWorkers": board.dispatched_workers,
+            }),
+            by: Some("controller".to_string()),
+            path: Some(task_relative_path(&task_dir, &report_dir)),
+        };
+        self.store.append_task_event(task_id, &event)?;
+
+        board.history.push(round);
+        if matches!(step, RoundStep::Next(_)) {
+            board.current += 1;
+        }
+        Ok(step)
+    }
+
+    fn evaluate_round(
+        &self,
+        task_id: &str,
+        actions: &OrchestratorActions,
+        joined: &JoinTaskResponse,
+        rounds: &ControllerRoundOptions,
+        board: &RoundBoard,
+        opts: &ControllerOptions,
+    ) -> Result<RoundPlannerOutput, OrchestratorError> {
+        let joined_json = fs::read_to_string(&joined.joined_summary_json)?;
+        let human_notes = self.read_human_notes(task_id);
+        let agent_specs = self.workspace_agent_specs()?;
+        let history = board
+            .history
+            .iter()
+            .map(|r| {
+                format!(
+                    "- round {}: {} ({})",
+                    r.round,
+                    r.decision.as_str(),
+                    r.reason
+                )
+            })
+            .collect::<Vec<_>>();
+        let budget = match rounds.max_dispatched_workers {
+            Some(max) => format!("{} of {max} workers dispatched", board.dispatched_workers),
+            None => format!("{} workers dispatched", board.dispatched_workers),
+        };
+
+        let prompt = format!(
+            "You are the round planner for Coco task `{task_id}`.\n\
+             Session goal: {goal}\n\
+             Round {round} of {max_rounds} just finished ({budget}).\n\n\
+             Decide whether the joined worker outputs below satisfy the session goal. If they \
+             do, return status=done with an empty tasks array. Otherwise return status=continue \
+             with the follow-up subtasks (e.g. fixes for bugs QA found, or workers' nextActions). \
+             Use new taskIds; workers only see their own prompt.\n\n\
+             {ROUND_DECISION_OUTPUT_CONTRACT}\n\
+             # Previous Rounds\n\n{history}\n\n\
+             # Human Notes (highest priority)\n\n{notes}\n\n\
+             # Agents\n\n{agents}\n\n\
+             # Joined Summary (round {round})\n\n```json\n{joined}\n```\n",
+            goal = actions.session_goal.trim(),
+            round = board.current,
+            max_rounds = board.max_rounds,
+            history = or_none(&history.join("\n")),
+            notes = or_none(&human_notes),
+            agents = or_none(&agent_specs.join("\n")),
+            joined = joined_json.trim(),
+        );
+        let value = self.run_oneshot_worker(
+            task_id,
+            &OneShotWorker {
+                agent_instance: ROUND_PLANNER_AGENT_INSTANCE,
+                adapter: &rounds.adapter,
+                prompt,
+                output_schema_path: &rounds.output_schema_path,
+                output_contract: ROUND_DECISION_OUTPUT_CONTRACT,
+            },
+            opts,
+        )?;
+        serde_json::from_value(value).map_err(|err| OrchestratorError::InvalidWorkerOutput {
+            agent_instance: ROUND_PLANNER_AGENT_INSTANCE.to_string(),
+            message: err.to_string(),
+        })
+    }
+
+    /// Agent instances are unique per task; suffix reused ones with `-r<round>` so the dispatch
+    /// does not mistake them for already-finished workers.
+    fn rename_reused_instances(
+        &self,
+        task_id: &str,
+        actions: &mut OrchestratorActions,
+        round: u32,
+    ) -> Result<(), OrchestratorError> {
+        let existing = self
+            .store
+            .read_task(task_id)?
+            .roster
+            .into_iter()
+            .map(|a| a.instance)
+            .collect::<HashSet<_>>();
+        for task in &mut actions.tasks {
+            let instance = task.resolved_agent_instance();
+            if existing.contains(&instance) {
+                task.agent_instance = Some(format!("{instance}-r{round}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn or_none(value: &str) -> String {
+    if value.trim().is_empty() {
+        "(none)".to_string()
+    } else {
+        value.trim().to_string()
+    }
+}
@@ -0,0 +1,318 @@
+use crate::oneshot::OneShotWorker;
+use crate::report::collect_worker_summaries;
+use crate::report::report_context;
+use crate::report::task_relative_path;
+use crate::ControllerOptions;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::JOINED_SUMMARY_JSON_FILE_NAME;
+use crate::TASK_SHARED_DIR_NAME;
+use crate::TASK_SHARED_REPORTS_DIR_NAME;
+use chrono::Utc;
+use coco_core::task::TaskEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The synthesizer records under `agents/synthesizer/` but is never added to the roster.
+pub const SYNTHESIZER_AGENT_INSTANCE: &str = "synthesizer";
+
+const SYNTHESIS_TEMPLATE_NAME: &str = "Synthesis";
+const SYNTHESIS_MD_FILE_NAME: &str = "synthesis.md";
+const SYNTHESIS_JSON_FILE_NAME: &str = "synthesis.json";
+
+const SYNTHESIS_OUTPUT_CONTRACT: &str = "# Output Contract\nReturn ONLY valid JSON matching schemas/synthesis-output.schema.json.\nRequired keys: status, summary, findings, conflicts, nextPlan. Every findings/conflicts/nextPlan item MUST cite at least one evidence id from the Evidence Index.\n";
+
+#[derive(Debug, Clone)]
+pub struct SynthesizerOptions {
+    /// `codex-exec` (default) or `codex-app-server`.
+    pub adapter: String,
+    /// JSON schema for the synthesizer output (used by codex-exec).
+    pub output_schema_path: PathBuf,
+}
+
+impl SynthesizerOptions {
+    pub fn new(workspace_root: &Path) -> Self {
+        Self {
+            adapter: "codex-exec".to_string(),
+            output_schema_path: workspace_root
+                .join("schemas")
+                .join("synthesis-output.schema.json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynthesisResult {
+    pub synthesis_md: PathBuf,
+    pub synthesis_json: PathBuf,
+    /// Items that cite no evidence id present in the index (or cite unknown ids); they are
+    /// dropped from the synthesis and only listed under its citation check.
+    pub uncited_claims: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SynthesisOutput {
+    status: String,
+    summary: String,
+    #[serde(default)]
+    findings: Vec<CitedClaim>,
+    #[serde(default)]
+    conflicts: Vec<SynthesisConflict>,
+    #[serde(default)]
+    next_plan: Vec<CitedClaim>,
+}
+
+impl SynthesisOutput {
+    fn claim_count(&self) -> usize {
+        self.findings.len() + self.conflicts.len() + self.next_plan.len()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CitedClaim {
+    claim: String,
+    #[serde(default)]
+    evidence: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SynthesisConflict {
+    claim: String,
+    #[serde(default)]
+    agent_instances: Vec<String>,
+    #[serde(default)]
+    evidence: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UncitedClaim {
+    section: String,
+    claim: String,
+    invalid: Vec<String>,
+}
+
+impl Orchestrator {
+    /// Run the synthesizer worker over `joined-summary.json` + the evidence index and write
+    /// `shared/reports/synthesis.{json,md}`. Runs `task_join` first when no join exists yet.
+    pub fn task_synthesize(
+        &self,
+        task_id: &str,
+        session_goal: &str,
+        opts: &ControllerOptions,
+        synth: &SynthesizerOptions,
+    ) -> Result<SynthesisResult, OrchestratorError> {
+        let task_dir = self.store.task_dir(task_id);
+        let reports_dir = task_dir
+            .join(TASK_SHARED_DIR_NAME)
+            .join(TASK_SHARED_REPORTS_DIR_NAME);
+        let joined_json_path = reports_dir.join(JOINED_SUMMARY_JSON_FILE_NAME);
+        if !joined_json_path.is_file() {
+            self.task_join(task_id)?;
+        }
+        let joined_json = fs::read_to_string(&joined_json_path)?;
+        let evidence = self.store.read_evidence_index(task_id)?;
+        let task = self.store.read_task(task_id)?;
+
+        let prompt = synthesis_prompt(
+            &task.id,
+            &task.title,
+            session_goal,
+            &joined_json,
+            &serde_json::to_string_pretty(&evidence)?,
+        );
+        let value = self.run_oneshot_worker(
+            task_id,
+            &OneShotWorker {
+                agent_instance: SYNTHESIZER_AGENT_INSTANCE,
+                adapter: &synth.adapter,
+                prompt,
+                output_schema_path: &synth.output_schema_path,
+                output_contract: SYNTHESIS_OUTPUT_CONTRACT,
+            },
+            opts,
+        )?;
+        let output: SynthesisOutput = serde_json::from_value(value).map_err(|err| {
+            OrchestratorError::InvalidWorkerOutput {
+                agent_instance: SYNTHESIZER_AGENT_INSTANCE.to_string(),
+                message: err.to_string(),
+            }
+        })?;
+
+        let known_ids = evidence
+            .iter()
+            .map(|e| e.id.as_str())
+            .collect::<HashSet<_>>();
+        let (output, uncited) = check_citations(output, &known_ids);
+        if output.claim_count() == 0 && !uncited.is_empty() {
+            return Err(OrchestratorError::InvalidWorkerOutput {
+                agent_instance: SYNTHESIZER_AGENT_INSTANCE.to_string(),
+                message: format!(
+                    "none of the {} synthesized claims cites evidence from the index",
+                    uncited.len()
+                ),
+            });
+        }
+
+        fs::create_dir_all(&reports_dir)?;
+        let json_path = reports_dir.join(SYNTHESIS_JSON_FILE_NAME);
+        let mut json_output = serde_json::to_value(&output)?;
+        json_output["uncitedClaims"] = serde_json::to_value(&uncited)?;
+        fs::write(&json_path, serde_json::to_string_pretty(&json_output)?)?;
+
+        let generated_at = Utc::now();
+        let workers = collect_worker_summaries(&task, &task_dir)?;
+        let usage = self.task_usage(task_id)?;
+        let mut context = report_context(&task, generated_at, &workers, &evidence, &usage);
+        context["sessionGoal"] = json!(session_goal);
+        context["synthesis"] = serde_json::to_value(&output)?;
+        context["uncitedClaims"] = serde_json::to_value(&uncited)?;
+        let content = self.render_template_with_context(SYNTHESIS_TEMPLATE_NAME, &context)?;
+        let md_path = reports_dir.join(SYNTHESIS_MD_FILE_NAME);
+        fs::write(&md_path, content)?;
+
+        let event = TaskEvent {
+            ts: Utc::now(),
+            event_type: "synthesis.completed".to_string(),
+            task_id: task_id.to_string(),
+            agent_instance: Some(SYNTHESIZER_AGENT_INSTANCE.to_string()),
+            turn_id: None,
+            payload: json!({
+                "status": output.status,
+                "findings": output.findings.len(),
+                "conflicts": output.conflicts.len(),
+                "nextPlan": output.next_plan.len(),
+                "uncitedClaims": uncited.len(),
+            }),
+            by: Some("controller".to_string()),
+            path: Some(task_relative_path(&task_dir, &md_path)),
+        };
+        self.store.append_task_event(task_id, &event)?;
+
+        Ok(SynthesisResult {
+            synthesis_md: md_path,
+            synthesis_json: json_path,
+            uncited_claims: uncited.len(),
+        })
+    }
+}
+
+fn synthesis_prompt(
+    task_id: &str,
+    title: &str,
+    session_goal: &str,
+    joined_json: &str,
+    evidence_json: &str,
+) -> String {
+    format!(
+        "You are the synthesizer for Coco task `{task_id}` ({title}).\n\
+         Session goal: {goal}\n\n\
+         Read the joined worker outputs and the evidence index below. Deduplicate findings, \
+         surface conflicts between workers, and propose the next plan.\n\n\
+         Rules:\n\
+         - Every finding, conflict and next-plan step MUST cite one or more evidence ids from the \
+         Evidence Index (ids only, without the `evidence:` prefix).\n\
+         - Never invent evidence ids; drop claims that no evidence supports.\n\n\
+         {SYNTHESIS_OUTPUT_CONTRACT}\n\
+         # Joined Summary (shared/reports/{JOINED_SUMMARY_JSON_FILE_NAME})\n\n\
+         ```json\n{joined}\n```\n\n\
+         # Evidence Index (shared/evidence/index.json)\n\n\
+         ```json\n{evidence}\n```\n",
+        goal = session_goal.trim(),
+        joined = joined_json.trim(),
+        evidence = evidence_json.trim(),
+    )
+}
+
+/// Split off the items whose citations are empty or reference ids missing from the evidence
+/// index; the returned output keeps only properly cited items.
+fn check_citations(
+    mut output: SynthesisOutput,
+    known_ids: &HashSet<&str>,
+) -> (SynthesisOutput, Vec<UncitedClaim>) {
+    let mut uncited = Vec::new();
+    let mut keep = |section: &str, claim: &str, evidence: &[String]| {
+        let invalid = evidence
+            .iter()
+            .map(|id| id.trim().trim_start_matches("evidence:"))
+            .filter(|id| !known_ids.contains(id))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if evidence.is_empty() || !invalid.is_empty() {
+            uncited.push(UncitedClaim {
+                section: section.to_string(),
+                claim: claim.trim().to_string(),
+                invalid,
+            });
+            return false;
+        }
+        true
+    };
+    output
+        .findings
+        .retain(|c| keep("findings", &c.claim, &c.evidence));
+    output
+        .conflicts
+        .retain(|c| keep("conflicts", &c.claim, &c.evidence));
+    output
+        .next_plan
+        .retain(|c| keep("nextPlan", &c.claim, &c.evidence));
+    (output, uncited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn citations_must_reference_known_evidence() {
+        let output: SynthesisOutput = serde_json::from_value(json!({
+            "status": "success",
+            "summary": "s",
+            "findings": [
+                { "claim": "ok", "evidence": ["worker-a"] },
+                { "claim": "prefixed", "evidence": ["evidence:worker-a"] },
+                { "claim": "missing", "evidence": [] },
+            ],
+            "conflicts": [
+                { "claim": "bad", "agentInstances": ["a", "b"], "evidence": ["worker-a", "nope"] },
+            ],
+            "nextPlan": [],
+        }))
+        .unwrap();
+        let known = HashSet::from(["worker-a"]);
+
+        let (output, uncited) = check_citations(output, &known);
+        let kept = output
+            .findings
+            .iter()
+            .map(|c| c.claim.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(kept, vec!["ok", "prefixed"]);
+        assert!(output.conflicts.is_empty());
+        assert_eq!(
+            uncited,
+            vec![
+                UncitedClaim {
+                    section: "findings".to_string(),
+                    claim: "missing".to_string(),
+                    invalid: Vec::new(),
+                },
+                UncitedClaim {
+                    section: "conflicts".to_string(),
+                    claim: "bad".to_string(),
+                    invalid: vec!["nope".to_string()],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::agent_dir;
+use crate::retry::attempt_runtime_dirs;
+use crate::Orchestrator;
+use crate::OrchestratorError;
+use crate::PLANNER_AGENT_INSTANCE;
+use crate::ROUND_PLANNER_AGENT_INSTANCE;
+use crate::RUNTIME_EVENTS_FILE_NAME;
+use crate::SYNTHESIZER_AGENT_INSTANCE;
+use crate::TASK_SHARED_DIR_NAME;
+use coco_codex::parse_events_usage;
+use coco_core::usage::PriceTable;
+use coco_core::usage::TaskUsage;
+use coco_core::usage::TokenUsage;
+use coco_core::usage::WorkerUsage;
+use std::fs;
+
+/// Optional `<workspace>/.coco/prices.yaml` (USD per million tokens, keyed by model).
+const PRICE_TABLE_FILE_NAME: &str = "prices.yaml";
+/// Task-level rollup written to `shared/usage.json`.
+pub(crate) const USAGE_FILE_NAME: &str = "usage.json";
+
+/// Helper workers run outside the roster (see `OneShotWorker`) but spend tokens all the same.
+const HELPER_AGENT_INSTANCES: &[&str] = &[
+    PLANNER_AGENT_INSTANCE,
+    ROUND_PLANNER_AGENT_INSTANCE,
+    SYNTHESIZER_AGENT_INSTANCE,
+];
+
+impl Orchestrator {
+    /// Re-read every worker's and helper's `runtime/events.jsonl` (archived attempts and earlier
+    /// helper runs included) and price it. Read-only; see `record_task_usage`.
+    pub fn task_usage(&self, task_id: &str) -> Result<TaskUsage, OrchestratorError> {
+        let task = self.store.read_task(task_id)?;
+        let task_dir = self.store.task_dir(task_id);
+        let prices = self.price_table()?;
+
+        let mut agents = task
+            .roster
+            .iter()
+            .map(|agent| (agent.instance.clone(), agent.agent.clone()))
+            .collect::<Vec<_>>();
+        for helper in HELPER_AGENT_INSTANCES {
+            let in_roster = agents.iter().any(|(instance, _)| instance == helper);
+            if !in_roster && agent_dir(&task_dir, helper).is_dir() {
+                agents.push((helper.to_string(), helper.to_string()));
+            }
+        }
+
+        let mut workers = Vec::new();
+        for (instance, agent) in agents {
+            let agent_dir = agent_dir(&task_dir, &instance);
+            let mut model = None;
+            let mut usage = TokenUsage::default();
+            for runtime_dir in attempt_runtime_dirs(&agent_dir)? {
+                let events_path = runtime_dir.join(RUNTIME_EVENTS_FILE_NAME);
+                if !events_path.is_file() {
+                    continue;
+                }
+                let parsed = parse_events_usage(&fs::read_to_string(&events_path)?);
+                usage += parsed.usage;
+                model = parsed.model.or(model);
+            }
+            if model.is_none() {
+                // `codex exec --json` does not announce the model; fall back to the spec default.
+                // A broken spec must not break accounting, so lookup errors count as "no spec".
+                model = self
+                    .find_agent_spec(&agent)
+                    .ok()
+                    .flatten()
+                    .and_then(|spec| spec.file.model);
+            }
+            workers.push(WorkerUsage {
+                agent_instance: instance,
+                agent,
+                cost_usd: prices.cost(model.as_deref(), &usage),
+                model,
+                usage,
+            });
+        }
+
+        Ok(TaskUsage::new(task.id, workers))
+    }
+
+    /// `task_usage`, persisted to `shared/usage.json` for the cluster totals. Called where usage
+    /// changes (a worker changes state, a helper finishes, a join), not on every read.
+    pub(crate) fn record_task_usage(&self, task_id: &str) -> Result<TaskUsage, OrchestratorError> {
+        let usage = self.task_usage(task_id)?;
+        let shared_dir = self.store.task_dir(task_id).join(TASK_SHARED_DIR_NAME);
+        fs::create_dir_all(&shared_dir)?;
+        fs::write(
+            shared_dir.join(USAGE_FILE_NAME),
+            serde_json::to_string_pretty(&usage)?,
+        )?;
+        Ok(usage)
+    }
+
+    /// Totals over every task's last `shared/usage.json` (not recomputed; unreadable files are
+    /// skipped).
+    pub(crate) fn recorded_usage_totals(&self) -> (TokenUsage, Option<f64>) {
+        let mut usage = TokenUsage::default();
+        let mut cost_usd = None;
+        for task in self.store.list_tasks().unwrap_or_default() {
+            let path = self
+                .store
+                .task_dir(&task.id)
+                .join(TASK_SHARED_DIR_NAME)
+                .join(USAGE_FILE_NAME);
+            let Some(recorded) = fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<TaskUsage>(&content).ok())
+            else {
+                continue;
+            };
+            usage += recorded.usage;
+            if let Some(cost) = recorded.cost_usd {
+                *cost_usd.get_or_insert(0.0) += cost;
+            }
+        }
+        (usage, cost_usd)
+    }
+
+    fn price_table(&self) -> Result<PriceTable, OrchestratorError> {
+        Ok(PriceTable::load(
+            &self
+                .workspace_root()
+                .join(".coco")
+                .join(PRICE_TABLE_FILE_NAME),
+        )?)
+    }
+}
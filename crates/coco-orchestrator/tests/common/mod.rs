@@ -0,0 +1,105 @@
+use coco_core::task::CreateTaskRequest;
+use coco_core::task::TaskTopology;
+use coco_orchestrator::Orchestrator;
+use coco_orchestrator::OrchestratorActions;
+use coco_orchestrator::OrchestratorSubtask;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Argument parsing shared by every `codex exec` stub: `$out` (`--output-last-message`),
+/// `$schema` (`--output-schema`) and `$prompt` (read from stdin for `-`) are set, and the
+/// directory of `$out` exists.
+const MOCK_CODEX_PRELUDE: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+out=""
+schema=""
+args=("$@")
+for ((i=0; i<${#args[@]}; i++)); do
+  if [[ "${args[$i]}" == "--output-last-message" ]]; then
+    out="${args[$((i+1))]}"
+  fi
+  if [[ "${args[$i]}" == "--output-schema" ]]; then
+    schema="${args[$((i+1))]}"
+  fi
+done
+prompt="${args[$((${#args[@]}-1))]}"
+if [[ "$prompt" == "-" ]]; then
+  prompt="$(cat)"
+fi
+
+if [[ -z "$out" ]]; then
+  echo "missing --output-last-message" >&2
+  exit 2
+fi
+mkdir -p "$(dirname "$out")"
+"#;
+
+/// Write `<dir>/mock_codex.sh`: the shared prelude followed by `body`, which decides what to
+/// write to `$out`.
+pub fn mock_codex(dir: &Path, body: &str) -> PathBuf {
+    let path = dir.join("mock_codex.sh");
+    write_executable(&path, &format!("{MOCK_CODEX_PRELUDE}\n{body}"));
+    path
+}
+
+pub fn write_executable(path: &Path, content: &str) {
+    fs::write(path, content).expect("write mock executable");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)
+            .expect("stat mock executable")
+            .permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms).expect("chmod mock executable");
+    }
+}
+
+/// A swarm task request with only a title; override other fields with struct update syntax.
+pub fn task_request(title: &str) -> CreateTaskRequest {
+    CreateTaskRequest {
+        title: title.to_string(),
+        description: "".to_string(),
+        topology: TaskTopology::Swarm,
+        milestones: Vec::new(),
+        roster: Vec::new(),
+        config: None,
+    }
+}
+
+/// Create a task from [`task_request`] and return its id.
+pub fn create_task(orchestrator: &Orchestrator, title: &str) -> String {
+    orchestrator
+        .create_task(task_request(title))
+        .expect("create task")
+        .id
+}
+
+/// A `codex-exec` subtask for the `worker` agent with every optional field unset.
+pub fn subtask(task_id: &str) -> OrchestratorSubtask {
+    OrchestratorSubtask {
+        task_id: task_id.to_string(),
+        agent_instance: None,
+        title: format!("task {task_id}"),
+        agent: "worker".to_string(),
+        adapter: "codex-exec".to_string(),
+        prompt: format!("do {task_id}"),
+        prompt_template: None,
+        mode: None,
+        forked_from_thread_id: None,
+        cwd: None,
+        output_schema_path: None,
+        timeout_seconds: None,
+        retry: None,
+    }
+}
+
+/// Actions as a planner would return them: `session_goal` over `tasks`.
+pub fn actions(session_goal: &str, tasks: Vec<OrchestratorSubtask>) -> OrchestratorActions {
+    OrchestratorActions {
+        session_goal: session_goal.to_string(),
+        tasks,
+    }
+}
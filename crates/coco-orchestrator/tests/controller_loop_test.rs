@@ -1,25 +1,18 @@
 use coco_core::task::CreateTaskRequest;
-use coco_core::task::TaskTopology;
+use coco_core::task::RetryClass;
+use coco_core::task::RetryPolicy;
 use coco_orchestrator::ControllerOptions;
 use coco_orchestrator::ControllerOutcome;
+use coco_orchestrator::ControllerRoundOptions;
 use coco_orchestrator::Orchestrator;
-use coco_orchestrator::OrchestratorActions;
+use coco_orchestrator::OrchestratorError;
 use coco_orchestrator::OrchestratorSubtask;
+use coco_orchestrator::PlannerOptions;
+use coco_orchestrator::RoundDecision;
+use coco_orchestrator::SynthesizerOptions;
 use std::fs;
-use std::path::PathBuf;
-
-fn write_executable(path: &PathBuf, content: &str) {
-    fs::write(path, content).expect("write mock executable");
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(path)
-            .expect("stat mock executable")
-            .permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(path, perms).expect("chmod mock executable");
-    }
-}
+
+mod common;
 
 #[test]
 fn controller_loop_spawns_workers_writes_stateboard_and_joined_reports() {
@@ -30,7 +23,7 @@ fn controller_loop_spawns_workers_writes_stateboard_and_joined_reports() {
 
     // A tiny "codex exec" stub that writes the final output file expected by the orchestrator.
     let mock_codex = tmp.join("mock_codex.sh");
-    write_executable(
+    common::write_executable(
         &mock_codex,
         r#"#!/usr/bin/env bash
 set -euo pipefail
@@ -63,59 +56,25 @@ echo '{"type":"thread.started","threadId":"thr_mock"}'
     );
 
     let orchestrator = Orchestrator::new(tmp.clone());
-    let created = orchestrator
-        .create_task(CreateTaskRequest {
-            title: "controller loop test".to_string(),
-            description: "".to_string(),
-            topology: TaskTopology::Swarm,
-            milestones: Vec::new(),
-            roster: Vec::new(),
-            config: None,
-        })
-        .expect("create task");
-
-    let actions = OrchestratorActions {
-        session_goal: "verify controller loop artifacts".to_string(),
-        tasks: vec![
-            OrchestratorSubtask {
-                task_id: "t1".to_string(),
-                agent_instance: None,
-                title: "task 1".to_string(),
-                agent: "worker".to_string(),
-                adapter: "codex-exec".to_string(),
-                prompt: "do thing 1".to_string(),
-                mode: None,
-                forked_from_thread_id: None,
-                cwd: None,
-                output_schema_path: None,
-            },
-            OrchestratorSubtask {
-                task_id: "t2".to_string(),
-                agent_instance: None,
-                title: "task 2".to_string(),
-                agent: "worker".to_string(),
-                adapter: "codex-exec".to_string(),
-                prompt: "do thing 2".to_string(),
-                mode: None,
-                forked_from_thread_id: None,
-                cwd: None,
-                output_schema_path: None,
-            },
-        ],
-    };
+    let task_id = common::create_task(&orchestrator, "controller loop test");
+
+    let actions = common::actions(
+        "verify controller loop artifacts",
+        vec![common::subtask("t1"), common::subtask("t2")],
+    );
 
     let mut opts = ControllerOptions::new(&tmp);
     opts.codex_bin = mock_codex.clone();
     opts.default_cwd = tmp.clone();
 
     let result = orchestrator
-        .controller_run_actions(&created.id, actions, opts)
+        .controller_run_actions(&task_id, actions, opts)
         .expect("controller run should succeed");
 
     assert_eq!(result.outcome, ControllerOutcome::Done);
     assert!(result.joined_summary.is_some());
 
-    let task_dir = tmp.join(".coco").join("tasks").join(&created.id);
+    let task_dir = tmp.join(".coco").join("tasks").join(&task_id);
     let state_board_path = task_dir.join("shared").join("state-board.md");
     let state_board = fs::read_to_string(&state_board_path).expect("read state-board.md");
     assert!(state_board.contains("sessionGoal: verify controller loop artifacts"));
@@ -147,3 +106,567 @@ echo '{"type":"thread.started","threadId":"thr_mock"}'
 
     let _ = fs::remove_dir_all(&tmp);
 }
+
+#[test]
+fn controller_loop_runs_synthesizer_and_flags_uncited_claims() {
+    let tmp = std::env::temp_dir().join(format!(
+        "coco-controller-synthesis-test-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(&tmp).expect("create tmp dir");
+
+    // Workers and the synthesizer share one stub; the output schema tells them apart.
+    let mock_codex = common::mock_codex(
+        &tmp,
+        r#"if [[ "$schema" == *synthesis* ]]; then
+cat > "$out" <<'EOF'
+{
+  "status": "success",
+  "summary": "synthesized",
+  "findings": [
+    { "claim": "worker t1 succeeded", "evidence": ["worker-t1"] },
+    { "claim": "made-up claim", "evidence": ["bogus"] }
+  ],
+  "conflicts": [],
+  "nextPlan": [
+    { "claim": "ship it", "evidence": ["worker-t1"] }
+  ]
+}
+EOF
+else
+cat > "$out" <<'EOF'
+{
+  "status": "success",
+  "summary": "mock success",
+  "questions": [],
+  "nextActions": []
+}
+EOF
+fi
+"#,
+    );
+
+    let orchestrator = Orchestrator::new(tmp.clone());
+    let task_id = common::create_task(&orchestrator, "synthesis test");
+
+    let actions = common::actions("verify synthesis", vec![common::subtask("t1")]);
+
+    let mut opts = ControllerOptions::new(&tmp);
+    opts.codex_bin = mock_codex.clone();
+    opts.default_cwd = tmp.clone();
+    opts.synthesizer = Some(SynthesizerOptions::new(&tmp));
+
+    let result = orchestrator
+        .controller_run_actions(&task_id, actions, opts)
+        .expect("controller run should succeed");
+
+    assert_eq!(result.outcome, ControllerOutcome::Done);
+    let synthesis = result.synthesis.expect("synthesis result");
+    assert_eq!(synthesis.uncited_claims, 1);
+
+    let synthesis_md = fs::read_to_string(&synthesis.synthesis_md).expect("read synthesis.md");
+    assert!(synthesis_md.contains("- worker t1 succeeded（evidence:worker-t1）"));
+    assert!(synthesis_md.contains("1. ship it（evidence:worker-t1）"));
+    assert!(synthesis_md.contains("## Citation Check"));
+    assert!(synthesis_md.contains("[findings] made-up claim（无效引用：bogus）"));
+    assert_eq!(synthesis_md.matches("made-up claim").count(), 1);
+
+    let synthesis_json: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(&synthesis.synthesis_json).expect("read synthesis.json"),
+    )
+    .expect("parse synthesis.json");
+    assert_eq!(synthesis_json["findings"].as_array().map(Vec::len), Some(1));
+    assert_eq!(synthesis_json["uncitedClaims"][0]["claim"], "made-up claim");
+
+    let task_dir = tmp.join(".coco").join("tasks").join(&task_id);
+    let state_board =
+        fs::read_to_string(task_dir.join("shared").join("state-board.md")).expect("state board");
+    assert!(state_board.contains("synthesisMd: `./shared/reports/synthesis.md`"));
+
+    // The synthesizer is not a roster worker, so the join only reports t1.
+    let subagents = orchestrator.subagent_list(&task_id).expect("list");
+    assert_eq!(subagents.len(), 1);
+
+    let events = fs::read_to_string(task_dir.join("events.jsonl")).expect("read events.jsonl");
+    assert!(events.contains("\"type\":\"synthesis.completed\""));
+
+    let _ = fs::remove_dir_all(&tmp);
+}
+
+#[test]
+fn synthesis_without_any_cited_claim_is_rejected() {
+    let tmp = std::env::temp_dir().join(format!(
+        "coco-controller-uncited-synthesis-test-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(&tmp).expect("create tmp dir");
+
+    let mock_codex = common::mock_codex(
+        &tmp,
+        r#"if [[ "$schema" == *synthesis* ]]; then
+cat > "$out" <<'EOF'
+{
+  "status": "success",
+  "summary": "synthesized",
+  "findings": [
+    { "claim": "made-up claim", "evidence": ["bogus"] },
+    { "claim": "bare claim", "evidence": [] }
+  ],
+  "conflicts": [],
+  "nextPlan": []
+}
+EOF
+else
+cat > "$out" <<'EOF'
+{
+  "status": "success",
+  "summary": "mock success",
+  "questions": [],
+  "nextActions": []
+}
+EOF
+fi
+"#,
+    );
+
+    let orchestrator = Orchestrator::new(tmp.clone());
+    let task_id = common::create_task(&orchestrator, "uncited synthesis test");
+
+    let actions = common::actions("verify synthesis", vec![common::subtask("t1")]);
+
+    let mut opts = ControllerOptions::new(&tmp);
+    opts.codex_bin = mock_codex.clone();
+    opts.default_cwd = tmp.clone();
+    opts.synthesizer = Some(SynthesizerOptions::new(&tmp));
+
+    let err = orchestrator
+        .controller_run_actions(&task_id, actions, opts)
+        .expect_err("a synthesis with no cited claim should fail the run");
+    assert!(
+        matches!(err, OrchestratorError::InvalidWorkerOutput { ref agent_instance, .. } if agent_instance == "synthesizer"),
+        "unexpected error: {err}"
+    );
+
+    let reports_dir = tmp
+        .join(".coco")
+        .join("tasks")
+        .join(&task_id)
+        .join("shared")
+        .join("reports");
+    assert!(!reports_dir.join("synthesis.md").exists());
+    assert!(!reports_dir.join("synthesis.json").exists());
+
+    let _ = fs::remove_dir_all(&tmp);
+}
+
+#[test]
+fn planner_writes_plan_and_waits_for_approval_before_dispatch() {
+    let tmp = std::env::temp_dir().join(format!(
+        "coco-controller-planner-test-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(tmp.join("agents").join("qa")).expect("create agents dir");
+    fs::write(
+        tmp.join("agents").join("qa").join("agents.md"),
+        "QA agent: runs the test suite.\n",
+    )
+    .expect("write agent spec");
+
+    // The planner stub echoes its prompt so the test can check which inputs it was given.
+    let mock_codex = common::mock_codex(
+        &tmp,
+        r#"if [[ "$schema" == *orchestrator-actions* ]]; then
+printf '%s' "$prompt" > "$(dirname "$out")/prompt.txt"
+cat > "$out" <<'EOF'
+{
+  "sessionGoal": "run the tests",
+  "tasks": [
+    { "taskId": "qa-1", "title": "run tests", "agent": "qa", "adapter": "codex-exec", "prompt": "run cargo test" }
+  ]
+}
+EOF
+else
+cat > "$out" <<'EOF'
+{ "status": "success", "summary": "tests pass", "questions": [], "nextActions": [] }
+EOF
+fi
+"#,
+    );
+
+    let orchestrator = Orchestrator::new(tmp.clone());
+    let task_id = orchestrator
+        .create_task(CreateTaskRequest {
+            description: "make sure the suite is green".to_string(),
+            ..common::task_request("planner test")
+        })
+        .expect("create task")
+        .id;
+
+    let mut opts = ControllerOptions::new(&tmp);
+    opts.codex_bin = mock_codex.clone();
+    opts.default_cwd = tmp.clone();
+    let planner = PlannerOptions::new(&tmp);
+
+    // `autoApprove` defaults to false, so the plan waits on a human-approval gate.
+    let result = orchestrator
+        .controller_plan_and_run(&task_id, opts.clone(), &planner)
+        .expect("plan and run");
+    assert_eq!(result.outcome, ControllerOutcome::Blocked);
+
+    let task_dir = tmp.join(".coco").join("tasks").join(&task_id);
+    let plan_path = task_dir.join("shared").join("plans").join("1.json");
+    let plan: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&plan_path).expect("read plan")).expect("json");
+    assert_eq!(plan["tasks"][0]["taskId"], "qa-1");
+    assert!(plan["tasks"][0].get("mode").is_none());
+
+    let prompt = fs::read_to_string(
+        task_dir
+            .join("agents")
+            .join("planner")
+            .join("artifacts")
+            .join("prompt.txt"),
+    )
+    .expect("read planner prompt");
+    assert!(prompt.contains("make sure the suite is green"));
+    assert!(prompt.contains("QA agent: runs the test suite."));
+    assert!(prompt.contains("attachments: []"));
+
+    let err = orchestrator
+        .controller_run_plan(&task_id, 1, opts.clone())
+        .expect_err("gate should block dispatch");
+    assert!(matches!(err, OrchestratorError::GateNotApproved { .. }));
+
+    orchestrator
+        .plan_resolve(&task_id, 1, true, Some("tester"))
+        .expect("approve plan");
+    let result = orchestrator
+        .controller_run_plan(&task_id, 1, opts)
+        .expect("run approved plan");
+    assert_eq!(result.outcome, ControllerOutcome::Done);
+
+    let subagents = orchestrator.subagent_list(&task_id).expect("list");
+    assert_eq!(subagents.len(), 1);
+    assert_eq!(subagents[0].agent_instance, "qa-1");
+
+    let events = fs::read_to_string(task_dir.join("events.jsonl")).expect("read events.jsonl");
+    assert!(events.contains("\"type\":\"plan.created\""));
+    assert!(events.contains("\"type\":\"gate.approved\""));
+
+    let _ = fs::remove_dir_all(&tmp);
+}
+
+#[test]
+fn controller_rounds_replan_until_goal_met_or_budget_exhausted() {
+    let tmp = std::env::temp_dir().join(format!(
+        "coco-controller-rounds-test-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(&tmp).expect("create tmp dir");
+
+    // The round planner asks for one follow-up in round 1 and declares the goal met in round 2.
+    let mock_codex = common::mock_codex(
+        &tmp,
+        r#"if [[ "$schema" == *round-decision* && "$prompt" == *"Round 1 of"* ]]; then
+cat > "$out" <<'EOF'
+{
+  "status": "continue",
+  "reason": "qa found a bug",
+  "tasks": [
+    { "taskId": "t1", "title": "fix bug", "agent": "backend", "adapter": "codex-exec", "prompt": "fix it" }
+  ]
+}
+EOF
+elif [[ "$schema" == *round-decision* ]]; then
+cat > "$out" <<'EOF'
+{ "status": "done", "reason": "bug fixed", "tasks": [] }
+EOF
+else
+cat > "$out" <<'EOF'
+{ "status": "success", "summary": "mock success", "questions": [], "nextActions": [] }
+EOF
+fi
+"#,
+    );
+
+    let orchestrator = Orchestrator::new(tmp.clone());
+    let actions = common::actions(
+        "ship without bugs",
+        vec![OrchestratorSubtask {
+            agent: "qa".to_string(),
+            ..common::subtask("t1")
+        }],
+    );
+
+    let mut opts = ControllerOptions::new(&tmp);
+    opts.codex_bin = mock_codex.clone();
+    opts.default_cwd = tmp.clone();
+    opts.rounds = Some(ControllerRoundOptions::new(&tmp));
+
+    let task_id = common::create_task(&orchestrator, "rounds test");
+    let result = orchestrator
+        .controller_run_actions(&task_id, actions.clone(), opts.clone())
+        .expect("controller run should succeed");
+    assert_eq!(result.outcome, ControllerOutcome::Done);
+    assert_eq!(result.rounds.len(), 2);
+    assert_eq!(result.rounds[0].decision, RoundDecision::Continue);
+    assert_eq!(result.rounds[1].decision, RoundDecision::Done);
+    // `t1` already ran in round 1, so the follow-up is renamed instead of being skipped.
+    assert_eq!(result.rounds[1].agent_instances, vec!["t1-r2".to_string()]);
+
+    let task_dir = tmp.join(".coco").join("tasks").join(&task_id);
+    let rounds_dir = task_dir.join("shared").join("reports").join("rounds");
+    assert!(rounds_dir.join("1").join("joined-summary.md").exists());
+    assert!(rounds_dir.join("2").join("decision.json").exists());
+
+    let state_board =
+        fs::read_to_string(task_dir.join("shared").join("state-board.md")).expect("state board");
+    assert!(state_board.contains("round: `2/3`"));
+    assert!(state_board.contains("## Rounds"));
+    assert!(state_board.contains("decision=`continue`"));
+
+    // With a one-worker cap the follow-up round is never dispatched.
+    let mut budget_opts = opts.clone();
+    if let Some(rounds) = budget_opts.rounds.as_mut() {
+        rounds.max_dispatched_workers = Some(1);
+    }
+    let task_id = common::create_task(&orchestrator, "rounds budget test");
+    let result = orchestrator
+        .controller_run_actions(&task_id, actions, budget_opts)
+        .expect("controller run should succeed");
+    assert_eq!(result.outcome, ControllerOutcome::BudgetExhausted);
+    assert_eq!(result.rounds.len(), 1);
+    assert_eq!(result.rounds[0].decision, RoundDecision::MaxWorkers);
+    assert_eq!(orchestrator.subagent_list(&task_id).expect("list").len(), 1);
+
+    let _ = fs::remove_dir_all(&tmp);
+}
+
+#[test]
+fn controller_loop_retries_rate_limited_worker_and_reports_attempt() {
+    let tmp =
+        std::env::temp_dir().join(format!("coco-controller-retry-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(&tmp).expect("create tmp dir");
+
+    // First call dies with a rate-limit error on stderr; the second one succeeds.
+    let mock_codex = common::mock_codex(
+        &tmp,
+        &format!(
+            r#"calls="{calls}"
+echo x >> "$calls"
+if [[ "$(wc -l < "$calls")" -eq 1 ]]; then
+  echo "stream error: 429 Too Many Requests (rate limit reached)" >&2
+  exit 1
+fi
+
+cat > "$out" <<'EOF'
+{{ "status": "success", "summary": "second try", "questions": [], "nextActions": [] }}
+EOF
+"#,
+            calls = tmp.join("calls.txt").display()
+        ),
+    );
+
+    let orchestrator = Orchestrator::new(tmp.clone());
+    let task_id = common::create_task(&orchestrator, "retry test");
+
+    let actions = common::actions(
+        "survive a rate limit",
+        vec![OrchestratorSubtask {
+            retry: Some(RetryPolicy {
+                max_attempts: 2,
+                backoff_seconds: 0,
+                retry_on: vec![RetryClass::RateLimit],
+            }),
+            ..common::subtask("t1")
+        }],
+    );
+
+    let mut opts = ControllerOptions::new(&tmp);
+    opts.codex_bin = mock_codex.clone();
+    opts.default_cwd = tmp.clone();
+    opts.poll_interval = std::time::Duration::from_millis(50);
+
+    let result = orchestrator
+        .controller_run_actions(&task_id, actions, opts)
+        .expect("controller run should succeed");
+    assert_eq!(result.outcome, ControllerOutcome::Done);
+
+    let task_dir = tmp.join(".coco").join("tasks").join(&task_id);
+    let archived = task_dir
+        .join("agents")
+        .join("t1")
+        .join("attempts")
+        .join("1");
+    let stderr =
+        fs::read_to_string(archived.join("runtime").join("stderr.log")).expect("archived stderr");
+    assert!(stderr.contains("429"));
+    let attempt: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(archived.join("attempt.json")).expect("read attempt.json"),
+    )
+    .expect("parse attempt.json");
+    assert_eq!(attempt["failureClass"], "rate-limit");
+
+    let joined: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(
+            task_dir
+                .join("shared")
+                .join("reports")
+                .join("joined-summary.json"),
+        )
+        .expect("read joined-summary.json"),
+    )
+    .expect("parse joined-summary.json");
+    assert_eq!(joined["workers"][0]["status"], "success");
+    assert_eq!(joined["workers"][0]["attempt"], 2);
+
+    let events = orchestrator
+        .get_task_events(&task_id, Some("agent."), usize::MAX, 0)
+        .expect("read events");
+    let types = events
+        .iter()
+        .map(|e| e.event_type.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![
+            "agent.started",
+            "agent.failed",
+            "agent.retrying",
+            "agent.started",
+            "agent.completed"
+        ]
+    );
+
+    let _ = fs::remove_dir_all(&tmp);
+}
+
+#[test]
+fn every_failed_attempt_gets_its_own_failure_event() {
+    let tmp = std::env::temp_dir().join(format!(
+        "coco-controller-retry-events-test-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(&tmp).expect("create tmp dir");
+
+    let mock_codex = common::mock_codex(
+        &tmp,
+        r#"echo "stream error: 429 Too Many Requests (rate limit reached)" >&2
+exit 1
+"#,
+    );
+
+    let orchestrator = Orchestrator::new(tmp.clone());
+    let task_id = common::create_task(&orchestrator, "retry events test");
+
+    let actions = common::actions(
+        "fail twice",
+        vec![OrchestratorSubtask {
+            retry: Some(RetryPolicy {
+                max_attempts: 2,
+                backoff_seconds: 0,
+                retry_on: vec![RetryClass::RateLimit],
+            }),
+            ..common::subtask("t1")
+        }],
+    );
+
+    let mut opts = ControllerOptions::new(&tmp);
+    opts.codex_bin = mock_codex.clone();
+    opts.default_cwd = tmp.clone();
+    opts.poll_interval = std::time::Duration::from_millis(50);
+
+    let _ = orchestrator.controller_run_actions(&task_id, actions, opts);
+
+    let events = orchestrator
+        .get_task_events(&task_id, Some("agent."), usize::MAX, 0)
+        .expect("read events");
+    let failures = events
+        .iter()
+        .filter(|e| e.event_type == "agent.failed")
+        .map(|e| e.payload["attempt"].as_u64())
+        .collect::<Vec<_>>();
+    assert_eq!(failures, vec![Some(1), Some(2)]);
+
+    let _ = fs::remove_dir_all(&tmp);
+}
+
+#[test]
+fn controller_loop_injects_context_manifest_attachments_per_worker() {
+    let tmp = std::env::temp_dir().join(format!(
+        "coco-controller-context-test-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(&tmp).expect("create tmp dir");
+
+    // Workers echo their prompt so the test can check the preamble they were given.
+    let mock_codex = common::mock_codex(
+        &tmp,
+        r#"printf '%s' "$prompt" > "$(dirname "$out")/prompt.txt"
+cat > "$out" <<'EOF'
+{ "status": "success", "summary": "mock success", "questions": [], "nextActions": [] }
+EOF
+"#,
+    );
+
+    let orchestrator = Orchestrator::new(tmp.clone());
+    let task_id = common::create_task(&orchestrator, "context manifest test");
+    let task_dir = tmp.join(".coco").join("tasks").join(&task_id);
+    fs::write(tmp.join("notes.md"), "use the v2 endpoint\n").expect("write notes");
+    fs::write(
+        task_dir.join("shared").join("context-manifest.yaml"),
+        "attachments:\n  - id: notes\n    kind: file\n    path: notes.md\n    agents: [t1]\n",
+    )
+    .expect("write context manifest");
+
+    let actions = common::actions(
+        "verify context injection",
+        vec![common::subtask("t1"), common::subtask("t2")],
+    );
+
+    let mut opts = ControllerOptions::new(&tmp);
+    opts.codex_bin = mock_codex.clone();
+    opts.default_cwd = tmp.clone();
+
+    let result = orchestrator
+        .controller_run_actions(&task_id, actions, opts)
+        .expect("controller run should succeed");
+    assert_eq!(result.outcome, ControllerOutcome::Done);
+
+    let context = |instance: &str| -> serde_json::Value {
+        serde_json::from_str(
+            &fs::read_to_string(task_dir.join("agents").join(instance).join("context.json"))
+                .expect("read context.json"),
+        )
+        .expect("parse context.json")
+    };
+    let t1_context = context("t1");
+    assert_eq!(t1_context["entries"][0]["source"], "notes.md");
+    assert!(t1_context["preamble"]
+        .as_str()
+        .unwrap()
+        .contains("use the v2 endpoint"));
+    assert_eq!(context("t2")["preamble"], "");
+
+    let prompt = |instance: &str| {
+        fs::read_to_string(
+            task_dir
+                .join("agents")
+                .join(instance)
+                .join("artifacts")
+                .join("prompt.txt"),
+        )
+        .expect("read worker prompt")
+    };
+    assert!(prompt("t1").contains("use the v2 endpoint"));
+    assert!(!prompt("t2").contains("use the v2 endpoint"));
+
+    let _ = fs::remove_dir_all(&tmp);
+}
@@ -0,0 +1,403 @@
+//! `/api/v1/*` routes. Each handler is a thin wrapper over the matching `Orchestrator` method and
+//! returns the same camelCase JSON shapes as `coco --json`.
+
+use crate::http::Request;
+use crate::http::Response;
+use coco_core::agent_spec::AgentSpecError;
+use coco_core::evidence::EvidenceEntry;
+use coco_core::evidence::EvidenceSource;
+use coco_core::task::CreateTaskRequest;
+use coco_core::task_store::TaskStoreError;
+use coco_orchestrator::Orchestrator;
+use coco_orchestrator::OrchestratorError;
+use coco_orchestrator::SubagentSpawnRequest;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub(crate) enum Routed {
+    Reply(Response),
+    /// `GET /api/v1/tasks/{id}/events/stream`: served as SSE by the connection loop.
+    EventStream {
+        task_id: String,
+        type_prefix: Option<String>,
+        offset: usize,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ApiError {
+    #[error("{0}")]
+    Orchestrator(#[from] OrchestratorError),
+    #[error("{0}")]
+    Store(#[from] TaskStoreError),
+    #[error("invalid request body: {0}")]
+    Body(#[from] serde_json::Error),
+    #[error("invalid {name}: {value}")]
+    InvalidParam { name: &'static str, value: String },
+    #[error("no route for {method} {path}")]
+    NoRoute { method: String, path: String },
+}
+
+impl ApiError {
+    fn status_and_code(&self) -> (u16, &'static str) {
+        match self {
+            ApiError::Store(TaskStoreError::TaskNotFound { .. })
+            | ApiError::Orchestrator(OrchestratorError::Store(TaskStoreError::TaskNotFound {
+                ..
+            }))
+            | ApiError::Orchestrator(OrchestratorError::SubagentNotFound { .. })
+            | ApiError::Orchestrator(OrchestratorError::EvidenceNotFound { .. })
+            | ApiError::Orchestrator(OrchestratorError::TemplateNotFound { .. })
+            | ApiError::Orchestrator(OrchestratorError::PlanNotFound { .. })
+            | ApiError::Orchestrator(OrchestratorError::GateNotFound { .. })
+            | ApiError::Orchestrator(OrchestratorError::AgentSpec(AgentSpecError::NotFound {
+                ..
+            }))
+            | ApiError::NoRoute { .. } => (404, "not-found"),
+            ApiError::Body(_)
+            | ApiError::InvalidParam { .. }
+            | ApiError::Orchestrator(OrchestratorError::InvalidAgentInstance { .. })
+            | ApiError::Orchestrator(OrchestratorError::InvalidPlan { .. })
+            | ApiError::Orchestrator(OrchestratorError::InvalidEvidenceId { .. })
+            | ApiError::Orchestrator(OrchestratorError::InvalidGateId { .. })
+            | ApiError::Orchestrator(OrchestratorError::AgentSpec(AgentSpecError::InvalidName {
+                ..
+            })) => (400, "invalid-request"),
+            ApiError::Orchestrator(OrchestratorError::SubagentAlreadyExists { .. })
+            | ApiError::Orchestrator(OrchestratorError::ConcurrencyLimit { .. })
+            | ApiError::Orchestrator(OrchestratorError::EvidenceAlreadyExists { .. })
+            | ApiError::Orchestrator(OrchestratorError::GateAlreadyExists { .. })
+            | ApiError::Orchestrator(OrchestratorError::GateNotApproved { .. })
+            | ApiError::Orchestrator(OrchestratorError::GateNotPending { .. }) => (409, "conflict"),
+            ApiError::Orchestrator(OrchestratorError::WaitAnyTimeout { .. }) => (408, "timeout"),
+            _ => (500, "internal"),
+        }
+    }
+
+    pub fn into_response(self) -> Response {
+        let (status, code) = self.status_and_code();
+        error_response(status, code, &self.to_string())
+    }
+}
+
+pub(crate) fn error_response(status: u16, code: &str, message: &str) -> Response {
+    Response::json(
+        status,
+        &json!({ "error": { "code": code, "message": message } }),
+    )
+}
+
+/// Body of `POST /tasks/{id}/subagents`. The Codex
+/// binary is the server's own (`--codex-bin`); clients cannot choose what gets executed.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct SpawnBody {
+    pub agent_instance: String,
+    pub agent: String,
+    pub prompt: String,
+    /// Defaults to the workspace root; must stay inside it.
+    pub cwd: Option<PathBuf>,
+    pub timeout_seconds: Option<u32>,
+}
+
+/// Body of `POST /tasks/{id}/gates` (and the `coco_gate_request` MCP tool).
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GateRequestBody {
+    /// Defaults to the next `request-<n>`.
+    gate_id: Option<String>,
+    reason: String,
+    instructions_ref: Option<String>,
+    by: Option<String>,
+}
+
+/// Body of `POST /tasks/{id}/evidence` (and the `coco_evidence_add` MCP tool).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EvidenceAddBody {
+    id: String,
+    kind: String,
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    sources: Vec<EvidenceSource>,
+    #[serde(default)]
+    artifact_refs: Vec<String>,
+}
+
+impl EvidenceAddBody {
+    pub fn into_entry(self) -> EvidenceEntry {
+        EvidenceEntry {
+            id: self.id,
+            kind: self.kind,
+            title: self.title,
+            summary: self.summary,
+            created_at: String::new(),
+            sources: self.sources,
+            artifact_refs: self.artifact_refs,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WaitAnyBody {
+    timeout_seconds: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResolveGateBody {
+    by: Option<String>,
+}
+
+pub(crate) fn route(orchestrator: &Orchestrator, codex_bin: &Path, req: &Request) -> Routed {
+    match handle(orchestrator, codex_bin, req) {
+        Ok(routed) => routed,
+        Err(err) => Routed::Reply(err.into_response()),
+    }
+}
+
+fn handle(
+    orchestrator: &Orchestrator,
+    codex_bin: &Path,
+    req: &Request,
+) -> Result<Routed, ApiError> {
+    let segments = req.segments.iter().map(String::as_str).collect::<Vec<_>>();
+    let rest = match segments.as_slice() {
+        ["api", "v1", rest @ ..] => rest,
+        _ => return Err(no_route(req)),
+    };
+
+    let value = match (req.method.as_str(), rest) {
+        ("GET", ["status"]) => json!(orchestrator.cluster_status()),
+        ("GET", ["report-templates"]) => json!(orchestrator.report_templates()?),
+        ("GET", ["tasks"]) => json!(orchestrator.list_tasks()?),
+        ("POST", ["tasks"]) => {
+            let body: CreateTaskRequest = serde_json::from_slice(&req.body)?;
+            let resp = orchestrator.create_task(body)?;
+            return Ok(Routed::Reply(Response::json(201, &json!(resp))));
+        }
+        ("GET", ["tasks", task_id]) => json!(orchestrator.get_task(task_id_param(task_id)?)?),
+        ("GET", ["tasks", task_id, "events"]) => {
+            let task_id = existing_task(orchestrator, task_id)?;
+            let limit = usize_param(req, "limit")?.unwrap_or(usize::MAX);
+            let offset = usize_param(req, "offset")?.unwrap_or(0);
+            json!(orchestrator.get_task_events(task_id, req.query("type"), limit, offset)?)
+        }
+        ("GET", ["tasks", task_id, "events", "stream"]) => {
+            let task_id = existing_task(orchestrator, task_id)?;
+            // `Last-Event-ID` (sent by EventSource on reconnect) wins over `?offset=`.
+            let offset = match req.header("last-event-id") {
+                Some(last) => parse_usize("Last-Event-ID", last)? + 1,
+                None => usize_param(req, "offset")?.unwrap_or(0),
+            };
+            return Ok(Routed::EventStream {
+                task_id: task_id.to_string(),
+                type_prefix: req.query("type").map(|v| v.to_string()),
+                offset,
+            });
+        }
+        ("GET", ["tasks", task_id, "subagents"]) => {
+            let task_id = existing_task(orchestrator, task_id)?;
+            subagents_json(orchestrator, task_id)?
+        }
+        ("POST", ["tasks", task_id, "subagents"]) => {
+            let task_id = existing_task(orchestrator, task_id)?;
+            let body = serde_json::from_slice(&req.body)?;
+            let value = spawn_subagent(orchestrator, codex_bin, task_id, body)?;
+            return Ok(Routed::Reply(Response::json(201, &value)));
+        }
+        ("POST", ["tasks", task_id, "subagents", "wait-any"]) => {
+            let task_id = existing_task(orchestrator, task_id)?;
+            let body: WaitAnyBody = optional_body(req)?;
+            let result = orchestrator.subagent_wait_any(task_id, body.timeout_seconds)?;
+            json!({
+                "agentInstance": result.agent_instance,
+                "status": result.status.as_str(),
+            })
+        }
+        ("POST", ["tasks", task_id, "subagents", agent_instance, "cancel"]) => {
+            let task_id = existing_task(orchestrator, task_id)?;
+            orchestrator.subagent_cancel(task_id, agent_instance)?;
+            json!({ "agentInstance": agent_instance, "cancelled": true })
+        }
+        ("GET", ["tasks", task_id, "gates"]) => {
+            json!(orchestrator.get_task(task_id_param(task_id)?)?.gates)
+        }
+        ("POST", ["tasks", task_id, "gates"]) => {
+            let task_id = task_id_param(task_id)?;
+            let value = request_gate(orchestrator, task_id, serde_json::from_slice(&req.body)?)?;
+            return Ok(Routed::Reply(Response::json(201, &value)));
+        }
+        ("POST", ["tasks", task_id, "gates", gate_id, decision @ ("approve" | "reject")]) => {
+            let task_id = task_id_param(task_id)?;
+            let body: ResolveGateBody = optional_body(req)?;
+            let approved = *decision == "approve";
+            orchestrator.gate_resolve(task_id, gate_id, approved, body.by.as_deref())?;
+            json!({ "gateId": gate_id, "state": if approved { "approved" } else { "rejected" } })
+        }
+        ("GET", ["tasks", task_id, "evidence"]) => {
+            json!(orchestrator.evidence_list(existing_task(orchestrator, task_id)?)?)
+        }
+        ("POST", ["tasks", task_id, "evidence"]) => {
+            let body: EvidenceAddBody = serde_json::from_slice(&req.body)?;
+            let entry = orchestrator.evidence_add(task_id_param(task_id)?, body.into_entry())?;
+            return Ok(Routed::Reply(Response::json(201, &json!(entry))));
+        }
+        ("GET", ["tasks", task_id, "evidence", evidence_id]) => {
+            let task_id = existing_task(orchestrator, task_id)?;
+            json!(orchestrator.evidence_resolve(task_id, evidence_id)?)
+        }
+        ("GET", ["tasks", task_id, "evidence-check"]) => {
+            json!(orchestrator.evidence_check(existing_task(orchestrator, task_id)?)?)
+        }
+        ("GET", ["tasks", task_id, "usage"]) => {
+            json!(orchestrator.task_usage(task_id_param(task_id)?)?)
+        }
+        ("GET", ["tasks", task_id, "budget"]) => {
+            json!(orchestrator.budget_status(task_id_param(task_id)?)?)
+        }
+        ("POST", ["tasks", task_id, "join"]) => {
+            let resp = orchestrator.task_join(task_id_param(task_id)?)?;
+            json!({
+                "joinedSummaryMd": resp.joined_summary_md,
+                "joinedSummaryJson": resp.joined_summary_json,
+            })
+        }
+        ("POST", ["tasks", task_id, "reports", template]) => {
+            json!(orchestrator.report_render(task_id_param(task_id)?, template, None)?)
+        }
+        _ => return Err(no_route(req)),
+    };
+    Ok(Routed::Reply(Response::json(200, &value)))
+}
+
+pub(crate) fn subagents_json(
+    orchestrator: &Orchestrator,
+    task_id: &str,
+) -> Result<serde_json::Value, ApiError> {
+    let subagents = orchestrator.subagent_list(task_id)?;
+    Ok(json!(subagents
+        .iter()
+        .map(|a| json!({
+            "agentInstance": a.agent_instance,
+            "agent": a.agent,
+            "status": a.status.as_str(),
+            "exitCode": a.exit_code,
+            "staleContext": a.stale_context,
+        }))
+        .collect::<Vec<_>>()))
+}
+
+pub(crate) fn spawn_subagent(
+    orchestrator: &Orchestrator,
+    codex_bin: &Path,
+    task_id: &str,
+    body: SpawnBody,
+) -> Result<serde_json::Value, ApiError> {
+    let output_schema_path = orchestrator.worker_output_schema_path(&body.agent)?;
+    let cwd = worker_cwd(orchestrator, body.cwd.as_deref())?;
+    let resp = orchestrator.subagent_spawn(SubagentSpawnRequest {
+        task_id: task_id.to_string(),
+        agent_instance: body.agent_instance,
+        agent: body.agent,
+        prompt: body.prompt,
+        cwd,
+        codex_bin: codex_bin.to_path_buf(),
+        output_schema_path,
+        timeout_seconds: body.timeout_seconds,
+    })?;
+    Ok(json!({ "agentInstance": resp.agent_instance, "pid": resp.pid }))
+}
+
+/// Relative `cwd`s resolve against the workspace root; the resolved directory (symlinks
+/// followed) must stay inside it.
+fn worker_cwd(orchestrator: &Orchestrator, cwd: Option<&Path>) -> Result<PathBuf, ApiError> {
+    let root = orchestrator.workspace_root();
+    let Some(cwd) = cwd else {
+        return Ok(root.to_path_buf());
+    };
+    let invalid = || ApiError::InvalidParam {
+        name: "cwd",
+        value: cwd.display().to_string(),
+    };
+    let canonical_root = fs::canonicalize(root).map_err(|_| invalid())?;
+    let resolved = fs::canonicalize(root.join(cwd)).map_err(|_| invalid())?;
+    if resolved.starts_with(&canonical_root) && resolved.is_dir() {
+        Ok(resolved)
+    } else {
+        Err(invalid())
+    }
+}
+
+pub(crate) fn request_gate(
+    orchestrator: &Orchestrator,
+    task_id: &str,
+    body: GateRequestBody,
+) -> Result<serde_json::Value, ApiError> {
+    let gate = orchestrator.gate_request(
+        task_id,
+        body.gate_id.as_deref(),
+        &body.reason,
+        body.instructions_ref.as_deref(),
+        body.by.as_deref(),
+    )?;
+    Ok(json!(gate))
+}
+
+fn no_route(req: &Request) -> ApiError {
+    ApiError::NoRoute {
+        method: req.method.clone(),
+        path: format!("/{}", req.segments.join("/")),
+    }
+}
+
+/// Task ids become directory names under `.coco/tasks/`; same charset as the CLI accepts.
+pub(crate) fn task_id_param(task_id: &str) -> Result<&str, ApiError> {
+    let is_ok = !task_id.is_empty()
+        && task_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_ok {
+        Ok(task_id)
+    } else {
+        Err(ApiError::InvalidParam {
+            name: "task id",
+            value: task_id.to_string(),
+        })
+    }
+}
+
+/// Like `task_id_param`, but 404s up front for routes whose orchestrator call would not.
+pub(crate) fn existing_task<'a>(
+    orchestrator: &Orchestrator,
+    task_id: &'a str,
+) -> Result<&'a str, ApiError> {
+    let task_id = task_id_param(task_id)?;
+    orchestrator.get_task(task_id)?;
+    Ok(task_id)
+}
+
+fn usize_param(req: &Request, name: &'static str) -> Result<Option<usize>, ApiError> {
+    req.query(name).map(|v| parse_usize(name, v)).transpose()
+}
+
+fn parse_usize(name: &'static str, value: &str) -> Result<usize, ApiError> {
+    value.parse().map_err(|_| ApiError::InvalidParam {
+        name,
+        value: value.to_string(),
+    })
+}
+
+fn optional_body<T: DeserializeOwned + Default>(req: &Request) -> Result<T, ApiError> {
+    if req.body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    Ok(serde_json::from_slice(&req.body)?)
+}
@@ -0,0 +1,422 @@
+//! `coco serve`: the `Orchestrator` as a localhost HTTP/JSON API, so other tools (and a web UI)
+//! can drive Coco without linking Rust.
+//!
+//! - Binds loopback addresses only; every request must carry the server token
+//!   (`Authorization: Bearer <token>`; the event stream also accepts `?token=` for `EventSource`).
+//! - One thread per connection; long calls such as `wait-any` only block their own client.
+//! - `GET /api/v1/tasks/{id}/events/stream` tails `events.jsonl` as Server-Sent Events.
+//!
+//! `coco mcp` exposes the same operations as MCP tools over stdio (see `McpServer`).
+
+use coco_orchestrator::Orchestrator;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+mod api;
+mod http;
+mod mcp;
+
+pub use mcp::McpServer;
+
+pub const DEFAULT_SERVE_PORT: u16 = 7878;
+/// `<workspace>/.coco/serve.json`: where a running server can be found (url + token).
+pub const SERVE_INFO_FILE_NAME: &str = "serve.json";
+
+const EVENT_STREAM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const EVENT_STREAM_KEEPALIVE: Duration = Duration::from_secs(15);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("refusing to listen on non-loopback address: {addr}")]
+    NonLoopback { addr: SocketAddr },
+}
+
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub addr: SocketAddr,
+    /// Generated (random) when None.
+    pub token: Option<String>,
+    /// Codex binary for spawned workers; clients cannot override it.
+    pub codex_bin: PathBuf,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_SERVE_PORT)),
+            token: None,
+            codex_bin: PathBuf::from("codex"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServeInfo {
+    pub url: String,
+    pub token: String,
+    pub pid: u32,
+}
+
+#[derive(Debug)]
+pub struct HttpServer {
+    listener: TcpListener,
+    orchestrator: Orchestrator,
+    token: String,
+    codex_bin: PathBuf,
+}
+
+impl HttpServer {
+    pub fn bind(orchestrator: Orchestrator, opts: ServeOptions) -> Result<Self, ServerError> {
+        if !opts.addr.ip().is_loopback() {
+            return Err(ServerError::NonLoopback { addr: opts.addr });
+        }
+        let listener = TcpListener::bind(opts.addr)?;
+        let token = opts
+            .token
+            .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+        Ok(Self {
+            listener,
+            orchestrator,
+            token,
+            codex_bin: opts.codex_bin,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn info(&self) -> io::Result<ServeInfo> {
+        Ok(ServeInfo {
+            url: format!("http://{}", self.local_addr()?),
+            token: self.token.clone(),
+            pid: std::process::id(),
+        })
+    }
+
+    /// Write `.coco/serve.json` (owner-only on unix) so local clients can discover the server.
+    pub fn write_info_file(&self) -> Result<PathBuf, ServerError> {
+        let dir = self.orchestrator.workspace_root().join(".coco");
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(SERVE_INFO_FILE_NAME);
+        let mut options = fs::OpenOptions::new();
+        options.create(true).write(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path)?;
+        file.write_all(serde_json::to_string_pretty(&self.info()?)?.as_bytes())?;
+        Ok(path)
+    }
+
+    /// Accept connections until the listener fails.
+    pub fn run(self) -> Result<(), ServerError> {
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let orchestrator = self.orchestrator.clone();
+            let token = self.token.clone();
+            let codex_bin = self.codex_bin.clone();
+            thread::spawn(move || handle_connection(&orchestrator, &token, &codex_bin, stream));
+        }
+        Ok(())
+    }
+}
+
+fn handle_connection(
+    orchestrator: &Orchestrator,
+    token: &str,
+    codex_bin: &Path,
+    stream: TcpStream,
+) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let request = match http::read_request(&stream) {
+        Ok(request) => request,
+        Err(err) => {
+            let status = match err {
+                http::HttpError::BodyTooLarge => 413,
+                _ => 400,
+            };
+            let response = api::error_response(status, "invalid-request", &err.to_string());
+            let _ = http::write_response(&stream, &response);
+            return;
+        }
+    };
+
+    if !authorized(&request, token) {
+        let response = api::error_response(401, "unauthorized", "missing or invalid token");
+        let _ = http::write_response(&stream, &response);
+        return;
+    }
+
+    match api::route(orchestrator, codex_bin, &request) {
+        api::Routed::Reply(response) => {
+            let _ = http::write_response(&stream, &response);
+        }
+        api::Routed::EventStream {
+            task_id,
+            type_prefix,
+            offset,
+        } => {
+            let _ = stream_events(
+                orchestrator,
+                &task_id,
+                type_prefix.as_deref(),
+                offset,
+                &stream,
+            );
+        }
+    }
+}
+
+/// `?token=` is only honoured on the event stream: `EventSource` cannot set headers, and a token
+/// in the URL of any other route would end up in logs and history for no reason.
+fn authorized(request: &http::Request, token: &str) -> bool {
+    let presented = request
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| {
+            is_event_stream(request)
+                .then(|| request.query("token"))
+                .flatten()
+        });
+    presented.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes()))
+}
+
+fn is_event_stream(request: &http::Request) -> bool {
+    let segments = request
+        .segments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    request.method == "GET"
+        && matches!(
+            segments.as_slice(),
+            ["api", "v1", "tasks", _, "events", "stream"]
+        )
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// SSE: every task event (matching `type_prefix`) from `offset` on, then new ones as they are
+/// appended. The event `id` is its index in that filtered sequence, so `Last-Event-ID` resumes.
+/// Returns once the client goes away.
+fn stream_events(
+    orchestrator: &Orchestrator,
+    task_id: &str,
+    type_prefix: Option<&str>,
+    mut offset: usize,
+    mut stream: &TcpStream,
+) -> io::Result<()> {
+    http::write_event_stream_head(stream)?;
+    let mut last_write = Instant::now();
+    loop {
+        // A torn last line (writer mid-append) fails to parse; it is picked up on the next poll.
+        if let Ok(events) = orchestrator.get_task_events(task_id, type_prefix, usize::MAX, offset) {
+            for event in events {
+                let data = serde_json::to_string(&event).map_err(io::Error::other)?;
+                write!(stream, "id: {offset}\ndata: {data}\n\n")?;
+                offset += 1;
+                last_write = Instant::now();
+            }
+        }
+        if last_write.elapsed() >= EVENT_STREAM_KEEPALIVE {
+            stream.write_all(b": keepalive\n\n")?;
+            last_write = Instant::now();
+        }
+        stream.flush()?;
+        thread::sleep(EVENT_STREAM_POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coco_core::task::CreateTaskRequest;
+    use coco_core::task::TaskTopology;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Read;
+
+    fn request(addr: SocketAddr, raw: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(raw.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1;
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn serves_tasks_gates_and_event_stream_behind_token() {
+        let root = std::env::temp_dir().join(format!("coco-serve-test-{}", uuid::Uuid::new_v4()));
+        let orchestrator = Orchestrator::new(root.clone());
+        let task_id = orchestrator
+            .create_task(CreateTaskRequest {
+                title: "Serve".to_string(),
+                description: String::new(),
+                topology: TaskTopology::Swarm,
+                milestones: Vec::new(),
+                roster: Vec::new(),
+                config: None,
+            })
+            .unwrap()
+            .id;
+
+        assert!(matches!(
+            HttpServer::bind(
+                orchestrator.clone(),
+                ServeOptions {
+                    addr: SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+                    ..ServeOptions::default()
+                },
+            ),
+            Err(ServerError::NonLoopback { .. })
+        ));
+        let server = HttpServer::bind(
+            orchestrator,
+            ServeOptions {
+                addr: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+                token: Some("secret".to_string()),
+                ..ServeOptions::default()
+            },
+        )
+        .unwrap();
+        let addr = server.local_addr().unwrap();
+        let info_path = server.write_info_file().unwrap();
+        let info: ServeInfo =
+            serde_json::from_str(&fs::read_to_string(info_path).unwrap()).unwrap();
+        assert_eq!(info.token, "secret");
+        thread::spawn(move || server.run());
+
+        let (status, body) = request(addr, "GET /api/v1/tasks HTTP/1.1\r\n\r\n");
+        assert_eq!(status, 401);
+        assert_eq!(body["error"]["code"], "unauthorized");
+
+        let (status, _) = request(addr, "GET /api/v1/tasks?token=secret HTTP/1.1\r\n\r\n");
+        assert_eq!(status, 401);
+
+        let auth = "Authorization: Bearer secret\r\n";
+        let (status, body) = request(addr, &format!("GET /api/v1/tasks HTTP/1.1\r\n{auth}\r\n"));
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["id"], task_id.as_str());
+
+        let (status, body) = request(
+            addr,
+            &format!("GET /api/v1/tasks/nope/subagents HTTP/1.1\r\n{auth}\r\n"),
+        );
+        assert_eq!(
+            (status, body["error"]["code"].as_str()),
+            (404, Some("not-found"))
+        );
+        let (status, _) = request(
+            addr,
+            &format!("GET /api/v1/tasks/..%2Fx HTTP/1.1\r\n{auth}\r\n"),
+        );
+        assert_eq!(status, 400);
+        let (status, _) = request(
+            addr,
+            &format!("POST /api/v1/tasks/{task_id}/gates/plan-9/approve HTTP/1.1\r\n{auth}\r\n"),
+        );
+        assert_eq!(status, 404);
+        let body = r#"{"reason":"Ship it?"}"#;
+        let (status, gate) = request(
+            addr,
+            &format!(
+                "POST /api/v1/tasks/{task_id}/gates HTTP/1.1\r\n{auth}Content-Length: {}\r\n\r\n{body}",
+                body.len()
+            ),
+        );
+        assert_eq!(status, 201);
+        let approve = format!(
+            "POST /api/v1/tasks/{task_id}/gates/{}/approve HTTP/1.1\r\n{auth}\r\n",
+            gate["id"].as_str().unwrap()
+        );
+        assert_eq!(request(addr, &approve).0, 200);
+        let (status, body) = request(addr, &approve);
+        assert_eq!(
+            (status, body["error"]["code"].as_str()),
+            (409, Some("conflict"))
+        );
+
+        for body in [
+            r#"{"agentInstance":"w1","agent":"qa","prompt":"p","cwd":"/"}"#,
+            r#"{"agentInstance":"w1","agent":"qa","prompt":"p","cwd":".."}"#,
+            r#"{"agentInstance":"w1","agent":"qa","prompt":"p","codexBin":"/bin/sh"}"#,
+        ] {
+            let (status, _) = request(
+                addr,
+                &format!(
+                    "POST /api/v1/tasks/{task_id}/subagents HTTP/1.1\r\n{auth}Content-Length: {}\r\n\r\n{body}",
+                    body.len()
+                ),
+            );
+            assert_eq!(status, 400, "{body}");
+        }
+
+        let body = r#"{"title":"Second","topology":"squad"}"#;
+        let (status, created) = request(
+            addr,
+            &format!(
+                "POST /api/v1/tasks HTTP/1.1\r\n{auth}Content-Length: {}\r\n\r\n{body}",
+                body.len()
+            ),
+        );
+        assert_eq!(status, 201);
+        let second = created["id"].as_str().unwrap().to_string();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET /api/v1/tasks/{second}/events/stream?token=secret HTTP/1.1\r\n\r\n"
+        )
+        .unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line.trim_end(), "HTTP/1.1 200 OK");
+        let mut data = None;
+        while data.is_none() {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            data = line
+                .strip_prefix("data: ")
+                .map(|v| v.trim_end().to_string());
+        }
+        let event: serde_json::Value = serde_json::from_str(&data.unwrap()).unwrap();
+        assert_eq!(event["type"], "task.created");
+        assert_eq!(event["taskId"], second.as_str());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
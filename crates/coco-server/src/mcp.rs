@@ -0,0 +1,493 @@
+//! `coco mcp`: the `Orchestrator` as a Model Context Protocol server over stdio (newline-delimited
+//! JSON-RPC 2.0), so an interactive Codex session or the planner worker can orchestrate subagents
+//! through tool calls. Tools reuse the HTTP API's request bodies and response shapes.
+
+use crate::api;
+use crate::api::ApiError;
+use crate::api::EvidenceAddBody;
+use crate::api::GateRequestBody;
+use crate::api::SpawnBody;
+use coco_core::task::CreateTaskRequest;
+use coco_core::task::TaskTopology;
+use coco_orchestrator::Orchestrator;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Newest first; an `initialize` asking for anything else gets the newest.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskIdArgs {
+    task_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskCreateArgs {
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "default_topology")]
+    topology: TaskTopology,
+}
+
+fn default_topology() -> TaskTopology {
+    TaskTopology::Swarm
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WithTaskId<T> {
+    task_id: String,
+    #[serde(flatten)]
+    rest: T,
+}
+
+/// Arguments of `coco_subagent_spawn`. Spelled out rather than `WithTaskId<SpawnBody>` because
+/// `flatten` cannot deny unknown fields: a model passing e.g. `codexBin` gets an error, not a
+/// silently ignored argument. The binary is always the server's own.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SubagentSpawnArgs {
+    task_id: String,
+    agent_instance: String,
+    agent: String,
+    prompt: String,
+    cwd: Option<PathBuf>,
+    timeout_seconds: Option<u32>,
+}
+
+impl SubagentSpawnArgs {
+    fn into_parts(self) -> (String, SpawnBody) {
+        let body = SpawnBody {
+            agent_instance: self.agent_instance,
+            agent: self.agent,
+            prompt: self.prompt,
+            cwd: self.cwd,
+            timeout_seconds: self.timeout_seconds,
+        };
+        (self.task_id, body)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WaitAnyArgs {
+    task_id: String,
+    timeout_seconds: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct McpServer {
+    orchestrator: Orchestrator,
+    codex_bin: PathBuf,
+}
+
+impl McpServer {
+    pub fn new(orchestrator: Orchestrator) -> Self {
+        Self {
+            orchestrator,
+            codex_bin: PathBuf::from("codex"),
+        }
+    }
+
+    /// Codex binary for workers started by `coco_subagent_spawn` (defaults to `codex` on PATH).
+    pub fn with_codex_bin(mut self, codex_bin: PathBuf) -> Self {
+        self.codex_bin = codex_bin;
+        self
+    }
+
+    /// Serve one JSON-RPC message per line until `input` is exhausted.
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(reply) = self.handle_message(&line) {
+                writeln!(output, "{reply}")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one JSON-RPC message; notifications get no reply.
+    pub fn handle_message(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(err) => return Some(rpc_error(Value::Null, PARSE_ERROR, &err.to_string())),
+        };
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses from the client (we never send requests) and malformed messages.
+            return message
+                .get("id")
+                .map(|id| rpc_error(id.clone(), INVALID_REQUEST, "missing method"));
+        };
+        let id = message.get("id").cloned()?;
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("method not found: {method}"))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => rpc_error(id, code, &message),
+        })
+    }
+
+    /// Orchestrator failures are tool results with `isError` (the model can react to them);
+    /// unknown tools and malformed arguments are JSON-RPC errors.
+    fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+        match self.dispatch(name, arguments) {
+            Ok(value) => Ok(json!({
+                "content": [{ "type": "text", "text": value.to_string() }],
+                "structuredContent": value,
+                "isError": false,
+            })),
+            Err(ToolError::InvalidParams(message)) => Err((INVALID_PARAMS, message)),
+            Err(ToolError::Failed(err)) => Ok(json!({
+                "content": [{ "type": "text", "text": err.to_string() }],
+                "isError": true,
+            })),
+        }
+    }
+
+    fn dispatch(&self, name: &str, arguments: Value) -> Result<Value, ToolError> {
+        let orchestrator = &self.orchestrator;
+        let value = match name {
+            "coco_task_create" => {
+                let args: TaskCreateArgs = parse_args(arguments)?;
+                json!(orchestrator
+                    .create_task(CreateTaskRequest {
+                        title: args.title,
+                        description: args.description,
+                        topology: args.topology,
+                        milestones: Vec::new(),
+                        roster: Vec::new(),
+                        config: None,
+                    })
+                    .map_err(ApiError::from)?)
+            }
+            "coco_subagent_spawn" => {
+                let (task_id, body) = parse_args::<SubagentSpawnArgs>(arguments)?.into_parts();
+                let task_id = api::existing_task(orchestrator, &task_id)?;
+                api::spawn_subagent(orchestrator, &self.codex_bin, task_id, body)?
+            }
+            "coco_subagent_list" => {
+                let args: TaskIdArgs = parse_args(arguments)?;
+                let task_id = api::existing_task(orchestrator, &args.task_id)?;
+                api::subagents_json(orchestrator, task_id)?
+            }
+            "coco_subagent_wait_any" => {
+                let args: WaitAnyArgs = parse_args(arguments)?;
+                let task_id = api::existing_task(orchestrator, &args.task_id)?;
+                let result = orchestrator
+                    .subagent_wait_any(task_id, args.timeout_seconds)
+                    .map_err(ApiError::from)?;
+                json!({
+                    "agentInstance": result.agent_instance,
+                    "status": result.status.as_str(),
+                })
+            }
+            "coco_task_join" => {
+                let args: TaskIdArgs = parse_args(arguments)?;
+                let task_id = api::task_id_param(&args.task_id)?;
+                let resp = orchestrator.task_join(task_id).map_err(ApiError::from)?;
+                let markdown = fs::read_to_string(&resp.joined_summary_md)
+                    .map_err(|err| ApiError::from(coco_orchestrator::OrchestratorError::Io(err)))?;
+                json!({
+                    "joinedSummaryMd": resp.joined_summary_md,
+                    "joinedSummaryJson": resp.joined_summary_json,
+                    "markdown": markdown,
+                })
+            }
+            "coco_evidence_add" => {
+                let args: WithTaskId<EvidenceAddBody> = parse_args(arguments)?;
+                let task_id = api::task_id_param(&args.task_id)?;
+                json!(orchestrator
+                    .evidence_add(task_id, args.rest.into_entry())
+                    .map_err(ApiError::from)?)
+            }
+            "coco_gate_request" => {
+                let args: WithTaskId<GateRequestBody> = parse_args(arguments)?;
+                let task_id = api::task_id_param(&args.task_id)?;
+                api::request_gate(orchestrator, task_id, args.rest)?
+            }
+            "coco_gate_list" => {
+                let args: TaskIdArgs = parse_args(arguments)?;
+                let task_id = api::task_id_param(&args.task_id)?;
+                json!(
+                    orchestrator
+                        .get_task(task_id)
+                        .map_err(ApiError::from)?
+                        .gates
+                )
+            }
+            _ => return Err(ToolError::InvalidParams(format!("unknown tool: {name}"))),
+        };
+        Ok(value)
+    }
+}
+
+enum ToolError {
+    InvalidParams(String),
+    Failed(ApiError),
+}
+
+impl From<ApiError> for ToolError {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::Body(_) | ApiError::InvalidParam { .. } => {
+                ToolError::InvalidParams(err.to_string())
+            }
+            _ => ToolError::Failed(err),
+        }
+    }
+}
+
+fn parse_args<T: DeserializeOwned>(arguments: Value) -> Result<T, ToolError> {
+    serde_json::from_value(arguments).map_err(|err| ToolError::from(ApiError::Body(err)))
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| Some(**v) == requested)
+        .unwrap_or(&SUPPORTED_PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "coco", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn tool_definitions() -> Value {
+    let task_id = json!({ "type": "string", "description": "Task id (task-...)." });
+    json!([
+        {
+            "name": "coco_task_create",
+            "description": "Create a Coco task (a directory under .coco/tasks/) and return its id.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "description": { "type": "string" },
+                    "topology": { "type": "string", "enum": ["swarm", "squad"], "default": "swarm" },
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "coco_subagent_spawn",
+            "description": "Start a codex exec worker for the task. Returns immediately; use coco_subagent_wait_any to wait for it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "taskId": task_id,
+                    "agentInstance": { "type": "string", "description": "Unique worker name within the task ([A-Za-z0-9_-])." },
+                    "agent": { "type": "string", "description": "Agent spec name under agents/<name>/." },
+                    "prompt": { "type": "string" },
+                    "cwd": { "type": "string", "description": "Worker directory inside the workspace; defaults to the workspace root." },
+                    "timeoutSeconds": { "type": "integer", "minimum": 1 },
+                },
+                "required": ["taskId", "agentInstance", "agent", "prompt"],
+                "additionalProperties": false,
+            },
+        },
+        {
+            "name": "coco_subagent_list",
+            "description": "List the task's workers with their status.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "taskId": task_id },
+                "required": ["taskId"],
+            },
+        },
+        {
+            "name": "coco_subagent_wait_any",
+            "description": "Block until any running worker of the task finishes (or the timeout passes).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "taskId": task_id,
+                    "timeoutSeconds": { "type": "integer", "minimum": 1, "description": "Defaults to task.yaml config.timeoutSeconds." },
+                },
+                "required": ["taskId"],
+            },
+        },
+        {
+            "name": "coco_task_join",
+            "description": "Join finished workers into shared/reports/joined-summary.{md,json} and return the Markdown.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "taskId": task_id },
+                "required": ["taskId"],
+            },
+        },
+        {
+            "name": "coco_evidence_add",
+            "description": "Add an entry to the task's evidence index so reports can cite it as evidence:<id>.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "taskId": task_id,
+                    "id": { "type": "string", "description": "[A-Za-z0-9_.-], not starting with worker-." },
+                    "kind": { "type": "string" },
+                    "title": { "type": "string" },
+                    "summary": { "type": "string" },
+                    "sources": { "type": "array", "items": { "type": "object" }, "description": "EvidenceSource objects (see schemas/evidence-entry.schema.json)." },
+                    "artifactRefs": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["taskId", "id", "kind", "title"],
+            },
+        },
+        {
+            "name": "coco_gate_request",
+            "description": "Open a blocked human-approval gate on the task; it stays blocked until a human approves or rejects it.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "taskId": task_id,
+                    "reason": { "type": "string" },
+                    "gateId": { "type": "string", "description": "Defaults to the next request-<n>." },
+                    "instructionsRef": { "type": "string", "description": "Task-relative file the reviewer should read." },
+                    "by": { "type": "string" },
+                },
+                "required": ["taskId", "reason"],
+            },
+        },
+        {
+            "name": "coco_gate_list",
+            "description": "List the task's gates with their state (open, blocked, approved, rejected).",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "taskId": task_id },
+                "required": ["taskId"],
+            },
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stdio_session_creates_task_adds_evidence_and_requests_gate() {
+        let root = std::env::temp_dir().join(format!("coco-mcp-test-{}", uuid::Uuid::new_v4()));
+        let server = McpServer::new(Orchestrator::new(root.clone()));
+
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"t","version":"0"}}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"coco_task_create","arguments":{"title":"MCP"}}}"#,
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        let replies = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str::<Value>(l).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0]["result"]["protocolVersion"], "2025-03-26");
+        let tools = replies[1]["result"]["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|t| t["name"] == "coco_subagent_wait_any"));
+        let task_id = replies[2]["result"]["structuredContent"]["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let call = |id: u32, name: &str, arguments: Value| {
+            server
+                .handle_message(
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "method": "tools/call",
+                        "params": { "name": name, "arguments": arguments },
+                    })
+                    .to_string(),
+                )
+                .unwrap()
+        };
+
+        let added = call(
+            4,
+            "coco_evidence_add",
+            json!({ "taskId": task_id, "id": "repro-1", "kind": "command", "title": "Repro" }),
+        );
+        assert_eq!(added["result"]["isError"], false);
+        let duplicate = call(
+            5,
+            "coco_evidence_add",
+            json!({ "taskId": task_id, "id": "repro-1", "kind": "command", "title": "Repro" }),
+        );
+        assert_eq!(duplicate["result"]["isError"], true);
+
+        let gate = call(
+            6,
+            "coco_gate_request",
+            json!({ "taskId": task_id, "reason": "Delete the legacy table?" }),
+        );
+        assert_eq!(gate["result"]["structuredContent"]["id"], "request-1");
+        assert_eq!(gate["result"]["structuredContent"]["state"], "blocked");
+        let orchestrator = Orchestrator::new(root.clone());
+        assert_eq!(
+            orchestrator.get_task(&task_id).unwrap().state,
+            coco_core::task::TaskState::InputRequired
+        );
+
+        let missing_args = call(7, "coco_subagent_spawn", json!({ "taskId": task_id }));
+        assert_eq!(missing_args["error"]["code"], INVALID_PARAMS);
+        let binary_override = call(
+            9,
+            "coco_subagent_spawn",
+            json!({
+                "taskId": task_id,
+                "agentInstance": "w1",
+                "agent": "qa",
+                "prompt": "p",
+                "codexBin": "/bin/sh",
+            }),
+        );
+        assert_eq!(binary_override["error"]["code"], INVALID_PARAMS);
+        let unknown = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":8,"method":"resources/list"}"#)
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}